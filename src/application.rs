@@ -22,26 +22,41 @@ Hexkudo. If not, see <https://www.gnu.org/licenses/>.
 SPDX-License-Identifier: GPL-3.0-or-later
 */
 
+use chrono::{Local, NaiveDate};
+use formatx::formatx;
 use gettextrs::gettext;
 use log::debug;
+use std::cell::RefCell;
 use std::collections::HashMap;
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use glib::{Variant, WeakRef};
-use gtk::{gio, glib};
+use glib::{Variant, WeakRef, clone};
+use gtk::{gdk, gio, glib};
 
+use crate::cli_options::StartRequest;
 use crate::config;
 use crate::game::Game;
 use crate::generator::puzzles;
-use crate::saver::game::SaverGame;
+use crate::highscores::HighScores;
+use crate::keybindings;
+use crate::player_profiles;
+use crate::report::MonthlyReport;
+use crate::saver::game::{ImportError, SaverGame, TooManySlots};
+use crate::saver::highscores::SaverHighScores;
+use crate::seasonal;
+use crate::shared_game;
+use crate::widgets::cheat_sheet::HexkudoCheatSheet;
 use crate::widgets::preferences_dialog::HexkudoPreferencesDialog;
 use crate::widgets::print_dialog::HexkudoPrintDialog;
+use crate::widgets::saved_games_dialog::HexkudoSavedGamesDialog;
+use crate::widgets::shortcuts_dialog::HexkudoShortcutsDialog;
+use crate::widgets::verify_dialog::HexkudoVerifyDialog;
 use crate::widgets::window::HexkudoWindow;
 
 mod imp {
     use super::*;
-    use std::cell::{OnceCell, RefCell};
+    use std::cell::OnceCell;
     use std::rc::Rc;
 
     pub struct HexkudoApplication {
@@ -56,6 +71,9 @@ mod imp {
 
         /// The [`Game`] object stores the parameters of the currently played game.
         pub game: Rc<RefCell<Game>>,
+
+        /// Game to start when the application is activated, from the command line.
+        pub start_request: RefCell<Option<StartRequest>>,
     }
 
     #[glib::object_subclass]
@@ -74,6 +92,7 @@ mod imp {
                 puzzle_list: c,
                 settings: gio::Settings::new(config::APPLICATION_ID),
                 game: Rc::default(),
+                start_request: RefCell::new(None),
             }
         }
     }
@@ -88,6 +107,21 @@ mod imp {
             let window: HexkudoWindow = application.get_main_window();
             // Ask the window manager/compositor to present the window
             window.present();
+            application.show_monthly_report();
+
+            let request: Option<StartRequest> = self.start_request.take();
+            if let Some(r) = request {
+                application.play_requested(r);
+            }
+        }
+
+        // Puzzle files opened from the file manager. Hexkudo plays one puzzle at a time, and
+        // only opens the first file.
+        fn open(&self, files: &[gio::File], _hint: &str) {
+            self.activate();
+            if let Some(file) = files.first() {
+                self.obj().open_game_file(file);
+            }
         }
 
         // Entry point for GApplication
@@ -95,6 +129,8 @@ mod imp {
             self.parent_startup();
 
             let application = self.obj();
+            seasonal::init();
+            application.migrate_settings();
 
             debug!("Getting the saved game");
             let saver: SaverGame = SaverGame::new(glib::user_data_dir());
@@ -127,28 +163,47 @@ mod imp {
                 .set(window.downgrade())
                 .expect("Failed to initialize the application window");
 
+            let started: bool = shared_game::try_read(&self.game).is_some_and(|g| g.started);
             application
                 .get_main_window()
-                .action_set_enabled("app.new-game", self.game.borrow().started);
+                .action_set_enabled("app.new-game", started);
             application
                 .get_main_window()
-                .action_set_enabled("app.new-game-same-puzzle", self.game.borrow().started);
+                .action_set_enabled("app.new-game-same-puzzle", started);
             application
                 .get_main_window()
                 .action_set_enabled("game-view.print-current", false);
+            application
+                .get_main_window()
+                .action_set_enabled("game-view.export-replay", false);
+            application
+                .get_main_window()
+                .action_set_enabled("game-view.export-puzzle", false);
             application
                 .get_main_window()
                 .action_set_enabled("game-view.zoom-out", false);
             application
                 .get_main_window()
                 .action_set_enabled("game-view.zoom-in", false);
-
-            application.set_accels_for_action("app.quit", &["<Primary>q"]);
-            application.set_accels_for_action("app.new-game", &["<Primary>n"]);
-            application.set_accels_for_action("app.preferences", &["<Primary>comma"]);
-            application.set_accels_for_action("app.help", &["F1"]);
-            application.set_accels_for_action("app.toggle-fullscreen", &["F11", "f"]);
-            application.set_accels_for_action("app.back-start", &["<Alt>Left", "<Alt>KP_Left"]);
+            application
+                .get_main_window()
+                .action_set_enabled("game-view.zoom-fit", false);
+            application
+                .get_main_window()
+                .action_set_enabled("app.back-start", false);
+            application
+                .get_main_window()
+                .action_set_enabled("app.forward", false);
+
+            keybindings::apply_app(&*application, &self.settings);
+            self.settings.connect_changed(
+                Some("custom-accels"),
+                clone!(
+                    #[weak(rename_to = mself)]
+                    self,
+                    move |settings, _| keybindings::apply_app(&*mself.obj(), settings)
+                ),
+            );
         }
 
         // Saving the currently played game (if any) on application shutdown.
@@ -157,7 +212,10 @@ mod imp {
 
             debug!("Saving the game");
             let saver: SaverGame = SaverGame::new(glib::user_data_dir());
-            let game = self.game.borrow();
+            let Some(game) = shared_game::try_read(&self.game) else {
+                debug!("Cannot save the game: the game data is in use");
+                return;
+            };
             if game.started && !game.solved {
                 match saver.save_game(&game) {
                     Ok(()) => (),
@@ -258,10 +316,16 @@ impl HexkudoApplication {
     pub fn new() -> Self {
         glib::Object::builder()
             .property("application-id", config::APPLICATION_ID)
+            .property("flags", gio::ApplicationFlags::HANDLES_OPEN)
             .property("resource-base-path", "/io/github/herve4m/Hexkudo") //"/io/github/herve4m/Hexkudo")
             .build()
     }
 
+    /// Store the game to start when the application is activated.
+    pub fn set_start_request(&self, request: StartRequest) {
+        self.imp().start_request.replace(Some(request));
+    }
+
     fn setup_gactions(&self) {
         let actions = [
             gio::ActionEntryBuilder::new("about")
@@ -270,15 +334,21 @@ impl HexkudoApplication {
             gio::ActionEntryBuilder::new("preferences")
                 .activate(|app: &Self, _, _| app.show_preferences())
                 .build(),
+            gio::ActionEntryBuilder::new("shortcuts")
+                .activate(|app: &Self, _, _| app.show_shortcuts())
+                .build(),
             gio::ActionEntryBuilder::new("help")
                 .activate(|app: &Self, _, _| app.help())
                 .build(),
             gio::ActionEntryBuilder::new("quit")
-                .activate(|app: &Self, _, _| app.quit())
+                .activate(|app: &Self, _, _| app.quit_game())
                 .build(),
             gio::ActionEntryBuilder::new("back-start")
                 .activate(move |app: &Self, _, _| app.back_start())
                 .build(),
+            gio::ActionEntryBuilder::new("forward")
+                .activate(move |app: &Self, _, _| app.forward())
+                .build(),
             gio::ActionEntryBuilder::new("select-puzzle")
                 .parameter_type(Some(&i32::static_variant_type()))
                 .activate(move |app: &Self, _, parameter| {
@@ -297,17 +367,72 @@ impl HexkudoApplication {
             gio::ActionEntryBuilder::new("scores")
                 .activate(move |app: &Self, _, _| app.scores())
                 .build(),
+            gio::ActionEntryBuilder::new("statistics")
+                .activate(move |app: &Self, _, _| app.statistics())
+                .build(),
+            gio::ActionEntryBuilder::new("verify-result")
+                .activate(move |app: &Self, _, _| app.verify_result())
+                .build(),
+            gio::ActionEntryBuilder::new("saved-games")
+                .activate(move |app: &Self, _, _| app.saved_games())
+                .build(),
+            gio::ActionEntryBuilder::new("load-slot")
+                .parameter_type(Some(&String::static_variant_type()))
+                .activate(move |app: &Self, _, parameter| {
+                    if let Some(name) = parameter.and_then(|p| p.get::<String>()) {
+                        app.load_slot(&name);
+                    }
+                })
+                .build(),
+            gio::ActionEntryBuilder::new("import-puzzle")
+                .activate(move |app: &Self, _, _| app.import_puzzle())
+                .build(),
+            gio::ActionEntryBuilder::new("import-save")
+                .activate(move |app: &Self, _, _| app.import_save())
+                .build(),
             gio::ActionEntryBuilder::new("print-multiple")
                 .activate(move |app: &Self, _, _| app.print_multiple())
                 .build(),
+            gio::ActionEntryBuilder::new("print-cheat-sheet")
+                .activate(move |app: &Self, _, _| app.print_cheat_sheet())
+                .build(),
             gio::ActionEntryBuilder::new("toggle-fullscreen")
                 .activate(move |app: &Self, _, _| app.toggle_fullscreen())
                 .build(),
+            // Automation actions, which the application exports on D-Bus. See the
+            // `automation-actions` GSettings key.
+            gio::ActionEntryBuilder::new("select-cell")
+                .parameter_type(Some(&i32::static_variant_type()))
+                .activate(move |app: &Self, _, parameter| {
+                    app.get_main_window().select_cell(parameter);
+                })
+                .build(),
+            gio::ActionEntryBuilder::new("set-cell")
+                .parameter_type(Some(&<(i32, i32)>::static_variant_type()))
+                .activate(move |app: &Self, _, parameter| {
+                    app.get_main_window().set_cell(parameter);
+                })
+                .build(),
         ];
 
         self.add_action_entries(actions);
     }
 
+    /// Move the values of the deprecated GSettings keys to the keys that replace them.
+    fn migrate_settings(&self) {
+        let settings: &gio::Settings = &self.imp().settings;
+
+        // The number-picker-second-click key is now a value of the popover-trigger key
+        if settings.boolean("number-picker-second-click") {
+            debug!("Migrate the number-picker-second-click setting");
+            if let Err(error) = settings.set_string("popover-trigger", "second-click") {
+                debug!("Cannot migrate the number-picker-second-click setting: {error}");
+                return;
+            }
+            settings.reset("number-picker-second-click");
+        }
+    }
+
     fn get_main_window(&self) -> HexkudoWindow {
         self.imp().window.get().unwrap().clone().upgrade().unwrap()
     }
@@ -345,6 +470,13 @@ impl HexkudoApplication {
         preferences_window.present(Some(&window));
     }
 
+    fn show_shortcuts(&self) {
+        let window: gtk::Window = self.active_window().unwrap();
+        let shortcuts_dialog: HexkudoShortcutsDialog =
+            HexkudoShortcutsDialog::new(&self.imp().settings);
+        shortcuts_dialog.present(Some(&window));
+    }
+
     /*
     Does not work with flatpak.
     See https://gitlab.gnome.org/GNOME/gtk/-/issues/6135
@@ -388,13 +520,26 @@ impl HexkudoApplication {
         self.get_main_window().go_to_select_puzzle(puzzles);
     }
 
-    fn back_start(&self) {
-        debug!("Back to starting page");
-        if !self.imp().game.borrow().started {
-            self.get_main_window().go_to_start();
+    /// Close the main window, which asks for confirmation when a game is in progress. The
+    /// application quits when the window is closed.
+    fn quit_game(&self) {
+        debug!("Quit");
+        match self.imp().window.get().and_then(|w| w.upgrade()) {
+            Some(window) => window.close(),
+            None => self.quit(),
         }
     }
 
+    fn back_start(&self) {
+        debug!("Back to the previous page");
+        self.get_main_window().go_back();
+    }
+
+    fn forward(&self) {
+        debug!("Forward to the next page");
+        self.get_main_window().go_forward();
+    }
+
     fn start_game(&self) {
         debug!("Switch to the game view");
         self.get_main_window().go_to_game();
@@ -402,13 +547,276 @@ impl HexkudoApplication {
 
     fn new_game(&self) {
         debug!("Start a new game");
-        self.imp().game.borrow_mut().clear();
+        self.confirm_discard(|app| app.discard_game());
+    }
+
+    /// Start a game with the puzzle requested from the command line, through the same actions
+    /// as the puzzle selection view.
+    fn play_requested(&self, request: StartRequest) {
+        debug!("Start the {} {:?} puzzle", request.name, request.difficulty);
+        self.confirm_discard(move |app| {
+            // The function runs from the action or from the response of the confirmation
+            // dialog, when no other code holds the game data
+            app.imp().game.borrow_mut().clear();
+
+            // The puzzle selection view selects the puzzle from the `puzzle` GSettings
+            if let Err(error) = app.imp().settings.set_string("puzzle", &request.name) {
+                debug!("Cannot select the {} puzzle: {error}", request.name);
+                return;
+            }
+            app.activate_action(
+                "select-puzzle",
+                Some(&(request.difficulty as i32).to_variant()),
+            );
+            app.activate_action("start-game", None);
+        });
+    }
+
+    /// Select a puzzle file and start a game with that puzzle.
+    fn import_puzzle(&self) {
+        debug!("Import a puzzle");
+        let window: gtk::Window = self.active_window().unwrap();
+        let filter: gtk::FileFilter = gtk::FileFilter::new();
+        filter.set_name(Some(&gettext("Hexkudo Puzzles")));
+        filter.add_suffix("hexkudo");
+        let filters: gio::ListStore = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let file_dialog: gtk::FileDialog = gtk::FileDialog::builder()
+            .title(gettext("Open Puzzle"))
+            .filters(&filters)
+            .modal(true)
+            .build();
+        file_dialog.open(
+            Some(&window),
+            gio::Cancellable::NONE,
+            glib::clone!(
+                #[weak(rename_to = app)]
+                self,
+                move |result| {
+                    if let Ok(file) = result {
+                        app.open_game_file(&file);
+                    }
+                }
+            ),
+        );
+    }
+
+    fn import_save(&self) {
+        debug!("Import a saved game");
+        let window: gtk::Window = self.active_window().unwrap();
+        let filter: gtk::FileFilter = gtk::FileFilter::new();
+        filter.set_name(Some(&gettext("Hexkudo Saved Games")));
+        filter.add_suffix("hexkudo-save");
+        let filters: gio::ListStore = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let file_dialog: gtk::FileDialog = gtk::FileDialog::builder()
+            .title(gettext("Import Save"))
+            .filters(&filters)
+            .modal(true)
+            .build();
+        file_dialog.open(
+            Some(&window),
+            gio::Cancellable::NONE,
+            glib::clone!(
+                #[weak(rename_to = app)]
+                self,
+                move |result| {
+                    if let Ok(file) = result
+                        && let Some(path) = file.path()
+                    {
+                        app.import_save_file(&path);
+                    }
+                }
+            ),
+        );
+    }
+
+    /// Continue the game from the given exported game file.
+    fn import_save_file(&self, path: &std::path::Path) {
+        debug!("Import the {path:?} saved game");
+        let game: Game = match SaverGame::import_game(path) {
+            Ok(g) => g,
+            Err(error) => {
+                debug!("Error importing the saved game: {error}");
+                let body: String = match error {
+                    ImportError::NewerVersion(version) => formatx!(
+                        gettext(
+                            "A newer version of Hexkudo ({version}) exported the game. Update \
+                             Hexkudo to continue the game."
+                        ),
+                        version = version
+                    )
+                    .unwrap(),
+                    ImportError::Invalid(_) => gettext("The file is not a valid Hexkudo save."),
+                };
+                let window: gtk::Window = self.active_window().unwrap();
+                let dialog: adw::AlertDialog =
+                    adw::AlertDialog::new(Some(&gettext("Cannot Import the Game")), Some(&body));
+                dialog.add_response("close", &gettext("_Close"));
+                dialog.present(Some(&window));
+                return;
+            }
+        };
+
+        self.start_loaded_game(game);
+    }
+
+    /// Start a game with the puzzle from the given file.
+    fn open_game_file(&self, file: &gio::File) {
+        let Some(path) = file.path() else {
+            debug!("Cannot open {}: not a local file", file.uri());
+            return;
+        };
+        debug!("Open the {path:?} puzzle file");
+
+        let game: Game = match SaverGame::load_game_file(&path) {
+            Ok(g) => g,
+            Err(error) => {
+                debug!("Error reading the puzzle file: {error}");
+                let window: gtk::Window = self.active_window().unwrap();
+                let dialog: adw::AlertDialog = adw::AlertDialog::new(
+                    Some(&gettext("Cannot Open the Puzzle")),
+                    Some(&gettext("The file is not a valid Hexkudo puzzle.")),
+                );
+                dialog.add_response("close", &gettext("_Close"));
+                dialog.present(Some(&window));
+                return;
+            }
+        };
+
+        self.start_loaded_game(game);
+    }
+
+    fn saved_games(&self) {
+        debug!("Display the saved games");
+        let window: gtk::Window = self.active_window().unwrap();
+        HexkudoSavedGamesDialog::new().present(Some(&window));
+    }
+
+    /// Continue the game from the save slot with the given name.
+    fn load_slot(&self, name: &str) {
+        debug!("Load the {name} save slot");
+        let game: Option<Game> = SaverGame::new_slot(glib::user_data_dir(), name)
+            .and_then(|s| s.get_game().ok().flatten());
+        let Some(game) = game else {
+            let window: gtk::Window = self.active_window().unwrap();
+            let dialog: adw::AlertDialog = adw::AlertDialog::new(
+                Some(&gettext("Cannot Load the Game")),
+                Some(&gettext("The saved game is not valid anymore.")),
+            );
+            dialog.add_response("close", &gettext("_Close"));
+            dialog.present(Some(&window));
+            return;
+        };
+
+        self.start_loaded_game(game);
+    }
+
+    /// Replace the current game with the given restored game, after asking for confirmation
+    /// when a game is in progress. The restored game continues if it was in progress, and
+    /// starts over otherwise.
+    fn start_loaded_game(&self, game: Game) {
+        let game: RefCell<Option<Game>> = RefCell::new(Some(game));
+        self.confirm_discard(move |app| {
+            let Some(g) = game.take() else {
+                return;
+            };
+            let in_progress: bool = g.started && !g.solved;
+            app.imp().game.replace(g);
+            if in_progress {
+                app.get_main_window().continue_game();
+            } else {
+                app.get_main_window().play_same_puzzle();
+            }
+        });
+    }
+
+    /// Run the given function, after asking for confirmation when a game is in progress.
+    fn confirm_discard<F: Fn(&Self) + 'static>(&self, discard: F) {
+        // When the game is being updated, ask for confirmation anyway
+        let in_progress: bool =
+            shared_game::try_read(&self.imp().game).is_none_or(|g| g.started && !g.solved);
+        if !in_progress {
+            discard(self);
+            return;
+        }
+
+        // Ask for confirmation before discarding the game in progress
+        let window: gtk::Window = self.active_window().unwrap();
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(
+            Some(&gettext("Start a New Game?")),
+            Some(&gettext(
+                "Your current game will be lost. You can save it to continue it later from the \
+                 Saved Games dialog, or cancel and continue playing instead.",
+            )),
+        );
+        dialog.add_response("cancel", &gettext("Continue Playing"));
+        dialog.add_response("discard", &gettext("Discard"));
+        dialog.add_response("save", &gettext("_Save and Continue"));
+        dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+        dialog.set_response_appearance("discard", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = app)]
+                self,
+                move |_, response| {
+                    if response == "discard" || (response == "save" && app.save_in_slot()) {
+                        discard(&app);
+                    }
+                }
+            ),
+        );
+        dialog.present(Some(&window));
+    }
+
+    /// Save the game in progress in a save slot named after the puzzle, replacing the previous
+    /// save of the same puzzle. When the game cannot be saved, tell the player and return
+    /// `false` so that the game is not discarded.
+    fn save_in_slot(&self) -> bool {
+        let error_message: String = {
+            let Some(game) = shared_game::try_read(&self.imp().game) else {
+                return false;
+            };
+            let name: String =
+                format!("{}-{:?}", game.puzzle.name, game.puzzle.difficulty).to_lowercase();
+            let result = SaverGame::new_slot(glib::user_data_dir(), &name)
+                .ok_or_else(|| format!("Invalid save slot name {name}").into())
+                .and_then(|saver| saver.save_game(&game));
+            match result {
+                Ok(()) => return true,
+                Err(error) if error.is::<TooManySlots>() => {
+                    gettext("Too many saved games. Delete some saved games first.")
+                }
+                Err(error) => {
+                    debug!("Error saving the game: {error}");
+                    gettext("The game cannot be saved.")
+                }
+            }
+        };
+
+        let window: gtk::Window = self.active_window().unwrap();
+        let dialog: adw::AlertDialog =
+            adw::AlertDialog::new(Some(&gettext("Cannot Save the Game")), Some(&error_message));
+        dialog.add_response("close", &gettext("_Close"));
+        dialog.present(Some(&window));
+        false
+    }
+
+    /// Clear the current game and go back to the starting page.
+    fn discard_game(&self) {
+        debug!("Discard the current game");
+        shared_game::with_game_mut(&self.imp().game, |game: &mut Game| game.clear());
         self.get_main_window().go_to_start();
     }
 
     fn new_game_same_puzzle(&self) {
         debug!("Start a new game with the previously selected difficulty and puzzle");
-        self.get_main_window().play_same_puzzle();
+        self.confirm_discard(|app| app.get_main_window().play_same_puzzle());
     }
 
     fn scores(&self) {
@@ -416,6 +824,18 @@ impl HexkudoApplication {
         self.get_main_window().display_scores();
     }
 
+    fn statistics(&self) {
+        debug!("Display statistics");
+        self.get_main_window().display_statistics();
+    }
+
+    fn verify_result(&self) {
+        debug!("Verify a proof string");
+        let window: gtk::Window = self.active_window().unwrap();
+        let verify_dialog: HexkudoVerifyDialog = HexkudoVerifyDialog::new();
+        verify_dialog.present(Some(&window));
+    }
+
     fn print_multiple(&self) {
         debug!("Print multiple puzzles");
         let window: gtk::Window = self.active_window().unwrap();
@@ -430,13 +850,114 @@ impl HexkudoApplication {
         print_dialog.present(Some(&window));
     }
 
+    fn print_cheat_sheet(&self) {
+        debug!("Print the solving technique cheat sheet");
+        let window: gtk::Window = self.active_window().unwrap();
+        HexkudoCheatSheet::new().print(&window);
+    }
+
+    /// Display the report of the previous month, once, at the start of a month.
+    fn show_monthly_report(&self) {
+        let settings: &gio::Settings = &self.imp().settings;
+        let today: NaiveDate = Local::now().date_naive();
+        let current_month: String = today.format("%Y-%m").to_string();
+        let last_month: glib::GString = settings.string("last-report-month");
+
+        if last_month.as_str() == current_month {
+            return;
+        }
+        let _ = settings.set_string("last-report-month", &current_month);
+
+        // No report the first time Hexkudo runs
+        if last_month.is_empty() || !settings.boolean("monthly-report") {
+            return;
+        }
+
+        let highscores: HighScores =
+            SaverHighScores::new(glib::user_data_dir(), &player_profiles::current(settings))
+                .get_highscores()
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+        let puzzle_list: &HashMap<(String, puzzles::Difficulty), puzzles::Puzzle> = self
+            .imp()
+            .puzzle_list
+            .get()
+            .expect("Cannot retrieve the list of puzzles");
+        let report: MonthlyReport = MonthlyReport::new(
+            &highscores,
+            puzzle_list,
+            MonthlyReport::previous_month(today),
+        );
+        if report.is_empty() {
+            return;
+        }
+
+        let window: HexkudoWindow = self.get_main_window();
+        let dialog: adw::AlertDialog =
+            adw::AlertDialog::new(Some(&report.title()), Some(&report.to_text()));
+        dialog.add_response("close", &gettext("_Close"));
+        dialog.add_response("export", &gettext("_Export…"));
+        dialog.set_default_response(Some("close"));
+        dialog.set_close_response("close");
+        dialog.connect_response(
+            Some("export"),
+            clone!(
+                #[weak]
+                window,
+                move |_, _| {
+                    Self::export_monthly_report(&window, report.clone());
+                }
+            ),
+        );
+        dialog.present(Some(&window));
+    }
+
+    /// Save the monthly report in a file. The report is in HTML format, unless the file name
+    /// ends with `.txt`.
+    fn export_monthly_report(window: &HexkudoWindow, report: MonthlyReport) {
+        let file_dialog: gtk::FileDialog = gtk::FileDialog::builder()
+            .title(gettext("Export the Monthly Report"))
+            .initial_name("hexkudo-report.html")
+            .modal(true)
+            .build();
+        file_dialog.save(Some(window), gio::Cancellable::NONE, move |result| {
+            if let Ok(file) = result
+                && let Some(path) = file.path()
+            {
+                let content: String = if path.extension().is_some_and(|e| e == "txt") {
+                    report.to_text()
+                } else {
+                    report.to_html()
+                };
+                if let Err(error) = std::fs::write(&path, content) {
+                    debug!("Error exporting the monthly report: {error}");
+                }
+            }
+        });
+    }
+
     fn toggle_fullscreen(&self) {
         debug!("Toggle fullscreen");
         let window: HexkudoWindow = self.get_main_window();
         if window.is_fullscreen() {
             window.unfullscreen();
-        } else {
-            window.fullscreen();
+            return;
+        }
+
+        // Use the monitor that the player selected in the Preferences dialog, if connected
+        let connector: glib::GString = self.imp().settings.string("fullscreen-monitor");
+        let monitor: Option<gdk::Monitor> = match gdk::Display::default() {
+            Some(display) if !connector.is_empty() => display
+                .monitors()
+                .iter::<gdk::Monitor>()
+                .filter_map(|m| m.ok())
+                .find(|m| m.connector().is_some_and(|c| c == connector)),
+            _ => None,
+        };
+        match monitor {
+            Some(m) => window.fullscreen_on_monitor(&m),
+            None => window.fullscreen(),
         }
     }
 }
@@ -0,0 +1,244 @@
+/*
+chain.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Complete the chains of consecutive values.
+//!
+//! A gap is a sequence of missing values between two placed values. When only one route of
+//! empty cells of the right length connects the two cells, the gap can be filled without
+//! guessing.
+//! The search is bounded, so that the large empty areas of the board do not freeze the
+//! interface. The gaps for which the search exceeds the limit are considered ambiguous.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::generator::regions;
+use crate::generator::vertexes::Vertexes;
+
+/// Maximum number of cells that the search visits for each gap.
+const MAX_VISITED_CELLS: usize = 20_000;
+
+/// Search state for the routes between the two ends of a gap.
+struct RouteSearch<'a> {
+    /// Board cells.
+    vertexes: &'a Vertexes,
+
+    /// Values that the player placed, indexed by the cell IDs. Routes only go through the
+    /// cells that have no value.
+    values: &'a HashMap<usize, usize>,
+
+    /// Cell at the end of the gap.
+    end_cell: usize,
+
+    /// Cells of the route being explored, without the starting cell.
+    route: Vec<usize>,
+
+    /// First route found.
+    found: Option<Vec<usize>>,
+
+    /// Number of routes found so far.
+    routes: usize,
+
+    /// Number of cells visited so far.
+    visited: usize,
+}
+
+impl RouteSearch<'_> {
+    /// Explore the routes from the given cell that reach the end cell in `remaining` moves.
+    /// Return false when the search can stop, because a second route exists or because the
+    /// search exceeded [`MAX_VISITED_CELLS`].
+    fn explore(&mut self, cell_id: usize, remaining: usize) -> bool {
+        self.visited += 1;
+        if self.visited > MAX_VISITED_CELLS {
+            return false;
+        }
+
+        if remaining == 1 {
+            if self.vertexes.is_adjacent(cell_id, self.end_cell) {
+                self.routes += 1;
+                if self.routes > 1 {
+                    return false;
+                }
+                self.found = Some(self.route.clone());
+            }
+            return true;
+        }
+
+        // The end cell is too far away
+        if distance(self.vertexes, cell_id, self.end_cell) > remaining {
+            return true;
+        }
+
+        for next in regions::adjacent_cells(self.vertexes, cell_id) {
+            if self.values.contains_key(&next) || self.route.contains(&next) {
+                continue;
+            }
+            self.route.push(next);
+            let keep_going: bool = self.explore(next, remaining - 1);
+            self.route.pop();
+            if !keep_going {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Return the minimum number of moves between two cells.
+pub fn distance(vertexes: &Vertexes, cell_id_1: usize, cell_id_2: usize) -> usize {
+    match (
+        vertexes.get_coordinates(cell_id_1),
+        vertexes.get_coordinates(cell_id_2),
+    ) {
+        (Some((x1, y1)), Some((x2, y2))) => {
+            // Horizontal moves cover two columns, and diagonal moves cover one column and one row
+            let dx: usize = x1.abs_diff(x2);
+            let dy: usize = y1.abs_diff(y2);
+            dy + dx.saturating_sub(dy) / 2
+        }
+        _ => 0,
+    }
+}
+
+/// Return the cells of the only route of `moves` moves between the two given cells, without
+/// these two cells, or None if there is no such route, several routes, or if the search
+/// exceeded its limit.
+fn unique_route(
+    vertexes: &Vertexes,
+    values: &HashMap<usize, usize>,
+    start_cell: usize,
+    end_cell: usize,
+    moves: usize,
+) -> Option<Vec<usize>> {
+    let mut search = RouteSearch {
+        vertexes,
+        values,
+        end_cell,
+        route: Vec::new(),
+        found: None,
+        routes: 0,
+        visited: 0,
+    };
+
+    if search.explore(start_cell, moves) && search.routes == 1 {
+        search.found
+    } else {
+        None
+    }
+}
+
+/// Return the cells and their values that fill the first gap that only one route can fill, or
+/// None if no such gap exists.
+///
+/// The `values` parameter provides the values that the player placed, indexed by the cell IDs.
+/// The values that the player placed in several cells are mistakes, and the gaps around these
+/// values are ignored.
+pub fn find_unambiguous_gap(
+    vertexes: &Vertexes,
+    values: &HashMap<usize, usize>,
+) -> Option<Vec<(usize, usize)>> {
+    let mut value_to_id: HashMap<usize, usize> = HashMap::new();
+    let mut duplicated: HashSet<usize> = HashSet::new();
+
+    for (cell_id, value) in values {
+        if value_to_id.insert(*value, *cell_id).is_some() {
+            duplicated.insert(*value);
+        }
+    }
+    for value in &duplicated {
+        value_to_id.remove(value);
+    }
+
+    let mut placed: Vec<usize> = value_to_id.keys().copied().collect();
+    placed.sort_unstable();
+    for pair in placed.windows(2) {
+        let (start, end): (usize, usize) = (pair[0], pair[1]);
+
+        if end - start < 2 || duplicated.iter().any(|v| *v > start && *v < end) {
+            continue;
+        }
+        if let Some(route) = unique_route(
+            vertexes,
+            values,
+            value_to_id[&start],
+            value_to_id[&end],
+            end - start,
+        ) {
+            return Some(route.into_iter().zip(start + 1..end).collect());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::generator::puzzle_parse::PuzzleParse;
+
+    /// Small board. The cell IDs are, from the top left corner:
+    ///
+    /// ```text
+    ///  0 1
+    /// 2 3 4
+    ///  5 6
+    /// ```
+    const MATRIX: &str = " o o
+o o o
+ o o
+";
+
+    /// Return the cells of the [`MATRIX`] board.
+    fn board() -> Vertexes {
+        let mut puzzle: PuzzleParse = PuzzleParse::new(MATRIX);
+        puzzle.build_edges().unwrap();
+        puzzle.vertexes
+    }
+
+    #[test]
+    fn unique_route_is_filled() {
+        let vertexes: Vertexes = board();
+
+        // Only the center cell connects the left and the right cells of the middle row
+        let values: HashMap<usize, usize> = HashMap::from([(2, 1), (4, 3)]);
+        assert_eq!(find_unambiguous_gap(&vertexes, &values), Some(vec![(3, 2)]));
+
+        // Only one route of three cells goes around the center cell, which has a value
+        let values: HashMap<usize, usize> = HashMap::from([(2, 1), (1, 5), (3, 10)]);
+        assert_eq!(
+            find_unambiguous_gap(&vertexes, &values),
+            Some(vec![(5, 2), (6, 3), (4, 4)])
+        );
+    }
+
+    #[test]
+    fn ambiguous_gap_is_left_alone() {
+        let vertexes: Vertexes = board();
+
+        // The top left and the bottom left cells are both adjacent to the cells 2 and 3
+        let values: HashMap<usize, usize> = HashMap::from([(0, 1), (5, 3)]);
+        assert_eq!(find_unambiguous_gap(&vertexes, &values), None);
+
+        // The gaps around a duplicated value are ignored
+        let values: HashMap<usize, usize> = HashMap::from([(2, 1), (4, 3), (0, 2), (6, 2)]);
+        assert_eq!(find_unambiguous_gap(&vertexes, &values), None);
+    }
+}
@@ -29,6 +29,8 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //!
 //! See [`crate::game`] where the checkpoints are organized as a stack: a player can create several
 //!  checkpoints, and can revert back to the last checkpoint, which is then deleted.
+//! Players can also name their checkpoints and revert back to any of them, in which case the
+//! more recent checkpoints are deleted too.
 
 use serde::{Deserialize, Serialize};
 
@@ -43,14 +45,19 @@ pub struct CheckPoint {
 
     /// ID of the currently selected cell.
     pub selected_cell: Option<usize>,
+
+    /// Name that the player gave to the checkpoint.
+    #[serde(default)]
+    pub label: String,
 }
 
 impl CheckPoint {
     /// Create a [`CheckPoint`] object for the provided [`Game`] object.
-    pub fn new(game: &Game) -> Self {
+    pub fn new(game: &Game, label: &str) -> Self {
         Self {
             player_input: game.player_input.clone(),
             selected_cell: game.get_selected_cell(),
+            label: label.to_string(),
         }
     }
 }
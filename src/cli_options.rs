@@ -22,12 +22,58 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Process command-line options.
 //!
-//! These options are intended for developers creating puzzles.
+//! Most of these options are intended for developers creating puzzles.
 //! In command-line mode, Hexkudo can generate random paths that developers can copy to complete
 //! their puzzle file in the `src/generator/puzzles` directory.
 //!
+//! The `--play` and `--daily` options skip the starting view and immediately start a game.
+//!
+//! The `--print` option generates puzzles and prints them without user interaction, for
+//! preparing handouts on a schedule.
+//!
+//! The `--seed` option makes the generation reproducible: the same seed produces the same paths
+//! and clues, which helps investigating the puzzles that exceed the generation delay.
+//!
+//! The `--solve` option verifies that puzzle files have a unique solution, for the authors of
+//! community puzzles.
+//!
 //! # Examples
 //!
+//! Verify a puzzle file:
+//!
+//! ```
+//! $ flatpak run io.github.herve4m.Hexkudo --solve classic-easy.hexkudo
+//! classic-easy.hexkudo: Classic Easy
+//!   solution: unique
+//!   path: 3 6 7 8 4 1 0 2 5 9 13 10 14 17 18 20 21 19 15 11 12 16
+//!   cells: 22, givens: 4, diamonds: 4, anti-diamonds: 0, regions: 0
+//!   explored cells: 1841, time: 0.002s
+//! ```
+//!
+//! Generate a path for the Classic map at the hard difficulty level, with a given seed:
+//!
+//! ```
+//! $ flatpak run io.github.herve4m.Hexkudo -p Classic -f hard --seed 1234 --summary
+//! ```
+//!
+//! Print ten puzzles for the Classic map at the easy difficulty level, four per page:
+//!
+//! ```
+//! $ flatpak run io.github.herve4m.Hexkudo --print --printer Lab_Printer -c 10 -p Classic -f easy --per-page 4
+//! ```
+//!
+//! Start a game with the Classic map at the hard difficulty level:
+//!
+//! ```
+//! $ flatpak run io.github.herve4m.Hexkudo --play classic:hard
+//! ```
+//!
+//! Start a game with the puzzle of the day:
+//!
+//! ```
+//! $ flatpak run io.github.herve4m.Hexkudo --daily
+//! ```
+//!
 //! List the available puzzles:
 //!
 //! ```
@@ -48,7 +94,7 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! ```
 //! $ flatpak run io.github.herve4m.Hexkudo -c 3 -p Classic -f easy
 //!
-//! pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
+//! pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {
 //!     let sample_path: [[u8; 22]; 3] = [
 //!         [3, 6, 7, 8, 4, 1, 0, 2, 5, 9, 13, 10, 14, 17, 18, 20, 21, 19, 15, 11, 12, 16],
 //!         [8, 7, 4, 1, 3, 0, 2, 6, 10, 5, 9, 13, 17, 20, 21, 19, 18, 14, 15, 11, 12, 16],
@@ -64,7 +110,7 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //!         [8, 16, 7].to_vec(),
 //!         [13, 16, 11, 12].to_vec(),
 //!     ];
-//!     let i:usize = rand::rng().random_range(0..sample_path.len());
+//!     let i: usize = (seed % sample_path.len() as u64) as usize;
 //!     puzzles::PuzzleSampleGame {
 //!         path: Vec::from(sample_path[i]),
 //!         diamonds: sample_diamonds[i].clone(),
@@ -73,18 +119,28 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! }
 //! ```
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use gtk::glib;
 use log::debug;
 use std::collections::HashMap;
 use std::env;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 use crate::config::COPYRIGHT_NOTICE;
+use crate::game::Game;
 use crate::generator::diamond_and_map;
 use crate::generator::diamonds;
 use crate::generator::path;
 use crate::generator::puzzles;
 use crate::generator::random_path;
 use crate::generator::vertexes::Vertexes;
+use crate::saver::game::SaverGame;
+use crate::solvability::{Analysis, Constraints};
+use crate::widgets::print_job::{HexkudoPrintJob, PrintJobParameters, generate_puzzle};
+
+/// Maximum time to search for the solutions of each puzzle file.
+const SOLVE_TIMEOUT: Duration = Duration::from_secs(60);
 
 /// Build random Hexkudo paths for developers.
 #[derive(Parser)]
@@ -110,13 +166,188 @@ struct Args {
     #[arg(short, long, default_value_t = false, requires = "generate")]
     summary: bool,
 
+    /// Print the generated puzzles instead of displaying the paths
+    #[arg(long, default_value_t = false, requires = "generate")]
+    print: bool,
+
+    /// Seed for the random generator. The same seed produces the same paths. A random seed is
+    /// used if not set
+    #[arg(long, requires = "generate")]
+    seed: Option<u64>,
+
+    /// Name of the printer. The default printer is used if not set
+    #[arg(long, requires = "print")]
+    printer: Option<String>,
+
+    /// Number of puzzles per page
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=20), requires = "print")]
+    per_page: u32,
+
+    /// Print the solutions after the puzzles
+    #[arg(long, default_value_t = false, requires = "print")]
+    solutions: bool,
+
+    /// Start a game with the given puzzle and difficulty level, such as classic:hard
+    #[arg(long, value_name = "PUZZLE:DIFFICULTY", value_parser = parse_play, conflicts_with_all = ["generate", "ls", "daily"])]
+    play: Option<(String, puzzles::Difficulty)>,
+
+    /// Start a game with the puzzle of the day
+    #[arg(long, default_value_t = false, conflicts_with_all = ["generate", "ls"])]
+    daily: bool,
+
+    /// Verify that the given puzzle files have a unique solution
+    #[arg(long, value_name = "FILE", num_args = 1.., conflicts_with_all = ["generate", "ls", "play", "daily"])]
+    solve: Vec<String>,
+
     /// Enable debug messages
     #[arg(short, long, default_value_t = false)]
     debug: bool,
+
+    /// Puzzle files to open
+    #[arg(value_name = "FILE")]
+    files: Vec<String>,
+}
+
+/// Game that the application starts immediately, instead of displaying the starting view.
+#[derive(Debug, Clone)]
+pub struct StartRequest {
+    /// Puzzle name.
+    pub name: String,
+
+    /// Difficulty level.
+    pub difficulty: puzzles::Difficulty,
+}
+
+/// Puzzles to print from the command line.
+#[derive(Debug, Clone)]
+pub struct PrintRequest {
+    /// Puzzle name.
+    name: String,
+
+    /// Difficulty level.
+    difficulty: puzzles::Difficulty,
+
+    /// Number of puzzles to print.
+    count: usize,
+
+    /// Number of puzzles per page.
+    per_page: u32,
+
+    /// Whether to print the solutions.
+    solutions: bool,
+
+    /// Name of the printer, or None for the default printer.
+    printer: Option<String>,
+
+    /// Seed for the first puzzle. The next puzzles use the following seeds.
+    seed: u64,
+}
+
+/// Result of the command-line processing.
+pub enum Outcome {
+    /// The options have been processed. Exit with the given code.
+    Exit(u8),
+
+    /// Run the application, and start the given game if any.
+    Run(Option<StartRequest>),
+
+    /// Print puzzles. Printing requires the application resources, which are not loaded yet
+    /// when parsing the options.
+    Print(PrintRequest),
+}
+
+/// Parse the value of the `--play` option, which has the `PUZZLE:DIFFICULTY` format.
+fn parse_play(value: &str) -> Result<(String, puzzles::Difficulty), String> {
+    let (name, difficulty) = value
+        .split_once(':')
+        .ok_or_else(|| format!("{value}: the expected format is PUZZLE:DIFFICULTY"))?;
+    let difficulty: puzzles::Difficulty = puzzles::Difficulty::from_str(difficulty, true)?;
+    Ok((name.to_string(), difficulty))
+}
+
+/// Return the puzzle of the day. The puzzle changes every day and cycles through all the puzzles
+/// and difficulty levels.
+fn daily_puzzle(
+    puzzle_hash: &HashMap<(String, puzzles::Difficulty), puzzles::Puzzle>,
+) -> Option<StartRequest> {
+    let mut keys: Vec<&(String, puzzles::Difficulty)> = puzzle_hash.keys().collect();
+    keys.sort_by_key(|(name, difficulty)| (name.clone(), *difficulty as i32));
+
+    let now: glib::DateTime = glib::DateTime::now_local().ok()?;
+    let day: i64 = (now.to_unix() + now.utc_offset().as_seconds()) / 86400;
+    let (name, difficulty) = keys.get(day.rem_euclid(keys.len() as i64) as usize)?;
+    Some(StartRequest {
+        name: name.clone(),
+        difficulty: *difficulty,
+    })
+}
+
+/// Verify that the given puzzle files have a unique solution, and print the solution and some
+/// difficulty metrics. Return 0 if all the puzzles have a unique solution.
+fn solve(files: &[String]) -> u8 {
+    let mut ret: u8 = 0;
+
+    for file in files {
+        let mut game: Game = match SaverGame::load_game_file(Path::new(file)) {
+            Ok(g) => g,
+            Err(error) => {
+                eprintln!("{file}: {error}");
+                ret = 1;
+                continue;
+            }
+        };
+        if let Err(msg) = game.puzzle.matrix.build_edges() {
+            eprintln!("{file}: {msg}");
+            ret = 1;
+            continue;
+        }
+
+        // The given cells are the mapped cells, with their values from the path
+        let path: &Vec<usize> = game.path.get();
+        let values: HashMap<usize, usize> = game
+            .map
+            .iter()
+            .filter_map(|c| path.iter().position(|p| p == c).map(|i| (*c, i + 1)))
+            .collect();
+
+        let start: Instant = Instant::now();
+        let analysis: Analysis = Constraints::from_game(&game).analyze(&values, SOLVE_TIMEOUT);
+        let duration: f64 = start.elapsed().as_secs_f64();
+
+        let verdict: &str = match (analysis.complete, analysis.solutions) {
+            (false, _) => "unknown, the search gave up",
+            (true, 0) => "no solution",
+            (true, 1) => "unique",
+            (true, _) => "several solutions",
+        };
+        if !analysis.complete || analysis.solutions != 1 {
+            ret = 1;
+        }
+
+        println!("{file}: {} {:?}", game.puzzle.name, game.puzzle.difficulty);
+        println!("  solution: {verdict}");
+        if let Some(solution) = &analysis.solution {
+            let cells: Vec<String> = solution.iter().map(|c| c.to_string()).collect();
+            println!("  path: {}", cells.join(" "));
+        }
+        println!(
+            "  cells: {}, givens: {}, diamonds: {}, anti-diamonds: {}, regions: {}",
+            game.puzzle.matrix.vertexes.num_vertexes,
+            game.map.len(),
+            game.diamonds.len(),
+            game.anti_diamonds.len(),
+            game.regions.len()
+        );
+        println!(
+            "  explored cells: {}, time: {duration:.3}s",
+            analysis.explored
+        );
+    }
+    ret
 }
 
 /// Parse and process command-line options.
-pub fn parse() -> Option<u8> {
+pub fn parse() -> Outcome {
     let args: Args = Args::parse();
 
     if args.debug {
@@ -127,8 +358,33 @@ pub fn parse() -> Option<u8> {
     }
     env_logger::init();
 
+    //
+    // Start a game with the requested puzzle
+    //
+    if let Some((name, difficulty)) = args.play {
+        return match puzzles::puzzle_map()
+            .into_keys()
+            .find(|(n, d)| n.eq_ignore_ascii_case(&name) && *d == difficulty)
+        {
+            Some((name, difficulty)) => Outcome::Run(Some(StartRequest { name, difficulty })),
+            None => {
+                eprintln!(
+                    "Unknown puzzle {name} {difficulty:?}. Use --ls to list the available puzzles."
+                );
+                Outcome::Exit(1)
+            }
+        };
+    }
+    if args.daily {
+        return Outcome::Run(daily_puzzle(&puzzles::puzzle_map()));
+    }
+
+    if !args.solve.is_empty() {
+        return Outcome::Exit(solve(&args.solve));
+    }
+
     if !args.ls && args.puzzle.is_none() {
-        return None;
+        return Outcome::Run(None);
     }
 
     let mut puzzle_hash: HashMap<(String, puzzles::Difficulty), puzzles::Puzzle> =
@@ -146,13 +402,15 @@ pub fn parse() -> Option<u8> {
                 puzzles::Difficulty::Hard => println!("{name} hard"),
             }
         }
-        return Some(0);
+        return Outcome::Exit(0);
     }
 
     //
     // Parse the definition of the requested puzzle and build its internal representation
     //
     let puzzle_name: String = args.puzzle.expect("Cannot retrieve puzzle name");
+    let seed: u64 = args.seed.unwrap_or_else(random_path::random_seed);
+    debug!("Seed {seed}");
     let mut path: random_path::RandomPath;
     let vertexes: &Vertexes;
 
@@ -162,7 +420,8 @@ pub fn parse() -> Option<u8> {
                 Ok(()) => (),
                 Err(msg) => panic!("Error: {puzzle_name}: {msg}"),
             }
-            path = random_path::RandomPath::new(&p.matrix.edges, &p.matrix.vertexes);
+            path =
+                random_path::RandomPath::new_with_seed(&p.matrix.edges, &p.matrix.vertexes, seed);
             vertexes = &p.matrix.vertexes;
         }
         None => {
@@ -170,10 +429,22 @@ pub fn parse() -> Option<u8> {
                 "Unknown puzzle {} {:?}. Use --ls to list the available puzzles.",
                 puzzle_name, args.difficulty
             );
-            return Some(1);
+            return Outcome::Exit(1);
         }
     }
 
+    if args.print {
+        return Outcome::Print(PrintRequest {
+            name: puzzle_name,
+            difficulty: args.difficulty,
+            count: args.count,
+            per_page: args.per_page,
+            solutions: args.solutions,
+            printer: args.printer,
+            seed,
+        });
+    }
+
     let mut path_list: Vec<String> = Vec::new();
     let mut map_list: Vec<String> = Vec::new();
     let mut diamond_list: Vec<String> = Vec::new();
@@ -216,8 +487,11 @@ pub fn parse() -> Option<u8> {
                 }
 
                 // Generate random diamonds and map for this path
+                let mut diamond: diamonds::Diamond =
+                    diamonds::Diamond::new(&path.edges, &random_path);
+                diamond.set_seed(seed.wrapping_add(i as u64));
                 let ret_diamonds: Result<diamond_and_map::DiamondAndMap, diamonds::DiamondError> =
-                    diamonds::Diamond::new(&path.edges, &random_path).generate_diamonds(vertexes);
+                    diamond.generate_diamonds(vertexes);
                 match ret_diamonds {
                     Ok(diamond_and_map) => {
                         map_list.push(format!("{:?}", diamond_and_map.get_map()));
@@ -251,7 +525,7 @@ pub fn parse() -> Option<u8> {
     println!(
         "
 /// Return a tuple with the path, the diamond, and the map lists.
-pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {{
+pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {{
     let sample_path: [[u8; {}]; {}] = [",
         path.num_vertexes, l
     );
@@ -277,7 +551,7 @@ pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {{
 
     println!(
         "    ];
-    let i:usize = rand::rng().random_range(0..sample_path.len());
+    let i: usize = (seed % sample_path.len() as u64) as usize;
     puzzles::PuzzleSampleGame {{
         path: Vec::from(sample_path[i]),
         diamonds: sample_diamonds[i].clone(),
@@ -294,13 +568,67 @@ pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {{
       average time = {}s
           max time = {}s
 average iterations = {}
-            errors = {}",
+            errors = {}
+              seed = {}",
             total,
             total / args.count as f32,
             max,
             iterations / args.count,
-            errors
+            errors,
+            seed
         );
     }
-    Some(0)
+    Outcome::Exit(0)
+}
+
+/// Generate and print the requested puzzles without user interaction.
+pub fn print(request: PrintRequest) -> u8 {
+    if let Err(error) = gtk::init() {
+        eprintln!("Cannot initialize GTK: {error}");
+        return 1;
+    }
+
+    let Some(mut puzzle) = puzzles::puzzle_map().remove(&(request.name, request.difficulty)) else {
+        return 1;
+    };
+    match puzzle.matrix.build_edges() {
+        Ok(()) => (),
+        Err(msg) => panic!("Error: {}: {msg}", puzzle.name),
+    }
+    puzzle.set_dark(false);
+
+    let mut paths: Vec<path::Path> = Vec::with_capacity(request.count);
+    let mut diamonds: Vec<Vec<(usize, usize)>> = Vec::with_capacity(request.count);
+    let mut maps: Vec<Vec<usize>> = Vec::with_capacity(request.count);
+    for i in 0..request.count {
+        debug!("Generating puzzle {i}");
+        let (path, diamonds_and_map) = generate_puzzle(
+            &puzzle.matrix.edges,
+            &puzzle.matrix.vertexes,
+            puzzle.get_sample_path_fn,
+            request.seed.wrapping_add(i as u64),
+        );
+        let (d, m) = diamonds_and_map.get_diamond_and_map();
+        paths.push(path);
+        diamonds.push(d);
+        maps.push(m);
+    }
+
+    let print_job: HexkudoPrintJob = HexkudoPrintJob::new(PrintJobParameters {
+        window: None,
+        puzzle,
+        paths,
+        diamonds,
+        maps,
+        n_puzzles: request.count,
+        n_puzzles_per_page: request.per_page,
+        solutions: request.solutions,
+    });
+    match print_job.print_silently(request.printer.as_deref()) {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("Error printing the puzzles: {error}");
+            1
+        }
+    }
 }
@@ -0,0 +1,100 @@
+/*
+clock.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Game clock.
+//!
+//! The clock accumulates the played time in a [`Duration`] object. While the clock is running,
+//! the time elapsed since the clock was started is added to the accumulated time.
+//! Only the total duration is saved, so that the time survives save and restore precisely. A
+//! restored clock is stopped: the caller must start it when the game continues.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::{Duration, Instant};
+
+/// Stopwatch that measures the game duration.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Clock {
+    /// Accumulated time of the previous running periods.
+    elapsed: Duration,
+
+    /// Time when the clock was last started. `None` when the clock is stopped.
+    running_since: Option<Instant>,
+}
+
+impl Clock {
+    /// Create a stopped [`Clock`] object.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the clock and clear the accumulated time.
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.running_since = None;
+    }
+
+    /// Start the clock. Do nothing if the clock is already running.
+    pub fn start(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    /// Stop the clock and accumulate the time of the current running period.
+    pub fn stop(&mut self) {
+        if let Some(t) = self.running_since.take() {
+            self.elapsed += t.elapsed();
+        }
+    }
+
+    /// Return the total measured time.
+    pub fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(t) => self.elapsed + t.elapsed(),
+            None => self.elapsed,
+        }
+    }
+}
+
+/// Serialize the clock as the total measured time.
+impl Serialize for Clock {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.elapsed().serialize(serializer)
+    }
+}
+
+/// Deserialize the clock from the total measured time. The returned clock is stopped.
+impl<'de> Deserialize<'de> for Clock {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let elapsed: Duration = Duration::deserialize(deserializer)?;
+        Ok(Self {
+            elapsed,
+            running_since: None,
+        })
+    }
+}
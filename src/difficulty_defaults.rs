@@ -0,0 +1,113 @@
+/*
+difficulty_defaults.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Settings that depend on the difficulty level.
+//!
+//! Players can override the `show-warnings`, `show-duplicates`, and zoom level settings for a
+//! difficulty level, for example to only get the warnings for the hard puzzles.
+//! The overrides are stored in the `difficulty-defaults` GSettings key, a dictionary indexed by
+//! the difficulty level nicknames. Each entry is a dictionary that only includes the overridden
+//! settings:
+//!
+//! ```text
+//! {'hard': {'show-warnings': 1, 'zoom-level': 2}}
+//! ```
+//!
+//! The boolean settings are stored as 0 or 1, and the zoom level as a [`ZoomLevel`] value.
+
+use log::debug;
+use std::collections::HashMap;
+
+use gtk::{gio, prelude::*};
+
+use crate::draw::ZoomLevel;
+use crate::generator::puzzles::Difficulty;
+
+/// GSettings key that stores the overrides.
+const SETTINGS_KEY: &str = "difficulty-defaults";
+
+/// Overrides for a difficulty level. None means that the global setting applies.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct DifficultyDefaults {
+    /// Whether to highlight the wrong values.
+    pub show_warnings: Option<bool>,
+
+    /// Whether to highlight the duplicated values.
+    pub show_duplicates: Option<bool>,
+
+    /// Zoom level for the cell numbers, instead of the zoom level for the board size.
+    pub zoom_level: Option<ZoomLevel>,
+}
+
+impl DifficultyDefaults {
+    /// Read the overrides for the given difficulty level from GSettings.
+    pub fn load(settings: &gio::Settings, difficulty: Difficulty) -> Self {
+        let all: HashMap<String, HashMap<String, i32>> =
+            settings.value(SETTINGS_KEY).get().unwrap_or_default();
+        let Some(entry) = all.get(Self::nick(difficulty)) else {
+            return Self::default();
+        };
+
+        Self {
+            show_warnings: entry.get("show-warnings").map(|v| *v != 0),
+            show_duplicates: entry.get("show-duplicates").map(|v| *v != 0),
+            zoom_level: entry
+                .get("zoom-level")
+                .and_then(|v| ZoomLevel::from_repr(*v)),
+        }
+    }
+
+    /// Store the overrides for the given difficulty level in GSettings.
+    pub fn save(&self, settings: &gio::Settings, difficulty: Difficulty) {
+        let mut all: HashMap<String, HashMap<String, i32>> =
+            settings.value(SETTINGS_KEY).get().unwrap_or_default();
+        let mut entry: HashMap<String, i32> = HashMap::new();
+
+        if let Some(v) = self.show_warnings {
+            entry.insert(String::from("show-warnings"), v as i32);
+        }
+        if let Some(v) = self.show_duplicates {
+            entry.insert(String::from("show-duplicates"), v as i32);
+        }
+        if let Some(v) = self.zoom_level {
+            entry.insert(String::from("zoom-level"), v as i32);
+        }
+
+        if entry.is_empty() {
+            all.remove(Self::nick(difficulty));
+        } else {
+            all.insert(Self::nick(difficulty).to_string(), entry);
+        }
+        if let Err(error) = settings.set_value(SETTINGS_KEY, &all.to_variant()) {
+            debug!("Cannot save the difficulty defaults: {error}");
+        }
+    }
+
+    /// Return the nickname of the difficulty level, as in the GSettings schema.
+    fn nick(difficulty: Difficulty) -> &'static str {
+        match difficulty {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+}
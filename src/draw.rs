@@ -23,17 +23,21 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! Draw puzzle components with Cairo.
 
 use log::{Level, debug, log_enabled};
+use std::collections::HashMap;
 use std::f64::consts::PI;
 use strum_macros::FromRepr;
 
 use gtk::cairo::*;
 use gtk::gdk;
 use gtk::gdk::prelude::TextureExt;
+use qrcodegen::QrCode;
 
 use crate::game::CellStatus;
 use crate::generator::path;
 use crate::generator::puzzles;
+use crate::generator::regions;
 use crate::generator::vertexes;
+use crate::number_words;
 use crate::player_input::PlayerInput;
 
 const SQRT_3: f64 = 1.732_050_807_568_877_2_f64;
@@ -43,6 +47,15 @@ const TWO_DIV_SQRT_3: f64 = 1.154_700_538_379_251_7_f64;
 // When applying the surfaces in the DrawingArea object, the surfaces are scaled.
 const SURFACE_SIZE: f64 = 1040.0;
 
+// Size of the thumbnail that is displayed, blurred, while the game is paused.
+const PAUSE_THUMBNAIL_SIZE: i32 = 24;
+
+// Number of notes (pencil marks) per row in a cell. The cell displays three rows at most.
+const NOTES_PER_ROW: usize = 3;
+
+// Width, in modules, of the blank margin that the QR code scanners need around the code.
+const QR_QUIET_ZONE: i32 = 4;
+
 /// Zoom level for the cell numbers.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr, Default, glib::Enum)]
 #[repr(i32)]
@@ -52,6 +65,8 @@ pub enum ZoomLevel {
     #[default]
     Medium,
     Large,
+    ExtraLarge,
+    Auto,
 }
 
 impl ZoomLevel {
@@ -65,7 +80,7 @@ impl ZoomLevel {
 
     /// Whether the zoom level is at its largest.
     pub fn is_fully_zoomed_in(self) -> bool {
-        if self == ZoomLevel::Large {
+        if self == ZoomLevel::ExtraLarge {
             return true;
         }
         false
@@ -74,8 +89,9 @@ impl ZoomLevel {
     /// Zoom out and return the new zoom level.
     pub fn zoom_out(self) -> Self {
         match self {
-            ZoomLevel::Small | ZoomLevel::Medium => ZoomLevel::Small,
+            ZoomLevel::Small | ZoomLevel::Medium | ZoomLevel::Auto => ZoomLevel::Small,
             ZoomLevel::Large => ZoomLevel::Medium,
+            ZoomLevel::ExtraLarge => ZoomLevel::Large,
         }
     }
 
@@ -83,7 +99,172 @@ impl ZoomLevel {
     pub fn zoom_in(self) -> Self {
         match self {
             ZoomLevel::Small => ZoomLevel::Medium,
-            ZoomLevel::Medium | ZoomLevel::Large => ZoomLevel::Large,
+            ZoomLevel::Medium | ZoomLevel::Auto => ZoomLevel::Large,
+            ZoomLevel::Large | ZoomLevel::ExtraLarge => ZoomLevel::ExtraLarge,
+        }
+    }
+
+    /// Return the zoom level to use for drawing.
+    ///
+    /// The automatic zoom level selects the size of the numbers from the size of the cells on the
+    /// screen, in pixels. This way, maximizing the window gives bigger numbers.
+    /// The other zoom levels are returned unchanged.
+    pub fn resolve(self, cell_size: f64) -> Self {
+        if self != ZoomLevel::Auto {
+            return self;
+        }
+        if cell_size < 36.0 {
+            ZoomLevel::Medium
+        } else if cell_size < 60.0 {
+            ZoomLevel::Large
+        } else {
+            ZoomLevel::ExtraLarge
+        }
+    }
+}
+
+/// Smallest magnification of the board. At this magnification, the board fits the widget.
+pub const MIN_MAGNIFICATION: f64 = 1.0;
+
+/// Largest magnification of the board. The surfaces have a fixed size, and become blurry when
+/// they are magnified further.
+pub const MAX_MAGNIFICATION: f64 = 3.0;
+
+/// Magnification and position of the board in the drawing area.
+///
+/// The board coordinates are the widget coordinates of the board when it fits the widget. The
+/// viewport displays the board at `board * magnification + offset`. The offset is always
+/// negative or zero, so that the magnified board covers the whole widget.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Viewport {
+    pub magnification: f64,
+    pub offset_x: f64,
+    pub offset_y: f64,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            magnification: MIN_MAGNIFICATION,
+            offset_x: 0.0,
+            offset_y: 0.0,
+        }
+    }
+}
+
+impl Viewport {
+    /// Whether the board is magnified.
+    pub fn is_magnified(&self) -> bool {
+        self.magnification > MIN_MAGNIFICATION
+    }
+
+    /// Convert widget coordinates into board coordinates.
+    pub fn to_board(self, x: f64, y: f64) -> (f64, f64) {
+        (
+            (x - self.offset_x) / self.magnification,
+            (y - self.offset_y) / self.magnification,
+        )
+    }
+
+    /// Convert board coordinates into widget coordinates.
+    pub fn to_widget(self, x: f64, y: f64) -> (f64, f64) {
+        (
+            x * self.magnification + self.offset_x,
+            y * self.magnification + self.offset_y,
+        )
+    }
+
+    /// Multiply the magnification by the given factor. The point at the given widget
+    /// coordinates stays in place.
+    pub fn zoom_at(&mut self, factor: f64, x: f64, y: f64, width: f64, height: f64) {
+        let (board_x, board_y) = self.to_board(x, y);
+
+        self.magnification =
+            (self.magnification * factor).clamp(MIN_MAGNIFICATION, MAX_MAGNIFICATION);
+        self.offset_x = x - board_x * self.magnification;
+        self.offset_y = y - board_y * self.magnification;
+        self.clamp(width, height);
+    }
+
+    /// Move the magnified board by the given distance, in widget coordinates.
+    pub fn pan(&mut self, dx: f64, dy: f64, width: f64, height: f64) {
+        self.offset_x += dx;
+        self.offset_y += dy;
+        self.clamp(width, height);
+    }
+
+    /// Move the magnified board so that the given rectangle, in board coordinates, is visible
+    /// with the given margin around it.
+    pub fn ensure_visible(
+        &mut self,
+        rectangle: (f64, f64, f64, f64),
+        margin: f64,
+        width: f64,
+        height: f64,
+    ) {
+        let (x, y, w, h) = rectangle;
+        let (left, top) = self.to_widget(x, y);
+        let right: f64 = left + w * self.magnification;
+        let bottom: f64 = top + h * self.magnification;
+
+        if left < margin {
+            self.offset_x += margin - left;
+        } else if right > width - margin {
+            self.offset_x -= right - (width - margin);
+        }
+        if top < margin {
+            self.offset_y += margin - top;
+        } else if bottom > height - margin {
+            self.offset_y -= bottom - (height - margin);
+        }
+        self.clamp(width, height);
+    }
+
+    /// Keep the magnified board over the whole widget, for example after the widget is resized.
+    pub fn clamp(&mut self, width: f64, height: f64) {
+        self.offset_x = self.offset_x.clamp(width * (1.0 - self.magnification), 0.0);
+        self.offset_y = self
+            .offset_y
+            .clamp(height * (1.0 - self.magnification), 0.0);
+    }
+}
+
+/// Debug overlays. Each flag hides one of the layers that compose the puzzle, or draws the
+/// cell bounding boxes. The flags can be changed from the GTK inspector or with the
+/// `game-view.debug-layer` action.
+#[glib::flags(name = "HexkudoDebugLayers")]
+pub enum DebugLayers {
+    #[flags_value(name = "Hide the background", nick = "background")]
+    HIDE_BACKGROUND = 1 << 0,
+    #[flags_value(name = "Hide the selected cell", nick = "selection")]
+    HIDE_SELECTION = 1 << 1,
+    #[flags_value(name = "Hide the borders and the diamonds", nick = "borders")]
+    HIDE_BORDERS = 1 << 2,
+    #[flags_value(name = "Hide the numbers", nick = "numbers")]
+    HIDE_NUMBERS = 1 << 3,
+    #[flags_value(name = "Hide the path", nick = "path")]
+    HIDE_PATH = 1 << 4,
+    #[flags_value(name = "Draw the cell bounding boxes", nick = "bounding-boxes")]
+    BOUNDING_BOXES = 1 << 5,
+}
+
+impl Default for DebugLayers {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl DebugLayers {
+    /// Return the flag that matches the given nick.
+    pub fn from_nick(nick: &str) -> Option<Self> {
+        match nick {
+            "background" => Some(Self::HIDE_BACKGROUND),
+            "selection" => Some(Self::HIDE_SELECTION),
+            "borders" => Some(Self::HIDE_BORDERS),
+            "numbers" => Some(Self::HIDE_NUMBERS),
+            "path" => Some(Self::HIDE_PATH),
+            "bounding-boxes" => Some(Self::BOUNDING_BOXES),
+            _ => None,
         }
     }
 }
@@ -141,6 +322,9 @@ pub struct Draw {
 
     /// List of cells with their coordinates.
     cells: Vec<DrawCell>,
+
+    /// Whether to also write the numbers in words (learning mode, for easy puzzles only).
+    number_words: bool,
 }
 
 impl Default for Draw {
@@ -161,6 +345,7 @@ impl Default for Draw {
             logo_height: 0.0,
             logo_scaling_factor: 0.0,
             cells: Vec::new(),
+            number_words: false,
         }
     }
 }
@@ -254,6 +439,7 @@ impl Draw {
             logo_height,
             logo_scaling_factor,
             cells: Vec::with_capacity(puzzle.matrix.vertexes.num_vertexes),
+            number_words: false,
         }
     }
 
@@ -282,6 +468,36 @@ impl Draw {
         SURFACE_SIZE
     }
 
+    /// Draw a heavily downscaled copy of the puzzle on a Cairo surface that is returned.
+    ///
+    /// The thumbnail is built from the cached background and border surfaces. When the
+    /// thumbnail is scaled back to the size of the window, the interpolation blurs the puzzle so
+    /// that the numbers cannot be read.
+    pub fn pause_thumbnail(&self) -> Result<ImageSurface> {
+        let surface: ImageSurface =
+            ImageSurface::create(Format::ARgb32, PAUSE_THUMBNAIL_SIZE, PAUSE_THUMBNAIL_SIZE)?;
+        let ctx: Context = Context::new(&surface)?;
+        let scale: f64 = PAUSE_THUMBNAIL_SIZE as f64 / SURFACE_SIZE;
+
+        ctx.scale(scale, scale);
+        ctx.set_source_surface(&self.background_surface, 0.0, 0.0)?;
+        ctx.paint()?;
+        ctx.set_source_surface(&self.border_surface, 0.0, 0.0)?;
+        ctx.paint()?;
+        drop(ctx);
+        Ok(surface)
+    }
+
+    /// Return the size of a cell in the surface.
+    pub fn cell_size(&self) -> f64 {
+        self.scaling_factor
+    }
+
+    /// Enable or disable the learning mode, which also writes the numbers in words.
+    pub fn set_number_words(&mut self, number_words: bool) {
+        self.number_words = number_words;
+    }
+
     /// Set the color scheme.
     pub fn set_dark(&mut self, is_dark: bool) {
         self.puzzle.set_dark(is_dark);
@@ -386,8 +602,27 @@ impl Draw {
         ctx.line_to(half_width, 0.0);
     }
 
+    /// Draw an anti-diamond template, which is a cross.
+    fn draw_anti_diamond_border(&self, ctx: &Context) {
+        let half_size: f64 = self.scaling_factor * 0.15;
+
+        ctx.move_to(-half_size, -half_size);
+        ctx.line_to(half_size, half_size);
+        ctx.move_to(-half_size, half_size);
+        ctx.line_to(half_size, -half_size);
+    }
+
+    /// Draw a diamond or an anti-diamond template.
+    fn draw_marker_border(&self, ctx: &Context, anti: bool) {
+        if anti {
+            self.draw_anti_diamond_border(ctx);
+        } else {
+            self.draw_diamond_border(ctx);
+        }
+    }
+
     /// Draw the diamond at the left of the given cell coordinates (west).
-    fn draw_diamond_w(&self, x: usize, y: usize, ctx: &Context) -> Result<()> {
+    fn draw_diamond_w(&self, x: usize, y: usize, ctx: &Context, anti: bool) -> Result<()> {
         debug!("Draw diamond west:");
         let (_x, s_y) = self.cell_to_surface_coordinates(x, y);
 
@@ -396,12 +631,12 @@ impl Draw {
             x as f64 * self.scaling_factor + self.margin + self.offset_x,
             s_y,
         );
-        self.draw_diamond_border(ctx);
+        self.draw_marker_border(ctx, anti);
         ctx.restore()
     }
 
     /// Draw the diamond at the top left of the given cell coordinates (north-west).
-    fn draw_diamond_nw(&self, x: usize, y: usize, ctx: &Context) -> Result<()> {
+    fn draw_diamond_nw(&self, x: usize, y: usize, ctx: &Context, anti: bool) -> Result<()> {
         debug!("Draw diamond north-west for cell ({x}, {y})");
         ctx.save()?;
         ctx.translate(
@@ -412,12 +647,12 @@ impl Draw {
                 + self.offset_y,
         );
         ctx.rotate(60.0_f64 * PI / 180.0);
-        self.draw_diamond_border(ctx);
+        self.draw_marker_border(ctx, anti);
         ctx.restore()
     }
 
     /// Draw the diamond at the bottom left of the given cell coordinates (south-west).
-    fn draw_diamond_sw(&self, x: usize, y: usize, ctx: &Context) -> Result<()> {
+    fn draw_diamond_sw(&self, x: usize, y: usize, ctx: &Context, anti: bool) -> Result<()> {
         debug!("Draw diamond south-west for cell ({x}, {y})");
         ctx.save()?;
         ctx.translate(
@@ -428,7 +663,7 @@ impl Draw {
                 + self.offset_y,
         );
         ctx.rotate(-60.0_f64 * PI / 180.0);
-        self.draw_diamond_border(ctx);
+        self.draw_marker_border(ctx, anti);
         ctx.restore()
     }
 
@@ -445,10 +680,20 @@ impl Draw {
         let (s_x, s_y) = self.cell_to_surface_coordinates(x, y);
         let text: String = format!("{number}");
 
+        // In learning mode, the digits are moved up to leave room for the words
+        let learning: bool =
+            self.number_words && self.puzzle.difficulty == puzzles::Difficulty::Easy;
+        let s_y: f64 = if learning {
+            s_y - 0.2 * self.scaling_factor
+        } else {
+            s_y
+        };
+
         ctx.save()?;
         match zoom_level {
+            ZoomLevel::ExtraLarge => ctx.set_font_size(1.3 * self.scaling_factor),
             ZoomLevel::Large => ctx.set_font_size(1.0 * self.scaling_factor),
-            ZoomLevel::Medium => ctx.set_font_size(0.8 * self.scaling_factor),
+            ZoomLevel::Medium | ZoomLevel::Auto => ctx.set_font_size(0.8 * self.scaling_factor),
             ZoomLevel::Small => ctx.set_font_size(0.6 * self.scaling_factor),
         }
 
@@ -462,6 +707,40 @@ impl Draw {
             s_y + text_height / 2.0 - font_extends.descent(),
         );
         ctx.show_text(&text)?;
+
+        if learning {
+            self.draw_cell_number_words(number, s_x, s_y + text_height / 2.0, ctx)?;
+        }
+        ctx.restore()
+    }
+
+    /// Write the number in words, centered horizontally and below the given surface coordinates.
+    ///
+    /// The font is reduced for long words, so that the text stays inside the cell.
+    fn draw_cell_number_words(
+        &self,
+        number: usize,
+        s_x: f64,
+        s_y: f64,
+        ctx: &Context,
+    ) -> Result<()> {
+        let words: String = number_words::number_to_words(number);
+        let max_width: f64 = 1.6 * self.scaling_factor;
+
+        ctx.save()?;
+        ctx.set_font_size(0.35 * self.scaling_factor);
+        let text_extends: TextExtents = ctx.text_extents(&words)?;
+        if text_extends.x_advance() > max_width {
+            ctx.set_font_size(0.35 * self.scaling_factor * max_width / text_extends.x_advance());
+        }
+        let font_extends: FontExtents = ctx.font_extents()?;
+        let text_extends: TextExtents = ctx.text_extents(&words)?;
+
+        ctx.move_to(
+            s_x - text_extends.x_advance() / 2.0,
+            s_y + font_extends.ascent(),
+        );
+        ctx.show_text(&words)?;
         ctx.restore()
     }
 
@@ -554,6 +833,8 @@ impl Draw {
         path: &path::Path,
         map: &Vec<usize>,
         diamonds: &Vec<(usize, usize)>,
+        anti_diamonds: &Vec<(usize, usize)>,
+        regions: &Vec<regions::Region>,
     ) -> Result<()> {
         // Surface and context where the map and diamonds are drawn
         let background_puzzle_ctx: Context = Context::new(&self.background_surface)?;
@@ -598,29 +879,125 @@ impl Draw {
         // Draw the diamonds
         border_puzzle_ctx.set_source_rgba(fg_diamond_r, fg_diamond_g, fg_diamond_b, fg_diamond_a);
         for (v1, v2) in diamonds {
-            // let (v1, v2) = d;
-            if let Some((x1, y1)) = self.puzzle.matrix.vertexes.get_coordinates(*v1)
-                && let Some((x2, y2)) = self.puzzle.matrix.vertexes.get_coordinates(*v2)
-            {
-                if x2 < x1 {
-                    if y2 == y1 {
-                        self.draw_diamond_w(x1, y1, &border_puzzle_ctx)?;
-                    } else if y2 < y1 {
-                        self.draw_diamond_nw(x1, y1, &border_puzzle_ctx)?;
-                    } else {
-                        self.draw_diamond_sw(x1, y1, &border_puzzle_ctx)?;
+            self.draw_edge_marker(*v1, *v2, &border_puzzle_ctx, false)?;
+        }
+        border_puzzle_ctx.fill()?;
+
+        // Draw the anti-diamonds
+        border_puzzle_ctx.set_line_width(0.06 * self.scaling_factor);
+        for (v1, v2) in anti_diamonds {
+            self.draw_edge_marker(*v1, *v2, &border_puzzle_ctx, true)?;
+        }
+        border_puzzle_ctx.stroke()?;
+
+        self.draw_regions(regions, &border_puzzle_ctx)
+    }
+
+    /// Draw the outlines of the regions and their sums.
+    ///
+    /// The outline of a region is a dashed line inside the cells, along the borders that are not
+    /// shared with another cell of the same region. The sum is displayed in the top left corner of
+    /// the upper cell of the region.
+    fn draw_regions(&self, regions: &Vec<regions::Region>, ctx: &Context) -> Result<()> {
+        if regions.is_empty() {
+            return Ok(());
+        }
+        let vertexes: &vertexes::Vertexes = &self.puzzle.matrix.vertexes;
+        let (fg_border_r, fg_border_g, fg_border_b, fg_border_a) = self.puzzle.colors.get_border();
+        let (fg_number_r, fg_number_g, fg_number_b, fg_number_a) = self.puzzle.colors.get_text();
+
+        // Size of the outline, relative to the cell size
+        let inset: f64 = 0.8;
+        let pt_top: f64 = self.scaling_factor * TWO_DIV_SQRT_3 * inset;
+        let pt_mid: f64 = self.scaling_factor / SQRT_3 * inset;
+        let pt_side: f64 = self.scaling_factor * inset;
+
+        ctx.save()?;
+        ctx.set_source_rgba(fg_border_r, fg_border_g, fg_border_b, fg_border_a);
+        ctx.set_line_width(0.04 * self.scaling_factor);
+        ctx.set_dash(
+            &[0.12 * self.scaling_factor, 0.08 * self.scaling_factor],
+            0.0,
+        );
+        for region in regions {
+            for cell_id in &region.cells {
+                let Some((x, y)) = vertexes.get_coordinates(*cell_id) else {
+                    continue;
+                };
+                let (s_x, s_y) = self.cell_to_surface_coordinates(x, y);
+                let adjacent: vertexes::Adjacent = vertexes.get_adjacent(*cell_id);
+
+                // Corners of the outline, clockwise from the top corner
+                let top: (f64, f64) = (s_x, s_y - pt_top);
+                let upper_right: (f64, f64) = (s_x + pt_side, s_y - pt_mid);
+                let lower_right: (f64, f64) = (s_x + pt_side, s_y + pt_mid);
+                let bottom: (f64, f64) = (s_x, s_y + pt_top);
+                let lower_left: (f64, f64) = (s_x - pt_side, s_y + pt_mid);
+                let upper_left: (f64, f64) = (s_x - pt_side, s_y - pt_mid);
+
+                for (neighbor, (x1, y1), (x2, y2)) in [
+                    (adjacent.ne, top, upper_right),
+                    (adjacent.e, upper_right, lower_right),
+                    (adjacent.se, lower_right, bottom),
+                    (adjacent.sw, bottom, lower_left),
+                    (adjacent.w, lower_left, upper_left),
+                    (adjacent.nw, upper_left, top),
+                ] {
+                    let same_region: bool = matches!(
+                        neighbor,
+                        Some(vertexes::CellType::Vertex(v)) if region.cells.contains(&v)
+                    );
+                    if !same_region {
+                        ctx.move_to(x1, y1);
+                        ctx.line_to(x2, y2);
                     }
-                } else if y2 == y1 {
-                    self.draw_diamond_w(x2, y2, &border_puzzle_ctx)?;
+                }
+            }
+        }
+        ctx.stroke()?;
+
+        // Draw the sums
+        ctx.set_source_rgba(fg_number_r, fg_number_g, fg_number_b, fg_number_a);
+        ctx.set_font_size(0.3 * self.scaling_factor);
+        for region in regions {
+            if let Some((x, y)) = region
+                .cells
+                .iter()
+                .filter_map(|c| vertexes.get_coordinates(*c))
+                .min_by_key(|(x, y)| (*y, *x))
+            {
+                let (s_x, s_y) = self.cell_to_surface_coordinates(x, y);
+                ctx.move_to(
+                    s_x - 0.45 * self.scaling_factor,
+                    s_y - 0.2 * self.scaling_factor,
+                );
+                ctx.show_text(&format!("{}", region.sum))?;
+            }
+        }
+        ctx.restore()
+    }
+
+    /// Draw a diamond, or an anti-diamond, on the edge between the two given cells.
+    fn draw_edge_marker(&self, v1: usize, v2: usize, ctx: &Context, anti: bool) -> Result<()> {
+        if let Some((x1, y1)) = self.puzzle.matrix.vertexes.get_coordinates(v1)
+            && let Some((x2, y2)) = self.puzzle.matrix.vertexes.get_coordinates(v2)
+        {
+            if x2 < x1 {
+                if y2 == y1 {
+                    self.draw_diamond_w(x1, y1, ctx, anti)?;
                 } else if y2 < y1 {
-                    self.draw_diamond_sw(x2, y2, &border_puzzle_ctx)?;
+                    self.draw_diamond_nw(x1, y1, ctx, anti)?;
                 } else {
-                    self.draw_diamond_nw(x2, y2, &border_puzzle_ctx)?;
+                    self.draw_diamond_sw(x1, y1, ctx, anti)?;
                 }
+            } else if y2 == y1 {
+                self.draw_diamond_w(x2, y2, ctx, anti)?;
+            } else if y2 < y1 {
+                self.draw_diamond_sw(x2, y2, ctx, anti)?;
+            } else {
+                self.draw_diamond_nw(x2, y2, ctx, anti)?;
             }
         }
-        border_puzzle_ctx.fill()?;
-
         Ok(())
     }
 
@@ -699,6 +1076,144 @@ impl Draw {
         Ok(number_ctx.target())
     }
 
+    /// Draw the notes (pencil marks) of the empty cells on a Cairo surface that is returned.
+    ///
+    /// The notes are small numbers in a grid of three rows of [`NOTES_PER_ROW`] numbers. When
+    /// a cell has more notes than the grid can display, the last position shows an ellipsis.
+    pub fn user_cell_notes(&self, notes: &HashMap<usize, Vec<usize>>) -> Result<Surface> {
+        // Surface and context where the notes are drawn
+        let notes_surface: ImageSurface =
+            ImageSurface::create(Format::ARgb32, SURFACE_SIZE as i32, SURFACE_SIZE as i32)?;
+        let notes_ctx: Context = Context::new(notes_surface)?;
+        let (fg_r, fg_g, fg_b, fg_a) = self.puzzle.colors.get_text();
+        let max_notes: usize = NOTES_PER_ROW * 3;
+        let step: f64 = 0.5 * self.scaling_factor;
+
+        notes_ctx.set_source_rgba(fg_r, fg_g, fg_b, fg_a * 0.7);
+        notes_ctx.set_font_size(0.3 * self.scaling_factor);
+        let font_extends: FontExtents = notes_ctx.font_extents()?;
+
+        for (cell_id, values) in notes {
+            let Some((x, y)) = self.puzzle.matrix.vertexes.get_coordinates(*cell_id) else {
+                continue;
+            };
+            debug!("Draw cell notes (cell ID = {cell_id}, notes = {values:?})");
+            let (s_x, s_y) = self.cell_to_surface_coordinates(x, y);
+
+            for (i, value) in values.iter().take(max_notes).enumerate() {
+                let text: String = if i == max_notes - 1 && values.len() > max_notes {
+                    "…".to_string()
+                } else {
+                    format!("{value}")
+                };
+                let text_extends: TextExtents = notes_ctx.text_extents(&text)?;
+                let n_x: f64 = s_x + ((i % NOTES_PER_ROW) as f64 - 1.0) * step;
+                let n_y: f64 = s_y + ((i / NOTES_PER_ROW) as f64 - 1.0) * step;
+
+                notes_ctx.move_to(
+                    n_x - text_extends.x_advance() / 2.0,
+                    n_y + font_extends.ascent() / 2.0,
+                );
+                notes_ctx.show_text(&text)?;
+            }
+        }
+
+        Ok(notes_ctx.target())
+    }
+
+    /// Draw the whole puzzle with the given cell values on a square Cairo surface of the given
+    /// size.
+    ///
+    /// The function is used to export the puzzle as images, and therefore it does not draw the
+    /// selected cell.
+    pub fn render_frame(
+        &self,
+        cells: Vec<CellStatus>,
+        player_input: &PlayerInput,
+        draw_path: bool,
+        zoom_level: ZoomLevel,
+        size: i32,
+    ) -> Result<ImageSurface> {
+        let surface: ImageSurface = ImageSurface::create(Format::ARgb32, size, size)?;
+        let ctx: Context = Context::new(&surface)?;
+        let scale: f64 = size as f64 / SURFACE_SIZE;
+
+        // Exported images must not be transparent
+        ctx.set_source_rgb(1.0, 1.0, 1.0);
+        ctx.paint()?;
+
+        ctx.scale(scale, scale);
+        ctx.set_source_surface(&self.background_surface, 0.0, 0.0)?;
+        ctx.paint()?;
+        ctx.set_source_surface(&self.border_surface, 0.0, 0.0)?;
+        ctx.paint()?;
+        ctx.set_source_surface(
+            self.user_cell_numbers(cells, false, false, zoom_level)?,
+            0.0,
+            0.0,
+        )?;
+        ctx.paint()?;
+        if draw_path {
+            ctx.set_source_surface(self.path_from_player_input(player_input)?, 0.0, 0.0)?;
+            ctx.paint()?;
+        }
+        drop(ctx);
+        surface.flush();
+        Ok(surface)
+    }
+
+    /// Convert a surface that [`Draw::render_frame`] returned into a texture, which can be
+    /// displayed in a [`gtk::Picture`] or saved as a PNG file.
+    pub fn to_texture(
+        surface: ImageSurface,
+    ) -> std::result::Result<gdk::MemoryTexture, BorrowError> {
+        let width: i32 = surface.width();
+        let height: i32 = surface.height();
+        let stride: usize = surface.stride() as usize;
+        let data: Vec<u8> = surface.take_data()?.to_vec();
+
+        Ok(gdk::MemoryTexture::new(
+            width,
+            height,
+            gdk::MemoryFormat::B8g8r8a8Premultiplied,
+            &glib::Bytes::from_owned(data),
+            stride,
+        ))
+    }
+
+    /// Draw the given QR code on a Cairo surface of the given size that is returned.
+    ///
+    /// The modules are black on a white background, whatever the color theme, so that the
+    /// scanners, and [`qr_decode::decode`](crate::qr_decode::decode), can read the code.
+    pub fn qr_code(qr: &QrCode, size: i32) -> Result<ImageSurface> {
+        let surface: ImageSurface = ImageSurface::create(Format::ARgb32, size, size)?;
+        let ctx: Context = Context::new(&surface)?;
+        let scale: f64 = size as f64 / (qr.size() + 2 * QR_QUIET_ZONE) as f64;
+
+        ctx.set_source_rgb(1.0, 1.0, 1.0);
+        ctx.paint()?;
+
+        // Without antialiasing, the adjacent modules do not show thin seams
+        ctx.set_antialias(Antialias::None);
+        ctx.scale(scale, scale);
+        ctx.set_source_rgb(0.0, 0.0, 0.0);
+        for y in 0..qr.size() {
+            for x in 0..qr.size() {
+                if qr.get_module(x, y) {
+                    ctx.rectangle(
+                        (x + QR_QUIET_ZONE) as f64,
+                        (y + QR_QUIET_ZONE) as f64,
+                        1.0,
+                        1.0,
+                    );
+                }
+            }
+        }
+        ctx.fill()?;
+        drop(ctx);
+        Ok(surface)
+    }
+
     /// Draw the selected cell on a Cairo surface that is returned.
     pub fn selected_cell(&self, selected_cell: Option<usize>, thick: bool) -> Result<Surface> {
         // Surface and context where the selected cell is drawn
@@ -733,8 +1248,111 @@ impl Draw {
         Ok(ctx.target())
     }
 
+    /// Draw the background of the given cells on a Cairo surface that is returned. The guided
+    /// reveal uses the surface to highlight an area of the board.
+    pub fn highlighted_cells(&self, cells: &[usize]) -> Result<Surface> {
+        let surface: ImageSurface =
+            ImageSurface::create(Format::ARgb32, SURFACE_SIZE as i32, SURFACE_SIZE as i32)?;
+        let ctx: Context = Context::new(surface)?;
+        let (sel_r, sel_g, sel_b, _) = self.puzzle.colors.get_selection();
+
+        ctx.set_source_rgba(sel_r, sel_g, sel_b, 0.4);
+        for cell_id in cells {
+            if let Some((x, y)) = self.puzzle.matrix.vertexes.get_coordinates(*cell_id) {
+                self.draw_cell(x, y, &ctx)?;
+                ctx.fill()?;
+            }
+        }
+        Ok(ctx.target())
+    }
+
+    /// Draw the background of the given cells with the selection color on a Cairo surface that
+    /// is returned. The drawing area animates the surface, with `progress` going from 0 to 1, to
+    /// flash twice the cells where a missing value could go.
+    pub fn flash_cells(&self, cells: &[usize], progress: f64) -> Result<Surface> {
+        let surface: ImageSurface =
+            ImageSurface::create(Format::ARgb32, SURFACE_SIZE as i32, SURFACE_SIZE as i32)?;
+        let ctx: Context = Context::new(surface)?;
+        let (sel_r, sel_g, sel_b, _) = self.puzzle.colors.get_selection();
+        let pulse: f64 = 0.5 - 0.5 * (4.0 * PI * progress.clamp(0.0, 1.0)).cos();
+
+        ctx.set_source_rgba(sel_r, sel_g, sel_b, 0.6 * pulse);
+        for cell_id in cells {
+            if let Some((x, y)) = self.puzzle.matrix.vertexes.get_coordinates(*cell_id) {
+                self.draw_cell(x, y, &ctx)?;
+                ctx.fill()?;
+            }
+        }
+        Ok(ctx.target())
+    }
+
+    /// Draw the background of the given cells with a color from green to red, depending on their
+    /// heat, between 0 and 1, on a Cairo surface that is returned. The post-game heat map uses
+    /// the surface to show the cells where the player struggled.
+    pub fn heat_map(&self, heat: &HashMap<usize, f64>) -> Result<Surface> {
+        let surface: ImageSurface =
+            ImageSurface::create(Format::ARgb32, SURFACE_SIZE as i32, SURFACE_SIZE as i32)?;
+        let ctx: Context = Context::new(surface)?;
+
+        for (cell_id, h) in heat {
+            if let Some((x, y)) = self.puzzle.matrix.vertexes.get_coordinates(*cell_id) {
+                // Green, then yellow, and then red
+                let h: f64 = h.clamp(0.0, 1.0);
+                let red: f64 = (2.0 * h).min(1.0);
+                let green: f64 = (2.0 * (1.0 - h)).min(1.0);
+                ctx.set_source_rgba(0.9 * red, 0.75 * green, 0.1, 0.5);
+                self.draw_cell(x, y, &ctx)?;
+                ctx.fill()?;
+            }
+        }
+        Ok(ctx.target())
+    }
+
+    /// Draw the background of the given cell with the color of the wrong values on a Cairo
+    /// surface that is returned. The drawing area animates the surface, with `progress` going
+    /// from 0 to 1, to shake and flash the cell where the player entered a wrong value.
+    pub fn wrong_entry(&self, cell_id: usize, progress: f64) -> Result<Surface> {
+        let surface: ImageSurface =
+            ImageSurface::create(Format::ARgb32, SURFACE_SIZE as i32, SURFACE_SIZE as i32)?;
+        let ctx: Context = Context::new(surface)?;
+        let (wrong_r, wrong_g, wrong_b, _) = self.puzzle.colors.get_text_wrong();
+        let fade: f64 = 1.0 - progress.clamp(0.0, 1.0);
+
+        if let Some((x, y)) = self.puzzle.matrix.vertexes.get_coordinates(cell_id) {
+            // Three damped oscillations from side to side
+            let shift: f64 = 0.1 * self.scaling_factor * (6.0 * PI * progress).sin() * fade;
+            ctx.translate(shift, 0.0);
+            ctx.set_source_rgba(wrong_r, wrong_g, wrong_b, 0.5 * fade);
+            self.draw_cell(x, y, &ctx)?;
+            ctx.fill()?;
+        }
+        Ok(ctx.target())
+    }
+
+    /// Draw a cross with the color of the wrong values on the edges between the given pairs of
+    /// cells, on a Cairo surface that is returned. In the strict diamonds variant, the crosses
+    /// show the adjacent cells that hold consecutive values without a diamond between them.
+    pub fn strict_conflicts(&self, conflicts: &[(usize, usize)]) -> Result<Surface> {
+        let surface: ImageSurface =
+            ImageSurface::create(Format::ARgb32, SURFACE_SIZE as i32, SURFACE_SIZE as i32)?;
+        let ctx: Context = Context::new(surface)?;
+        let (wrong_r, wrong_g, wrong_b, wrong_a) = self.puzzle.colors.get_text_wrong();
+
+        ctx.set_source_rgba(wrong_r, wrong_g, wrong_b, wrong_a);
+        ctx.set_line_width(0.08 * self.scaling_factor);
+        ctx.set_line_cap(LineCap::Round);
+        for (v1, v2) in conflicts {
+            self.draw_edge_marker(*v1, *v2, &ctx, true)?;
+        }
+        ctx.stroke()?;
+        Ok(ctx.target())
+    }
+
     /// Draw a line over the path to show the solution on a Cairo surface that is returned.
-    pub fn path(&self, path: &path::Path) -> Result<Surface> {
+    ///
+    /// The `progress` parameter, between 0 and 1, is the portion of the path to draw from the
+    /// start cell. The completion animation increases it to trace the path over time.
+    pub fn path(&self, path: &path::Path, progress: f64) -> Result<Surface> {
         // Surface and context where the path line is drawn
         let path_surface: ImageSurface =
             ImageSurface::create(Format::ARgb32, SURFACE_SIZE as i32, SURFACE_SIZE as i32)?;
@@ -746,8 +1364,10 @@ impl Draw {
         path_ctx.set_line_cap(LineCap::Round);
         path_ctx.set_line_join(LineJoin::Round);
 
-        let mut start: bool = true;
-        for v in path.get() {
+        // Number of segments to draw, the last one possibly partially
+        let length: f64 = progress.clamp(0.0, 1.0) * path.get().len().saturating_sub(1) as f64;
+        let mut previous: Option<(f64, f64)> = None;
+        for (i, v) in path.get().iter().enumerate() {
             let (x, y) = self
                 .puzzle
                 .matrix
@@ -756,12 +1376,18 @@ impl Draw {
                 .expect("Cannot retrieve the cell coordinates 4");
             let (s_x, s_y) = self.cell_to_surface_coordinates(x, y);
 
-            if start {
-                path_ctx.move_to(s_x, s_y);
-                start = false;
-            } else {
-                path_ctx.line_to(s_x, s_y);
+            match previous {
+                None => path_ctx.move_to(s_x, s_y),
+                Some((p_x, p_y)) => {
+                    let remaining: f64 = length - (i - 1) as f64;
+                    if remaining <= 0.0 {
+                        break;
+                    }
+                    let t: f64 = remaining.min(1.0);
+                    path_ctx.line_to(p_x + (s_x - p_x) * t, p_y + (s_y - p_y) * t);
+                }
             }
+            previous = Some((s_x, s_y));
         }
         path_ctx.stroke()?;
         Ok(path_ctx.target())
@@ -829,6 +1455,98 @@ impl Draw {
         Ok(path_ctx.target())
     }
 
+    /// Draw arrows from the selected cell toward the cells that the movement keys reach.
+    pub fn movement_arrows(&self, cell_id: usize, targets: &[usize]) -> Result<Surface> {
+        let arrows_surface: ImageSurface =
+            ImageSurface::create(Format::ARgb32, SURFACE_SIZE as i32, SURFACE_SIZE as i32)?;
+        let arrows_ctx: Context = Context::new(arrows_surface)?;
+        let (path_r, path_g, path_b, path_a) = self.puzzle.colors.get_path();
+
+        arrows_ctx.set_source_rgba(path_r, path_g, path_b, path_a);
+        arrows_ctx.set_line_width(0.12 * self.scaling_factor);
+        arrows_ctx.set_line_cap(LineCap::Round);
+        arrows_ctx.set_line_join(LineJoin::Round);
+
+        let (x1, y1) = match self.puzzle.matrix.vertexes.get_coordinates(cell_id) {
+            Some(c) => c,
+            None => return Ok(arrows_ctx.target()),
+        };
+        let (s_x1, s_y1) = self.cell_to_surface_coordinates(x1, y1);
+        let head_size: f64 = 0.35 * self.scaling_factor;
+
+        for target in targets {
+            if *target == cell_id {
+                continue;
+            }
+            let (x2, y2) = match self.puzzle.matrix.vertexes.get_coordinates(*target) {
+                Some(c) => c,
+                None => continue,
+            };
+            let (s_x2, s_y2) = self.cell_to_surface_coordinates(x2, y2);
+
+            // Stop the arrow before reaching the center of the target cell so that the number
+            // in that cell stays readable
+            let angle: f64 = (s_y2 - s_y1).atan2(s_x2 - s_x1);
+            let length: f64 = ((s_x2 - s_x1).powi(2) + (s_y2 - s_y1).powi(2)).sqrt();
+            let start: f64 = 0.6 * self.scaling_factor;
+            let end: f64 = (length - 0.6 * self.scaling_factor).max(start);
+            let (a_x1, a_y1) = (s_x1 + start * angle.cos(), s_y1 + start * angle.sin());
+            let (a_x2, a_y2) = (s_x1 + end * angle.cos(), s_y1 + end * angle.sin());
+
+            arrows_ctx.move_to(a_x1, a_y1);
+            arrows_ctx.line_to(a_x2, a_y2);
+            arrows_ctx.move_to(
+                a_x2 - head_size * (angle - PI / 6.0).cos(),
+                a_y2 - head_size * (angle - PI / 6.0).sin(),
+            );
+            arrows_ctx.line_to(a_x2, a_y2);
+            arrows_ctx.line_to(
+                a_x2 - head_size * (angle + PI / 6.0).cos(),
+                a_y2 - head_size * (angle + PI / 6.0).sin(),
+            );
+        }
+        arrows_ctx.stroke()?;
+
+        Ok(arrows_ctx.target())
+    }
+
+    /// Draw the bounding box and the ID of each cell, for diagnosing rendering issues.
+    pub fn cell_bounding_boxes(&self) -> Result<Surface> {
+        let boxes_surface: ImageSurface =
+            ImageSurface::create(Format::ARgb32, SURFACE_SIZE as i32, SURFACE_SIZE as i32)?;
+        let boxes_ctx: Context = Context::new(boxes_surface)?;
+
+        boxes_ctx.set_source_rgba(1.0, 0.0, 1.0, 0.8);
+        boxes_ctx.set_line_width(1.0);
+        boxes_ctx.set_font_size(0.3 * self.scaling_factor);
+
+        for cell in &self.cells {
+            let s_x: f64 = cell.surface_x * self.scaling_factor;
+            let s_y: f64 = cell.surface_y * self.scaling_factor;
+
+            boxes_ctx.rectangle(
+                s_x - self.scaling_factor,
+                s_y - TWO_DIV_SQRT_3 * self.scaling_factor,
+                2.0 * self.scaling_factor,
+                2.0 * TWO_DIV_SQRT_3 * self.scaling_factor,
+            );
+            boxes_ctx.stroke()?;
+
+            let label: String = match cell.cell_type {
+                vertexes::CellType::Vertex(v) => format!("{v}"),
+                vertexes::CellType::Logo => String::from("L"),
+                vertexes::CellType::Background => continue,
+            };
+            boxes_ctx.move_to(
+                s_x - 0.9 * self.scaling_factor,
+                s_y - 0.6 * self.scaling_factor,
+            );
+            boxes_ctx.show_text(&label)?;
+        }
+
+        Ok(boxes_ctx.target())
+    }
+
     /// Return the coordinates of the cell that matches the given surface coordinates.
     pub fn surface_to_cell_coordinates(
         &self,
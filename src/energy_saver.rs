@@ -0,0 +1,53 @@
+/*
+energy_saver.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Energy-aware rendering policy.
+//!
+//! On laptops, Hexkudo reduces its energy consumption when the system power saver is enabled
+//! (see [`gio::PowerProfileMonitor`]). The player can also force the energy saver on or off with
+//! the `energy-saver` GSettings key.
+//!
+//! When the energy saver is active:
+//!
+//! - The timer refreshes once per second instead of ten times per second.
+//! - The animations are skipped.
+//! - The next game is not generated in the background. It is generated when the player starts
+//!   it.
+//! - The game in progress is saved automatically every two minutes instead of every 30 seconds.
+//!
+//! The timer, the animations, the automatic save, and the generation cache call [`is_active`] before doing their
+//! work, so that the policy applies as soon as the power saver state changes.
+
+use gtk::gio;
+use gtk::prelude::*;
+
+/// GSettings nicks of the `energy-saver` key, in the order of the enum values.
+pub const MODES: [&str; 3] = ["auto", "always", "never"];
+
+/// Whether Hexkudo must reduce its energy consumption.
+pub fn is_active(settings: &gio::Settings) -> bool {
+    match settings.enum_("energy-saver") {
+        1 => true,
+        2 => false,
+        _ => gio::PowerProfileMonitor::get_default().is_power_saver_enabled(),
+    }
+}
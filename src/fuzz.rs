@@ -0,0 +1,71 @@
+/*
+fuzz.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Entry points for fuzzing the parsers that process external input.
+//!
+//! The `cargo fuzz` targets in the `fuzz` directory call these functions with random data.
+//! The functions must never panic, whatever the input.
+//!
+//! ```
+//! $ cargo +nightly fuzz run ascii_puzzle
+//! ```
+
+use std::str;
+
+use crate::game::Game;
+use crate::generator::puzzle_parse::PuzzleParse;
+use crate::generator::puzzles::PuzzleExport;
+use crate::highscores::HighScores;
+
+/// Parse an "ASCII art" puzzle and build its internal representation.
+pub fn parse_ascii_puzzle(data: &[u8]) {
+    let Ok(source) = str::from_utf8(data) else {
+        return;
+    };
+    let mut puzzle: PuzzleParse = PuzzleParse::new(source);
+    if puzzle.build_edges().is_ok() {
+        for cell_id in 0..puzzle.vertexes.num_vertexes {
+            puzzle.vertexes.get_adjacent(cell_id);
+        }
+    }
+}
+
+/// Parse and verify a saved game and a high scores file.
+pub fn parse_save_file(data: &[u8]) {
+    if let Ok(game) = serde_json::from_slice::<Game>(data) {
+        let _ = game.validate();
+    }
+    if let Ok(mut highscores) = serde_json::from_slice::<HighScores>(data)
+        && highscores.validate().is_ok()
+    {
+        highscores.migrate();
+    }
+}
+
+/// Parse a puzzle in the text format, and export it again.
+pub fn parse_puzzle_export(data: &[u8]) {
+    if let Ok(text) = str::from_utf8(data)
+        && let Ok(export) = text.parse::<PuzzleExport>()
+    {
+        let _ = export.to_string();
+    }
+}
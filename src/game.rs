@@ -23,16 +23,31 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! Manage the status of a game in progress.
 
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
+use crate::chain;
 use crate::checkpoint::CheckPoint;
-use crate::generator::diamond_and_map::DiamondAndMap;
+use crate::clock::Clock;
+use crate::generator::diamond_and_map::{DiamondAndMap, Endpoints};
 use crate::generator::path::Path;
-use crate::generator::puzzles::Puzzle;
+use crate::generator::puzzles::{MAX_VERTEXES, Puzzle, PuzzleExport};
+use crate::generator::regions::{self, Region};
+use crate::generator::seed_code::{SeedCode, SeedOptions};
+use crate::generator::share_code::ShareCode;
 use crate::generator::vertexes;
 use crate::input_errors::InputErrors;
 use crate::player_input::PlayerInput;
-use crate::saver::game::instant;
+use crate::scoring::Scoring;
+
+pub mod hint;
+
+/// Completion percentages at which the split times are recorded.
+pub const SPLIT_PERCENTS: [usize; 3] = [25, 50, 75];
+
+/// Number of correct values that the player must place to reach the first milestone of the game.
+pub const FIRST_MILESTONE_VALUES: usize = 10;
 
 /// Status of a cell that the player completed.
 pub struct CellStatus {
@@ -42,13 +57,46 @@ pub struct CellStatus {
     /// Value set by the player.
     pub cell_value: usize,
 
-    /// Whether several cells have the same value.
+    /// Whether several cells have the same value, or whether the value is consecutive to the
+    /// value of a cell on the other side of an anti-diamond, or of an adjacent cell without a
+    /// diamond in the strict diamonds variant, or whether the values of the region do not match
+    /// its sum. In all cases, the value breaks the rules of the puzzle.
     pub duplicated: bool,
 
     /// Whether the player entered the wrong value.
     pub error: bool,
 }
 
+/// Origin of a change of a cell value in the move log.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum MoveKind {
+    /// The player entered or cleared the value. The mistake counter processes the change.
+    Entry,
+
+    /// An undo, redo, history, checkpoint, revert, or restart operation restored a previous
+    /// value, or cleared the cell.
+    Restore,
+
+    /// The game helped the player: revealed hint, completed chains, or solved puzzle.
+    Assist,
+}
+
+/// Change of a cell value, in the move log of the game.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Move {
+    /// Game duration when the change happened, in milliseconds.
+    pub time_ms: u64,
+
+    /// Cell ID.
+    pub cell_id: usize,
+
+    /// New value of the cell, or None when the cell was cleared.
+    pub value: Option<usize>,
+
+    /// Origin of the change.
+    pub kind: MoveKind,
+}
+
 /// Manage the status of the game in progress.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Game {
@@ -74,10 +122,52 @@ pub struct Game {
     /// List of diamonds.
     pub diamonds: Vec<(usize, usize)>,
 
+    /// List of anti-diamonds (negative clues variant).
+    #[serde(default)]
+    pub anti_diamonds: Vec<(usize, usize)>,
+
+    /// List of regions with their sums (sum clues variant).
+    #[serde(default)]
+    pub regions: Vec<Region>,
+
     /// Whether the player asked for a cell value or for solving the puzzle (those are options in
     /// the menu). In this case the user time is not added to the score board.
     pub user_has_cheated: bool,
 
+    /// Whether the player asked for solving the whole puzzle. Games that the player completed
+    /// with some help are added to the assisted score boards, but not the solved games.
+    #[serde(default)]
+    pub auto_solved: bool,
+
+    /// Number of forced cells that the player asked for. After [`hint::FREE_HINTS`] hints, the
+    /// game counts as assisted.
+    #[serde(default)]
+    hints_used: usize,
+
+    /// Number of cells that the player solved with the Solve Current Cell action.
+    /// See [`crate::generator::puzzles::Difficulty::solve_cell_budget`].
+    #[serde(default)]
+    solved_cells: usize,
+
+    /// Seed that generated the puzzle, so that players can replay the same puzzle instance.
+    /// The imported puzzles and the games that older versions saved have no seed.
+    #[serde(default)]
+    pub seed: Option<u64>,
+
+    /// Generation options that, with the seed, reproduce the puzzle instance.
+    /// The games that older versions saved have no options, and therefore no seed code.
+    #[serde(default)]
+    pub seed_options: Option<SeedOptions>,
+
+    /// Whether the game is in zen mode: no mistake counter, no timer, no highlighting of the
+    /// mistakes, and no scoreboards.
+    #[serde(default)]
+    pub zen: bool,
+
+    /// Whether the player can select the mapped (hint) cells. Those cells stay read-only.
+    #[serde(skip)]
+    pub select_map_cells: bool,
+
     /// Whether the player paused the game. In that case, the game board id hidden.
     pub paused: bool,
 
@@ -87,18 +177,56 @@ pub struct Game {
     /// Whether the puzzle is solved.
     pub solved: bool,
 
-    /// Time when the game started. Used to compute game duration.
-    #[serde(with = "instant")]
-    start_time: Instant,
+    /// Game duration. The clock is stopped while the game is paused.
+    #[serde(alias = "start_time")]
+    clock: Clock,
+
+    /// Time when the player paused the game.
+    #[serde(skip)]
+    pause_start: Option<Instant>,
+
+    /// Number of times the player paused the game.
+    #[serde(default)]
+    pause_count: usize,
 
-    /// The elapsed time when the player paused the game.
-    pause_duration: Option<Duration>,
+    /// Accumulated time during which the game was paused.
+    #[serde(default)]
+    pause_total: Duration,
+
+    /// Game duration when the player reached each of the [`SPLIT_PERCENTS`] completion levels.
+    #[serde(default)]
+    splits: Vec<Duration>,
+
+    /// Log of the correct values that the player placed: cell ID and game duration at that time.
+    #[serde(default)]
+    move_times: Vec<(usize, Duration)>,
+
+    /// Log of all the changes of the cell values, in order. Unlike the undo list, the log is
+    /// never trimmed, so that the game can be replayed for verifying a proof string.
+    #[serde(default)]
+    moves: Vec<Move>,
+
+    /// Time during which each cell stayed selected, measured with the game clock.
+    #[serde(default)]
+    dwell_times: HashMap<usize, Duration>,
+
+    /// Game duration when the selected cell was selected.
+    #[serde(default)]
+    selection_time: Duration,
 
     /// List of checkpoints set by the player.
     checkpoints: Vec<CheckPoint>,
 
     /// Manage input errors and the mistake counter.
     input_errors: InputErrors,
+
+    /// Points that the player earned (optional points-based scoring).
+    #[serde(default)]
+    scoring: Scoring,
+
+    /// Whether the board changed since the last automatic save.
+    #[serde(skip)]
+    unsaved: Cell<bool>,
 }
 
 impl Default for Game {
@@ -118,14 +246,32 @@ impl Game {
             path: Path::default(),
             map: Vec::new(),
             diamonds: Vec::new(),
+            anti_diamonds: Vec::new(),
+            regions: Vec::new(),
             user_has_cheated: false,
+            auto_solved: false,
+            hints_used: 0,
+            solved_cells: 0,
+            seed: None,
+            seed_options: None,
+            zen: false,
+            select_map_cells: false,
             paused: false,
             started: false,
             solved: false,
-            start_time: Instant::now(),
-            pause_duration: None,
+            clock: Clock::new(),
+            pause_start: None,
+            pause_count: 0,
+            pause_total: Duration::ZERO,
+            splits: Vec::new(),
+            move_times: Vec::new(),
+            moves: Vec::new(),
+            dwell_times: HashMap::new(),
+            selection_time: Duration::ZERO,
             checkpoints: Vec::new(),
             input_errors: InputErrors::new(),
+            scoring: Scoring::new(),
+            unsaved: Cell::new(false),
         }
     }
 
@@ -138,37 +284,164 @@ impl Game {
         self.path.clear();
         self.map.clear();
         self.diamonds.clear();
+        self.anti_diamonds.clear();
+        self.regions.clear();
         self.user_has_cheated = false;
+        self.auto_solved = false;
+        self.hints_used = 0;
+        self.solved_cells = 0;
+        self.seed = None;
+        self.seed_options = None;
+        self.zen = false;
         self.paused = false;
         self.started = false;
         self.solved = false;
-        self.pause_duration = None;
+        self.clock.reset();
+        self.pause_start = None;
+        self.pause_count = 0;
+        self.pause_total = Duration::ZERO;
+        self.splits.clear();
+        self.move_times.clear();
+        self.moves.clear();
+        self.dwell_times.clear();
+        self.selection_time = Duration::ZERO;
         self.checkpoints.clear();
         self.input_errors.clear();
+        self.scoring.clear();
+        self.unsaved.set(false);
     }
 
     /// Restart the game status (player inputs), but keep the puzzle data (structure, errors, timer)
     pub fn reset(&mut self) {
+        let before: HashMap<usize, usize> = self.player_input.get_values().clone();
+
         self.player_input.clear();
         self.checkpoints.clear();
         self.init_path();
         self.paused = false;
         self.started = true;
         if self.solved {
-            self.start_time = Instant::now();
+            // The replay of a solved puzzle starts a new game, with a new move log
+            self.clock.reset();
+            self.clock.start();
+            self.pause_count = 0;
+            self.pause_total = Duration::ZERO;
+            self.splits.clear();
+            self.move_times.clear();
+            self.moves.clear();
+            self.dwell_times.clear();
+            self.selection_time = Duration::ZERO;
+            self.scoring.clear();
+            self.input_errors.clear();
             self.solved = false;
+        } else {
+            self.log_changes(&before, MoveKind::Restore);
         }
     }
 
+    /// Return the game duration, truncated to the millisecond, for the move log.
+    ///
+    /// The mistake counter uses the same value, so that the replay of the move log gives the
+    /// same number of mistakes.
+    fn log_time(&self) -> Duration {
+        Duration::from_millis(u64::try_from(self.clock.elapsed().as_millis()).unwrap_or(u64::MAX))
+    }
+
+    /// Add a change of a cell value to the move log.
+    fn log_move(&mut self, cell_id: usize, value: Option<usize>, kind: MoveKind) {
+        let time_ms: u64 = u64::try_from(self.log_time().as_millis()).unwrap_or(u64::MAX);
+        self.unsaved.set(true);
+        self.moves.push(Move {
+            time_ms,
+            cell_id,
+            value,
+            kind,
+        });
+    }
+
+    /// Add to the move log the cells which values differ from the given previous values.
+    fn log_changes(&mut self, before: &HashMap<usize, usize>, kind: MoveKind) {
+        let values: HashMap<usize, usize> = self.player_input.get_values().clone();
+        let mut cells: Vec<usize> = before.keys().chain(values.keys()).copied().collect();
+
+        cells.sort_unstable();
+        cells.dedup();
+        for cell_id in cells {
+            let value: Option<usize> = values.get(&cell_id).copied();
+            if before.get(&cell_id).copied() != value {
+                self.log_move(cell_id, value, kind);
+            }
+        }
+    }
+
+    /// Whether the board changed since the last automatic save.
+    pub fn is_unsaved(&self) -> bool {
+        self.unsaved.get()
+    }
+
+    /// Record that the game was saved, so that the next automatic save can be skipped if the
+    /// board does not change.
+    pub fn mark_saved(&self) {
+        self.unsaved.set(false);
+    }
+
+    /// Return the log of the changes of the cell values.
+    pub fn get_moves(&self) -> &Vec<Move> {
+        &self.moves
+    }
+
     /// Change the currently selected cell.
     pub fn set_selected_cell(&mut self, cell_id: Option<usize>) {
         if cell_id == self.selected_cell {
             return;
         }
+        self.record_dwell();
         self.selected_cell = cell_id;
         self.selected_cell_value_updated = false;
     }
 
+    /// Add the time since the selection of the selected cell to the dwell time of that cell.
+    fn record_dwell(&mut self) {
+        let elapsed: Duration = self.clock.elapsed();
+
+        if let Some(cell_id) = self.selected_cell {
+            *self.dwell_times.entry(cell_id).or_default() +=
+                elapsed.saturating_sub(self.selection_time);
+        }
+        self.selection_time = elapsed;
+    }
+
+    /// Whether the given value could go in the given cell.
+    ///
+    /// The value is not plausible when another cell already has it, or when the previous or the
+    /// next value is in a hint cell that is not adjacent to the given cell.
+    pub fn is_value_plausible(&self, cell_id: usize, cell_value: usize) -> bool {
+        if self.player_input.contains_value(cell_value)
+            && self.player_input.get_value_from_id(cell_id) != Some(cell_value)
+        {
+            return false;
+        }
+        for given in &self.map {
+            if let Some(v) = self.player_input.get_value_from_id(*given)
+                && v.abs_diff(cell_value) == 1
+                && !self.puzzle.matrix.vertexes.is_adjacent(cell_id, *given)
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Whether the given cell is a mapped (hint) cell, which value the player cannot change.
+    pub fn is_given(&self, cell_id: usize) -> bool {
+        self.map.contains(&cell_id)
+    }
+
+    /// Whether the player can move the selection to the given cell.
+    pub fn is_selectable(&self, cell_id: usize) -> bool {
+        self.select_map_cells || !self.is_given(cell_id)
+    }
+
     /// Get the cell ID of the selected cell.
     pub fn get_selected_cell(&self) -> Option<usize> {
         self.selected_cell
@@ -205,9 +478,137 @@ impl Game {
     pub fn set_path(&mut self, path: &Path, d_and_m: &DiamondAndMap) {
         self.path = path.clone();
         (self.diamonds, self.map) = d_and_m.get_diamond_and_map();
+        self.anti_diamonds = d_and_m.get_anti_diamonds();
         self.init_path();
         self.started = true;
-        self.start_time = Instant::now();
+        self.clock.reset();
+        self.clock.start();
+    }
+
+    /// Start a game with the given puzzle, solution path, and clues.
+    pub fn set_export(&mut self, export: &PuzzleExport) {
+        self.set_puzzle(&export.puzzle);
+
+        let mut path: Path = Path::new(export.path.len());
+        for cell_id in &export.path {
+            path.push(*cell_id);
+        }
+        let mut d_and_m: DiamondAndMap = DiamondAndMap::new(
+            export.path.len(),
+            path.get_first().unwrap_or_default(),
+            path.get_last().unwrap_or_default(),
+        );
+        for (cell_id_1, cell_id_2) in &export.diamonds {
+            d_and_m.insert(*cell_id_1, *cell_id_2);
+        }
+        for (cell_id_1, cell_id_2) in &export.anti_diamonds {
+            d_and_m.insert_anti(*cell_id_1, *cell_id_2);
+        }
+        for cell_id in &export.map {
+            d_and_m.insert_map(*cell_id);
+        }
+        self.set_path(&path, &d_and_m);
+        self.regions = export.regions.clone();
+    }
+
+    /// Verify that the cell IDs and the values are consistent with the puzzle.
+    ///
+    /// The restored games come from files that the players can modify. Without this
+    /// verification, a corrupted or a tampered file could make the game access cells that do not
+    /// exist.
+    pub fn validate(&self) -> Result<(), String> {
+        let vertexes: &vertexes::Vertexes = &self.puzzle.matrix.vertexes;
+        let num_vertexes: usize = vertexes.num_vertexes;
+        if num_vertexes > MAX_VERTEXES {
+            return Err(format!("The puzzle has more than {MAX_VERTEXES} cells"));
+        }
+
+        // The path is empty when the game was saved before the end of the puzzle generation
+        let path: &Vec<usize> = self.path.get();
+        if !path.is_empty() {
+            if path.len() != num_vertexes {
+                return Err(format!(
+                    "The path has {} cells instead of {num_vertexes}",
+                    path.len()
+                ));
+            }
+            let mut seen: Vec<bool> = vec![false; num_vertexes];
+            for cell_id in path {
+                if *cell_id >= num_vertexes || seen[*cell_id] {
+                    return Err(format!("Invalid or duplicated cell in the path: {cell_id}"));
+                }
+                seen[*cell_id] = true;
+            }
+            if let Some(pair) = path.windows(2).find(|w| !vertexes.is_adjacent(w[0], w[1])) {
+                return Err(format!(
+                    "The path goes through non-adjacent cells: {} and {}",
+                    pair[0], pair[1]
+                ));
+            }
+        }
+        if self.map.len() > num_vertexes || self.splits.len() > SPLIT_PERCENTS.len() {
+            return Err(String::from("Too many hint cells or split times"));
+        }
+
+        let error_cells: Vec<usize> = self.input_errors.get_error_cells();
+        let cells = self
+            .map
+            .iter()
+            .chain(self.diamonds.iter().flat_map(|(a, b)| [a, b]))
+            .chain(self.anti_diamonds.iter().flat_map(|(a, b)| [a, b]))
+            .chain(self.regions.iter().flat_map(|r| r.cells.iter()))
+            .chain(self.move_times.iter().map(|(c, _)| c))
+            .chain(self.moves.iter().map(|m| &m.cell_id))
+            .chain(self.dwell_times.keys())
+            .chain(error_cells.iter())
+            .chain(self.selected_cell.iter())
+            .chain(
+                self.checkpoints
+                    .iter()
+                    .filter_map(|c| c.selected_cell.as_ref()),
+            );
+        for cell_id in cells {
+            if *cell_id >= num_vertexes {
+                return Err(format!("Invalid cell ID: {cell_id}"));
+            }
+        }
+
+        if let Some(m) = self
+            .moves
+            .iter()
+            .find(|m| m.value.is_some_and(|v| v == 0 || v > num_vertexes))
+        {
+            return Err(format!("Invalid value in the move log: {m:?}"));
+        }
+
+        self.player_input.validate(num_vertexes)?;
+        for checkpoint in &self.checkpoints {
+            checkpoint.player_input.validate(num_vertexes)?;
+        }
+        Ok(())
+    }
+
+    /// Return the puzzle, its solution path, and its clues, for sharing the puzzle.
+    pub fn to_export(&self) -> PuzzleExport {
+        PuzzleExport {
+            puzzle: self.puzzle.clone(),
+            path: self.path.get().clone(),
+            map: self.map.clone(),
+            diamonds: self.diamonds.clone(),
+            anti_diamonds: self.anti_diamonds.clone(),
+            regions: self.regions.clone(),
+        }
+    }
+
+    /// Return the share code that starts the puzzle instance, or None if the game has no seed
+    /// code or if Hexkudo does not provide the puzzle.
+    pub fn share_code(&self) -> Option<ShareCode> {
+        let code: SeedCode = self.seed_code()?;
+        self.puzzle.is_builtin().then(|| ShareCode {
+            name: self.puzzle.name.clone(),
+            difficulty: self.puzzle.difficulty,
+            code,
+        })
     }
 
     /// Initialize the game: declare the mapped (hint) cells and choose the first selected cell.
@@ -306,99 +707,700 @@ impl Game {
                 }
             }
         }
+        self.record_dwell();
         self.solved = true;
+        self.clock.stop();
         true
     }
 
+    /// Return the number of cells that the player completed and the number of cells to complete.
+    /// The mapped (hint) cells are not counted.
+    pub fn get_progress(&self) -> (usize, usize) {
+        let to_complete: usize = self.puzzle.matrix.vertexes.num_vertexes - self.map.len();
+        let completed: usize = self.player_input.len().saturating_sub(self.map.len());
+        (completed, to_complete)
+    }
+
+    /// Return the smallest value that the player has not placed yet, or None if all the cells
+    /// have a value.
+    pub fn get_next_missing_value(&self) -> Option<usize> {
+        (1..=self.puzzle.matrix.vertexes.num_vertexes)
+            .find(|v| !self.player_input.contains_value(*v))
+    }
+
+    /// Return the largest value that the player has not placed yet, or None if all the cells
+    /// have a value.
+    pub fn get_last_missing_value(&self) -> Option<usize> {
+        (1..=self.puzzle.matrix.vertexes.num_vertexes)
+            .rev()
+            .find(|v| !self.player_input.contains_value(*v))
+    }
+
+    /// Return the values that the player has not placed yet, in increasing order.
+    pub fn get_missing_values(&self) -> Vec<usize> {
+        self.player_input
+            .missing_values(self.puzzle.matrix.vertexes.num_vertexes)
+    }
+
+    /// Return the empty cells where the given value could go.
+    ///
+    /// When the player already placed the previous or the next value, the value must go in a
+    /// cell adjacent to them. The cells where the value is not plausible are also excluded. See
+    /// [`Self::is_value_plausible`].
+    pub fn get_candidate_cells(&self, value: usize) -> Vec<usize> {
+        let vertexes: &vertexes::Vertexes = &self.puzzle.matrix.vertexes;
+        let mut cells: Vec<usize> = (0..vertexes.num_vertexes)
+            .filter(|c| {
+                self.player_input.get_value_from_id(*c).is_none()
+                    && self.is_value_plausible(*c, value)
+            })
+            .collect();
+
+        for neighbour in [value.checked_sub(1), Some(value + 1)]
+            .into_iter()
+            .flatten()
+        {
+            let ids: &[usize] = self.player_input.get_ids_from_value(neighbour);
+            if ids.is_empty() {
+                continue;
+            }
+            let adjacent: Vec<usize> = ids
+                .iter()
+                .flat_map(|id| regions::adjacent_cells(vertexes, *id))
+                .collect();
+            cells.retain(|c| adjacent.contains(c));
+        }
+        cells
+    }
+
+    /// Return a cell to select for placing the given missing value.
+    ///
+    /// The function looks for the cell that has the neighbour value, which is the end of the
+    /// chain of placed values (`value - 1` when `ascending` is set, `value + 1` otherwise), and
+    /// returns an empty adjacent cell. If all the adjacent cells have a value, then the function
+    /// returns the chain end itself, if the player can select it.
+    pub fn get_cell_near_chain_end(&self, value: usize, ascending: bool) -> Option<usize> {
+        let end_value: usize = if ascending {
+            value.checked_sub(1)?
+        } else {
+            value + 1
+        };
+        let end_cell: usize = self.player_input.get_id_from_value(end_value)?;
+
+        regions::adjacent_cells(&self.puzzle.matrix.vertexes, end_cell)
+            .into_iter()
+            .find(|c| self.is_selectable(*c) && self.player_input.get_value_from_id(*c).is_none())
+            .or_else(|| self.is_selectable(end_cell).then_some(end_cell))
+    }
+
+    /// Fill the gaps between placed values that only one route of empty cells can fill.
+    ///
+    /// The values are added as a single group in the undo list, and the game is flagged as
+    /// assisted. Return the number of cells that the function filled.
+    pub fn complete_chains(&mut self) -> usize {
+        let mut values: HashMap<usize, usize> = self.player_input.get_values().clone();
+        let mut cells: Vec<(usize, usize)> = Vec::new();
+
+        while let Some(gap) = chain::find_unambiguous_gap(&self.puzzle.matrix.vertexes, &values) {
+            values.extend(gap.iter().copied());
+            cells.extend(gap);
+        }
+        if !cells.is_empty() {
+            self.player_input.add_group(&cells);
+            for (cell_id, value) in &cells {
+                self.log_move(*cell_id, Some(*value), MoveKind::Assist);
+            }
+            self.user_has_cheated = true;
+            self.record_splits();
+        }
+        cells.len()
+    }
+
     /// Return the number of errors so far.
     pub fn get_errors(&self) -> usize {
         self.input_errors.get_errors()
     }
 
+    /// Return the correct numbers of the cells where the player made mistakes, in increasing
+    /// order.
+    pub fn get_error_numbers(&self) -> Vec<usize> {
+        let path: &Vec<usize> = self.path.get();
+        let mut numbers: Vec<usize> = self
+            .input_errors
+            .get_error_cells()
+            .iter()
+            .filter_map(|cell_id| path.iter().position(|c| c == cell_id))
+            .map(|pos| pos + 1)
+            .collect();
+        numbers.sort_unstable();
+        numbers
+    }
+
     /// Return the number of checkpoints that the player created.
     pub fn checkpoints_len(&self) -> usize {
         self.checkpoints.len()
     }
 
-    /// Set a checkpoint.
-    pub fn set_checkpoint(&mut self) {
-        self.checkpoints.push(CheckPoint::new(self));
+    /// Return the names of the checkpoints, from the oldest to the most recent.
+    pub fn get_checkpoint_labels(&self) -> Vec<String> {
+        self.checkpoints.iter().map(|c| c.label.clone()).collect()
+    }
+
+    /// Set a checkpoint with the given name.
+    pub fn set_checkpoint(&mut self, label: &str) {
+        self.checkpoints.push(CheckPoint::new(self, label));
+        self.unsaved.set(true);
+    }
+
+    /// Revert back to the checkpoint at the given index. That checkpoint and the more recent
+    /// ones are removed.
+    pub fn jump_to_checkpoint(&mut self, index: usize) {
+        if index < self.checkpoints.len() {
+            self.checkpoints.truncate(index + 1);
+            self.undo_checkpoint();
+        }
     }
 
     /// Revert back to the last checkpoint.
     pub fn undo_checkpoint(&mut self) {
         // The checkpoint is removed
         if let Some(c) = self.checkpoints.pop() {
+            let before: HashMap<usize, usize> = self.player_input.get_values().clone();
+            let max_undo: usize = self.player_input.get_max_undo();
             self.player_input = c.player_input;
+            self.player_input.set_max_undo(max_undo);
+            self.record_dwell();
             self.selected_cell = c.selected_cell;
+
+            // The hints revealed after the checkpoint stay revealed
+            for cell_id in &self.map {
+                if let Some(v) = self.path.vertex_index(*cell_id) {
+                    self.player_input.reveal(*cell_id, v + 1);
+                }
+            }
+            self.log_changes(&before, MoveKind::Restore);
         }
     }
 
+    /// Revert the given cell to its value at the last checkpoint, or to an empty cell if the
+    /// player has not set any checkpoint. The other cells are not changed, and the checkpoint
+    /// is kept.
+    ///
+    /// The operation is added to the undo list. Return whether the cell value changed.
+    pub fn revert_cell(&mut self, cell_id: usize) -> bool {
+        if self.is_given(cell_id) {
+            return false;
+        }
+        let saved_value: Option<usize> = self
+            .checkpoints
+            .last()
+            .and_then(|c| c.player_input.get_value_from_id(cell_id));
+        if saved_value == self.player_input.get_value_from_id(cell_id) {
+            return false;
+        }
+
+        match saved_value {
+            Some(v) => self.player_input.add(cell_id, v),
+            None => self.player_input.remove(cell_id),
+        }
+        self.log_move(cell_id, saved_value, MoveKind::Restore);
+        true
+    }
+
+    /// Return the known values, indexed by the cell IDs, and the cells that the player does not
+    /// know yet, with their correct values. The hints and the cells with a correct value count as
+    /// known.
+    ///
+    /// The solver chooses the cell to reveal among the unknown cells. See
+    /// [`crate::solvability::Constraints::most_informative_cell`].
+    pub fn get_hint_candidates(&self) -> (HashMap<usize, usize>, Vec<(usize, usize)>) {
+        let mut known: HashMap<usize, usize> = HashMap::new();
+        let mut unknown: Vec<(usize, usize)> = Vec::new();
+
+        for (i, cell_id) in self.path.get().iter().enumerate() {
+            if self.map.contains(cell_id)
+                || self.player_input.get_value_from_id(*cell_id) == Some(i + 1)
+            {
+                known.insert(*cell_id, i + 1);
+            } else {
+                unknown.push((*cell_id, i + 1));
+            }
+        }
+        (known, unknown)
+    }
+
+    /// Reveal the given cell as an additional hint. Return false if the cell is already a hint.
+    ///
+    /// Because the player receives assistance, the game time is not added to the score board.
+    pub fn reveal_cell(&mut self, cell_id: usize) -> bool {
+        if self.map.contains(&cell_id) {
+            return false;
+        }
+        let Some(index) = self.path.vertex_index(cell_id) else {
+            return false;
+        };
+        self.map.push(cell_id);
+        self.player_input.reveal(cell_id, index + 1);
+        self.log_move(cell_id, Some(index + 1), MoveKind::Assist);
+        self.user_has_cheated = true;
+        true
+    }
+
+    /// Return a cell whose value can be deduced from the hint cells and from the correct values
+    /// that the player placed, or None if no such cell is found. The wrong values are ignored,
+    /// so that the deduction is always right.
+    pub fn find_forced_cell(&self) -> Option<hint::Hint> {
+        if self.path.get().is_empty() {
+            return None;
+        }
+        let values: HashMap<usize, usize> = self
+            .player_input
+            .get_values()
+            .iter()
+            .filter(|(cell_id, value)| !self.is_cell_error(**cell_id, **value))
+            .map(|(cell_id, value)| (*cell_id, *value))
+            .collect();
+
+        hint::find_forced_cell(
+            &self.puzzle.matrix.vertexes,
+            &values,
+            &self.diamonds,
+            &self.anti_diamonds,
+        )
+    }
+
+    /// Count a hint that the player asked for. Return true when that hint makes the game
+    /// assisted, because the player used all the free hints.
+    pub fn use_hint(&mut self) -> bool {
+        self.hints_used += 1;
+        if self.hints_used > hint::FREE_HINTS && !self.user_has_cheated {
+            self.user_has_cheated = true;
+            return true;
+        }
+        false
+    }
+
+    /// Return the number of forced cells that the player asked for.
+    pub fn get_hints_used(&self) -> usize {
+        self.hints_used
+    }
+
+    /// Return the number of cells that the player can still solve with the Solve Current Cell
+    /// action.
+    pub fn solve_cell_budget(&self) -> usize {
+        self.puzzle
+            .difficulty
+            .solve_cell_budget()
+            .saturating_sub(self.solved_cells)
+    }
+
+    /// Count a cell that the player solved with the Solve Current Cell action.
+    pub fn use_solve_cell_budget(&mut self) {
+        self.solved_cells += 1;
+    }
+
     /// Whether the given value is the correct value for the given cell ID.
-    fn is_cell_error(&self, cell_id: usize, cell_value: usize) -> bool {
+    pub fn is_cell_error(&self, cell_id: usize, cell_value: usize) -> bool {
         match self.path.get_vertex_from_value(cell_value) {
             Some(cid) => cid != cell_id,
             None => true,
         }
     }
 
+    /// Whether the given value is consecutive to the value of a cell on the other side of an
+    /// anti-diamond.
+    fn is_anti_diamond_conflict(
+        &self,
+        player_input: &PlayerInput,
+        cell_id: usize,
+        cell_value: usize,
+    ) -> bool {
+        self.anti_diamonds.iter().any(|(v1, v2)| {
+            let other: usize = if *v1 == cell_id {
+                *v2
+            } else if *v2 == cell_id {
+                *v1
+            } else {
+                return false;
+            };
+            player_input
+                .get_value_from_id(other)
+                .is_some_and(|v| v.abs_diff(cell_value) == 1)
+        })
+    }
+
+    /// Whether the diamonds mark all the adjacent cells that hold consecutive numbers (strict
+    /// diamonds variant).
+    pub fn has_strict_diamonds(&self) -> bool {
+        self.seed_options.is_some_and(|o| o.strict_diamonds)
+    }
+
+    /// Return the pairs of adjacent cells that hold consecutive values in the given player
+    /// input, without a diamond between them. In the strict diamonds variant, these values break
+    /// the rules of the puzzle. For the other games, the list is always empty.
+    pub fn get_strict_conflicts_for(&self, player_input: &PlayerInput) -> Vec<(usize, usize)> {
+        if !self.has_strict_diamonds() {
+            return Vec::new();
+        }
+        let mut conflicts: Vec<(usize, usize)> = Vec::new();
+        for (cell_id, cell_value) in player_input.get_values() {
+            for other in regions::adjacent_cells(&self.puzzle.matrix.vertexes, *cell_id) {
+                if other > *cell_id
+                    && player_input
+                        .get_value_from_id(other)
+                        .is_some_and(|v| v.abs_diff(*cell_value) == 1)
+                    && !self.diamonds.iter().any(|(v1, v2)| {
+                        (*v1 == *cell_id && *v2 == other) || (*v1 == other && *v2 == *cell_id)
+                    })
+                {
+                    conflicts.push((*cell_id, other));
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Whether the values in the region of the given cell exceed the sum of the region, or do
+    /// not match that sum when the region is complete.
+    fn is_region_conflict(&self, player_input: &PlayerInput, cell_id: usize) -> bool {
+        let Some(region) = self.regions.iter().find(|r| r.cells.contains(&cell_id)) else {
+            return false;
+        };
+        let values: Vec<usize> = region
+            .cells
+            .iter()
+            .filter_map(|c| player_input.get_value_from_id(*c))
+            .collect();
+        let sum: usize = values.iter().sum();
+
+        sum > region.sum || (values.len() == region.cells.len() && sum != region.sum)
+    }
+
     /// Get the list of all the cells that the player completed as well as the mapped cells.
     /// For each cell, the [`CellStatus`] object indicate whether the value is wrong and/or
     /// duplicated.
     pub fn get_cells(&self) -> Vec<CellStatus> {
+        self.get_cells_for(&self.player_input)
+    }
+
+    /// Same as [`Game::get_cells`], but for the given player input instead of the current one.
+    pub fn get_cells_for(&self, player_input: &PlayerInput) -> Vec<CellStatus> {
         let mut ret: Vec<CellStatus> = Vec::with_capacity(self.path.len());
+        let strict_conflicts: Vec<(usize, usize)> = self.get_strict_conflicts_for(player_input);
 
-        for (cell_id, cell_value) in self.player_input.get_values() {
+        for (cell_id, cell_value) in player_input.get_values() {
             ret.push(CellStatus {
                 cell_id: *cell_id,
                 cell_value: *cell_value,
-                duplicated: self.player_input.is_value_duplicated(*cell_value),
+                duplicated: player_input.is_value_duplicated(*cell_value)
+                    || self.is_anti_diamond_conflict(player_input, *cell_id, *cell_value)
+                    || strict_conflicts
+                        .iter()
+                        .any(|(v1, v2)| *v1 == *cell_id || *v2 == *cell_id)
+                    || self.is_region_conflict(player_input, *cell_id),
                 error: self.is_cell_error(*cell_id, *cell_value),
             });
         }
         ret
     }
 
+    /// Return the player input of the initial puzzle, where only the mapped cells have a value.
+    pub fn get_initial_input(&self) -> PlayerInput {
+        let mut player_input: PlayerInput = PlayerInput::new();
+
+        for cell_id in &self.map {
+            if let Some(v) = self.path.vertex_index(*cell_id) {
+                player_input.add_no_undo(*cell_id, v + 1);
+            }
+        }
+        player_input
+    }
+
+    /// Return the initial player input and the successive changes of the cell values, for
+    /// replaying the game. Each change is a cell ID and its new value, or None when the cell was
+    /// cleared.
+    ///
+    /// The changes come from the move log, which is never trimmed. The replay therefore shows
+    /// all the moves, including the moves that the player undid.
+    pub fn get_replay(&self) -> (PlayerInput, Vec<(usize, Option<usize>)>) {
+        let changes: Vec<(usize, Option<usize>)> = self
+            .moves
+            .iter()
+            .filter(|m| !self.map.contains(&m.cell_id))
+            .map(|m| (m.cell_id, m.value))
+            .collect();
+        (self.get_initial_input(), changes)
+    }
+
     /// Add the value that the player provided to the given cell.
     pub fn add_value_to_cell(&mut self, cell_id: usize, cell_value: usize) {
+        if self.is_given(cell_id) {
+            return;
+        }
         self.player_input.add(cell_id, cell_value);
+        self.log_move(cell_id, Some(cell_value), MoveKind::Entry);
         // Verify whether this is the correct value. If not, then the error counter is incremented.
+        let in_error: bool = self.is_cell_error(cell_id, cell_value);
         self.input_errors
-            .add_cell(cell_id, self.is_cell_error(cell_id, cell_value));
+            .add_cell(cell_id, in_error, self.log_time());
+        if !in_error {
+            self.move_times.push((cell_id, self.clock.elapsed()));
+        }
+        self.scoring
+            .add_cell(cell_id, !in_error, self.clock.elapsed());
+        self.record_splits();
+    }
+
+    /// Add the given candidate number to the notes of a cell, or remove it if the cell already
+    /// has that note. Return false if the cell does not accept notes or if the number is not
+    /// a value of the puzzle.
+    pub fn toggle_note(&mut self, cell_id: usize, cell_value: usize) -> bool {
+        if self.is_given(cell_id)
+            || cell_value == 0
+            || cell_value > self.puzzle.matrix.vertexes.num_vertexes
+        {
+            return false;
+        }
+        self.player_input.toggle_note(cell_id, cell_value);
+        self.unsaved.set(true);
+        true
+    }
+
+    /// Return the notes of the cells that have no value, indexed by the cell IDs.
+    pub fn get_notes(&self) -> HashMap<usize, Vec<usize>> {
+        self.player_input
+            .get_all_notes()
+            .iter()
+            .filter(|(cell_id, _)| self.player_input.get_value_from_id(**cell_id).is_none())
+            .map(|(cell_id, notes)| (*cell_id, notes.iter().copied().collect()))
+            .collect()
+    }
+
+    /// Return the number of consecutive correct values that the player placed.
+    pub fn get_streak(&self) -> usize {
+        self.scoring.get_streak()
+    }
+
+    /// Return the longest streak of correct values of the game.
+    pub fn get_best_streak(&self) -> usize {
+        self.scoring.get_best_streak()
+    }
+
+    /// Return the points that the player earned, with the penalties for the mistakes and the
+    /// bonus for the hidden endpoints.
+    pub fn get_points(&self) -> u32 {
+        let points: u32 = self.scoring.get_points(self.input_errors.get_errors());
+        points.saturating_mul(100 + self.get_endpoints().bonus_percent()) / 100
+    }
+
+    /// Return the seed code that reproduces the puzzle instance, or None if the game has no
+    /// seed or no generation options.
+    pub fn seed_code(&self) -> Option<SeedCode> {
+        Some(SeedCode {
+            seed: self.seed?,
+            options: self.seed_options?,
+        })
+    }
+
+    /// Return the endpoints of the path that the game revealed at the beginning.
+    ///
+    /// The option is deduced from the map, so that the restored and the imported games report
+    /// the endpoints that the player actually got. Before the path is generated, the endpoints
+    /// are considered revealed.
+    pub fn get_endpoints(&self) -> Endpoints {
+        let revealed = |cell: Option<usize>| cell.is_none_or(|c| self.map.contains(&c));
+        match (
+            revealed(self.path.get_first()),
+            revealed(self.path.get_last()),
+        ) {
+            (true, true) => Endpoints::Both,
+            (true, false) => Endpoints::Start,
+            (false, true) => Endpoints::End,
+            (false, false) => Endpoints::None,
+        }
+    }
+
+    /// Return the game duration when the player placed the first [`FIRST_MILESTONE_VALUES`]
+    /// correct values, or None if the player has not reached that milestone.
+    pub fn get_first_milestone(&self) -> Option<Duration> {
+        self.get_timeline().get(FIRST_MILESTONE_VALUES - 1).copied()
+    }
+
+    /// Return the timeline of the game: the game duration when the player placed each new
+    /// correct value, in order. The hints are not part of the timeline.
+    pub fn get_timeline(&self) -> Vec<Duration> {
+        let mut cells: Vec<usize> = Vec::new();
+        let mut timeline: Vec<Duration> = Vec::new();
+
+        for (cell_id, time) in &self.move_times {
+            if self.map.contains(cell_id) || cells.contains(cell_id) {
+                continue;
+            }
+            cells.push(*cell_id);
+            timeline.push(*time);
+        }
+        timeline
+    }
+
+    /// Return how much each cell made the player struggle, between 0 (the fastest cell) and 1
+    /// (the slowest cell). The hint cells are not part of the result.
+    ///
+    /// The time spent on a cell is the longest of the time during which the cell stayed
+    /// selected, and the time that the player took to place its final correct value since the
+    /// previous correct value.
+    pub fn get_heat_map(&self) -> HashMap<usize, f64> {
+        let mut times: HashMap<usize, Duration> = self
+            .dwell_times
+            .iter()
+            .filter(|(cell_id, _)| !self.map.contains(cell_id))
+            .map(|(cell_id, time)| (*cell_id, *time))
+            .collect();
+        let mut previous: Duration = Duration::ZERO;
+        let mut solve_times: HashMap<usize, Duration> = HashMap::new();
+
+        // The last correct value of a cell is its final value
+        for (cell_id, time) in &self.move_times {
+            solve_times.insert(*cell_id, time.saturating_sub(previous));
+            previous = *time;
+        }
+        for (cell_id, time) in solve_times {
+            if self.map.contains(&cell_id) {
+                continue;
+            }
+            let t: &mut Duration = times.entry(cell_id).or_default();
+            *t = (*t).max(time);
+        }
+
+        let max: f64 = times
+            .values()
+            .map(Duration::as_secs_f64)
+            .fold(0.0, f64::max);
+        times
+            .into_iter()
+            .map(|(cell_id, time)| {
+                let heat: f64 = if max > 0.0 {
+                    time.as_secs_f64() / max
+                } else {
+                    0.0
+                };
+                (cell_id, heat)
+            })
+            .collect()
+    }
+
+    /// Record the split times for the completion levels that the player reached.
+    fn record_splits(&mut self) {
+        let (completed, to_complete) = self.get_progress();
+        while let Some(percent) = SPLIT_PERCENTS.get(self.splits.len())
+            && completed * 100 >= to_complete * percent
+        {
+            self.splits.push(self.clock.elapsed());
+        }
+    }
+
+    /// Return the split times recorded so far.
+    pub fn get_splits(&self) -> &Vec<Duration> {
+        &self.splits
     }
 
     /// Remove the value of the given cell.
     pub fn remove_value_from_cell(&mut self, cell_id: usize) {
+        if self.is_given(cell_id) {
+            return;
+        }
+        if self.player_input.get_value_from_id(cell_id).is_some() {
+            self.log_move(cell_id, None, MoveKind::Entry);
+        }
         self.player_input.remove(cell_id);
-        self.input_errors.clear_cell(cell_id);
+        self.input_errors.clear_cell(cell_id, self.log_time());
+    }
+
+    /// Undo the last move, or the last group of moves.
+    pub fn undo(&mut self) {
+        let before: HashMap<usize, usize> = self.player_input.get_values().clone();
+        self.player_input.undo();
+        self.log_changes(&before, MoveKind::Restore);
+    }
+
+    /// Redo the last undone move, or the last undone group of moves.
+    pub fn redo(&mut self) {
+        let before: HashMap<usize, usize> = self.player_input.get_values().clone();
+        self.player_input.redo();
+        self.log_changes(&before, MoveKind::Restore);
+    }
+
+    /// Undo or redo the moves until the board is in the state after the given step of the
+    /// history. See [`PlayerInput::jump_to`].
+    pub fn jump_to_history(&mut self, step: usize) {
+        let before: HashMap<usize, usize> = self.player_input.get_values().clone();
+        self.player_input.jump_to(step);
+        self.log_changes(&before, MoveKind::Restore);
+    }
+
+    /// Fill all the cells with their correct values.
+    ///
+    /// Solved games are not added to the score boards.
+    pub fn solve(&mut self) {
+        let before: HashMap<usize, usize> = self.player_input.get_values().clone();
+
+        self.user_has_cheated = true;
+        self.auto_solved = true;
+        self.player_input.clear();
+        for (i, cell_id) in self.path.get().clone().iter().enumerate() {
+            self.player_input.add_no_undo(*cell_id, i + 1);
+        }
+        self.log_changes(&before, MoveKind::Assist);
     }
 
     /// Pause the game.
     pub fn pause(&mut self) {
-        // Store the played time so far, so that the pause time can be deduced when the
-        // player resumes the game.
-        self.pause_duration = Some(self.start_time.elapsed());
+        if !self.paused {
+            self.pause_count += 1;
+        }
+        self.clock.stop();
+        self.pause_start = Some(Instant::now());
         self.paused = true;
     }
 
     /// Resume the game.
     pub fn resume(&mut self) {
-        // Refresh the game elapsed time by removing the pause time.
-        if let Some(d) = self.pause_duration {
-            self.start_time += self.start_time.elapsed() - d;
-            self.pause_duration = None;
+        if let Some(t) = self.pause_start.take() {
+            self.pause_total += t.elapsed();
         }
+        self.clock.start();
         self.paused = false;
     }
 
+    /// Start the game clock if the game is in progress.
+    ///
+    /// The clock of a restored game is stopped. It must be started if the game is not paused.
+    /// The function is also used to restart the clock after [`Game::freeze_clock`].
+    pub fn start_clock(&mut self) {
+        if self.started && !self.paused && !self.solved {
+            self.clock.start();
+        }
+    }
+
+    /// Stop the game clock without pausing the game.
+    ///
+    /// Used to exclude the time spent with dialogs open from the game duration.
+    pub fn freeze_clock(&mut self) {
+        self.clock.stop();
+    }
+
+    /// Return the number of times the game was paused and the total pause time.
+    pub fn get_pauses(&self) -> (usize, Duration) {
+        (self.pause_count, self.pause_total)
+    }
+
     /// Return the game duration.
     pub fn get_duration(&self) -> Duration {
-        self.start_time.elapsed()
+        self.clock.elapsed()
     }
 
     /// Return the game duration in hours, minutes, and seconds
     pub fn get_duration_hms(&self) -> (u64, u64, u64) {
-        let duration: u64 = self.start_time.elapsed().as_secs();
+        let duration: u64 = self.clock.elapsed().as_secs();
         (
             duration / 3600,
             (duration % 3600) / 60,
@@ -0,0 +1,342 @@
+/*
+hint.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Find a cell whose value is logically forced.
+//!
+//! The analysis only relies on the values that the player placed correctly and on the hint
+//! cells, and applies two deductions:
+//!
+//! - A missing value can only go in one empty cell.
+//! - An empty cell can only receive one of the missing values.
+//!
+//! A value fits in a cell when the cell is close enough to the previous and the next placed
+//! values, and when the diamonds and the anti-diamonds around the cell allow it.
+//! These rules ignore some constraints, such as the region sums, and therefore never exclude
+//! the correct value. When only one candidate remains, it is the solution.
+
+use std::collections::HashMap;
+
+use crate::chain::distance;
+use crate::generator::vertexes::Vertexes;
+
+/// Number of hints that players can request before the game counts as assisted.
+pub const FREE_HINTS: usize = 3;
+
+/// Deduction that forces the value of the cell.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Reason {
+    /// The value cannot go in any other empty cell.
+    OnlyCell,
+
+    /// No other missing value fits in the cell.
+    OnlyValue,
+}
+
+/// Cell whose value is forced.
+#[derive(Debug, Copy, Clone)]
+pub struct Hint {
+    /// Cell ID.
+    pub cell_id: usize,
+
+    /// Forced value.
+    pub value: usize,
+
+    /// Deduction that forces the value.
+    pub reason: Reason,
+}
+
+/// Board state for the analysis.
+struct Analysis<'a> {
+    /// Board cells.
+    vertexes: &'a Vertexes,
+
+    /// Known values, indexed by the cell IDs.
+    values: &'a HashMap<usize, usize>,
+
+    /// Cell of each known value, indexed by the value. The index 0 is not used.
+    value_to_cell: Vec<Option<usize>>,
+
+    /// Pairs of cells that must have consecutive values.
+    diamonds: &'a [(usize, usize)],
+
+    /// Pairs of cells that must not have consecutive values.
+    anti_diamonds: &'a [(usize, usize)],
+}
+
+impl Analysis<'_> {
+    /// Whether the given value can go in the given empty cell.
+    fn is_candidate(&self, cell_id: usize, value: usize) -> bool {
+        // The cell must be reachable from the previous and the next known values
+        if let Some((prev, prev_cell)) = (1..value)
+            .rev()
+            .find_map(|v| self.value_to_cell[v].map(|c| (v, c)))
+            && distance(self.vertexes, prev_cell, cell_id) > value - prev
+        {
+            return false;
+        }
+        if let Some((next, next_cell)) = (value + 1..self.value_to_cell.len())
+            .find_map(|v| self.value_to_cell[v].map(|c| (v, c)))
+            && distance(self.vertexes, cell_id, next_cell) > next - value
+        {
+            return false;
+        }
+
+        // The cells across the diamonds hold the previous or the next value
+        for partner in Self::partners(self.diamonds, cell_id) {
+            match self.values.get(&partner) {
+                Some(v) => {
+                    if v.abs_diff(value) != 1 {
+                        return false;
+                    }
+                }
+                None => {
+                    let fits = |v: usize| {
+                        v > 0
+                            && v < self.value_to_cell.len()
+                            && self.value_to_cell[v].is_none_or(|c| c == partner)
+                    };
+                    if !fits(value - 1) && !fits(value + 1) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        // The cells across the anti-diamonds do not hold the previous or the next value
+        Self::partners(self.anti_diamonds, cell_id)
+            .filter_map(|partner| self.values.get(&partner))
+            .all(|v| v.abs_diff(value) != 1)
+    }
+
+    /// Return the cells that the given pairs link to the given cell.
+    fn partners(pairs: &[(usize, usize)], cell_id: usize) -> impl Iterator<Item = usize> + '_ {
+        pairs.iter().filter_map(move |(a, b)| {
+            if *a == cell_id {
+                Some(*b)
+            } else if *b == cell_id {
+                Some(*a)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Return a cell whose value is forced, or None if the deductions do not find any.
+///
+/// The `values` parameter provides the known values, indexed by the cell IDs. These values must
+/// be correct, which is the case for the hint cells and the values that match the solution.
+pub fn find_forced_cell(
+    vertexes: &Vertexes,
+    values: &HashMap<usize, usize>,
+    diamonds: &[(usize, usize)],
+    anti_diamonds: &[(usize, usize)],
+) -> Option<Hint> {
+    let num_vertexes: usize = vertexes.num_vertexes;
+    let mut value_to_cell: Vec<Option<usize>> = vec![None; num_vertexes + 1];
+    for (cell_id, value) in values {
+        if *value > 0 && *value <= num_vertexes {
+            value_to_cell[*value] = Some(*cell_id);
+        }
+    }
+    let analysis = Analysis {
+        vertexes,
+        values,
+        value_to_cell,
+        diamonds,
+        anti_diamonds,
+    };
+
+    let empty_cells: Vec<usize> = (0..num_vertexes)
+        .filter(|c| !values.contains_key(c))
+        .collect();
+    let missing_values: Vec<usize> = (1..=num_vertexes)
+        .filter(|v| analysis.value_to_cell[*v].is_none())
+        .collect();
+
+    // A missing value that only one cell can receive
+    for value in &missing_values {
+        let mut candidates = empty_cells
+            .iter()
+            .filter(|c| analysis.is_candidate(**c, *value));
+        if let Some(cell_id) = candidates.next()
+            && candidates.next().is_none()
+        {
+            return Some(Hint {
+                cell_id: *cell_id,
+                value: *value,
+                reason: Reason::OnlyCell,
+            });
+        }
+    }
+
+    // An empty cell that only one missing value fits in
+    for cell_id in &empty_cells {
+        let mut candidates = missing_values
+            .iter()
+            .filter(|v| analysis.is_candidate(*cell_id, **v));
+        if let Some(value) = candidates.next()
+            && candidates.next().is_none()
+        {
+            return Some(Hint {
+                cell_id: *cell_id,
+                value: *value,
+                reason: Reason::OnlyValue,
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::puzzle_parse::PuzzleParse;
+
+    /// Small board. The cell IDs are, from the top left corner:
+    ///
+    /// ```text
+    ///  0 1 2
+    /// 3 4 5 6
+    ///  7 8 9
+    /// ```
+    const MATRIX: &str = " o o o
+o o o o
+ o o o
+";
+
+    /// Solution path of the tests, on the [`MATRIX`] board.
+    const PATH: [usize; 10] = [3, 0, 1, 2, 6, 5, 4, 7, 8, 9];
+
+    /// Return the cells of the [`MATRIX`] board.
+    fn board() -> Vertexes {
+        let mut puzzle: PuzzleParse = PuzzleParse::new(MATRIX);
+        puzzle.build_edges().unwrap();
+        puzzle.vertexes
+    }
+
+    /// Return the value of each cell for all the paths that match the known values and the
+    /// diamonds.
+    fn solutions(
+        vertexes: &Vertexes,
+        values: &HashMap<usize, usize>,
+        diamonds: &[(usize, usize)],
+        anti_diamonds: &[(usize, usize)],
+    ) -> Vec<Vec<usize>> {
+        fn extend(
+            vertexes: &Vertexes,
+            values: &HashMap<usize, usize>,
+            path: &mut Vec<usize>,
+            found: &mut Vec<Vec<usize>>,
+        ) {
+            if path.len() == vertexes.num_vertexes {
+                let mut cells: Vec<usize> = vec![0; path.len()];
+                for (i, cell_id) in path.iter().enumerate() {
+                    cells[*cell_id] = i + 1;
+                }
+                found.push(cells);
+                return;
+            }
+            for cell_id in 0..vertexes.num_vertexes {
+                if path.contains(&cell_id)
+                    || path
+                        .last()
+                        .is_some_and(|c| !vertexes.is_adjacent(*c, cell_id))
+                    || values.get(&cell_id).is_some_and(|v| *v != path.len() + 1)
+                {
+                    continue;
+                }
+                path.push(cell_id);
+                extend(vertexes, values, path, found);
+                path.pop();
+            }
+        }
+
+        let mut found: Vec<Vec<usize>> = Vec::new();
+        extend(vertexes, values, &mut Vec::new(), &mut found);
+        found.retain(|cells| {
+            diamonds
+                .iter()
+                .all(|(a, b)| cells[*a].abs_diff(cells[*b]) == 1)
+                && anti_diamonds
+                    .iter()
+                    .all(|(a, b)| cells[*a].abs_diff(cells[*b]) != 1)
+        });
+        found
+    }
+
+    #[test]
+    fn hinted_values_match_every_solution() {
+        let vertexes: Vertexes = board();
+        let diamonds: [(usize, usize); 2] = [(6, 5), (1, 2)];
+        let anti_diamonds: [(usize, usize); 1] = [(0, 4)];
+        let mut values: HashMap<usize, usize> = HashMap::from([(3, 1), (2, 4), (7, 8), (9, 10)]);
+
+        let mut hints: usize = 0;
+        while let Some(hint) = find_forced_cell(&vertexes, &values, &diamonds, &anti_diamonds) {
+            let solutions: Vec<Vec<usize>> =
+                solutions(&vertexes, &values, &diamonds, &anti_diamonds);
+            assert!(!solutions.is_empty());
+            assert!(
+                solutions.iter().all(|s| s[hint.cell_id] == hint.value),
+                "{hint:?} is not forced"
+            );
+            assert_eq!(PATH[hint.value - 1], hint.cell_id);
+            values.insert(hint.cell_id, hint.value);
+            hints += 1;
+        }
+        assert!(hints > 0);
+    }
+
+    #[test]
+    fn forced_cells_are_found() {
+        let vertexes: Vertexes = board();
+
+        // Value 2 must be next to value 1, and only cell 0 is
+        let values: HashMap<usize, usize> = HashMap::from([(3, 1), (4, 7), (7, 8), (9, 10)]);
+        let hint: Hint = find_forced_cell(&vertexes, &values, &[], &[]).unwrap();
+        assert_eq!(hint.reason, Reason::OnlyCell);
+        assert!(
+            solutions(&vertexes, &values, &[], &[])
+                .iter()
+                .all(|s| s[hint.cell_id] == hint.value)
+        );
+
+        // The last missing value goes in the last empty cell
+        let values: HashMap<usize, usize> = PATH
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != 8)
+            .map(|(i, c)| (*c, i + 1))
+            .collect();
+        let hint: Hint = find_forced_cell(&vertexes, &values, &[], &[]).unwrap();
+        assert_eq!((hint.cell_id, hint.value), (8, 9));
+    }
+
+    #[test]
+    fn ambiguous_boards_have_no_hint() {
+        let vertexes: Vertexes = board();
+        let values: HashMap<usize, usize> = HashMap::from([(4, 5)]);
+        assert!(solutions(&vertexes, &values, &[], &[]).len() > 1);
+        assert!(find_forced_cell(&vertexes, &values, &[], &[]).is_none());
+    }
+}
@@ -0,0 +1,280 @@
+/*
+generation_cache.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Generate the next game in the background.
+//!
+//! Generating the path and the clues of the large puzzles can take several seconds. After a game
+//! starts, the cache speculatively generates the next game for the same puzzle in a background
+//! task, so that starting a new game with the same puzzle is immediate.
+//!
+//! The generated game depends on the puzzle and on the clue preferences. The cache only returns
+//! the generated game when these parameters did not change (see [`CacheKey`]).
+//!
+//! When the generation takes too long, [`generate`] falls back to a sample game, from the
+//! puzzle definition or from the local pool of the games that the generator previously produced
+//! (see [`crate::sample_pool`]). The seed does not reproduce the games that come from the pool,
+//! because the pool differs between computers.
+
+use log::debug;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gtk::{gio, glib};
+
+use crate::generator::diamond_and_map::{DiamondAndMap, Endpoints};
+use crate::generator::diamonds;
+use crate::generator::path::Path;
+use crate::generator::puzzles::{Difficulty, Puzzle, PuzzleSampleGame};
+use crate::generator::random_path;
+use crate::sample_pool;
+
+/// Parameters that the generated games depend on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheKey {
+    /// Puzzle name.
+    pub name: String,
+
+    /// Difficulty level.
+    pub difficulty: Difficulty,
+
+    /// Endpoints of the path to reveal.
+    pub endpoints: Endpoints,
+
+    /// Whether to generate anti-diamonds.
+    pub negative_clues: bool,
+
+    /// Whether to mark all the adjacent cells that hold consecutive numbers with a diamond.
+    pub strict_diamonds: bool,
+}
+
+/// Path and clues of a generated game.
+#[derive(Debug)]
+pub struct GeneratedGame {
+    /// Seed that produced the game.
+    pub seed: u64,
+
+    /// Solution path.
+    pub path: Path,
+
+    /// Diamonds, anti-diamonds, and mapped cells.
+    pub diamond_and_map: DiamondAndMap,
+
+    /// Game to add to the sample pool, when the generation succeeded.
+    pub sample: Option<PuzzleSampleGame>,
+
+    /// Whether the seed reproduces the game. The games from the sample pool depend on the
+    /// content of the pool, and therefore cannot be reproduced on another computer.
+    pub reproducible: bool,
+}
+
+/// Generate the path and the clues of a game for the given puzzle.
+///
+/// When the generation takes too long, return a path, diamonds, and map from the puzzle's
+/// sample list or from the provided pool samples. The seed selects the sample. The sample
+/// games always reveal both endpoints. Provide an empty pool so that the seed always
+/// reproduces the game.
+///
+/// The [`GeneratedGame::sample`] is the generated game, in the format of the sample games,
+/// when the generation succeeded. Only the games that reveal both endpoints and that have no
+/// anti-diamonds and no strict diamonds are returned, because the sample games cannot represent
+/// the other games.
+pub fn generate(
+    puzzle: &Puzzle,
+    seed: u64,
+    endpoints: Endpoints,
+    negative_clues: bool,
+    strict_diamonds: bool,
+    pool: &[PuzzleSampleGame],
+) -> GeneratedGame {
+    let mut random_path: random_path::RandomPath =
+        random_path::RandomPath::new_with_seed(&puzzle.matrix.edges, &puzzle.matrix.vertexes, seed);
+
+    // Retrieve a path, map, and diamond from the puzzle's list or from the pool in case the
+    // process that generates the puzzle or the diamonds takes too long
+    let index: usize = (seed % (pool.len() as u64 + 1)) as usize;
+    let (random, reproducible): (PuzzleSampleGame, bool) = match pool.get(index) {
+        Some(s) => (s.clone(), false),
+        None => ((puzzle.get_sample_path_fn)(seed), true),
+    };
+    let sample = || {
+        let path: Path = Path::from_vec(&random.path);
+        let path_len: usize = path.len();
+        let path_first: usize = path
+            .get_first()
+            .expect("Cannot retrieve the first cell in the path");
+        let path_last: usize = path
+            .get_last()
+            .expect("Cannot retrieve the last cell in the path");
+        let d_and_m: DiamondAndMap = DiamondAndMap::from_vec(
+            &random.diamonds,
+            &random.map,
+            path_len,
+            path_first,
+            path_last,
+        );
+        GeneratedGame {
+            seed,
+            path,
+            diamond_and_map: d_and_m,
+            sample: None,
+            reproducible,
+        }
+    };
+
+    // With strict diamonds, the diamonds follow the whole path, and therefore there is no
+    // search for a unique path that could take too long
+    if strict_diamonds {
+        let (path, reproducible): (Path, bool) = match random_path.generate(None) {
+            Ok(p) => (p, true),
+            Err(_) => (Path::from_vec(&random.path), reproducible),
+        };
+        let mut d_and_m: DiamondAndMap = DiamondAndMap::new(
+            path.len(),
+            path.get_first().unwrap_or_default(),
+            path.get_last().unwrap_or_default(),
+        );
+        d_and_m.set_endpoints(endpoints);
+        d_and_m.compute_strict(path.get());
+        return GeneratedGame {
+            seed,
+            path,
+            diamond_and_map: d_and_m,
+            sample: None,
+            reproducible,
+        };
+    }
+
+    // Generate a random path
+    match random_path.generate(None) {
+        // Too long, the generating process gave up
+        Err(_) => {
+            debug!("Too long (path)");
+            sample()
+        }
+        Ok(p) => {
+            // Generate diamonds and map
+            let mut diamonds: diamonds::Diamond = diamonds::Diamond::new(&random_path.edges, &p);
+            diamonds.set_seed(seed);
+            diamonds.set_endpoints(endpoints);
+            diamonds.set_negative_clues(negative_clues);
+            match diamonds.generate_diamonds(&puzzle.matrix.vertexes) {
+                // Too long, the generating process gave up
+                Err(_) => {
+                    debug!("Too long (diamonds and map)");
+                    sample()
+                }
+                Ok(d_and_m) => {
+                    let pooled: bool = endpoints == Endpoints::Both && !negative_clues;
+                    let new_sample: Option<PuzzleSampleGame> = pooled
+                        .then(|| sample_pool::to_sample(&p, &d_and_m))
+                        .flatten();
+                    GeneratedGame {
+                        seed,
+                        path: p,
+                        diamond_and_map: d_and_m,
+                        sample: new_sample,
+                        reproducible: true,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Cache for the next game.
+#[derive(Debug, Default)]
+pub struct GenerationCache {
+    /// Parameters of the game being generated or already generated.
+    key: Option<CacheKey>,
+
+    /// Generated game, when the background task is complete.
+    game: Rc<RefCell<Option<GeneratedGame>>>,
+
+    /// Identifier of the last background task. The results of the previous tasks are dropped.
+    serial: Rc<Cell<u64>>,
+}
+
+impl GenerationCache {
+    /// Start generating a game for the given puzzle in the background, unless the cache
+    /// already has, or is already generating, a game with the same parameters.
+    ///
+    /// The edges of the puzzle must be built. The pool samples are the fallback games that
+    /// complete the puzzle's sample list (see [`generate`]).
+    pub fn prepare(&mut self, key: CacheKey, puzzle: &Puzzle, pool: Vec<PuzzleSampleGame>) {
+        if self.key.as_ref() == Some(&key) {
+            return;
+        }
+        self.clear();
+
+        let serial: u64 = self.serial.get();
+        let seed: u64 = random_path::random_seed();
+        let endpoints: Endpoints = key.endpoints;
+        let negative_clues: bool = key.negative_clues;
+        let strict_diamonds: bool = key.strict_diamonds;
+        let puzzle: Puzzle = puzzle.clone();
+        let game: Rc<RefCell<Option<GeneratedGame>>> = Rc::clone(&self.game);
+        let current_serial: Rc<Cell<u64>> = Rc::clone(&self.serial);
+
+        debug!("Generating the next {} {:?} game", key.name, key.difficulty);
+        self.key = Some(key);
+        glib::spawn_future_local(async move {
+            let generated: GeneratedGame = gio::spawn_blocking(move || {
+                generate(
+                    &puzzle,
+                    seed,
+                    endpoints,
+                    negative_clues,
+                    strict_diamonds,
+                    &pool,
+                )
+            })
+            .await
+            .expect("Task needs to finish successfully");
+
+            // The cache was cleared in the meantime
+            if current_serial.get() != serial {
+                return;
+            }
+            debug!("Next game ready");
+            game.replace(Some(generated));
+        });
+    }
+
+    /// Return the generated game if it matches the given parameters and if its generation is
+    /// complete. The cache is empty afterward.
+    pub fn take(&mut self, key: &CacheKey) -> Option<GeneratedGame> {
+        let game: Option<GeneratedGame> = if self.key.as_ref() == Some(key) {
+            self.game.take()
+        } else {
+            None
+        };
+        self.clear();
+        game
+    }
+
+    /// Empty the cache and drop the result of the generation in progress, if any.
+    pub fn clear(&mut self) {
+        self.key = None;
+        self.game.replace(None);
+        self.serial.set(self.serial.get() + 1);
+    }
+}
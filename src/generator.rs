@@ -57,4 +57,7 @@ pub mod path;
 pub mod puzzle_parse;
 pub mod puzzles;
 pub mod random_path;
+pub mod regions;
+pub mod seed_code;
+pub mod share_code;
 pub mod vertexes;
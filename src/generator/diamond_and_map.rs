@@ -26,12 +26,97 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! numbers.
 //! A map is a cell where the number is already provided at the beginning
 //! of the puzzle. It provides a hint to the user.
+//!
+//! An anti-diamond is a marking between two adjacent cells that indicates that the numbers are
+//! not consecutive. Anti-diamonds are only generated for the negative clues variant.
+//! The anti-diamonds are not exhaustive: two adjacent cells without any marking can still hold
+//! consecutive numbers.
+//!
+//! In the strict diamonds variant, all the adjacent cells that hold consecutive numbers have a
+//! diamond, so that the absence of a marking guarantees non-consecutive numbers. The diamonds
+//! then show the whole path, and the player only has to number it from the revealed cells.
+//!
+//! By default, the starting and ending cells of the path are always mapped.
+//! The [`Endpoints`] option controls which of these cells are revealed.
 
+use gettextrs::gettext;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
+use strum_macros::FromRepr;
 
 use super::vertexes;
 
+/// Endpoints of the path that are revealed (mapped) at the beginning of the game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[repr(i32)]
+pub enum Endpoints {
+    #[default]
+    Both,
+    Start,
+    End,
+    None,
+}
+
+impl Endpoints {
+    /// All the options, from the easiest to the hardest.
+    pub const ALL: [Endpoints; 4] = [
+        Endpoints::Both,
+        Endpoints::Start,
+        Endpoints::End,
+        Endpoints::None,
+    ];
+
+    /// Return the name that identifies the option in the scoreboard keys, or None for the
+    /// default option, so that the scoreboards recorded before the option existed stay valid.
+    pub fn board_suffix(self) -> Option<&'static str> {
+        match self {
+            Endpoints::Both => None,
+            Endpoints::Start => Some("start"),
+            Endpoints::End => Some("end"),
+            Endpoints::None => Some("none"),
+        }
+    }
+
+    /// Return the option from its name in the scoreboard keys.
+    pub fn from_board_suffix(suffix: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|e| e.board_suffix() == Some(suffix))
+    }
+
+    /// Return the percentage of bonus points for solving the puzzle with hidden endpoints.
+    pub fn bonus_percent(self) -> u32 {
+        match self {
+            Endpoints::Both => 0,
+            Endpoints::Start | Endpoints::End => 15,
+            Endpoints::None => 30,
+        }
+    }
+
+    /// Return the description of the option for the scoreboards and the statistics, or None
+    /// for the default option.
+    pub fn description(self) -> Option<String> {
+        match self {
+            Endpoints::Both => None,
+            Endpoints::Start => Some(gettext("First Cell Only")),
+            Endpoints::End => Some(gettext("Last Cell Only")),
+            Endpoints::None => Some(gettext("No Revealed Endpoints")),
+        }
+    }
+
+    /// Whether the starting cell is revealed.
+    pub fn reveals_start(self) -> bool {
+        self == Endpoints::Both || self == Endpoints::Start
+    }
+
+    /// Whether the ending cell is revealed.
+    pub fn reveals_end(self) -> bool {
+        self == Endpoints::Both || self == Endpoints::End
+    }
+}
+
 /// Diamond representation.
 #[derive(Debug, Default, Clone)]
 struct Diamond {
@@ -82,6 +167,9 @@ pub struct DiamondAndMap {
     /// List of diamonds.
     diamonds: HashSet<Diamond>,
 
+    /// List of anti-diamonds (negative clues).
+    anti_diamonds: HashSet<Diamond>,
+
     /// List of hints.
     maps: HashSet<usize>,
 
@@ -93,6 +181,9 @@ pub struct DiamondAndMap {
 
     /// Final vertex.
     ending_vertex: usize,
+
+    /// Endpoints to reveal.
+    endpoints: Endpoints,
 }
 
 impl DiamondAndMap {
@@ -100,13 +191,25 @@ impl DiamondAndMap {
     pub fn new(num_vertexes: usize, starting_vertex: usize, ending_vertex: usize) -> Self {
         Self {
             diamonds: HashSet::new(),
+            anti_diamonds: HashSet::new(),
             maps: HashSet::with_capacity(num_vertexes),
             num_vertexes,
             starting_vertex,
             ending_vertex,
+            endpoints: Endpoints::Both,
         }
     }
 
+    /// Select the endpoints to reveal when computing the map list.
+    pub fn set_endpoints(&mut self, endpoints: Endpoints) {
+        self.endpoints = endpoints;
+    }
+
+    /// Map the given vertex.
+    pub fn insert_map(&mut self, vertex: usize) {
+        self.maps.insert(vertex);
+    }
+
     /// Create a [`DiamondAndMap`] object from the provided diamond and map lists.
     pub fn from_vec(
         diamond_list: &Vec<(u8, u8)>,
@@ -129,6 +232,7 @@ impl DiamondAndMap {
     /// Remove all the diamonds.
     pub fn clear(&mut self) {
         self.diamonds.clear();
+        self.anti_diamonds.clear();
     }
 
     /// Add a diamond to the object.
@@ -136,6 +240,11 @@ impl DiamondAndMap {
         self.diamonds.insert(Diamond { vertex1, vertex2 });
     }
 
+    /// Add an anti-diamond to the object.
+    pub fn insert_anti(&mut self, vertex1: usize, vertex2: usize) {
+        self.anti_diamonds.insert(Diamond { vertex1, vertex2 });
+    }
+
     /// Remove a diamond from the object.
     pub fn remove(&mut self, vertex1: usize, vertex2: usize) {
         self.diamonds.remove(&Diamond { vertex1, vertex2 });
@@ -145,9 +254,13 @@ impl DiamondAndMap {
     pub fn compute(&mut self, vertexes: &vertexes::Vertexes) {
         self.maps.clear();
 
-        // The starting and ending vertexes are always mapped
-        self.maps.insert(self.starting_vertex);
-        self.maps.insert(self.ending_vertex);
+        // The starting and ending vertexes are mapped, unless the player wants to find them
+        if self.endpoints.reveals_start() {
+            self.maps.insert(self.starting_vertex);
+        }
+        if self.endpoints.reveals_end() {
+            self.maps.insert(self.ending_vertex);
+        }
 
         // When a vertex has two opposite diamonds, then remove the diamonds and map the
         // destination vertexes.
@@ -179,9 +292,40 @@ impl DiamondAndMap {
             }
         }
 
-        // Mapped vertexes should not have diamonds. If a map vertex has a diamond, then remove
-        // the diamond, and map the destination vertex.
-        //
+        self.propagate_maps();
+
+        // Anti-diamonds between two mapped vertexes do not provide any information
+        let maps: &HashSet<usize> = &self.maps;
+        self.anti_diamonds
+            .retain(|d| !maps.contains(&d.vertex1) || !maps.contains(&d.vertex2));
+    }
+
+    /// Add a diamond between all the consecutive vertexes of the given path, and map the
+    /// revealed endpoints (strict diamonds variant).
+    ///
+    /// Unlike [`DiamondAndMap::compute`], the diamonds are kept on the mapped vertexes, so that
+    /// the absence of a diamond always means non-consecutive numbers. When no endpoint is
+    /// revealed, the starting vertex is mapped anyway, to give the direction of the path.
+    pub fn compute_strict(&mut self, path: &[usize]) {
+        self.diamonds.clear();
+        self.anti_diamonds.clear();
+        self.maps.clear();
+        for pair in path.windows(2) {
+            self.insert(pair[0], pair[1]);
+        }
+        if self.endpoints.reveals_start() || !self.endpoints.reveals_end() {
+            self.maps.insert(self.starting_vertex);
+        }
+        if self.endpoints.reveals_end() {
+            self.maps.insert(self.ending_vertex);
+        }
+    }
+
+    /// Remove the diamonds from the mapped vertexes.
+    ///
+    /// Mapped vertexes should not have diamonds. If a map vertex has a diamond, then remove
+    /// the diamond, and map the destination vertex.
+    pub fn propagate_maps(&mut self) {
         //  / \    / \           / \    / \
         // | 4 |<>|   |   ===>  | 4 |  | 5 |
         //  \ /    \ /           \ /    \ /
@@ -223,6 +367,14 @@ impl DiamondAndMap {
             .collect()
     }
 
+    /// Return the list of anti-diamonds.
+    pub fn get_anti_diamonds(&self) -> Vec<(usize, usize)> {
+        self.anti_diamonds
+            .iter()
+            .map(|d| (d.vertex1, d.vertex2))
+            .collect()
+    }
+
     /// Return the list of diamonds and the list of maps.
     pub fn get_diamond_and_map(&self) -> (Vec<(usize, usize)>, Vec<usize>) {
         (self.get_diamonds(), self.get_map())
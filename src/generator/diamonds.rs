@@ -28,6 +28,8 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! of the puzzle. It provides a hint to the user.
 
 use log::{Level, debug, log_enabled};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use std::time::Instant;
 
@@ -36,10 +38,12 @@ use super::edges;
 use super::path;
 use super::vertexes;
 
-// Max duration for trying to find an alternate path, otherwise an error is raised.
+// Max number of iterations for trying to find alternate paths, otherwise an error is raised.
 // For large puzzle, it might take too long to find alternate paths. In that case a precomputed
 // puzzle is used.
-const MAX_TIME_SEC: u64 = 6;
+// The limit does not depend on the duration, so that the seed produces the same diamonds on fast
+// and slow computers. It corresponds to about six seconds on a recent computer.
+const MAX_ITERATIONS: usize = 3_000_000;
 
 /// Type of errors.
 #[derive(Debug, PartialEq)]
@@ -47,8 +51,8 @@ pub enum DiamondError {
     /// No possible path.
     NoPath,
 
-    /// No path found before the timeout.
-    DurationExceeded,
+    /// No path found within the maximum number of iterations.
+    IterationsExceeded,
 }
 
 /// Diamond object.
@@ -68,6 +72,12 @@ pub struct Diamond<'a> {
     /// Last vertex.
     ending_vertex: usize,
 
+    /// Endpoints that are revealed to the player.
+    endpoints: diamond_and_map::Endpoints,
+
+    /// Whether to also generate anti-diamonds (negative clues).
+    negative_clues: bool,
+
     /// Temporary working alternate path.
     wpath: path::Path,
 
@@ -79,6 +89,9 @@ pub struct Diamond<'a> {
 
     /// Time when the alternate path research started. Used to compute the [`Diamond::duration`].
     start: Instant,
+
+    /// Random number generator for the order in which the diamonds are removed.
+    rng: StdRng,
 }
 
 impl<'a> Diamond<'a> {
@@ -104,13 +117,46 @@ impl<'a> Diamond<'a> {
             num_vertexes,
             starting_vertex: path.get()[0],
             ending_vertex: *path.get().last().unwrap_or(&0),
+            endpoints: diamond_and_map::Endpoints::Both,
+            negative_clues: false,
             wpath: path::Path::new(num_vertexes),
             iteration: 0,
             duration: 0.0,
             start: Instant::now(),
+            rng: StdRng::from_rng(&mut rand::rng()),
         }
     }
 
+    /// Initialize the random number generator from the given seed, so that the same seed
+    /// produces the same diamonds.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Select the endpoints to reveal to the player.
+    ///
+    /// When an endpoint is hidden, the search for alternate paths must consider all the possible
+    /// starting or ending vertexes, which takes longer. For large puzzles, the generation is
+    /// likely to exceed the maximum duration.
+    pub fn set_endpoints(&mut self, endpoints: diamond_and_map::Endpoints) {
+        self.endpoints = endpoints;
+    }
+
+    /// Enable or disable the generation of anti-diamonds (negative clues).
+    ///
+    /// When enabled, all the edges that are not part of the path start as anti-diamonds. The
+    /// anti-diamonds and the diamonds are then removed in a random order, as long as the path
+    /// stays unique.
+    pub fn set_negative_clues(&mut self, negative_clues: bool) {
+        self.negative_clues = negative_clues;
+    }
+
+    /// Whether the given vertex is an endpoint that the player knows.
+    fn is_known_endpoint(&self, vertex: usize) -> bool {
+        (self.endpoints.reveals_start() && vertex == self.starting_vertex)
+            || (self.endpoints.reveals_end() && vertex == self.ending_vertex)
+    }
+
     /// Generate and return diamonds and maps.
     pub fn generate_diamonds(
         &mut self,
@@ -123,6 +169,7 @@ impl<'a> Diamond<'a> {
                 self.starting_vertex,
                 self.ending_vertex,
             );
+        diamond_and_map.set_endpoints(self.endpoints);
 
         // Store deleted diamonds
         let mut deleted_diamonds: Vec<(usize, usize)> = Vec::with_capacity(self.num_vertexes);
@@ -130,8 +177,26 @@ impl<'a> Diamond<'a> {
         // Create a random list of diamonds. When starting, all the diamonds for all the edges
         // exist. Then, in each iteration, a diamond is removed and an alternate path is searched.
         // If such path exists, then the diamond is required, otherwise it is dropped.
-        let mut diamonds: Vec<usize> = Vec::from_iter(0..self.num_vertexes - 1);
-        diamonds.shuffle(&mut rand::rng());
+        // For negative clues, the list also includes the anti-diamonds, which are the edges that
+        // are not part of the path. The Boolean indicates an anti-diamond.
+        let mut diamonds: Vec<(usize, usize, bool)> = (0..self.num_vertexes - 1)
+            .map(|d| (self.path.get()[d], self.path.get()[d + 1], false))
+            .collect();
+        if self.negative_clues {
+            for vertex1 in 0..self.num_vertexes {
+                for vertex2 in self
+                    .edges
+                    .get_vertexes(vertex1, edges::EdgeStatus::Undecided)
+                {
+                    if vertex1 < vertex2 {
+                        self.edges
+                            .set_status(vertex1, vertex2, edges::EdgeStatus::Deleted);
+                        diamonds.push((vertex1, vertex2, true));
+                    }
+                }
+            }
+        }
+        diamonds.shuffle(&mut self.rng);
 
         if log_enabled!(Level::Debug) {
             debug!("Finding unique path");
@@ -145,12 +210,10 @@ impl<'a> Diamond<'a> {
         self.duration = 0.0;
         self.start = Instant::now();
 
-        for d in diamonds {
+        for (vertex1, vertex2, anti) in diamonds {
             let mut e: edges::Edges = self.edges.clone();
-            let vertex1: usize = self.path.get()[d];
-            let vertex2: usize = self.path.get()[d + 1];
 
-            debug!("=== deleting diamond {vertex1} <> {vertex2}");
+            debug!("=== deleting diamond {vertex1} <> {vertex2} (anti-diamond = {anti})");
             deleted_diamonds.push((vertex1, vertex2));
 
             // Synchronize the deleted diamonds with the edge list: for deleted diamonds, the
@@ -164,18 +227,21 @@ impl<'a> Diamond<'a> {
                 self.set_status_adjacent(*v2, &mut e);
             }
 
-            self.wpath.clear();
             // Search for an alternate path
-            match self.is_there_another_path(self.starting_vertex, &mut e) {
+            match self.search_alternate_path(&e) {
                 Ok(()) => {
                     debug!("    requiring diamond {vertex1} <> {vertex2}");
-                    self.edges
-                        .set_status(vertex1, vertex2, edges::EdgeStatus::Required);
                     deleted_diamonds.pop();
-                    diamond_and_map.insert(vertex1, vertex2);
+                    if anti {
+                        diamond_and_map.insert_anti(vertex1, vertex2);
+                    } else {
+                        self.edges
+                            .set_status(vertex1, vertex2, edges::EdgeStatus::Required);
+                        diamond_and_map.insert(vertex1, vertex2);
+                    }
                 }
                 Err(e) => {
-                    if e == DiamondError::DurationExceeded {
+                    if e == DiamondError::IterationsExceeded {
                         self.duration = self.start.elapsed().as_secs_f32();
                         return Err(e);
                     }
@@ -188,9 +254,46 @@ impl<'a> Diamond<'a> {
             self.iteration, self.duration
         );
         diamond_and_map.compute(vertexes);
+
+        // Without endpoints, the reversed path is also a solution. At least one hint must
+        // orient the path.
+        if !self.endpoints.reveals_start() && !self.endpoints.reveals_end() {
+            let oriented: bool = diamond_and_map.get_map().iter().any(|v| {
+                self.path
+                    .vertex_index(*v)
+                    .is_some_and(|i| 2 * i + 1 != self.num_vertexes)
+            });
+            if !oriented {
+                diamond_and_map.insert_map(self.starting_vertex);
+                diamond_and_map.propagate_maps();
+            }
+        }
         Ok(diamond_and_map)
     }
 
+    /// Search for an alternate path from all the possible starting vertexes.
+    fn search_alternate_path(&mut self, edges: &edges::Edges) -> Result<(), DiamondError> {
+        if self.endpoints.reveals_start() {
+            self.wpath.clear();
+            return self.is_there_another_path(self.starting_vertex, &mut edges.clone());
+        }
+
+        for vertex in 0..self.num_vertexes {
+            if self.endpoints.reveals_end() && vertex == self.ending_vertex {
+                continue;
+            }
+            self.wpath.clear();
+            match self.is_there_another_path(vertex, &mut edges.clone()) {
+                Ok(()) => return Ok(()),
+                Err(DiamondError::IterationsExceeded) => {
+                    return Err(DiamondError::IterationsExceeded);
+                }
+                Err(DiamondError::NoPath) => (),
+            }
+        }
+        Err(DiamondError::NoPath)
+    }
+
     // Search for an alternate path.
     fn is_there_another_path(
         &mut self,
@@ -207,8 +310,14 @@ impl<'a> Diamond<'a> {
         }
         self.wpath.push(current_vertex);
 
-        // The end vertex has been reached
-        if current_vertex == self.ending_vertex {
+        // The end vertex has been reached. When the ending vertex is hidden, any vertex can end
+        // the path once all the vertexes are visited.
+        let end_reached: bool = if self.endpoints.reveals_end() {
+            current_vertex == self.ending_vertex
+        } else {
+            self.wpath.len() == self.num_vertexes
+        };
+        if end_reached {
             if log_enabled!(Level::Debug) {
                 debug!("   End reached");
                 debug!("     current path = {:?}", self.wpath.get());
@@ -224,14 +333,24 @@ impl<'a> Diamond<'a> {
                 self.wpath.pop();
                 return Err(DiamondError::NoPath);
             }
+            // Without endpoints, the reversed path is not an alternate path: a hint orients the
+            // path at the end of the generation
+            if !self.endpoints.reveals_start()
+                && !self.endpoints.reveals_end()
+                && self.wpath.get().iter().eq(self.path.get().iter().rev())
+            {
+                debug!("   the paths are reversed");
+                self.wpath.pop();
+                return Err(DiamondError::NoPath);
+            }
             // An alternate path exists
             debug!("   the paths have the same size but are different: alternate path found");
             return Ok(());
         }
 
         self.iteration += 1;
-        if self.start.elapsed().as_secs() >= MAX_TIME_SEC {
-            return Err(DiamondError::DurationExceeded);
+        if self.iteration >= MAX_ITERATIONS {
+            return Err(DiamondError::IterationsExceeded);
         }
 
         // Verify quickly if there is a required edge
@@ -283,7 +402,7 @@ impl<'a> Diamond<'a> {
             match self.is_there_another_path(v2, &mut new_edges) {
                 Ok(()) => return Ok(()),
                 Err(e) => {
-                    if e == DiamondError::DurationExceeded {
+                    if e == DiamondError::IterationsExceeded {
                         return Err(e);
                     }
                 }
@@ -306,7 +425,7 @@ impl<'a> Diamond<'a> {
         let mut vertexes_to_update: Vec<usize> = Vec::with_capacity(self.num_vertexes);
 
         // For the starting vertex, keep the required edge, but remove all the other edges
-        if self.starting_vertex == v1 || self.ending_vertex == v1 {
+        if self.is_known_endpoint(v1) {
             // Ensure that at least one edge is required
             if !edges
                 .get_vertexes(v1, edges::EdgeStatus::Required)
@@ -350,8 +469,9 @@ impl<'a> Diamond<'a> {
                 debug!("   Edge {v1}-{v2} deleted");
             }
         }
-        // If the vertex has two edges or less, then all are required
-        else if num_edges <= 2 {
+        // If the vertex has two edges or less, then all are required. This is not true when an
+        // endpoint is hidden, because the vertex might be that endpoint.
+        else if num_edges <= 2 && self.endpoints.reveals_start() && self.endpoints.reveals_end() {
             for v2 in edges.get_vertexes(v1, edges::EdgeStatus::Undecided) {
                 edges.set_status(v1, v2, edges::EdgeStatus::Required);
                 vertexes_to_update.push(v2);
@@ -38,15 +38,26 @@ pub struct PuzzleParse {
 
     /// Vertex matrix.
     pub vertexes: vertexes::Vertexes,
+
+    /// Error from the verification of the source puzzle size, reported when the puzzle is
+    /// parsed.
+    #[serde(skip)]
+    size_error: Option<String>,
 }
 
 /// Puzzle parsing object.
 impl PuzzleParse {
     /// Create a [`PuzzleParse`] object.
+    ///
+    /// An oversized source puzzle is not kept. The [`PuzzleParse::build_edges`] method then
+    /// returns the error.
     pub fn new(puzzle: &str) -> Self {
+        let size_error: Option<String> = vertexes::check_size(puzzle).err();
+        let source: &str = if size_error.is_some() { "" } else { puzzle };
         Self {
             edges: edges::Edges::new(),
-            vertexes: vertexes::Vertexes::new(puzzle),
+            vertexes: vertexes::Vertexes::new(source),
+            size_error,
         }
     }
 
@@ -56,11 +67,15 @@ impl PuzzleParse {
     ///
     /// The method returns an error when the source puzzle as errors, such as an isolated vertex
     /// (with no edges), or several vertexes with only one edge (only one such vertex is allowed;
-    /// it becomes the starting vertex).
+    /// it becomes the starting vertex), or when the source puzzle is too large.
     pub fn build_edges(&mut self) -> Result<(), String> {
+        if let Some(e) = &self.size_error {
+            return Err(e.clone());
+        }
+
         // Parse the source puzzle in its intermediate representation
         if self.vertexes.num_vertexes == 0 {
-            self.vertexes.build();
+            self.vertexes.build()?;
         }
 
         self.edges.clear();
@@ -106,11 +121,9 @@ impl PuzzleParse {
                     return Err(format!("Vertex {v1} does not have any edges"));
                 }
                 if num_edges == 1 {
-                    if self.vertexes.required_starting_vertex.is_some() {
+                    if let Some(v0) = self.vertexes.required_starting_vertex {
                         return Err(format!(
-                            "Vertexes {} and {} have only one edge (only one such vertex is allowed)",
-                            self.vertexes.required_starting_vertex.unwrap(),
-                            v1
+                            "Vertexes {v0} and {v1} have only one edge (only one such vertex is allowed)"
                         ));
                     }
                     self.vertexes.required_starting_vertex = Some(v1);
@@ -133,3 +146,31 @@ impl PuzzleParse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oversized_puzzles_are_rejected() {
+        let wide_row: String = "o ".repeat(vertexes::MAX_WIDTH);
+        let tall: String = " o\no\n".repeat(vertexes::MAX_HEIGHT);
+        let area: String = format!("{}\n", "x".repeat(200)).repeat(100);
+        let cells: String = "o o o o o o o o o o\n o o o o o o o o o\n".repeat(25);
+        let long: String = " ".repeat(vertexes::MAX_SOURCE_LENGTH + 1);
+
+        for source in [&wide_row, &tall, &area, &cells, &long] {
+            let mut puzzle: PuzzleParse = PuzzleParse::new(source);
+            assert!(puzzle.build_edges().is_err());
+            assert_eq!(puzzle.vertexes.source(), "");
+
+            let mut vertexes: vertexes::Vertexes = vertexes::Vertexes::new(source);
+            assert!(vertexes.build().is_err());
+            assert_eq!((vertexes.width, vertexes.height), (0, 0));
+        }
+
+        let mut puzzle: PuzzleParse = PuzzleParse::new(" o o\no o o\n o o\n");
+        assert!(puzzle.build_edges().is_ok());
+        assert_eq!(puzzle.vertexes.num_vertexes, 7);
+    }
+}
@@ -34,12 +34,15 @@ pub mod medium_heart_45;
 pub mod medium_square_38;
 
 use super::puzzle_parse;
+use super::regions::Region;
+use super::vertexes::{self, Vertexes};
 use clap::ValueEnum;
 use gettextrs::gettext;
 use gtk::glib;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::str::FromStr;
 use strum_macros::FromRepr;
 
 /// Puzzle difficulty level.
@@ -77,6 +80,18 @@ impl fmt::Display for Difficulty {
     }
 }
 
+impl Difficulty {
+    /// Return the number of cells that players can solve with the Solve Current Cell action
+    /// in each game, unless they enable the unlimited assistance.
+    pub fn solve_cell_budget(self) -> usize {
+        match self {
+            Difficulty::Easy => 5,
+            Difficulty::Medium => 3,
+            Difficulty::Hard => 1,
+        }
+    }
+}
+
 /// Cell colors.
 ///
 /// Color components are integers between 0 and 255.
@@ -320,6 +335,17 @@ impl PuzzleCustomColor {
     }
 }
 
+/// Named color palettes that replace all the custom colors at once.
+/// See [`PuzzleColorTheme::preset`].
+#[derive(Debug, Copy, Clone, PartialEq, FromRepr)]
+#[repr(i32)]
+pub enum ColorPreset {
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
 /// Manage the colors for the puzzle.
 #[derive(Debug, Clone)]
 pub struct PuzzleColorTheme {
@@ -337,11 +363,83 @@ pub struct PuzzleColorTheme {
 }
 
 impl PuzzleColorTheme {
+    /// Return the custom colors of the given palette preset.
+    ///
+    /// The color vision deficiency presets use colors from the Okabe-Ito palette, so that the
+    /// wrong values, the selected cell, and the path stay distinguishable from each other. The
+    /// high-contrast preset uses black and white cells with a yellow selection.
+    pub fn preset(preset: ColorPreset) -> PuzzleCustomColor {
+        // Text, wrong text, background, hint background, selection, borders, and path
+        let colors: [(f64, f64, f64, f64); 7] = match preset {
+            ColorPreset::Deuteranopia => [
+                (0.0, 0.0, 0.0, 1.0),
+                (0.835, 0.369, 0.0, 1.0),
+                (0.98, 0.98, 0.98, 1.0),
+                (0.8, 0.882, 0.941, 1.0),
+                (0.902, 0.624, 0.0, 1.0),
+                (0.0, 0.0, 0.0, 1.0),
+                (0.0, 0.447, 0.698, 0.6),
+            ],
+            ColorPreset::Protanopia => [
+                (0.0, 0.0, 0.0, 1.0),
+                (0.0, 0.447, 0.698, 1.0),
+                (0.98, 0.98, 0.98, 1.0),
+                (0.878, 0.878, 0.878, 1.0),
+                (0.941, 0.894, 0.259, 1.0),
+                (0.0, 0.0, 0.0, 1.0),
+                (0.902, 0.624, 0.0, 0.6),
+            ],
+            ColorPreset::Tritanopia => [
+                (0.0, 0.0, 0.0, 1.0),
+                (0.835, 0.369, 0.0, 1.0),
+                (0.98, 0.98, 0.98, 1.0),
+                (0.878, 0.878, 0.878, 1.0),
+                (0.6, 0.847, 0.788, 1.0),
+                (0.0, 0.0, 0.0, 1.0),
+                (0.8, 0.475, 0.655, 0.6),
+            ],
+            ColorPreset::HighContrast => [
+                (0.0, 0.0, 0.0, 1.0),
+                (0.8, 0.0, 0.0, 1.0),
+                (1.0, 1.0, 1.0, 1.0),
+                (0.749, 0.749, 0.749, 1.0),
+                (1.0, 0.851, 0.0, 1.0),
+                (0.0, 0.0, 0.0, 1.0),
+                (0.0, 0.0, 0.0, 0.8),
+            ],
+        };
+        let [text, text_wrong, bg, bg_map, selection, border, path] = colors;
+
+        let mut custom: PuzzleCustomColor = PuzzleCustomColor::new();
+        custom.set_text(text.0, text.1, text.2, text.3);
+        custom.set_custom_text(true);
+        custom.set_text_wrong(text_wrong.0, text_wrong.1, text_wrong.2, text_wrong.3);
+        custom.set_custom_text_wrong(true);
+        custom.set_bg(bg.0, bg.1, bg.2, bg.3);
+        custom.set_custom_bg(true);
+        custom.set_bg_map(bg_map.0, bg_map.1, bg_map.2, bg_map.3);
+        custom.set_custom_bg_map(true);
+        custom.set_selection(selection.0, selection.1, selection.2, selection.3);
+        custom.set_custom_selection(true);
+        custom.set_border(border.0, border.1, border.2, border.3);
+        custom.set_custom_border(true);
+        custom.set_path(path.0, path.1, path.2, path.3);
+        custom.set_custom_path(true);
+        custom
+    }
+
     /// Switch to the dark theme.
     pub fn set_dark(&mut self, is_dark: bool) {
         self.is_dark = is_dark;
     }
 
+    /// Replace the default colors for the light and the dark themes.
+    /// The colors set by the user still overwrite these colors.
+    pub fn set_palettes(&mut self, light: PuzzleColor, dark: PuzzleColor) {
+        self.light = light;
+        self.dark = dark;
+    }
+
     /// Convert a color in the 0-255 range to the 0-1 range.
     fn to_cairo(&self, color: (u8, u8, u8, u8)) -> (f64, f64, f64, f64) {
         (
@@ -475,6 +573,7 @@ impl PuzzleColorTheme {
 }
 
 /// Random puzzle parameters.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PuzzleSampleGame {
     /// Path as a list of cell IDs.
     pub path: Vec<u8>,
@@ -514,7 +613,8 @@ pub struct PuzzleParameters<'a> {
     pub matrix: &'a str,
 
     /// Function to retrieve a static random puzzle in case generating the puzzle takes too long.
-    pub get_sample_path_fn: fn() -> PuzzleSampleGame,
+    /// The seed selects the sample.
+    pub get_sample_path_fn: fn(u64) -> PuzzleSampleGame,
 }
 
 /// Puzzle parameters.
@@ -541,8 +641,8 @@ pub struct Puzzle {
     /// Cell colors.
     pub colors: PuzzleColorTheme,
 
-    /// Return a puzzle path from a sample path list.
-    pub get_sample_path_fn: fn() -> PuzzleSampleGame,
+    /// Return a puzzle path from a sample path list. The seed selects the sample.
+    pub get_sample_path_fn: fn(u64) -> PuzzleSampleGame,
 }
 
 impl Default for Puzzle {
@@ -581,7 +681,7 @@ impl Default for Puzzle {
                 is_dark: false,
             },
 
-            get_sample_path_fn: || -> PuzzleSampleGame {
+            get_sample_path_fn: |_| -> PuzzleSampleGame {
                 PuzzleSampleGame {
                     path: Vec::new(),
                     diamonds: Vec::new(),
@@ -616,6 +716,13 @@ impl Puzzle {
     pub fn set_dark(&mut self, is_dark: bool) {
         self.colors.set_dark(is_dark);
     }
+
+    /// Whether Hexkudo provides the puzzle, with the same matrix.
+    pub fn is_builtin(&self) -> bool {
+        puzzle_map()
+            .get(&(self.name.clone(), self.difficulty))
+            .is_some_and(|p| p.matrix.vertexes.source() == self.matrix.vertexes.source())
+    }
 }
 
 /// Return the puzzle list, indexed by name and difficulty.
@@ -652,3 +759,277 @@ pub fn puzzle_map() -> HashMap<(String, Difficulty), Puzzle> {
 
     puzzles
 }
+
+/// Version of the puzzle text format.
+const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// Maximum number of cells of the imported puzzles.
+pub const MAX_VERTEXES: usize = 400;
+
+/// Puzzle with its solution path and clues, which players can share.
+///
+/// The text format lists one `key: value` pair per line, and ends with the "ASCII art" matrix of
+/// the puzzle. The lines that start with `#` are comments.
+///
+/// ```text
+/// # Hexkudo puzzle
+/// format: 1
+/// name: Classic
+/// difficulty: easy
+/// path: 3 6 7 8 4 1 0 2 5 9 13 10 14 17 18 20 21 19 15 11 12 16
+/// map: 3 6 8 16
+/// diamonds: 18-20 9-13 17-18 13-10
+/// anti-diamonds:
+/// regions:
+/// matrix:
+///    o o
+///   o o o
+///  o o o o
+/// o o x o o
+///  o o o o
+///   o o o
+///    o o
+/// ```
+///
+/// The regions are listed as `sum=cell,cell,...` items.
+/// When importing a puzzle, the colors and the icons come from the puzzle with the same name and
+/// difficulty level, if Hexkudo provides it.
+#[derive(Debug, Clone)]
+pub struct PuzzleExport {
+    /// Puzzle parameters, with the imported matrix.
+    pub puzzle: Puzzle,
+
+    /// Solution path as a list of cell IDs.
+    pub path: Vec<usize>,
+
+    /// List of mapped cells (hints).
+    pub map: Vec<usize>,
+
+    /// List of diamonds.
+    pub diamonds: Vec<(usize, usize)>,
+
+    /// List of anti-diamonds (negative clues variant).
+    pub anti_diamonds: Vec<(usize, usize)>,
+
+    /// List of regions with their sums (sum clues variant).
+    pub regions: Vec<Region>,
+}
+
+/// Return the name of the difficulty level in the puzzle text format.
+pub fn difficulty_name(difficulty: Difficulty) -> &'static str {
+    match difficulty {
+        Difficulty::Easy => "easy",
+        Difficulty::Medium => "medium",
+        Difficulty::Hard => "hard",
+    }
+}
+
+/// Parse a cell ID.
+fn parse_cell(value: &str) -> Result<usize, String> {
+    value
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| format!("Invalid cell ID: {value}"))
+}
+
+/// Parse a space separated list of cell pairs, such as `1-2 5-8`.
+fn parse_pairs(value: &str) -> Result<Vec<(usize, usize)>, String> {
+    value
+        .split_whitespace()
+        .map(|pair| {
+            let (a, b) = pair
+                .split_once('-')
+                .ok_or_else(|| format!("Invalid pair of cells: {pair}"))?;
+            Ok((parse_cell(a)?, parse_cell(b)?))
+        })
+        .collect()
+}
+
+/// Format a list of cell pairs as a space separated list, such as `1-2 5-8`.
+fn format_pairs(pairs: &[(usize, usize)]) -> String {
+    pairs
+        .iter()
+        .map(|(a, b)| format!("{a}-{b}"))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Format a list of cell IDs as a space separated list.
+fn format_cells(cells: &[usize]) -> String {
+    cells
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+impl PuzzleExport {
+    /// Verify that the path, the clues, and the regions match the puzzle matrix.
+    fn validate(&self) -> Result<(), String> {
+        let vertexes: &Vertexes = &self.puzzle.matrix.vertexes;
+        let num_vertexes: usize = vertexes.num_vertexes;
+
+        if num_vertexes > MAX_VERTEXES {
+            return Err(format!("The puzzle has more than {MAX_VERTEXES} cells"));
+        }
+        if self.path.len() != num_vertexes {
+            return Err(format!(
+                "The path has {} cells instead of {num_vertexes}",
+                self.path.len()
+            ));
+        }
+        let mut seen: HashSet<usize> = HashSet::with_capacity(num_vertexes);
+        for cell_id in &self.path {
+            if *cell_id >= num_vertexes || !seen.insert(*cell_id) {
+                return Err(format!("Invalid or duplicated cell in the path: {cell_id}"));
+            }
+        }
+        if let Some(pair) = self
+            .path
+            .windows(2)
+            .find(|w| !vertexes.is_adjacent(w[0], w[1]))
+        {
+            return Err(format!(
+                "The path goes through non-adjacent cells: {} and {}",
+                pair[0], pair[1]
+            ));
+        }
+
+        let cells = self
+            .map
+            .iter()
+            .chain(self.diamonds.iter().flat_map(|(a, b)| [a, b]))
+            .chain(self.anti_diamonds.iter().flat_map(|(a, b)| [a, b]))
+            .chain(self.regions.iter().flat_map(|r| r.cells.iter()));
+        for cell_id in cells {
+            if *cell_id >= num_vertexes {
+                return Err(format!("Invalid cell ID: {cell_id}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PuzzleExport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let regions: Vec<String> = self
+            .regions
+            .iter()
+            .map(|r| {
+                let cells: Vec<String> = r.cells.iter().map(|c| c.to_string()).collect();
+                format!("{}={}", r.sum, cells.join(","))
+            })
+            .collect();
+
+        writeln!(f, "# Hexkudo puzzle")?;
+        writeln!(f, "format: {EXPORT_FORMAT_VERSION}")?;
+        writeln!(f, "name: {}", self.puzzle.name)?;
+        writeln!(f, "difficulty: {}", difficulty_name(self.puzzle.difficulty))?;
+        writeln!(f, "path: {}", format_cells(&self.path))?;
+        writeln!(f, "map: {}", format_cells(&self.map))?;
+        writeln!(f, "diamonds: {}", format_pairs(&self.diamonds))?;
+        writeln!(f, "anti-diamonds: {}", format_pairs(&self.anti_diamonds))?;
+        writeln!(f, "regions: {}", regions.join(" "))?;
+        writeln!(f, "matrix:")?;
+        for line in self.puzzle.matrix.vertexes.source().lines() {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PuzzleExport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut name: Option<String> = None;
+        let mut difficulty: Option<Difficulty> = None;
+        let mut path: Vec<usize> = Vec::new();
+        let mut map: Vec<usize> = Vec::new();
+        let mut diamonds: Vec<(usize, usize)> = Vec::new();
+        let mut anti_diamonds: Vec<(usize, usize)> = Vec::new();
+        let mut regions: Vec<Region> = Vec::new();
+        let mut matrix: Option<String> = None;
+
+        let mut lines = s.lines();
+        while let Some(line) = lines.next() {
+            let line: &str = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid line: {line}"))?;
+            let value: &str = value.trim();
+            match key.trim() {
+                "format" => {
+                    if value.parse::<u32>().ok() != Some(EXPORT_FORMAT_VERSION) {
+                        return Err(format!("Unsupported format version: {value}"));
+                    }
+                }
+                "name" => name = Some(value.to_string()),
+                "difficulty" => difficulty = Some(Difficulty::from_str(value, true)?),
+                "path" => {
+                    path = value
+                        .split_whitespace()
+                        .map(parse_cell)
+                        .collect::<Result<_, _>>()?
+                }
+                "map" => {
+                    map = value
+                        .split_whitespace()
+                        .map(parse_cell)
+                        .collect::<Result<_, _>>()?
+                }
+                "diamonds" => diamonds = parse_pairs(value)?,
+                "anti-diamonds" => anti_diamonds = parse_pairs(value)?,
+                "regions" => {
+                    for region in value.split_whitespace() {
+                        let (sum, cells) = region
+                            .split_once('=')
+                            .ok_or_else(|| format!("Invalid region: {region}"))?;
+                        regions.push(Region {
+                            cells: cells.split(',').map(parse_cell).collect::<Result<_, _>>()?,
+                            sum: sum
+                                .parse::<usize>()
+                                .map_err(|_| format!("Invalid region sum: {sum}"))?,
+                        });
+                    }
+                }
+                // The matrix runs to the end of the text
+                "matrix" => {
+                    matrix = Some(lines.by_ref().collect::<Vec<&str>>().join("\n"));
+                }
+                k => return Err(format!("Unknown key: {k}")),
+            }
+        }
+
+        let name: String = name.ok_or("The puzzle name is missing")?;
+        let difficulty: Difficulty = difficulty.ok_or("The difficulty level is missing")?;
+        let matrix: String = matrix.ok_or("The puzzle matrix is missing")?;
+        vertexes::check_size(&matrix)?;
+
+        // Reuse the colors and the icons of the puzzle that Hexkudo provides, if any
+        let mut puzzle: Puzzle = puzzle_map()
+            .remove(&(name.clone(), difficulty))
+            .unwrap_or_else(|| Puzzle {
+                name: name.clone(),
+                name_i18n: name,
+                difficulty,
+                ..Default::default()
+            });
+        puzzle.matrix = puzzle_parse::PuzzleParse::new(&matrix);
+        puzzle.matrix.build_edges()?;
+
+        let export = PuzzleExport {
+            puzzle,
+            path,
+            map,
+            diamonds,
+            anti_diamonds,
+            regions,
+        };
+        export.validate()?;
+        Ok(export)
+    }
+}
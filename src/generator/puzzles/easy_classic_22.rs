@@ -40,7 +40,7 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //!     $ flatpak run io.github.herve4m.Hexkudo -c 100 -p <puzzle_name> -f <puzzle_difficulty>
 //!
 //!     /// Return a tuple with the path, the diamond, and the map lists.
-//!     pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
+//!     pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {
 //!         let sample_path: [[u8; 22]; 100] = [
 //!             [13, 9, 10, 6, 5, 2, 3, 0, 1, 4, 7, 11, 8, 12, 16, 19, 21, 20, 17, 14, 15, 18],
 //!             [1, 3, 0, 2, 6, 7, 4, 8, 11, 12, 16, 15, 14, 18, 19, 21, 20, 17, 13, 9, 5, 10],
@@ -58,7 +58,7 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //!             [10, 5, 3, 1].to_vec(),
 //!             [14, 3, 19, 15, 0].to_vec(),
 //!         ];
-//!         let i:usize = rand::rng().random_range(0..sample_path.len());
+//!         let i: usize = (seed % sample_path.len() as u64) as usize;
 //!         puzzles::PuzzleSampleGame {
 //!             path: Vec::from(sample_path[i]),
 //!             diamonds: sample_diamonds[i].clone(),
@@ -73,7 +73,6 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::generator::puzzles;
 use gettextrs::gettext;
-use rand::Rng;
 
 /// Puzzle's name
 pub const NAME: &str = "Classic";
@@ -196,7 +195,7 @@ pub fn get() -> puzzles::Puzzle {
 /// ```
 /// $ flatpak run io.github.herve4m.Hexkudo -c 100 -p <puzzle_name> -f <puzzle_difficulty>
 /// ```
-pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
+pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {
     let sample_path: [[u8; 22]; 100] = [
         [
             11, 7, 4, 8, 12, 16, 19, 21, 20, 18, 15, 14, 17, 13, 10, 9, 5, 6, 2, 3, 1, 0,
@@ -838,7 +837,7 @@ pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
         [3, 16, 15, 2].to_vec(),
         [15, 16, 0, 3].to_vec(),
     ];
-    let i: usize = rand::rng().random_range(0..sample_path.len());
+    let i: usize = (seed % sample_path.len() as u64) as usize;
     puzzles::PuzzleSampleGame {
         path: Vec::from(sample_path[i]),
         diamonds: sample_diamonds[i].clone(),
@@ -26,7 +26,6 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::generator::puzzles;
 use gettextrs::gettext;
-use rand::Rng;
 
 pub const NAME: &str = "Heart";
 pub const DIFFICULTY: puzzles::Difficulty = puzzles::Difficulty::Easy;
@@ -119,7 +118,7 @@ pub fn get() -> puzzles::Puzzle {
 }
 
 /// Return a tuple with the path, the diamond, and the map lists.
-pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
+pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {
     let sample_path: [[u8; 24]; 100] = [
         [
             4, 10, 11, 15, 16, 17, 20, 19, 22, 23, 21, 18, 14, 13, 8, 9, 3, 2, 7, 12, 6, 5, 0, 1,
@@ -765,7 +764,7 @@ pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
         [22, 20, 15].to_vec(),
         [18, 6, 11].to_vec(),
     ];
-    let i: usize = rand::rng().random_range(0..sample_path.len());
+    let i: usize = (seed % sample_path.len() as u64) as usize;
     puzzles::PuzzleSampleGame {
         path: Vec::from(sample_path[i]),
         diamonds: sample_diamonds[i].clone(),
@@ -26,7 +26,6 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::generator::puzzles;
 use gettextrs::gettext;
-use rand::Rng;
 
 pub const NAME: &str = "Square";
 pub const DIFFICULTY: puzzles::Difficulty = puzzles::Difficulty::Easy;
@@ -117,7 +116,7 @@ pub fn get() -> puzzles::Puzzle {
 }
 
 /// Return a tuple with the path, the diamond, and the map lists.
-pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
+pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {
     let sample_path: [[u8; 22]; 100] = [
         [
             3, 7, 6, 2, 1, 0, 5, 9, 10, 14, 13, 17, 18, 19, 15, 20, 21, 16, 11, 12, 8, 4,
@@ -624,7 +623,7 @@ pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
         [2, 0].to_vec(),
         [12, 17].to_vec(),
     ];
-    let i: usize = rand::rng().random_range(0..sample_path.len());
+    let i: usize = (seed % sample_path.len() as u64) as usize;
     puzzles::PuzzleSampleGame {
         path: Vec::from(sample_path[i]),
         diamonds: sample_diamonds[i].clone(),
@@ -26,7 +26,6 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::generator::puzzles;
 use gettextrs::gettext;
-use rand::Rng;
 
 pub const NAME: &str = "Classic";
 pub const DIFFICULTY: puzzles::Difficulty = puzzles::Difficulty::Hard;
@@ -121,7 +120,7 @@ pub fn get() -> puzzles::Puzzle {
 }
 
 /// Return a tuple with the path, the diamond, and the map lists.
-pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
+pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {
     let sample_path: [[u8; 60]; 100] = [
         [
             27, 26, 18, 19, 11, 5, 0, 1, 7, 2, 3, 8, 9, 4, 10, 16, 17, 25, 33, 32, 41, 48, 47, 46,
@@ -2661,7 +2660,7 @@ pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
         [59, 50, 46, 49, 40].to_vec(),
         [28, 22, 30, 45, 4, 43, 23, 2, 35, 44, 9, 15, 18].to_vec(),
     ];
-    let i: usize = rand::rng().random_range(0..sample_path.len());
+    let i: usize = (seed % sample_path.len() as u64) as usize;
     puzzles::PuzzleSampleGame {
         path: Vec::from(sample_path[i]),
         diamonds: sample_diamonds[i].clone(),
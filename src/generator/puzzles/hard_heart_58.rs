@@ -26,7 +26,6 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::generator::puzzles;
 use gettextrs::gettext;
-use rand::Rng;
 
 pub const NAME: &str = "Heart";
 pub const DIFFICULTY: puzzles::Difficulty = puzzles::Difficulty::Hard;
@@ -123,7 +122,7 @@ pub fn get() -> puzzles::Puzzle {
 }
 
 /// Return a tuple with the path, the diamond, and the map lists.
-pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
+pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {
     let sample_path: [[u8; 58]; 100] = [
         [
             47, 42, 35, 34, 41, 46, 50, 54, 56, 57, 55, 51, 52, 48, 43, 37, 30, 21, 22, 13, 12, 5,
@@ -2587,7 +2586,7 @@ pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
         [18, 10, 26, 25, 41, 7, 28, 16, 27].to_vec(),
         [18, 1, 32, 15, 25, 11, 20, 7, 40, 27].to_vec(),
     ];
-    let i: usize = rand::rng().random_range(0..sample_path.len());
+    let i: usize = (seed % sample_path.len() as u64) as usize;
     puzzles::PuzzleSampleGame {
         path: Vec::from(sample_path[i]),
         diamonds: sample_diamonds[i].clone(),
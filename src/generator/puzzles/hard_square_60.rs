@@ -26,7 +26,6 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::generator::puzzles;
 use gettextrs::gettext;
-use rand::Rng;
 
 pub const NAME: &str = "Square";
 pub const DIFFICULTY: puzzles::Difficulty = puzzles::Difficulty::Hard;
@@ -121,7 +120,7 @@ pub fn get() -> puzzles::Puzzle {
 }
 
 /// Return a tuple with the path, the diamond, and the map lists.
-pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
+pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {
     let sample_path: [[u8; 60]; 100] = [
         [
             54, 55, 56, 57, 51, 50, 44, 43, 49, 48, 42, 47, 41, 34, 27, 26, 19, 13, 14, 20, 21, 28,
@@ -2600,7 +2599,7 @@ pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
         [33, 29].to_vec(),
         [58, 36, 14, 10, 52, 22, 56, 9, 7, 4].to_vec(),
     ];
-    let i: usize = rand::rng().random_range(0..sample_path.len());
+    let i: usize = (seed % sample_path.len() as u64) as usize;
     puzzles::PuzzleSampleGame {
         path: Vec::from(sample_path[i]),
         diamonds: sample_diamonds[i].clone(),
@@ -26,7 +26,6 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::generator::puzzles;
 use gettextrs::gettext;
-use rand::Rng;
 
 pub const NAME: &str = "Classic";
 pub const DIFFICULTY: puzzles::Difficulty = puzzles::Difficulty::Medium;
@@ -119,7 +118,7 @@ pub fn get() -> puzzles::Puzzle {
 }
 
 /// Return a tuple with the path, the diamond, and the map lists.
-pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
+pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {
     let sample_path: [[u8; 36]; 100] = [
         [
             27, 21, 16, 15, 9, 10, 4, 0, 1, 6, 5, 11, 17, 23, 22, 28, 32, 33, 34, 30, 29, 24, 25,
@@ -1575,7 +1574,7 @@ pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
         [31, 24, 26, 23, 30].to_vec(),
         [12, 10, 25, 18].to_vec(),
     ];
-    let i: usize = rand::rng().random_range(0..sample_path.len());
+    let i: usize = (seed % sample_path.len() as u64) as usize;
     puzzles::PuzzleSampleGame {
         path: Vec::from(sample_path[i]),
         diamonds: sample_diamonds[i].clone(),
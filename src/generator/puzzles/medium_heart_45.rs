@@ -26,7 +26,6 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::generator::puzzles;
 use gettextrs::gettext;
-use rand::Rng;
 
 pub const NAME: &str = "Heart";
 pub const DIFFICULTY: puzzles::Difficulty = puzzles::Difficulty::Medium;
@@ -122,7 +121,7 @@ pub fn get() -> puzzles::Puzzle {
 }
 
 /// Return a tuple with the path, the diamond, and the map lists.
-pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
+pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {
     let sample_path: [[u8; 45]; 100] = [
         [
             2, 7, 14, 15, 16, 8, 3, 9, 17, 24, 30, 23, 29, 35, 34, 33, 38, 39, 42, 41, 44, 43, 40,
@@ -1959,7 +1958,7 @@ pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
         [12, 43, 41, 14, 2].to_vec(),
         [1, 40, 35, 31].to_vec(),
     ];
-    let i: usize = rand::rng().random_range(0..sample_path.len());
+    let i: usize = (seed % sample_path.len() as u64) as usize;
     puzzles::PuzzleSampleGame {
         path: Vec::from(sample_path[i]),
         diamonds: sample_diamonds[i].clone(),
@@ -26,7 +26,6 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 use crate::generator::puzzles;
 use gettextrs::gettext;
-use rand::Rng;
 
 pub const NAME: &str = "Square";
 pub const DIFFICULTY: puzzles::Difficulty = puzzles::Difficulty::Medium;
@@ -119,7 +118,7 @@ pub fn get() -> puzzles::Puzzle {
 }
 
 /// Return a tuple with the path, the diamond, and the map lists.
-pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
+pub fn get_random_sample_path(seed: u64) -> puzzles::PuzzleSampleGame {
     let sample_path: [[u8; 38]; 100] = [
         [
             23, 29, 30, 31, 26, 25, 24, 19, 20, 16, 15, 9, 10, 5, 4, 3, 2, 8, 14, 13, 18, 12, 7, 1,
@@ -1471,7 +1470,7 @@ pub fn get_random_sample_path() -> puzzles::PuzzleSampleGame {
         [3, 24, 19, 23, 15].to_vec(),
         [29, 37].to_vec(),
     ];
-    let i: usize = rand::rng().random_range(0..sample_path.len());
+    let i: usize = (seed % sample_path.len() as u64) as usize;
     puzzles::PuzzleSampleGame {
         path: Vec::from(sample_path[i]),
         diamonds: sample_diamonds[i].clone(),
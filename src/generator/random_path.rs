@@ -21,19 +21,30 @@ SPDX-License-Identifier: GPL-3.0-or-later
 */
 
 //! Generate a random path.
+//!
+//! The generation is driven by a seed, so that the same seed on the same board produces the
+//! same path. Players can share the seed to replay a specific puzzle instance.
+//!
+//! The generation gives up after a number of iterations, and not after a duration, so that the
+//! seed produces the same result on fast and slow computers.
 
 use log::debug;
-use rand::Rng;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use std::time::Instant;
 
 use super::edges;
 use super::path;
 use super::vertexes;
 
-// Max duration for trying to find a path, otherwise an error is raised. For large puzzle, it might
-// take too long to find a path. In that case a precomputed puzzle is used.
-const MAX_TIME_SEC: u64 = 6;
+// Max number of iterations for trying to find a path, otherwise an error is raised. For large
+// puzzle, it might take too long to find a path. In that case a precomputed puzzle is used.
+// The limit corresponds to about six seconds on a recent computer.
+const MAX_ITERATIONS: usize = 1_500_000;
+
+/// Upper bound for the random seeds. The seeds stay short so that players can easily copy them.
+const MAX_RANDOM_SEED: u64 = u32::MAX as u64;
 
 /// Type of errors.
 #[derive(Debug, PartialEq)]
@@ -41,8 +52,8 @@ pub enum RandomPathError {
     /// No possible path.
     NoPath,
 
-    /// No path found before the timeout.
-    DurationExceeded,
+    /// No path found within the maximum number of iterations.
+    IterationsExceeded,
 }
 
 /// [`RandomPath`] object.
@@ -69,11 +80,19 @@ pub struct RandomPath {
 
     /// Starting vertex, if the puzzle requires one.
     required_starting_vertex: Option<usize>,
+
+    /// Random number generator, initialized from the seed.
+    rng: StdRng,
+}
+
+/// Return a new random seed.
+pub fn random_seed() -> u64 {
+    rand::rng().random_range(0..=MAX_RANDOM_SEED)
 }
 
 impl RandomPath {
-    /// Create the object.
-    pub fn new(edges: &edges::Edges, vertexes: &vertexes::Vertexes) -> Self {
+    /// Create the object. The given seed initializes the random number generator.
+    pub fn new_with_seed(edges: &edges::Edges, vertexes: &vertexes::Vertexes, seed: u64) -> Self {
         Self {
             starting_vertex: 0,
             num_vertexes: vertexes.num_vertexes,
@@ -82,6 +101,7 @@ impl RandomPath {
             duration: 0.0,
             start: Instant::now(),
             required_starting_vertex: vertexes.required_starting_vertex,
+            rng: StdRng::seed_from_u64(seed),
         }
     }
 
@@ -116,7 +136,7 @@ impl RandomPath {
                         v
                     }
                 }
-                None => rand::rng().random_range(0..self.num_vertexes),
+                None => self.rng.random_range(0..self.num_vertexes),
             },
         };
 
@@ -160,8 +180,8 @@ impl RandomPath {
         }
 
         self.iteration += 1;
-        if self.start.elapsed().as_secs() >= MAX_TIME_SEC {
-            return Err(RandomPathError::DurationExceeded);
+        if self.iteration >= MAX_ITERATIONS {
+            return Err(RandomPathError::IterationsExceeded);
         }
 
         // Verify quickly if there is a required edge
@@ -196,7 +216,7 @@ impl RandomPath {
             .filter(|&vertex| !path.contains(*vertex))
             .copied()
             .collect();
-        indices.shuffle(&mut rand::rng());
+        indices.shuffle(&mut self.rng);
 
         for v2 in indices {
             debug!("    Selecting edge {current_vertex}-{v2}");
@@ -214,7 +234,7 @@ impl RandomPath {
             match self.find_path(v2, &mut new_edges, path) {
                 Ok(()) => return Ok(()),
                 Err(e) => {
-                    if e == RandomPathError::DurationExceeded {
+                    if e == RandomPathError::IterationsExceeded {
                         return Err(e);
                     }
                 }
@@ -316,3 +336,29 @@ impl RandomPath {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::puzzles;
+
+    #[test]
+    fn same_seed_produces_same_path() {
+        let mut puzzle: puzzles::Puzzle = puzzles::puzzle_map()
+            .remove(&(String::from("Classic"), puzzles::Difficulty::Medium))
+            .unwrap();
+        puzzle.matrix.build_edges().unwrap();
+        let edges: &edges::Edges = &puzzle.matrix.edges;
+        let vertexes: &vertexes::Vertexes = &puzzle.matrix.vertexes;
+
+        for seed in 0..10 {
+            let first = RandomPath::new_with_seed(edges, vertexes, seed).generate(None);
+            let second = RandomPath::new_with_seed(edges, vertexes, seed).generate(None);
+            match (first, second) {
+                (Ok(p1), Ok(p2)) => assert_eq!(p1.get(), p2.get(), "seed {seed}"),
+                (Err(e1), Err(e2)) => assert_eq!(e1, e2, "seed {seed}"),
+                _ => panic!("seed {seed}: different results"),
+            }
+        }
+    }
+}
@@ -0,0 +1,198 @@
+/*
+regions.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Generate regions for the sum clues variant.
+//!
+//! In that variant, the board is partitioned into small regions of contiguous cells.
+//! Each region displays the sum of the numbers of its cells.
+//! The regions are built after the path is generated, by using the solution path to compute the
+//! sums.
+//!
+//! The regions only add information to the puzzle, which already has a unique solution with its
+//! diamonds and maps. Therefore, the regions do not need to be verified by the diamond generator.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::path;
+use super::vertexes;
+
+/// Maximum number of cells in a region, before the single cells join their adjacent regions.
+const MAX_REGION_SIZE: usize = 4;
+
+/// Region of contiguous cells.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Region {
+    /// List of the cell IDs in the region.
+    pub cells: Vec<usize>,
+
+    /// Sum of the numbers of the cells in the region.
+    pub sum: usize,
+}
+
+/// Return the IDs of the cells adjacent to the given cell.
+pub fn adjacent_cells(vertexes: &vertexes::Vertexes, cell_id: usize) -> Vec<usize> {
+    let adjacent: vertexes::Adjacent = vertexes.get_adjacent(cell_id);
+
+    [
+        adjacent.w,
+        adjacent.nw,
+        adjacent.ne,
+        adjacent.e,
+        adjacent.se,
+        adjacent.sw,
+    ]
+    .iter()
+    .filter_map(|c| match c {
+        Some(vertexes::CellType::Vertex(v)) => Some(*v),
+        _ => None,
+    })
+    .collect()
+}
+
+/// Partition the board into random regions and compute their sums from the given path.
+/// The same seed produces the same regions.
+///
+/// A cell that ends up alone, because all its neighbors were already in other regions, joins
+/// the smallest adjacent region: a single-cell region would reveal the number of its cell.
+pub fn generate(path: &path::Path, vertexes: &vertexes::Vertexes, seed: u64) -> Vec<Region> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut assigned: Vec<bool> = vec![false; vertexes.num_vertexes];
+    let mut regions: Vec<Vec<usize>> = Vec::new();
+
+    let mut seeds: Vec<usize> = Vec::from_iter(0..vertexes.num_vertexes);
+    seeds.shuffle(&mut rng);
+
+    for seed in seeds {
+        if assigned[seed] {
+            continue;
+        }
+        assigned[seed] = true;
+        let mut cells: Vec<usize> = vec![seed];
+        let size: usize = rng.random_range(2..=MAX_REGION_SIZE);
+
+        // Grow the region with random adjacent cells that are not already in a region
+        while cells.len() < size {
+            let mut candidates: Vec<usize> = cells
+                .iter()
+                .flat_map(|c| adjacent_cells(vertexes, *c))
+                .filter(|c| !assigned[*c])
+                .collect();
+            candidates.shuffle(&mut rng);
+            match candidates.first() {
+                Some(c) => {
+                    assigned[*c] = true;
+                    cells.push(*c);
+                }
+                None => break,
+            }
+        }
+
+        regions.push(cells);
+    }
+
+    // Merge the single-cell regions into their smallest adjacent region
+    let mut region_of: Vec<usize> = vec![0; vertexes.num_vertexes];
+    for (i, cells) in regions.iter().enumerate() {
+        for c in cells {
+            region_of[*c] = i;
+        }
+    }
+    for i in 0..regions.len() {
+        if regions[i].len() != 1 {
+            continue;
+        }
+        let cell_id: usize = regions[i][0];
+        let Some(target) = adjacent_cells(vertexes, cell_id)
+            .into_iter()
+            .map(|c| region_of[c])
+            .filter(|r| *r != i)
+            .min_by_key(|r| (regions[*r].len(), *r))
+        else {
+            continue;
+        };
+        regions[i].clear();
+        regions[target].push(cell_id);
+        region_of[cell_id] = target;
+    }
+
+    regions
+        .into_iter()
+        .filter(|cells| !cells.is_empty())
+        .map(|cells| {
+            let sum: usize = cells
+                .iter()
+                .filter_map(|c| path.vertex_index(*c))
+                .map(|i| i + 1)
+                .sum();
+            Region { cells, sum }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    use crate::game::Game;
+    use crate::generator::puzzles::PuzzleExport;
+
+    /// Puzzle from the documentation of [`PuzzleExport`].
+    const PUZZLE: &str = "format: 1
+name: Classic
+difficulty: easy
+path: 3 6 7 8 4 1 0 2 5 9 13 10 14 17 18 20 21 19 15 11 12 16
+map: 3 6 8 16
+diamonds: 18-20 9-13 17-18 13-10
+anti-diamonds:
+regions:
+matrix:
+   o o
+  o o o
+ o o o o
+o o x o o
+ o o o o
+  o o o
+   o o
+";
+
+    #[test]
+    fn no_single_cell_region() {
+        let mut game: Game = Game::new();
+        game.set_export(&PuzzleExport::from_str(PUZZLE).unwrap());
+        let vertexes: &vertexes::Vertexes = &game.puzzle.matrix.vertexes;
+
+        for seed in 0..500 {
+            let regions: Vec<Region> = generate(&game.path, vertexes, seed);
+            assert!(regions.iter().all(|r| r.cells.len() > 1), "seed {seed}");
+
+            let mut cells: Vec<usize> = regions.iter().flat_map(|r| r.cells.clone()).collect();
+            cells.sort();
+            assert_eq!(cells, Vec::from_iter(0..vertexes.num_vertexes));
+            let sum: usize = regions.iter().map(|r| r.sum).sum();
+            assert_eq!(sum, vertexes.num_vertexes * (vertexes.num_vertexes + 1) / 2);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+/*
+seed_code.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Seed codes that players share to replay a generated puzzle.
+//!
+//! The seed alone does not reproduce a game: the revealed endpoints, the negative clues, the
+//! strict diamonds, and the sum regions options also change the generated clues. The seed code combines the seed with
+//! these options. The options are letters after the seed, such as `1234567-sar`:
+//!
+//! - `s`: only the first cell is revealed.
+//! - `e`: only the last cell is revealed.
+//! - `n`: no endpoint is revealed.
+//! - `a`: anti-diamonds (negative clues).
+//! - `d`: strict diamonds, on all the adjacent cells that hold consecutive numbers.
+//! - `r`: sum regions.
+//!
+//! Without any option, the code is the seed alone.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use super::diamond_and_map::Endpoints;
+
+/// Generation options that change the generated game for a given seed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SeedOptions {
+    /// Endpoints of the path to reveal.
+    pub endpoints: Endpoints,
+
+    /// Whether to generate anti-diamonds.
+    pub negative_clues: bool,
+
+    /// Whether to mark all the adjacent cells that hold consecutive numbers with a diamond.
+    #[serde(default)]
+    pub strict_diamonds: bool,
+
+    /// Whether to generate sum regions.
+    pub sum_regions: bool,
+}
+
+/// Seed and generation options of a game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SeedCode {
+    /// Seed of the random generation.
+    pub seed: u64,
+
+    /// Generation options.
+    pub options: SeedOptions,
+}
+
+impl fmt::Display for SeedCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut letters: String = String::new();
+        match self.options.endpoints {
+            Endpoints::Both => (),
+            Endpoints::Start => letters.push('s'),
+            Endpoints::End => letters.push('e'),
+            Endpoints::None => letters.push('n'),
+        }
+        if self.options.negative_clues {
+            letters.push('a');
+        }
+        if self.options.strict_diamonds {
+            letters.push('d');
+        }
+        if self.options.sum_regions {
+            letters.push('r');
+        }
+
+        if letters.is_empty() {
+            write!(f, "{}", self.seed)
+        } else {
+            write!(f, "{}-{letters}", self.seed)
+        }
+    }
+}
+
+impl FromStr for SeedCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (seed, letters) = s.trim().split_once('-').unwrap_or((s.trim(), ""));
+        let seed: u64 = seed
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid seed: {seed}"))?;
+
+        let mut options: SeedOptions = SeedOptions::default();
+        let mut endpoints: Option<Endpoints> = None;
+        for letter in letters.to_lowercase().chars() {
+            let duplicated: bool = match letter {
+                's' | 'e' | 'n' => endpoints
+                    .replace(match letter {
+                        's' => Endpoints::Start,
+                        'e' => Endpoints::End,
+                        _ => Endpoints::None,
+                    })
+                    .is_some(),
+                'a' => std::mem::replace(&mut options.negative_clues, true),
+                'd' => std::mem::replace(&mut options.strict_diamonds, true),
+                'r' => std::mem::replace(&mut options.sum_regions, true),
+                _ => return Err(format!("Invalid option in the seed code: {letter}")),
+            };
+            if duplicated {
+                return Err(format!("Duplicated option in the seed code: {letter}"));
+            }
+        }
+        options.endpoints = endpoints.unwrap_or_default();
+        Ok(SeedCode { seed, options })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for endpoints in Endpoints::ALL {
+            for negative_clues in [false, true] {
+                for strict_diamonds in [false, true] {
+                    for sum_regions in [false, true] {
+                        let code: SeedCode = SeedCode {
+                            seed: 1234567,
+                            options: SeedOptions {
+                                endpoints,
+                                negative_clues,
+                                strict_diamonds,
+                                sum_regions,
+                            },
+                        };
+                        assert_eq!(code.to_string().parse::<SeedCode>(), Ok(code));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn seed_alone_uses_default_options() {
+        let code: SeedCode = " 42 ".parse().unwrap();
+        assert_eq!(code.seed, 42);
+        assert_eq!(code.options, SeedOptions::default());
+        assert_eq!(code.to_string(), "42");
+    }
+
+    #[test]
+    fn invalid_codes_are_rejected() {
+        for code in [
+            "", "-a", "abc", "-1", "12-x", "12-ss", "12-se", "12-aa", "12-dd", "12-a-r",
+        ] {
+            assert!(code.parse::<SeedCode>().is_err(), "{code}");
+        }
+    }
+}
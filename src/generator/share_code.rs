@@ -0,0 +1,128 @@
+/*
+share_code.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Share codes that start a generated game from the code alone.
+//!
+//! A seed code only reproduces a game on the same puzzle. The share code adds the name and the
+//! difficulty level of the puzzle, such as `hexkudo:Classic:hard:1234567-sar`. The QR codes that
+//! Hexkudo saves encode that share code.
+
+use clap::ValueEnum;
+use std::fmt;
+use std::str::FromStr;
+
+use super::puzzles::{Difficulty, difficulty_name};
+use super::seed_code::SeedCode;
+
+/// Prefix of the share codes.
+const SHARE_CODE_PREFIX: &str = "hexkudo:";
+
+/// Puzzle and seed code of a game, to start the same game from the code alone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareCode {
+    /// Name of the puzzle, not translated.
+    pub name: String,
+
+    /// Difficulty level of the puzzle.
+    pub difficulty: Difficulty,
+
+    /// Seed and generation options.
+    pub code: SeedCode,
+}
+
+impl fmt::Display for ShareCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{SHARE_CODE_PREFIX}{}:{}:{}",
+            self.name,
+            difficulty_name(self.difficulty),
+            self.code
+        )
+    }
+}
+
+impl FromStr for ShareCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: &str = s.trim();
+        let rest: &str = s
+            .get(..SHARE_CODE_PREFIX.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(SHARE_CODE_PREFIX))
+            .map(|_| &s[SHARE_CODE_PREFIX.len()..])
+            .ok_or_else(|| format!("Not a share code: {s}"))?;
+
+        // The puzzle name comes first, so that it can include colons
+        let mut fields = rest.rsplitn(3, ':');
+        let (Some(code), Some(difficulty), Some(name)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(format!("Incomplete share code: {s}"));
+        };
+        if name.is_empty() {
+            return Err(format!("The puzzle name is missing: {s}"));
+        }
+        Ok(ShareCode {
+            name: name.to_string(),
+            difficulty: Difficulty::from_str(difficulty, true)?,
+            code: code.parse::<SeedCode>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_code_round_trip() {
+        let share: ShareCode = ShareCode {
+            name: String::from("Classic"),
+            difficulty: Difficulty::Hard,
+            code: "1234567-sar".parse().unwrap(),
+        };
+        assert_eq!(share.to_string(), "hexkudo:Classic:hard:1234567-sar");
+        assert_eq!(share.to_string().parse::<ShareCode>(), Ok(share));
+
+        let share: ShareCode = " HEXKUDO:Time: 2:Medium:42 ".parse().unwrap();
+        assert_eq!(share.name, "Time: 2");
+        assert_eq!(share.difficulty, Difficulty::Medium);
+        assert_eq!(share.code.seed, 42);
+    }
+
+    #[test]
+    fn invalid_share_codes_are_rejected() {
+        for code in [
+            "",
+            "1234567-sar",
+            "hexkudo:",
+            "hexkudo:hard:42",
+            "hexkudo::hard:42",
+            "hexkudo:Classic:extreme:42",
+            "hexkudo:Classic:hard:42-x",
+            "hexkudé:Classic:hard:42",
+        ] {
+            assert!(code.parse::<ShareCode>().is_err(), "{code}");
+        }
+    }
+}
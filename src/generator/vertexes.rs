@@ -26,6 +26,69 @@ use log::{Level, debug, log_enabled};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::puzzles::MAX_VERTEXES;
+
+/// Maximum length, in bytes, of the source "ASCII art" puzzle.
+pub const MAX_SOURCE_LENGTH: usize = 65536;
+
+/// Maximum number of characters in a row of the source puzzle.
+pub const MAX_WIDTH: usize = 256;
+
+/// Maximum number of rows of the source puzzle.
+pub const MAX_HEIGHT: usize = 256;
+
+/// Maximum number of characters in the vertex matrix, after the rows are padded to the widest
+/// row.
+pub const MAX_AREA: usize = 16384;
+
+/// Verify that the source "ASCII art" puzzle is small enough to be converted into a vertex
+/// matrix.
+///
+/// The imported puzzles and the restored games come from files that the players can modify.
+/// The verification rejects the oversized puzzles before any memory is allocated for them.
+///
+/// # Errors
+///
+/// The function returns an error when the source is too long, when it has too many rows or too
+/// many cells, or when a row is too wide.
+pub fn check_size(puzzle: &str) -> Result<(), String> {
+    if puzzle.len() > MAX_SOURCE_LENGTH {
+        return Err(format!(
+            "The puzzle has more than {MAX_SOURCE_LENGTH} characters"
+        ));
+    }
+    let mut max_width: usize = 0;
+    let mut height: usize = 0;
+    let mut num_vertexes: usize = 0;
+    for row in puzzle.lines() {
+        let r: &str = row.trim_end();
+        let row_length: usize = r.chars().count();
+        if row_length == 0 {
+            continue;
+        }
+        if row_length > MAX_WIDTH {
+            return Err(format!(
+                "A row of the puzzle has more than {MAX_WIDTH} characters"
+            ));
+        }
+        max_width = max_width.max(row_length);
+        height += 1;
+        num_vertexes += r.chars().filter(|c| matches!(c, 'o' | 'O')).count();
+    }
+    if height > MAX_HEIGHT {
+        return Err(format!("The puzzle has more than {MAX_HEIGHT} rows"));
+    }
+    if num_vertexes > MAX_VERTEXES {
+        return Err(format!("The puzzle has more than {MAX_VERTEXES} cells"));
+    }
+    if max_width * height > MAX_AREA {
+        return Err(format!(
+            "The puzzle matrix has more than {MAX_AREA} characters"
+        ));
+    }
+    Ok(())
+}
+
 /// Representation of an Hexkudo cell from the "ASCII art" representation.
 ///
 /// - A `Background` cell is a cell outside the puzzle.
@@ -150,18 +213,34 @@ impl Vertexes {
         }
     }
 
+    /// Return the source "ASCII art" puzzle.
+    pub fn source(&self) -> &str {
+        &self.puzzle_source
+    }
+
     /// Convert the source "ASCII art" puzzle into a vertex matrix.
-    pub fn build(&mut self) {
+    ///
+    /// # Errors
+    ///
+    /// The method returns an error, and leaves the vertex matrix empty, when the source puzzle
+    /// is too large. See [`check_size`].
+    pub fn build(&mut self) -> Result<(), String> {
+        self.num_vertexes = 0;
+        self.width = 0;
+        self.height = 0;
+        self.vertex_array.clear();
+        self.vertex_coordinates.clear();
+        self.logo_coordinates.clear();
+        check_size(&self.puzzle_source)?;
+
         let mut max_width: usize = 0;
         let mut cell_number: usize = 0;
         let mut x: usize;
         let mut y: usize = 0;
 
-        self.vertex_array.clear();
-
         for row in self.puzzle_source.lines() {
             let r: &str = row.trim_end();
-            let row_length: usize = r.len();
+            let row_length: usize = r.chars().count();
             if row_length == 0 {
                 continue;
             }
@@ -216,6 +295,7 @@ impl Vertexes {
                 debug!("{s}");
             }
         }
+        Ok(())
     }
 
     /// Return the coordinates of the given cell.
@@ -264,28 +344,28 @@ impl Vertexes {
                 };
 
                 // North-east
-                let ne: Option<CellType> = if x <= self.width - 2 && y >= 1 {
+                let ne: Option<CellType> = if x + 1 < self.width && y >= 1 {
                     Some(self.get_cell(x + 1, y - 1))
                 } else {
                     None
                 };
 
                 // East
-                let e: Option<CellType> = if x <= self.width - 3 {
+                let e: Option<CellType> = if x + 2 < self.width {
                     Some(self.get_cell(x + 2, y))
                 } else {
                     None
                 };
 
                 // South-east
-                let se: Option<CellType> = if x <= self.width - 2 && y <= self.height - 2 {
+                let se: Option<CellType> = if x + 1 < self.width && y + 1 < self.height {
                     Some(self.get_cell(x + 1, y + 1))
                 } else {
                     None
                 };
 
                 // South-west
-                let sw: Option<CellType> = if x >= 1 && y <= self.height - 2 {
+                let sw: Option<CellType> = if x >= 1 && y + 1 < self.height {
                     Some(self.get_cell(x - 1, y + 1))
                 } else {
                     None
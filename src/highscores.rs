@@ -27,18 +27,42 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! is restored when Hexkudo starts.
 //! See the [`crate::saver::highscores`] module that saves and restores the [`HighScores`] object.
 
+use chrono::{DateTime, Local};
 use std::collections::HashMap;
 use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
 
+use crate::config;
+use crate::game;
+use crate::generator::diamond_and_map::Endpoints;
 use crate::generator::puzzles;
 
 /// Number of entries per scoreboard (number of top scores to keep).
 const BOARD_SIZE: usize = 10;
 
+/// Number of milestone times to keep per puzzle.
+const MILESTONE_HISTORY_SIZE: usize = 50;
+
+/// Number of completed games to keep in the history.
+const GAME_HISTORY_SIZE: usize = 1000;
+
+/// Version of the saved format. See [`HighScores::migrate`].
+///
+/// - Version 1: initial format.
+/// - Version 2: the scores record the version of the application.
+const FORMAT_VERSION: u32 = 2;
+
+/// Version of the application that recorded the scores of the format version 1. These scores
+/// do not include the application version.
+const FORMAT_1_APP_VERSION: &str = "0.1.0";
+
+/// Versions of the application that are known to compute incorrect scores. The scoreboards flag
+/// the scores recorded with these versions.
+const FLAGGED_VERSIONS: &[&str] = &[];
+
 /// Object that represent a score.
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Score {
     /// How long did it take for solving the puzzle.
     pub time: Duration,
@@ -48,6 +72,69 @@ pub struct Score {
 
     /// Completion timestamp, which is used to display the date and time in the scoreboard.
     pub when: SystemTime,
+
+    /// Points, when the player enabled the points-based scoring.
+    #[serde(default)]
+    pub points: Option<u32>,
+
+    /// Version of the application that recorded the score.
+    #[serde(default)]
+    pub version: String,
+}
+
+impl Score {
+    /// Create a [`Score`] object for the running version of the application.
+    ///
+    /// The `points` parameter is None when the player did not enable the points-based scoring.
+    pub fn new(time: Duration, errors: usize, points: Option<u32>, when: SystemTime) -> Self {
+        Self {
+            time,
+            errors,
+            when,
+            points,
+            version: String::from(config::VERSION),
+        }
+    }
+
+    /// Whether the score was recorded with a version of the application that is known to
+    /// compute incorrect scores.
+    pub fn is_flagged(&self) -> bool {
+        FLAGGED_VERSIONS.contains(&self.version.as_str())
+    }
+}
+
+/// Completed game, as recorded in the history of the games.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameRecord {
+    /// Name of the puzzle.
+    pub puzzle_name: String,
+
+    /// Difficulty level of the puzzle.
+    pub difficulty: puzzles::Difficulty,
+
+    /// Whether the player got help (hints or cell values).
+    pub assisted: bool,
+
+    /// Endpoints of the path that the game revealed.
+    #[serde(default)]
+    pub endpoints: Endpoints,
+
+    /// Completion timestamp.
+    pub when: SystemTime,
+
+    /// Game duration.
+    pub time: Duration,
+
+    /// Number of mistakes.
+    pub errors: usize,
+
+    /// Points, when the player enabled the points-based scoring.
+    #[serde(default)]
+    pub points: Option<u32>,
+
+    /// Number of cells in the puzzle. 0 for the games recorded before this field existed.
+    #[serde(default)]
+    pub cells: usize,
 }
 
 /// Sorted list of the top scores for a puzzle.
@@ -56,6 +143,24 @@ struct PuzzleHighScoreBoard {
     /// Sorted list of the top scores.
     /// The number of scores in this list is controlled by the [`BOARD_SIZE`] constant.
     top: Vec<Score>,
+
+    /// Best split times, one for each of the [`crate::game::SPLIT_PERCENTS`] completion levels.
+    #[serde(default)]
+    best_splits: Vec<Duration>,
+
+    /// Number of games added to the scoreboard, including the games that did not make it to
+    /// the top scores.
+    #[serde(default)]
+    played: usize,
+
+    /// History of the times to reach the first milestone of the game, with the completion
+    /// timestamp, oldest first. See [`crate::game::FIRST_MILESTONE_VALUES`].
+    #[serde(default)]
+    milestones: Vec<(SystemTime, Duration)>,
+
+    /// Timeline of the best game: game duration when each correct value was placed.
+    #[serde(default)]
+    best_timeline: Vec<Duration>,
 }
 
 impl PuzzleHighScoreBoard {
@@ -63,6 +168,10 @@ impl PuzzleHighScoreBoard {
     fn new() -> Self {
         Self {
             top: Vec::with_capacity(BOARD_SIZE),
+            best_splits: Vec::new(),
+            played: 0,
+            milestones: Vec::new(),
+            best_timeline: Vec::new(),
         }
     }
 
@@ -70,51 +179,94 @@ impl PuzzleHighScoreBoard {
     /// score does not make it to the board.
     ///
     /// The returned position starts at 1 (top score).
-    fn add_score(&mut self, time: Duration, errors: usize) -> Option<usize> {
+    fn add_score(&mut self, new_score: Score) -> Option<usize> {
         let mut new_score_position: Option<usize> = None;
         let mut tmp_top: Vec<Score> = Vec::with_capacity(BOARD_SIZE);
         let mut i: usize = 0;
 
+        self.played += 1;
         for score in &self.top {
             // Insert the new score to the temporary board
-            if time < score.time && new_score_position.is_none() {
+            if new_score.time < score.time && new_score_position.is_none() {
                 new_score_position = Some(i + 1);
-                tmp_top.push(Score {
-                    time,
-                    errors,
-                    when: SystemTime::now(),
-                });
+                tmp_top.push(new_score.clone());
                 i += 1;
             }
             // Do not add more scores than the board size
             if i >= BOARD_SIZE {
                 break;
             }
-            tmp_top.push(*score);
+            tmp_top.push(score.clone());
             i += 1;
         }
         // If the board is not full and the new score has not been added yet, then add the new
         // score at the end of the board
         if i < BOARD_SIZE && new_score_position.is_none() {
             new_score_position = Some(i + 1);
-            tmp_top.push(Score {
-                time,
-                errors,
-                when: SystemTime::now(),
-            });
+            tmp_top.push(new_score);
         }
         self.top = tmp_top;
         new_score_position
     }
 }
 
+/// File formats for exporting the scoreboards. See [`HighScores::export`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Score in the exported scoreboards.
+#[derive(Serialize)]
+struct ExportedScore<'a> {
+    puzzle: &'a str,
+    difficulty: &'a str,
+    endpoints: Endpoints,
+    assisted: bool,
+    position: usize,
+    /// Duration of the game in seconds.
+    time: f64,
+    errors: usize,
+    points: Option<u32>,
+    /// Completion date and time in the RFC 3339 format.
+    date: String,
+    version: &'a str,
+}
+
+/// Quote a CSV field when it includes a separator, a quote, or a line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        String::from(value)
+    }
+}
+
 /// List of the scoreboards for the puzzles.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HighScores {
     /// Map fo the [`PuzzleHighScoreBoard`] scoreboards indexed by the puzzle.
     ///
-    /// The puzzle index is a string in the format "<puzzle_name>@@<difficulty>".
+    /// The puzzle index is a string in the format "<puzzle_name>@@<difficulty>". The games
+    /// with hidden endpoints use the "<puzzle_name>@@<difficulty>@@<endpoints>" format (see
+    /// [`Endpoints::board_suffix`]). The boards for the assisted games, where the player asked
+    /// for hints or cell values, add the "@@assisted" suffix.
     board: HashMap<String, PuzzleHighScoreBoard>,
+
+    /// History of the completed games, oldest first. The number of games is controlled by the
+    /// [`GAME_HISTORY_SIZE`] constant.
+    #[serde(default)]
+    history: Vec<GameRecord>,
+
+    /// Version of the saved format. The files that do not have this field use the version 1.
+    #[serde(default = "default_format_version")]
+    format_version: u32,
+}
+
+/// Format version of the saved files that do not record it.
+fn default_format_version() -> u32 {
+    1
 }
 
 impl Default for HighScores {
@@ -128,30 +280,140 @@ impl HighScores {
     pub fn new() -> Self {
         Self {
             board: HashMap::new(),
+            history: Vec::new(),
+            format_version: FORMAT_VERSION,
         }
     }
 
+    /// Verify that the lists do not exceed their maximum sizes.
+    /// A restored object that fails the verification comes from a corrupted or a tampered file.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.history.len() > GAME_HISTORY_SIZE {
+            return Err(format!(
+                "Too many games in the history: {}",
+                self.history.len()
+            ));
+        }
+        for (key, scoreboard) in &self.board {
+            if scoreboard.top.len() > BOARD_SIZE
+                || scoreboard.best_splits.len() > game::SPLIT_PERCENTS.len()
+                || scoreboard.milestones.len() > MILESTONE_HISTORY_SIZE
+                || scoreboard.best_timeline.len() > puzzles::MAX_VERTEXES
+            {
+                return Err(format!("Invalid scoreboard: {key}"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Update the scoreboards that were saved in an older format.
+    pub fn migrate(&mut self) {
+        if self.format_version < 2 {
+            for scoreboard in self.board.values_mut() {
+                for score in scoreboard.top.iter_mut().filter(|s| s.version.is_empty()) {
+                    score.version = String::from(FORMAT_1_APP_VERSION);
+                }
+            }
+        }
+        self.format_version = FORMAT_VERSION;
+    }
+
     /// Return the string that is used as an index for the list of scoreboards.
-    fn build_key(&self, puzzle_name: &String, difficulty: puzzles::Difficulty) -> String {
-        format!("{puzzle_name}@@{difficulty}")
+    fn build_key(
+        &self,
+        puzzle_name: &String,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+        assisted: bool,
+    ) -> String {
+        let mut key: String = format!("{puzzle_name}@@{difficulty}");
+        if let Some(suffix) = endpoints.board_suffix() {
+            key.push_str("@@");
+            key.push_str(suffix);
+        }
+        if assisted {
+            key.push_str("@@assisted");
+        }
+        key
+    }
+
+    /// Return the puzzle name, the difficulty level, the endpoints, and whether the board is
+    /// for the assisted games, from the given scoreboard index.
+    fn split_key(key: &str) -> (&str, &str, Endpoints, bool) {
+        let mut parts = key.split("@@");
+        let puzzle_name: &str = parts.next().unwrap_or_default();
+        let difficulty: &str = parts.next().unwrap_or_default();
+        let mut endpoints: Endpoints = Endpoints::Both;
+        let mut assisted: bool = false;
+        for part in parts {
+            match Endpoints::from_board_suffix(part) {
+                Some(e) => endpoints = e,
+                None => assisted = part == "assisted",
+            }
+        }
+        (puzzle_name, difficulty, endpoints, assisted)
     }
 
     /// Add the a score to the scoreboard of the provided puzzle and return the position in the
     /// scoreboard, or None if the score does not make it to the board.
     ///
     /// The returned position starts at 1 (top score).
+    /// The `assisted` parameter selects the scoreboard of the games where the player got help.
     pub fn add_score(
         &mut self,
         puzzle_name: &String,
         difficulty: puzzles::Difficulty,
-        time: Duration,
-        errors: usize,
+        endpoints: Endpoints,
+        assisted: bool,
+        score: Score,
     ) -> Option<usize> {
-        let key: String = self.build_key(puzzle_name, difficulty);
+        let key: String = self.build_key(puzzle_name, difficulty, endpoints, assisted);
         let scoreboard: &mut PuzzleHighScoreBoard =
             self.board.entry(key).or_insert(PuzzleHighScoreBoard::new());
 
-        scoreboard.add_score(time, errors)
+        scoreboard.add_score(score)
+    }
+
+    /// Add a completed game to the history of the games.
+    ///
+    /// The history is only recorded when the player allows it (`record-history` GSettings key).
+    /// The `cells` parameter provides the number of cells in the puzzle.
+    pub fn add_history(
+        &mut self,
+        puzzle_name: &str,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+        assisted: bool,
+        cells: usize,
+        score: &Score,
+    ) {
+        self.history.push(GameRecord {
+            puzzle_name: String::from(puzzle_name),
+            difficulty,
+            assisted,
+            endpoints,
+            when: score.when,
+            time: score.time,
+            errors: score.errors,
+            points: score.points,
+            cells,
+        });
+        if self.history.len() > GAME_HISTORY_SIZE {
+            self.history.remove(0);
+        }
+    }
+
+    /// Delete the history of the games and the milestone times, but keep the top scores.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        for scoreboard in self.board.values_mut() {
+            scoreboard.milestones.clear();
+        }
+    }
+
+    /// Return the history of the completed games, oldest first.
+    pub fn get_history(&self) -> &Vec<GameRecord> {
+        &self.history
     }
 
     /// Return the list of [`Score`] for the given puzzle.
@@ -161,8 +423,10 @@ impl HighScores {
         &self,
         puzzle_name: &String,
         difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+        assisted: bool,
     ) -> Option<&Vec<Score>> {
-        let key: String = self.build_key(puzzle_name, difficulty);
+        let key: String = self.build_key(puzzle_name, difficulty, endpoints, assisted);
 
         match self.board.get(&key) {
             Some(b) => Some(&b.top),
@@ -170,8 +434,274 @@ impl HighScores {
         }
     }
 
-    /// Return whether the list of scoreboard is empty (no scoreboard for any puzzle)
+    /// Remove the score at the given index from the scoreboard of the given puzzle.
+    ///
+    /// Return whether the score has been removed.
+    pub fn remove_score(
+        &mut self,
+        puzzle_name: &String,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+        assisted: bool,
+        index: usize,
+    ) -> bool {
+        let key: String = self.build_key(puzzle_name, difficulty, endpoints, assisted);
+
+        match self.board.get_mut(&key) {
+            Some(b) if index < b.top.len() => {
+                b.top.remove(index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Update the best split times of the given puzzle and return whether a split time improved.
+    pub fn update_best_splits(
+        &mut self,
+        puzzle_name: &String,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+        splits: &[Duration],
+    ) -> bool {
+        let key: String = self.build_key(puzzle_name, difficulty, endpoints, false);
+        let scoreboard: &mut PuzzleHighScoreBoard =
+            self.board.entry(key).or_insert(PuzzleHighScoreBoard::new());
+        let mut updated: bool = false;
+
+        for (i, split) in splits.iter().enumerate() {
+            match scoreboard.best_splits.get_mut(i) {
+                Some(best) => {
+                    if split < best {
+                        *best = *split;
+                        updated = true;
+                    }
+                }
+                None => {
+                    scoreboard.best_splits.push(*split);
+                    updated = true;
+                }
+            }
+        }
+        updated
+    }
+
+    /// Return the best split times for the given puzzle.
+    ///
+    /// Return None when no split time has been recorded yet.
+    pub fn get_best_splits(
+        &self,
+        puzzle_name: &String,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+    ) -> Option<&Vec<Duration>> {
+        let key: String = self.build_key(puzzle_name, difficulty, endpoints, false);
+
+        match self.board.get(&key) {
+            Some(b) if !b.best_splits.is_empty() => Some(&b.best_splits),
+            _ => None,
+        }
+    }
+
+    /// Return the best time and the number of games played for all the puzzles of the given
+    /// difficulty level.
+    ///
+    /// The best time is None when no score has been recorded yet for the difficulty level.
+    pub fn get_difficulty_stats(
+        &self,
+        difficulty: puzzles::Difficulty,
+    ) -> (Option<Duration>, usize) {
+        let difficulty: String = difficulty.to_string();
+        let mut best: Option<Duration> = None;
+        let mut played: usize = 0;
+
+        for (key, scoreboard) in &self.board {
+            let (_, key_difficulty, _, assisted) = Self::split_key(key);
+            if assisted || key_difficulty != difficulty {
+                continue;
+            }
+            played += scoreboard.played.max(scoreboard.top.len());
+            if let Some(score) = scoreboard.top.first()
+                && best.is_none_or(|b| score.time < b)
+            {
+                best = Some(score.time);
+            }
+        }
+        (best, played)
+    }
+
+    /// Add the time to reach the first milestone of the game to the history of the given puzzle.
+    pub fn add_milestone(
+        &mut self,
+        puzzle_name: &String,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+        time: Duration,
+    ) {
+        let key: String = self.build_key(puzzle_name, difficulty, endpoints, false);
+        let scoreboard: &mut PuzzleHighScoreBoard =
+            self.board.entry(key).or_insert(PuzzleHighScoreBoard::new());
+
+        scoreboard.milestones.push((SystemTime::now(), time));
+        if scoreboard.milestones.len() > MILESTONE_HISTORY_SIZE {
+            scoreboard.milestones.remove(0);
+        }
+    }
+
+    /// Return the history of the times to reach the first milestone for the given puzzle.
+    ///
+    /// Return None when no milestone has been recorded yet.
+    pub fn get_milestones(
+        &self,
+        puzzle_name: &String,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+    ) -> Option<&Vec<(SystemTime, Duration)>> {
+        let key: String = self.build_key(puzzle_name, difficulty, endpoints, false);
+
+        match self.board.get(&key) {
+            Some(b) if !b.milestones.is_empty() => Some(&b.milestones),
+            _ => None,
+        }
+    }
+
+    /// Store the timeline of the best game for the given puzzle.
+    pub fn set_best_timeline(
+        &mut self,
+        puzzle_name: &String,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+        timeline: Vec<Duration>,
+    ) {
+        let key: String = self.build_key(puzzle_name, difficulty, endpoints, false);
+        let scoreboard: &mut PuzzleHighScoreBoard =
+            self.board.entry(key).or_insert(PuzzleHighScoreBoard::new());
+
+        scoreboard.best_timeline = timeline;
+    }
+
+    /// Return the timeline of the best game for the given puzzle.
+    ///
+    /// Return None when no timeline has been recorded yet.
+    pub fn get_best_timeline(
+        &self,
+        puzzle_name: &String,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+    ) -> Option<&Vec<Duration>> {
+        let key: String = self.build_key(puzzle_name, difficulty, endpoints, false);
+
+        match self.board.get(&key) {
+            Some(b) if !b.best_timeline.is_empty() => Some(&b.best_timeline),
+            _ => None,
+        }
+    }
+
+    /// Return whether the player completed the given puzzle at least once without assistance,
+    /// whatever the revealed endpoints.
+    pub fn is_beaten(&self, puzzle_name: &String, difficulty: puzzles::Difficulty) -> bool {
+        Endpoints::ALL.into_iter().any(|endpoints| {
+            self.get_score(puzzle_name, difficulty, endpoints, false)
+                .is_some_and(|scores| !scores.is_empty())
+        })
+    }
+
+    /// Suggest the next puzzle to play after the player completed the given puzzle.
+    ///
+    /// The suggestion is a puzzle that the player has not beaten yet. In order of preference:
+    /// the same puzzle at a harder difficulty level, another puzzle at the same difficulty
+    /// level, and then another puzzle at a harder difficulty level.
+    /// Return None when the player already beat all these puzzles.
+    pub fn next_challenge<'a>(
+        &self,
+        puzzle_list: &'a HashMap<(String, puzzles::Difficulty), puzzles::Puzzle>,
+        puzzle_name: &String,
+        difficulty: puzzles::Difficulty,
+    ) -> Option<&'a puzzles::Puzzle> {
+        puzzle_list
+            .values()
+            .filter(|p| p.difficulty >= difficulty)
+            .filter(|p| !(p.name == *puzzle_name && p.difficulty == difficulty))
+            .filter(|p| !self.is_beaten(&p.name, p.difficulty))
+            .min_by_key(|p| {
+                let rank: u8 = if p.name == *puzzle_name {
+                    0
+                } else if p.difficulty == difficulty {
+                    1
+                } else {
+                    2
+                };
+                (rank, p.difficulty as i32, p.name_i18n.clone())
+            })
+    }
+
+    /// Return whether the list of scoreboard is empty (no score for any puzzle)
     pub fn is_empty(&self) -> bool {
-        self.board.len() == 0
+        self.board.values().all(|b| b.top.is_empty())
+    }
+
+    /// Return the scoreboards in the given format, for reading them in a spreadsheet or in
+    /// other tools.
+    ///
+    /// When `puzzle` is provided (puzzle name, difficulty level, revealed endpoints, and
+    /// whether the board is for the assisted games), only the scoreboard of that puzzle is
+    /// exported. Otherwise, all the scoreboards are exported.
+    pub fn export(
+        &self,
+        puzzle: Option<(&String, puzzles::Difficulty, Endpoints, bool)>,
+        format: ExportFormat,
+    ) -> Result<String, serde_json::Error> {
+        let mut keys: Vec<&String> = match puzzle {
+            Some((puzzle_name, difficulty, endpoints, assisted)) => {
+                let key: String = self.build_key(puzzle_name, difficulty, endpoints, assisted);
+                self.board.keys().filter(|k| **k == key).collect()
+            }
+            None => self.board.keys().collect(),
+        };
+        keys.sort();
+
+        let mut scores: Vec<ExportedScore> = Vec::new();
+        for key in keys {
+            let (puzzle_name, difficulty, endpoints, assisted) = Self::split_key(key);
+
+            for (i, score) in self.board[key].top.iter().enumerate() {
+                let date: DateTime<Local> = DateTime::from(score.when);
+                scores.push(ExportedScore {
+                    puzzle: puzzle_name,
+                    difficulty,
+                    endpoints,
+                    assisted,
+                    position: i + 1,
+                    time: score.time.as_secs_f64(),
+                    errors: score.errors,
+                    points: score.points,
+                    date: date.to_rfc3339(),
+                    version: &score.version,
+                });
+            }
+        }
+
+        if format == ExportFormat::Json {
+            return serde_json::to_string_pretty(&scores);
+        }
+        let mut csv: String = String::from(
+            "puzzle,difficulty,endpoints,assisted,position,time,errors,points,date,version\n",
+        );
+        for s in &scores {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{:.2},{},{},{},{}\n",
+                csv_field(s.puzzle),
+                csv_field(s.difficulty),
+                s.endpoints.board_suffix().unwrap_or("both"),
+                s.assisted,
+                s.position,
+                s.time,
+                s.errors,
+                s.points.map(|p| p.to_string()).unwrap_or_default(),
+                s.date,
+                csv_field(s.version),
+            ));
+        }
+        Ok(csv)
     }
 }
@@ -24,7 +24,7 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 use log::debug;
 use std::collections::HashMap;
-use std::time::Instant;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
@@ -38,10 +38,17 @@ pub struct InputErrors {
     // Number of errors.
     count: usize,
 
-    // List of the cells in error, and the time the mistake was made. This enables decreasing the
-    // error counter if the mistake is fixed in less that three seconds.
+    // List of the cells in error, and the game duration when the mistake was made. This enables
+    // decreasing the error counter if the mistake is fixed in less that three seconds.
+    // Using the game duration instead of the wall clock time makes the counter reproducible
+    // from the move log of the game (see [`crate::proof`]).
     #[serde(skip)]
-    cell_set_time: HashMap<usize, Instant>,
+    cell_set_time: HashMap<usize, Duration>,
+
+    // Number of errors per cell. The mystery mode uses it to review the mistakes at the end of
+    // the game.
+    #[serde(default)]
+    cell_count: HashMap<usize, usize>,
 }
 
 impl InputErrors {
@@ -50,6 +57,7 @@ impl InputErrors {
         Self {
             count: 0,
             cell_set_time: HashMap::new(),
+            cell_count: HashMap::new(),
         }
     }
 
@@ -57,6 +65,7 @@ impl InputErrors {
     pub fn clear(&mut self) {
         self.count = 0;
         self.cell_set_time.clear();
+        self.cell_count.clear();
     }
 
     /// Return the number of mistakes.
@@ -64,24 +73,50 @@ impl InputErrors {
         self.count
     }
 
-    /// Process the error status of the given cell, which the player just set.
+    /// Return the IDs of the cells where the player made mistakes.
+    pub fn get_error_cells(&self) -> Vec<usize> {
+        let mut cells: Vec<usize> = self.cell_count.keys().copied().collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    /// Increment the error counter for the given cell.
+    fn increment(&mut self, cell_id: usize) {
+        self.count += 1;
+        *self.cell_count.entry(cell_id).or_insert(0) += 1;
+    }
+
+    /// Decrement the error counter for the given cell.
+    fn decrement(&mut self, cell_id: usize) {
+        self.count -= 1;
+        if let Some(c) = self.cell_count.get_mut(&cell_id) {
+            *c -= 1;
+            if *c == 0 {
+                self.cell_count.remove(&cell_id);
+            }
+        }
+    }
+
+    /// Process the error status of the given cell, which the player just set. `now` is the game
+    /// duration.
     ///
     /// A cell in error increases the error counter.
     /// A cell with the correct value decreases the counter if the cell has been in error for less
     /// that three seconds.
-    pub fn add_cell(&mut self, cell_id: usize, in_error: bool) {
+    pub fn add_cell(&mut self, cell_id: usize, in_error: bool, now: Duration) {
         match self.cell_set_time.get(&cell_id) {
             // The cell has been in error previously
-            Some(i) => {
+            Some(t) => {
+                let elapsed: u64 = now.saturating_sub(*t).as_secs();
                 if in_error {
-                    if i.elapsed().as_secs() > TOLERATION_SEC {
-                        self.count += 1;
+                    if elapsed > TOLERATION_SEC {
+                        self.increment(cell_id);
                         debug!("Error for cell {cell_id}: error count + 1 = {}", self.count);
                     }
-                    self.cell_set_time.insert(cell_id, Instant::now());
+                    self.cell_set_time.insert(cell_id, now);
                 } else {
-                    if i.elapsed().as_secs() <= TOLERATION_SEC && self.count > 0 {
-                        self.count -= 1;
+                    if elapsed <= TOLERATION_SEC && self.count > 0 {
+                        self.decrement(cell_id);
                         debug!(
                             "Error fixed for cell {cell_id} in less than {TOLERATION_SEC}s: error count - 1 = {}",
                             self.count
@@ -94,19 +129,20 @@ impl InputErrors {
             // The cell has not yet been in error
             None => {
                 if in_error {
-                    self.cell_set_time.insert(cell_id, Instant::now());
-                    self.count += 1;
+                    self.cell_set_time.insert(cell_id, now);
+                    self.increment(cell_id);
                     debug!("Error for cell {cell_id}: error count + 1 = {}", self.count);
                 }
             }
         }
     }
 
-    /// Process the error status of the given cell, which the player cleared.
-    pub fn clear_cell(&mut self, cell_id: usize) {
-        if let Some(i) = self.cell_set_time.get(&cell_id) {
-            if i.elapsed().as_secs() <= TOLERATION_SEC && self.count > 0 {
-                self.count -= 1;
+    /// Process the error status of the given cell, which the player cleared. `now` is the game
+    /// duration.
+    pub fn clear_cell(&mut self, cell_id: usize, now: Duration) {
+        if let Some(t) = self.cell_set_time.get(&cell_id) {
+            if now.saturating_sub(*t).as_secs() <= TOLERATION_SEC && self.count > 0 {
+                self.decrement(cell_id);
                 debug!(
                     "Removed cell {cell_id} in less than {TOLERATION_SEC}s: error count - 1 = {}",
                     self.count
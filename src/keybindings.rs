@@ -0,0 +1,417 @@
+/*
+keybindings.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Customizable keyboard shortcuts.
+//!
+//! [`BINDINGS`] lists the shortcuts that players can remap, with their default accelerators.
+//! The bindings are of three kinds:
+//!
+//! - The application actions (`app.*`), which [`apply_app`] registers with
+//!   [`gtk::Application::set_accels_for_action`].
+//! - The game view actions (`game-view.*`), which [`fill_controller`] installs in the shortcut
+//!   controller of the game view.
+//! - The moves of the selection in the board (`move-*`), which the drawing area checks with
+//!   [`matches`] when it receives a key press.
+//!
+//! The `custom-accels` GSettings key stores the accelerators that the player has changed. It is
+//! a dictionary indexed by the binding name. The bindings that are not in the dictionary use
+//! their default accelerators. An empty list of accelerators disables the shortcut.
+
+use std::collections::HashMap;
+
+use gettextrs::pgettext;
+use gtk::prelude::*;
+use gtk::{gdk, gio};
+use log::debug;
+
+/// GSettings key that stores the custom accelerators.
+const SETTINGS_KEY: &str = "custom-accels";
+
+/// Groups of bindings, as the shortcuts dialog displays them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Section {
+    /// Application actions.
+    General,
+
+    /// Game view actions.
+    Game,
+
+    /// Moves of the selection in the board.
+    Navigation,
+}
+
+/// Keyboard shortcut that players can remap.
+#[derive(Debug)]
+pub struct Binding {
+    /// Action name, or `move-*` for the moves of the selection.
+    pub name: &'static str,
+
+    /// Group of the binding.
+    pub section: Section,
+
+    /// Default accelerators, in the format of [`gtk::accelerator_parse`].
+    pub defaults: &'static [&'static str],
+}
+
+/// List of the bindings.
+pub const BINDINGS: [Binding; 31] = [
+    Binding {
+        name: "app.new-game",
+        section: Section::General,
+        defaults: &["<Primary>n"],
+    },
+    Binding {
+        name: "app.import-puzzle",
+        section: Section::General,
+        defaults: &["<Primary>o"],
+    },
+    Binding {
+        name: "app.back-start",
+        section: Section::General,
+        defaults: &["<Alt>Left", "<Alt>KP_Left"],
+    },
+    Binding {
+        name: "app.forward",
+        section: Section::General,
+        defaults: &["<Alt>Right", "<Alt>KP_Right"],
+    },
+    Binding {
+        name: "app.toggle-fullscreen",
+        section: Section::General,
+        defaults: &["F11", "f"],
+    },
+    Binding {
+        name: "app.preferences",
+        section: Section::General,
+        defaults: &["<Primary>comma"],
+    },
+    Binding {
+        name: "app.shortcuts",
+        section: Section::General,
+        defaults: &["<Primary>question"],
+    },
+    Binding {
+        name: "app.help",
+        section: Section::General,
+        defaults: &["F1"],
+    },
+    Binding {
+        name: "app.quit",
+        section: Section::General,
+        defaults: &["<Primary>q"],
+    },
+    Binding {
+        name: "game-view.undo",
+        section: Section::Game,
+        defaults: &["u", "<Primary>z"],
+    },
+    Binding {
+        name: "game-view.redo",
+        section: Section::Game,
+        defaults: &["r", "<Shift><Primary>z"],
+    },
+    Binding {
+        name: "game-view.history",
+        section: Section::Game,
+        defaults: &["<Shift><Primary>h"],
+    },
+    Binding {
+        name: "game-view.pause-resume",
+        section: Section::Game,
+        defaults: &["p"],
+    },
+    Binding {
+        name: "game-view.toggle-notes",
+        section: Section::Game,
+        defaults: &["n"],
+    },
+    Binding {
+        name: "game-view.revert-cell",
+        section: Section::Game,
+        defaults: &["<Shift>Delete"],
+    },
+    Binding {
+        name: "game-view.set-checkpoint",
+        section: Section::Game,
+        defaults: &["<Primary>s"],
+    },
+    Binding {
+        name: "game-view.solve-current-cell",
+        section: Section::Game,
+        defaults: &["<Primary>l"],
+    },
+    Binding {
+        name: "game-view.hint",
+        section: Section::Game,
+        defaults: &["<Primary>h"],
+    },
+    Binding {
+        name: "game-view.complete-chains",
+        section: Section::Game,
+        defaults: &["<Primary>j"],
+    },
+    Binding {
+        name: "game-view.show-warnings",
+        section: Section::Game,
+        defaults: &["<Primary>e"],
+    },
+    Binding {
+        name: "game-view.show-duplicates",
+        section: Section::Game,
+        defaults: &["<Primary>d"],
+    },
+    Binding {
+        name: "game-view.announce-remaining",
+        section: Section::Game,
+        defaults: &["<Primary>i"],
+    },
+    Binding {
+        name: "game-view.reset-puzzle",
+        section: Section::Game,
+        defaults: &["<Primary>r"],
+    },
+    Binding {
+        name: "game-view.zoom-in",
+        section: Section::Game,
+        defaults: &[
+            "<Primary>plus",
+            "<Primary>equal",
+            "ZoomIn",
+            "<Primary>KP_Add",
+        ],
+    },
+    Binding {
+        name: "game-view.zoom-out",
+        section: Section::Game,
+        defaults: &["<Primary>minus", "ZoomOut", "<Primary>KP_Subtract"],
+    },
+    Binding {
+        name: "game-view.zoom-fit",
+        section: Section::Game,
+        defaults: &["<Primary>0", "<Primary>KP_0"],
+    },
+    Binding {
+        name: "game-view.print-current",
+        section: Section::Game,
+        defaults: &["<Primary>p"],
+    },
+    Binding {
+        name: "move-left",
+        section: Section::Navigation,
+        defaults: &["Left", "KP_Left", "a"],
+    },
+    Binding {
+        name: "move-right",
+        section: Section::Navigation,
+        defaults: &["Right", "KP_Right", "d"],
+    },
+    Binding {
+        name: "move-up",
+        section: Section::Navigation,
+        defaults: &["Up", "KP_Up", "w"],
+    },
+    Binding {
+        name: "move-down",
+        section: Section::Navigation,
+        defaults: &["Down", "KP_Down", "s"],
+    },
+];
+
+impl Binding {
+    /// Return the translated title of the binding.
+    pub fn title(&self) -> String {
+        match self.name {
+            "app.new-game" => pgettext("Shortcuts Window", "New Game"),
+            "app.import-puzzle" => pgettext("Shortcuts Window", "Open Puzzle File"),
+            "app.back-start" => pgettext("Shortcuts Window", "Go Back"),
+            "app.forward" => pgettext("Shortcuts Window", "Go Forward"),
+            "app.toggle-fullscreen" => pgettext("Shortcuts Window", "Fullscreen"),
+            "app.preferences" => pgettext("Shortcuts Window", "Preferences"),
+            "app.shortcuts" => pgettext("Shortcuts Window", "Keyboard Shortcuts"),
+            "app.help" => pgettext("Shortcuts Window", "Help"),
+            "app.quit" => pgettext("Shortcuts Window", "Quit"),
+            "game-view.undo" => pgettext("Shortcuts Window", "Undo"),
+            "game-view.redo" => pgettext("Shortcuts Window", "Redo"),
+            "game-view.history" => pgettext("Shortcuts Window", "Move History"),
+            "game-view.pause-resume" => pgettext("Shortcuts Window", "Pause/Resume"),
+            "game-view.toggle-notes" => {
+                pgettext("Shortcuts Window", "Enter Numbers as Notes/Values")
+            }
+            "game-view.revert-cell" => {
+                pgettext("Shortcuts Window", "Revert Cell to Last Checkpoint")
+            }
+            "game-view.set-checkpoint" => pgettext("Shortcuts Window", "Set Checkpoint"),
+            "game-view.solve-current-cell" => pgettext("Shortcuts Window", "Solve Selected Cell"),
+            "game-view.hint" => pgettext("Shortcuts Window", "Find a Cell That Can Be Deduced"),
+            "game-view.complete-chains" => {
+                pgettext("Shortcuts Window", "Complete the Unambiguous Chains")
+            }
+            "game-view.show-warnings" => pgettext("Shortcuts Window", "Show/Hide Errors"),
+            "game-view.show-duplicates" => {
+                pgettext("Shortcuts Window", "Show/Hide Duplicated Cells")
+            }
+            "game-view.announce-remaining" => {
+                pgettext("Shortcuts Window", "Announce the Remaining Cells")
+            }
+            "game-view.reset-puzzle" => pgettext("Shortcuts Window", "Reset Puzzle"),
+            "game-view.zoom-in" => pgettext("Shortcuts Window", "Zoom In"),
+            "game-view.zoom-out" => pgettext("Shortcuts Window", "Zoom Out"),
+            "game-view.zoom-fit" => pgettext("Shortcuts Window", "Fit Numbers to the Window Size"),
+            "game-view.print-current" => pgettext("Shortcuts Window", "Print Current Puzzle"),
+            "move-left" => pgettext("Shortcuts Window", "Move Left"),
+            "move-right" => pgettext("Shortcuts Window", "Move Right"),
+            "move-up" => pgettext("Shortcuts Window", "Move Up"),
+            "move-down" => pgettext("Shortcuts Window", "Move Down"),
+            _ => self.name.to_string(),
+        }
+    }
+
+    /// Return the accelerators of the binding.
+    pub fn accels(&self, settings: &gio::Settings) -> Vec<String> {
+        self.accels_in(&custom_accels(settings))
+    }
+
+    /// Return the accelerators of the binding from the given custom accelerators.
+    fn accels_in(&self, custom: &HashMap<String, Vec<String>>) -> Vec<String> {
+        custom
+            .get(self.name)
+            .cloned()
+            .unwrap_or_else(|| self.defaults.iter().map(|a| a.to_string()).collect())
+    }
+
+    /// Whether the player has changed the accelerators of the binding.
+    pub fn is_custom(&self, settings: &gio::Settings) -> bool {
+        custom_accels(settings).contains_key(self.name)
+    }
+
+    /// Restore the default accelerators of the binding.
+    pub fn reset(&self, settings: &gio::Settings) {
+        let mut custom: HashMap<String, Vec<String>> = custom_accels(settings);
+        if custom.remove(self.name).is_some() {
+            save_custom_accels(settings, &custom);
+        }
+    }
+}
+
+/// Return the binding with the given name.
+pub fn lookup(name: &str) -> Option<&'static Binding> {
+    BINDINGS.iter().find(|b| b.name == name)
+}
+
+/// Return the custom accelerators from GSettings.
+fn custom_accels(settings: &gio::Settings) -> HashMap<String, Vec<String>> {
+    settings.value(SETTINGS_KEY).get().unwrap_or_default()
+}
+
+/// Store the custom accelerators into GSettings.
+fn save_custom_accels(settings: &gio::Settings, custom: &HashMap<String, Vec<String>>) {
+    if let Err(error) = settings.set_value(SETTINGS_KEY, &custom.to_variant()) {
+        debug!("Cannot save the custom accelerators: {error}");
+    }
+}
+
+/// Return the canonical form of an accelerator, so that `<Primary>z` and `<Control>z`, for
+/// example, compare equal.
+fn normalize(accel: &str) -> Option<String> {
+    gtk::accelerator_parse(accel).map(|(key, mods)| gtk::accelerator_name(key, mods).to_string())
+}
+
+/// Assign a single accelerator to a binding.
+///
+/// The accelerator is removed from the other bindings that use it, so that a key press
+/// always triggers one action.
+pub fn assign(settings: &gio::Settings, binding: &Binding, accel: &str) {
+    let new_accel: Option<String> = normalize(accel);
+    let mut custom: HashMap<String, Vec<String>> = custom_accels(settings);
+
+    for other in BINDINGS.iter().filter(|b| b.name != binding.name) {
+        let accels: Vec<String> = other.accels_in(&custom);
+        let kept: Vec<String> = accels
+            .iter()
+            .filter(|a| new_accel.is_none() || normalize(a) != new_accel)
+            .cloned()
+            .collect();
+        if kept.len() != accels.len() {
+            custom.insert(other.name.to_string(), kept);
+        }
+    }
+    custom.insert(binding.name.to_string(), vec![accel.to_string()]);
+    save_custom_accels(settings, &custom);
+}
+
+/// Disable the shortcut of a binding.
+pub fn disable(settings: &gio::Settings, binding: &Binding) {
+    let mut custom: HashMap<String, Vec<String>> = custom_accels(settings);
+    custom.insert(binding.name.to_string(), Vec::new());
+    save_custom_accels(settings, &custom);
+}
+
+/// Restore the default accelerators of all the bindings.
+pub fn reset_all(settings: &gio::Settings) {
+    settings.reset(SETTINGS_KEY);
+}
+
+/// Register the accelerators of the application actions.
+pub fn apply_app(application: &impl IsA<gtk::Application>, settings: &gio::Settings) {
+    for binding in BINDINGS.iter().filter(|b| b.section == Section::General) {
+        let accels: Vec<String> = binding.accels(settings);
+        let accels: Vec<&str> = accels.iter().map(String::as_str).collect();
+        application.set_accels_for_action(binding.name, &accels);
+    }
+}
+
+/// Replace the shortcuts of the given controller by the shortcuts of the game view actions.
+pub fn fill_controller(controller: &gtk::ShortcutController, settings: &gio::Settings) {
+    while let Some(shortcut) = controller.item(0).and_downcast::<gtk::Shortcut>() {
+        controller.remove_shortcut(&shortcut);
+    }
+    for binding in BINDINGS.iter().filter(|b| b.section == Section::Game) {
+        let accels: Vec<String> = binding.accels(settings);
+        if accels.is_empty() {
+            continue;
+        }
+        match gtk::ShortcutTrigger::parse_string(&accels.join("|")) {
+            Some(trigger) => controller.add_shortcut(gtk::Shortcut::new(
+                Some(trigger),
+                Some(gtk::NamedAction::new(binding.name)),
+            )),
+            None => debug!("Invalid accelerators for {}: {accels:?}", binding.name),
+        }
+    }
+}
+
+/// Whether the key press matches one of the accelerators of the binding with the given name.
+pub fn matches(
+    settings: &gio::Settings,
+    name: &str,
+    keyval: gdk::Key,
+    modifier: gdk::ModifierType,
+) -> bool {
+    let Some(binding) = lookup(name) else {
+        return false;
+    };
+    let modifier: gdk::ModifierType = modifier & gtk::accelerator_get_default_mod_mask();
+    binding.accels(settings).iter().any(|accel| {
+        gtk::accelerator_parse(accel)
+            .is_some_and(|(key, mods)| key.to_lower() == keyval.to_lower() && mods == modifier)
+    })
+}
@@ -0,0 +1,48 @@
+/*
+lib.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Hexkudo library, for fuzzing only.
+//!
+//! Hexkudo is an application. The library is empty unless the `fuzzing` feature is enabled, in
+//! which case it exposes the [`fuzz`] entry points and the modules that they need.
+
+#![cfg(feature = "fuzzing")]
+#![allow(
+    dead_code,
+    reason = "The fuzzing entry points only use a part of the modules"
+)]
+
+mod chain;
+mod checkpoint;
+mod clock;
+mod config;
+pub mod fuzz;
+mod game;
+mod generator;
+mod highscores;
+mod input_errors;
+mod player_input;
+mod sample_pool;
+mod saver;
+mod scoring;
+mod statistics;
+mod theme;
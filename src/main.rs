@@ -23,16 +23,38 @@ SPDX-License-Identifier: GPL-3.0-or-later
 */
 
 mod application;
+mod chain;
 mod checkpoint;
 mod cli_options;
+mod clock;
 mod config;
+mod difficulty_defaults;
 mod draw;
+mod energy_saver;
 mod game;
+mod generation_cache;
 mod generator;
 mod highscores;
 mod input_errors;
+mod keybindings;
+mod navigation;
+mod network;
+mod number_words;
 mod player_input;
+mod player_profiles;
+mod proof;
+mod qr_decode;
+mod recommendation;
+mod report;
+mod sample_pool;
 mod saver;
+mod scoring;
+mod seasonal;
+mod shared_game;
+mod solvability;
+mod statistics;
+mod summary;
+mod theme;
 mod widgets;
 
 use self::application::HexkudoApplication;
@@ -45,7 +67,8 @@ use gtk::{gio, glib};
 fn main() -> glib::ExitCode {
     // Hexkudo does not use the option parsing feature provided by GApplication. Clap is used
     // instead.
-    if let Some(ret) = cli_options::parse() {
+    let outcome: cli_options::Outcome = cli_options::parse();
+    if let cli_options::Outcome::Exit(ret) = outcome {
         return glib::ExitCode::from(ret);
     }
 
@@ -60,10 +83,26 @@ fn main() -> glib::ExitCode {
         .expect("Cannot load resources");
     gio::resources_register(&resources);
 
+    let start_request: Option<cli_options::StartRequest> = match outcome {
+        cli_options::Outcome::Print(request) => {
+            return glib::ExitCode::from(cli_options::print(request));
+        }
+        cli_options::Outcome::Run(request) => request,
+        cli_options::Outcome::Exit(_) => None,
+    };
+
     // Create a new GtkApplication. The application manages our main loop,
     // application windows, integration with the window manager/compositor, and
     // desktop features such as file opening and single-instance applications.
     let app: HexkudoApplication = HexkudoApplication::new();
 
-    app.run()
+    match start_request {
+        Some(request) => {
+            // GApplication does not know the options that clap already processed
+            app.set_start_request(request);
+            let program: String = std::env::args().next().unwrap_or_default();
+            app.run_with_args(&[program])
+        }
+        None => app.run(),
+    }
 }
@@ -0,0 +1,155 @@
+/*
+navigation.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Navigation between the views of the main window.
+//!
+//! The main window displays one view at a time: the starting view where players choose the
+//! difficulty level, the puzzle selection view, and the game view.
+//! The [`Navigation`] state machine defines the allowed transitions between these views, and
+//! keeps a history so that the Back and Forward actions behave predictably.
+//!
+//! Adding a view consists in adding a [`View`] variant and declaring its transitions in
+//! [`View::can_go_to`].
+
+/// Views of the main window.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum View {
+    /// Starting view, where players choose the difficulty level.
+    Start,
+
+    /// Puzzle selection view.
+    SelectPuzzle,
+
+    /// Game view.
+    Game,
+}
+
+impl View {
+    /// Whether the window can go from this view to the given view.
+    pub fn can_go_to(self, to: View) -> bool {
+        matches!(
+            (self, to),
+            (View::Start, View::SelectPuzzle)
+                | (View::Start, View::Game)
+                | (View::SelectPuzzle, View::Start)
+                | (View::SelectPuzzle, View::Game)
+                | (View::Game, View::Start)
+                | (View::Game, View::SelectPuzzle)
+                | (View::Game, View::Game)
+        )
+    }
+
+    /// Whether the player can leave the view with the Back and Forward actions.
+    /// The player leaves the game view by starting a new game.
+    fn is_browsable(self) -> bool {
+        self != View::Game
+    }
+}
+
+/// State machine for the navigation between the views of the main window.
+#[derive(Debug)]
+pub struct Navigation {
+    /// View that the window displays.
+    current: View,
+
+    /// Views that the Back action returns to, the most recent last.
+    back: Vec<View>,
+
+    /// Views that the Forward action returns to, the most recent last.
+    forward: Vec<View>,
+}
+
+impl Default for Navigation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Navigation {
+    /// Create a [`Navigation`] object. The window starts with the starting view.
+    pub fn new() -> Self {
+        Self {
+            current: View::Start,
+            back: Vec::new(),
+            forward: Vec::new(),
+        }
+    }
+
+    /// Return the view that the window displays.
+    pub fn current(&self) -> View {
+        self.current
+    }
+
+    /// Go to the given view. Return false if the transition is not allowed.
+    ///
+    /// Going to a new view clears the Forward history. Entering the game view clears the whole
+    /// history, because the player leaves that view by starting a new game.
+    pub fn go_to(&mut self, view: View) -> bool {
+        if view == self.current && view.is_browsable() {
+            return true;
+        }
+        if !self.current.can_go_to(view) {
+            return false;
+        }
+        if view == View::Game {
+            self.back.clear();
+        } else if self.current.is_browsable() {
+            self.back.push(self.current);
+        }
+        self.forward.clear();
+        self.current = view;
+        true
+    }
+
+    /// Go back to the previous view and return it, or return None if there is no previous view.
+    pub fn back(&mut self) -> Option<View> {
+        if !self.can_go_back() {
+            return None;
+        }
+        let view: View = self.back.pop()?;
+        self.forward.push(self.current);
+        self.current = view;
+        Some(view)
+    }
+
+    /// Go forward to the view that the player left with the Back action and return it, or
+    /// return None if there is no such view.
+    pub fn forward(&mut self) -> Option<View> {
+        if !self.can_go_forward() {
+            return None;
+        }
+        let view: View = self.forward.pop()?;
+        self.back.push(self.current);
+        self.current = view;
+        Some(view)
+    }
+
+    /// Whether the Back action has a view to return to.
+    pub fn can_go_back(&self) -> bool {
+        self.current.is_browsable() && !self.back.is_empty()
+    }
+
+    /// Whether the Forward action has a view to return to.
+    pub fn can_go_forward(&self) -> bool {
+        self.current.is_browsable() && !self.forward.is_empty()
+    }
+}
@@ -0,0 +1,199 @@
+/*
+network.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Optional client for an online leaderboard.
+//!
+//! The leaderboard is disabled by default. When the player enables it with the
+//! `leaderboard-enabled` GSettings key, Hexkudo posts the results of the completed games to the
+//! endpoint that the `leaderboard-url` key defines, and the scores dialog displays the standings
+//! that the endpoint returns.
+//!
+//! The endpoint must support the following requests:
+//!
+//! - `POST <url>` with a [`GameResult`] JSON object as the body.
+//! - `GET <url>?puzzle=<puzzle_name>&difficulty=<difficulty>`, which returns a JSON array of
+//!   [`Standing`] objects, best first.
+//!
+//! The requests use HTTP/1.0 through [`gio::SocketClient`], with TLS for the `https` URLs, so
+//! that the responses are never chunked.
+
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::time::Duration;
+
+use crate::config;
+use crate::generator::puzzles::Difficulty;
+
+/// Maximum size of a response, in bytes. The larger responses are rejected.
+const MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+
+/// Maximum number of standings to display.
+const MAX_STANDINGS: usize = 100;
+
+/// Timeout for the network operations, in seconds.
+const TIMEOUT: u32 = 10;
+
+/// Result of a completed game, as posted to the leaderboard.
+#[derive(Serialize, Debug)]
+pub struct GameResult {
+    pub puzzle: String,
+    pub difficulty: Difficulty,
+    /// Duration of the game in seconds.
+    pub time: f64,
+    pub errors: usize,
+    pub version: String,
+}
+
+impl GameResult {
+    /// Create a [`GameResult`] object for the running version of the application.
+    pub fn new(puzzle: &str, difficulty: Difficulty, time: Duration, errors: usize) -> Self {
+        Self {
+            puzzle: String::from(puzzle),
+            difficulty,
+            time: time.as_secs_f64(),
+            errors,
+            version: String::from(config::VERSION),
+        }
+    }
+}
+
+/// Entry in the leaderboard standings.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Standing {
+    /// Name of the player, when the leaderboard provides it.
+    #[serde(default)]
+    pub player: String,
+    /// Duration of the game in seconds.
+    pub time: f64,
+    #[serde(default)]
+    pub errors: usize,
+}
+
+/// Post the result of a completed game to the leaderboard endpoint.
+pub async fn post_result(url: &str, result: &GameResult) -> Result<(), Box<dyn Error>> {
+    let body: String = serde_json::to_string(result)?;
+    request("POST", url, Some(body)).await?;
+    Ok(())
+}
+
+/// Retrieve the standings for the given puzzle from the leaderboard endpoint.
+pub async fn fetch_standings(
+    url: &str,
+    puzzle_name: &str,
+    difficulty: Difficulty,
+) -> Result<Vec<Standing>, Box<dyn Error>> {
+    let separator: char = if url.contains('?') { '&' } else { '?' };
+    let query_url: String = format!(
+        "{url}{separator}puzzle={}&difficulty={difficulty:?}",
+        glib::Uri::escape_string(puzzle_name, None, false)
+    );
+    let body: String = request("GET", &query_url, None).await?;
+    let mut standings: Vec<Standing> = serde_json::from_str(&body)?;
+
+    standings.truncate(MAX_STANDINGS);
+    Ok(standings)
+}
+
+/// Send an HTTP request and return the body of the response.
+///
+/// Return an error when the server does not answer with a 2xx status code.
+async fn request(method: &str, url: &str, body: Option<String>) -> Result<String, Box<dyn Error>> {
+    let uri: glib::Uri = glib::Uri::parse(url, glib::UriFlags::NONE)?;
+    let tls: bool = match uri.scheme().as_str() {
+        "https" => true,
+        "http" => false,
+        scheme => return Err(format!("Unsupported URL scheme: {scheme}").into()),
+    };
+    let host: glib::GString = uri.host().ok_or("The URL does not include a host")?;
+    let host_header: String = match uri.port() {
+        -1 => host.to_string(),
+        port => format!("{host}:{port}"),
+    };
+    let mut target: String = uri.path().to_string();
+    if target.is_empty() {
+        target.push('/');
+    }
+    if let Some(query) = uri.query() {
+        target.push('?');
+        target.push_str(&query);
+    }
+
+    let client: gio::SocketClient = gio::SocketClient::new();
+    client.set_tls(tls);
+    client.set_timeout(TIMEOUT);
+    let connection: gio::SocketConnection = client
+        .connect_to_uri_future(url, if tls { 443 } else { 80 })
+        .await?;
+
+    let body: String = body.unwrap_or_default();
+    let message: String = format!(
+        "{method} {target} HTTP/1.0\r\n\
+         Host: {host_header}\r\n\
+         User-Agent: Hexkudo/{}\r\n\
+         Accept: application/json\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {body}",
+        config::VERSION,
+        body.len()
+    );
+    connection
+        .output_stream()
+        .write_all_future(message.into_bytes(), glib::Priority::DEFAULT)
+        .await
+        .map_err(|(_, error)| error)?;
+
+    // The server closes the connection after the response
+    let input: gio::InputStream = connection.input_stream();
+    let mut response: Vec<u8> = Vec::new();
+    loop {
+        let bytes: glib::Bytes = input
+            .read_bytes_future(8192, glib::Priority::DEFAULT)
+            .await?;
+        if bytes.is_empty() {
+            break;
+        }
+        response.extend_from_slice(&bytes);
+        if response.len() > MAX_RESPONSE_SIZE {
+            return Err("The response is too large".into());
+        }
+    }
+    let _ = connection.close_future(glib::Priority::DEFAULT).await;
+
+    let response: String = String::from_utf8(response)?;
+    let (head, content) = response
+        .split_once("\r\n\r\n")
+        .ok_or("Malformed HTTP response")?;
+    let status: u16 = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or("Malformed HTTP status line")?;
+    if !(200..300).contains(&status) {
+        return Err(format!("The server answered with the HTTP status {status}").into());
+    }
+    Ok(String::from(content))
+}
@@ -0,0 +1,144 @@
+/*
+number_words.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Spelling of the numbers in words.
+//!
+//! The learning mode displays the numbers in words below the digits. Languages build compound
+//! numbers in too many different ways for a pattern that combines the tens and the units, so
+//! each number is a whole translatable word. The largest built-in puzzle has 60 cells, and the
+//! words cover the numbers up to 99. The larger numbers, which only the imported puzzles have,
+//! are displayed as digits.
+
+use gettextrs::pgettext;
+
+/// Largest number that has a translated word.
+const MAX_NUMBER_WORD: usize = 99;
+
+/// Return the given number in words, or in digits when it is larger than [`MAX_NUMBER_WORD`].
+pub fn number_to_words(number: usize) -> String {
+    if number > MAX_NUMBER_WORD {
+        return format!("{number}");
+    }
+    match number {
+        // Translators: Spell each number as a whole, with the compound rules of your language
+        0 => pgettext("Number", "zero"),
+        1 => pgettext("Number", "one"),
+        2 => pgettext("Number", "two"),
+        3 => pgettext("Number", "three"),
+        4 => pgettext("Number", "four"),
+        5 => pgettext("Number", "five"),
+        6 => pgettext("Number", "six"),
+        7 => pgettext("Number", "seven"),
+        8 => pgettext("Number", "eight"),
+        9 => pgettext("Number", "nine"),
+        10 => pgettext("Number", "ten"),
+        11 => pgettext("Number", "eleven"),
+        12 => pgettext("Number", "twelve"),
+        13 => pgettext("Number", "thirteen"),
+        14 => pgettext("Number", "fourteen"),
+        15 => pgettext("Number", "fifteen"),
+        16 => pgettext("Number", "sixteen"),
+        17 => pgettext("Number", "seventeen"),
+        18 => pgettext("Number", "eighteen"),
+        19 => pgettext("Number", "nineteen"),
+        20 => pgettext("Number", "twenty"),
+        21 => pgettext("Number", "twenty-one"),
+        22 => pgettext("Number", "twenty-two"),
+        23 => pgettext("Number", "twenty-three"),
+        24 => pgettext("Number", "twenty-four"),
+        25 => pgettext("Number", "twenty-five"),
+        26 => pgettext("Number", "twenty-six"),
+        27 => pgettext("Number", "twenty-seven"),
+        28 => pgettext("Number", "twenty-eight"),
+        29 => pgettext("Number", "twenty-nine"),
+        30 => pgettext("Number", "thirty"),
+        31 => pgettext("Number", "thirty-one"),
+        32 => pgettext("Number", "thirty-two"),
+        33 => pgettext("Number", "thirty-three"),
+        34 => pgettext("Number", "thirty-four"),
+        35 => pgettext("Number", "thirty-five"),
+        36 => pgettext("Number", "thirty-six"),
+        37 => pgettext("Number", "thirty-seven"),
+        38 => pgettext("Number", "thirty-eight"),
+        39 => pgettext("Number", "thirty-nine"),
+        40 => pgettext("Number", "forty"),
+        41 => pgettext("Number", "forty-one"),
+        42 => pgettext("Number", "forty-two"),
+        43 => pgettext("Number", "forty-three"),
+        44 => pgettext("Number", "forty-four"),
+        45 => pgettext("Number", "forty-five"),
+        46 => pgettext("Number", "forty-six"),
+        47 => pgettext("Number", "forty-seven"),
+        48 => pgettext("Number", "forty-eight"),
+        49 => pgettext("Number", "forty-nine"),
+        50 => pgettext("Number", "fifty"),
+        51 => pgettext("Number", "fifty-one"),
+        52 => pgettext("Number", "fifty-two"),
+        53 => pgettext("Number", "fifty-three"),
+        54 => pgettext("Number", "fifty-four"),
+        55 => pgettext("Number", "fifty-five"),
+        56 => pgettext("Number", "fifty-six"),
+        57 => pgettext("Number", "fifty-seven"),
+        58 => pgettext("Number", "fifty-eight"),
+        59 => pgettext("Number", "fifty-nine"),
+        60 => pgettext("Number", "sixty"),
+        61 => pgettext("Number", "sixty-one"),
+        62 => pgettext("Number", "sixty-two"),
+        63 => pgettext("Number", "sixty-three"),
+        64 => pgettext("Number", "sixty-four"),
+        65 => pgettext("Number", "sixty-five"),
+        66 => pgettext("Number", "sixty-six"),
+        67 => pgettext("Number", "sixty-seven"),
+        68 => pgettext("Number", "sixty-eight"),
+        69 => pgettext("Number", "sixty-nine"),
+        70 => pgettext("Number", "seventy"),
+        71 => pgettext("Number", "seventy-one"),
+        72 => pgettext("Number", "seventy-two"),
+        73 => pgettext("Number", "seventy-three"),
+        74 => pgettext("Number", "seventy-four"),
+        75 => pgettext("Number", "seventy-five"),
+        76 => pgettext("Number", "seventy-six"),
+        77 => pgettext("Number", "seventy-seven"),
+        78 => pgettext("Number", "seventy-eight"),
+        79 => pgettext("Number", "seventy-nine"),
+        80 => pgettext("Number", "eighty"),
+        81 => pgettext("Number", "eighty-one"),
+        82 => pgettext("Number", "eighty-two"),
+        83 => pgettext("Number", "eighty-three"),
+        84 => pgettext("Number", "eighty-four"),
+        85 => pgettext("Number", "eighty-five"),
+        86 => pgettext("Number", "eighty-six"),
+        87 => pgettext("Number", "eighty-seven"),
+        88 => pgettext("Number", "eighty-eight"),
+        89 => pgettext("Number", "eighty-nine"),
+        90 => pgettext("Number", "ninety"),
+        91 => pgettext("Number", "ninety-one"),
+        92 => pgettext("Number", "ninety-two"),
+        93 => pgettext("Number", "ninety-three"),
+        94 => pgettext("Number", "ninety-four"),
+        95 => pgettext("Number", "ninety-five"),
+        96 => pgettext("Number", "ninety-six"),
+        97 => pgettext("Number", "ninety-seven"),
+        98 => pgettext("Number", "ninety-eight"),
+        _ => pgettext("Number", "ninety-nine"),
+    }
+}
@@ -23,10 +23,24 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! Manage the player's cell input.
 //!
 //! The module manges the cell values that the player entered, as well as the undo and redo lists.
+//!
+//! The undo and redo lists are saved with the game in progress, so that players can undo their
+//! recent moves after restarting Hexkudo. Only the most recent operations are saved.
+//!
+//! Each operation records the time when the player performed it, so that the history of the
+//! moves can be listed, and the player can jump back to any previous state.
+//!
+//! The module also manages the notes (pencil marks): candidate numbers that the player writes in
+//! the empty cells. Notes are not part of the undo and redo lists.
+
+use std::collections::{BTreeSet, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize, Serializer};
 
-use serde::{Deserialize, Serialize};
+/// Maximum number of operations that are saved in each of the undo and redo lists. The older
+/// operations are dropped when the game is saved.
+const SAVED_OPERATIONS: usize = 500;
 
 /// Undo and redo operations.
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -47,6 +61,55 @@ struct DoOperation {
 
     /// Cell value.
     cell_value: usize,
+
+    /// Whether the operation is undone and redone with the previous operation, as part of
+    /// a group of operations.
+    #[serde(default)]
+    grouped: bool,
+
+    /// Time of the operation, in seconds since the Unix epoch. Zero for the operations that
+    /// older versions saved.
+    #[serde(default)]
+    time: u64,
+}
+
+impl DoOperation {
+    /// Create an operation performed now.
+    fn new(operation: Operation, cell_id: usize, cell_value: usize, grouped: bool) -> Self {
+        Self {
+            operation,
+            cell_id,
+            cell_value,
+            grouped,
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Step in the history of the moves. A step is a single operation, or a group of operations
+/// that are undone and redone together.
+#[derive(Debug, Clone)]
+pub struct HistoryStep {
+    /// Cell ID of the first operation of the step.
+    pub cell_id: usize,
+
+    /// Cell value of the first operation of the step.
+    pub cell_value: usize,
+
+    /// Whether the step removed the value from the cell.
+    pub removed: bool,
+
+    /// Number of cells that the step changed.
+    pub cells: usize,
+
+    /// Time of the step, in seconds since the Unix epoch, or zero if unknown.
+    pub time: u64,
+
+    /// Whether the step is undone, and can be redone.
+    pub undone: bool,
 }
 
 /// Manage the puzzle cells that the player completed.
@@ -61,10 +124,37 @@ pub struct PlayerInput {
     value_to_ids: HashMap<usize, Vec<usize>>,
 
     /// List of undo operations.
+    #[serde(serialize_with = "serialize_recent_ops")]
     undo_op: Vec<DoOperation>,
 
     /// List of redo operations.
+    #[serde(serialize_with = "serialize_recent_ops")]
     redo_op: Vec<DoOperation>,
+
+    /// Maximum number of operations in the undo list, or 0 for no limit.
+    /// The oldest operations are dropped, and their values become part of the starting state.
+    #[serde(skip)]
+    max_undo: usize,
+
+    /// Candidate numbers that the player wrote in the cells, indexed by the cell IDs.
+    #[serde(default)]
+    notes: HashMap<usize, BTreeSet<usize>>,
+}
+
+/// Serialize the [`SAVED_OPERATIONS`] most recent operations of an undo or a redo list.
+fn serialize_recent_ops<S>(ops: &[DoOperation], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut recent: Vec<DoOperation> = ops[ops.len().saturating_sub(SAVED_OPERATIONS)..].to_vec();
+
+    // The first saved operation is not linked to the dropped operations anymore
+    if recent.len() < ops.len()
+        && let Some(op) = recent.first_mut()
+    {
+        op.grouped = false;
+    }
+    recent.serialize(serializer)
 }
 
 impl Default for PlayerInput {
@@ -81,6 +171,8 @@ impl PlayerInput {
             value_to_ids: HashMap::new(),
             undo_op: Vec::new(),
             redo_op: Vec::new(),
+            max_undo: 0,
+            notes: HashMap::new(),
         }
     }
 
@@ -90,6 +182,7 @@ impl PlayerInput {
         self.value_to_ids.clear();
         self.undo_op.clear();
         self.redo_op.clear();
+        self.notes.clear();
     }
 
     /// Return the cell values in an [`HashMap`] indexed by the cell IDs.
@@ -97,6 +190,30 @@ impl PlayerInput {
         &self.id_to_value
     }
 
+    /// Set the maximum number of operations in the undo list. Zero means no limit.
+    pub fn set_max_undo(&mut self, max_undo: usize) {
+        self.max_undo = max_undo;
+        self.trim_undo();
+    }
+
+    /// Return the maximum number of operations in the undo list. Zero means no limit.
+    pub fn get_max_undo(&self) -> usize {
+        self.max_undo
+    }
+
+    /// Drop the oldest operations from the undo list when the list is longer than the maximum.
+    /// A group of operations is dropped as a whole.
+    fn trim_undo(&mut self) {
+        if self.max_undo == 0 || self.undo_op.len() <= self.max_undo {
+            return;
+        }
+        let mut end: usize = self.undo_op.len() - self.max_undo;
+        while self.undo_op.get(end).is_some_and(|op| op.grouped) {
+            end += 1;
+        }
+        self.undo_op.drain(..end);
+    }
+
     /// Return a cell's value or None is the player has not filled that cell yet.
     pub fn get_value_from_id(&self, cell_id: usize) -> Option<usize> {
         self.id_to_value.get(&cell_id).copied()
@@ -117,6 +234,22 @@ impl PlayerInput {
         }
     }
 
+    /// Return the IDs of all the cells that have the given value. The list has several cells
+    /// when the player used the same value more than once.
+    pub fn get_ids_from_value(&self, cell_value: usize) -> &[usize] {
+        match self.value_to_ids.get(&cell_value) {
+            Some(ids) => &ids[..],
+            None => &[],
+        }
+    }
+
+    /// Return the values, from 1 to `num_vertexes`, that no cell has yet, in increasing order.
+    pub fn missing_values(&self, num_vertexes: usize) -> Vec<usize> {
+        (1..=num_vertexes)
+            .filter(|v| !self.contains_value(*v))
+            .collect()
+    }
+
     /// Whether a cell has the provided value.
     pub fn contains_value(&self, cell_value: usize) -> bool {
         match self.value_to_ids.get(&cell_value) {
@@ -150,18 +283,32 @@ impl PlayerInput {
         // First, remove the previous value
         self.remove(cell_id);
         self.add_no_undo(cell_id, cell_value);
-        self.undo_op.push(DoOperation {
-            operation: Operation::Add,
-            cell_id,
-            cell_value,
-        });
+        self.undo_op
+            .push(DoOperation::new(Operation::Add, cell_id, cell_value, false));
         self.redo_op.clear();
+        self.trim_undo();
+    }
+
+    /// Add values to several cells, and add the operations to the undo list as a single group.
+    pub fn add_group(&mut self, cells: &[(usize, usize)]) {
+        for (i, (cell_id, cell_value)) in cells.iter().enumerate() {
+            self.remove_no_undo(*cell_id);
+            self.add_no_undo(*cell_id, *cell_value);
+            self.undo_op.push(DoOperation::new(
+                Operation::Add,
+                *cell_id,
+                *cell_value,
+                i > 0,
+            ));
+        }
+        self.redo_op.clear();
+        self.trim_undo();
     }
 
     /// Remove the value from the given cell and return the removed value or None if the cell
     /// had no value.
     /// Do not update the undo list.
-    fn remove_no_undo(&mut self, cell_id: usize) -> Option<usize> {
+    pub fn remove_no_undo(&mut self, cell_id: usize) -> Option<usize> {
         match self.id_to_value.remove(&cell_id) {
             Some(cell_value) => {
                 // Remove the cell ID from value-to-cell vector.
@@ -177,15 +324,49 @@ impl PlayerInput {
     /// Remove the value from the given cell.
     pub fn remove(&mut self, cell_id: usize) {
         if let Some(cell_value) = self.remove_no_undo(cell_id) {
-            self.undo_op.push(DoOperation {
-                operation: Operation::Remove,
+            self.undo_op.push(DoOperation::new(
+                Operation::Remove,
                 cell_id,
                 cell_value,
-            });
+                false,
+            ));
             self.redo_op.clear();
+            self.trim_undo();
         }
     }
 
+    /// Set the value of a revealed cell (hint).
+    /// The operations on that cell are removed from the undo and redo lists, so that undoing
+    /// other operations cannot change the revealed value.
+    pub fn reveal(&mut self, cell_id: usize, cell_value: usize) {
+        self.remove_no_undo(cell_id);
+        self.add_no_undo(cell_id, cell_value);
+        Self::remove_cell_ops(&mut self.undo_op, cell_id);
+        // In the redo list, the first operation of a group is the last one
+        self.redo_op.reverse();
+        Self::remove_cell_ops(&mut self.redo_op, cell_id);
+        self.redo_op.reverse();
+    }
+
+    /// Remove the operations on the given cell from an operation list.
+    /// When the first operation of a group is removed, the next operation of the group becomes
+    /// the first one.
+    fn remove_cell_ops(ops: &mut Vec<DoOperation>, cell_id: usize) {
+        let mut promote: bool = false;
+
+        ops.retain_mut(|op| {
+            if op.cell_id == cell_id {
+                promote |= !op.grouped;
+                return false;
+            }
+            if promote && op.grouped {
+                op.grouped = false;
+            }
+            promote = false;
+            true
+        });
+    }
+
     /// Whether the player entered the given value in multiple cells, which is a mistake.
     pub fn is_value_duplicated(&self, cell_value: usize) -> bool {
         match self.value_to_ids.get(&cell_value) {
@@ -194,9 +375,9 @@ impl PlayerInput {
         }
     }
 
-    /// Undo the last operation.
+    /// Undo the last operation, or the last group of operations.
     pub fn undo(&mut self) {
-        if let Some(op) = self.undo_op.pop() {
+        while let Some(op) = self.undo_op.pop() {
             match op.operation {
                 Operation::Add => {
                     self.remove_no_undo(op.cell_id);
@@ -205,13 +386,17 @@ impl PlayerInput {
                     self.add_no_undo(op.cell_id, op.cell_value);
                 }
             }
+            let grouped: bool = op.grouped;
             self.redo_op.push(op);
+            if !grouped {
+                break;
+            }
         }
     }
 
-    /// Redo the last undo operation.
+    /// Redo the last undo operation, or the last undone group of operations.
     pub fn redo(&mut self) {
-        if let Some(op) = self.redo_op.pop() {
+        while let Some(op) = self.redo_op.pop() {
             match op.operation {
                 Operation::Add => {
                     self.add_no_undo(op.cell_id, op.cell_value);
@@ -221,9 +406,139 @@ impl PlayerInput {
                 }
             }
             self.undo_op.push(op);
+            if !self.redo_op.last().is_some_and(|op| op.grouped) {
+                break;
+            }
         }
     }
 
+    /// Return the steps of the undo list, oldest first, followed by the steps of the redo list,
+    /// in the order in which they would be redone.
+    pub fn history(&self) -> Vec<HistoryStep> {
+        let mut steps: Vec<HistoryStep> = Vec::new();
+
+        // In the redo list, the first operation of a group is the last one
+        let ops = self
+            .undo_op
+            .iter()
+            .map(|op| (op, false))
+            .chain(self.redo_op.iter().rev().map(|op| (op, true)));
+        for (op, undone) in ops {
+            match steps.last_mut() {
+                Some(step) if op.grouped && step.undone == undone => step.cells += 1,
+                _ => steps.push(HistoryStep {
+                    cell_id: op.cell_id,
+                    cell_value: op.cell_value,
+                    removed: matches!(op.operation, Operation::Remove),
+                    cells: 1,
+                    time: op.time,
+                    undone,
+                }),
+            }
+        }
+        steps
+    }
+
+    /// Return the number of steps in the undo list.
+    pub fn undo_steps(&self) -> usize {
+        self.undo_op
+            .iter()
+            .enumerate()
+            .filter(|(i, op)| *i == 0 || !op.grouped)
+            .count()
+    }
+
+    /// Undo or redo steps until the undo list has the given number of steps.
+    /// The steps are numbered as in the list that [`PlayerInput::history`] returns.
+    pub fn jump_to(&mut self, step: usize) {
+        let current: usize = self.undo_steps();
+
+        for _ in step..current {
+            self.undo();
+        }
+        for _ in current..step {
+            if self.redo_op.is_empty() {
+                break;
+            }
+            self.redo();
+        }
+    }
+
+    /// Add the given candidate number to the notes of a cell, or remove it if the cell already
+    /// has that note. Return whether the cell has the note after the operation.
+    pub fn toggle_note(&mut self, cell_id: usize, cell_value: usize) -> bool {
+        let notes: &mut BTreeSet<usize> = self.notes.entry(cell_id).or_default();
+
+        let added: bool = notes.insert(cell_value);
+        if !added {
+            notes.remove(&cell_value);
+            if notes.is_empty() {
+                self.notes.remove(&cell_id);
+            }
+        }
+        added
+    }
+
+    /// Return the notes of a cell, or None if the cell has no notes.
+    pub fn get_notes(&self, cell_id: usize) -> Option<&BTreeSet<usize>> {
+        self.notes.get(&cell_id)
+    }
+
+    /// Return the notes of all the cells, indexed by the cell IDs.
+    pub fn get_all_notes(&self) -> &HashMap<usize, BTreeSet<usize>> {
+        &self.notes
+    }
+
+    /// Remove the notes of a cell.
+    pub fn clear_notes(&mut self, cell_id: usize) {
+        self.notes.remove(&cell_id);
+    }
+
+    /// Verify that the cell IDs are below the given number of cells, and that the values are
+    /// between 1 and that number.
+    /// A restored object that fails the verification comes from a corrupted or a tampered file.
+    pub fn validate(&self, num_vertexes: usize) -> Result<(), String> {
+        let is_valid = |cell_id: usize, cell_value: usize| -> bool {
+            cell_id < num_vertexes && cell_value > 0 && cell_value <= num_vertexes
+        };
+
+        if self.undo_op.len() > SAVED_OPERATIONS || self.redo_op.len() > SAVED_OPERATIONS {
+            return Err(String::from("Too many undo or redo operations"));
+        }
+        for (cell_id, cell_value) in &self.id_to_value {
+            if !is_valid(*cell_id, *cell_value)
+                || !self
+                    .value_to_ids
+                    .get(cell_value)
+                    .is_some_and(|ids| ids.contains(cell_id))
+            {
+                return Err(format!("Invalid cell value: {cell_id}={cell_value}"));
+            }
+        }
+        for (cell_value, ids) in &self.value_to_ids {
+            if ids
+                .iter()
+                .any(|id| self.id_to_value.get(id) != Some(cell_value))
+            {
+                return Err(format!("Inconsistent cells for the value {cell_value}"));
+            }
+        }
+        for op in self.undo_op.iter().chain(self.redo_op.iter()) {
+            if !is_valid(op.cell_id, op.cell_value) {
+                return Err(format!(
+                    "Invalid undo or redo operation: {}={}",
+                    op.cell_id, op.cell_value
+                ));
+            }
+        }
+        for (cell_id, notes) in &self.notes {
+            if notes.len() > num_vertexes || notes.iter().any(|n| !is_valid(*cell_id, *n)) {
+                return Err(format!("Invalid notes for the cell {cell_id}"));
+            }
+        }
+        Ok(())
+    }
+
     /// Return the length of the undo list.
     pub fn undo_len(&self) -> usize {
         self.undo_op.len()
@@ -234,3 +549,110 @@ impl PlayerInput {
         self.redo_op.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return the cell values, sorted by cell ID.
+    fn values(player_input: &PlayerInput) -> Vec<(usize, usize)> {
+        let mut values: Vec<(usize, usize)> = player_input
+            .get_values()
+            .iter()
+            .map(|(c, v)| (*c, *v))
+            .collect();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn trim_drops_oldest_operations() {
+        let mut player_input: PlayerInput = PlayerInput::new();
+        player_input.set_max_undo(3);
+        for cell_id in 0..5 {
+            player_input.add(cell_id, cell_id + 1);
+        }
+
+        assert_eq!(player_input.undo_len(), 3);
+        for _ in 0..5 {
+            player_input.undo();
+        }
+        assert_eq!(values(&player_input), vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn trim_drops_whole_groups() {
+        let mut player_input: PlayerInput = PlayerInput::new();
+        player_input.set_max_undo(3);
+        player_input.add_group(&[(0, 1), (1, 2), (2, 3)]);
+        player_input.add(3, 4);
+
+        // The group does not fit in the remaining two operations
+        assert_eq!(player_input.undo_len(), 1);
+        player_input.undo();
+        player_input.undo();
+        assert_eq!(values(&player_input), vec![(0, 1), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn lower_limit_trims_the_list() {
+        let mut player_input: PlayerInput = PlayerInput::new();
+        for cell_id in 0..10 {
+            player_input.add(cell_id, cell_id + 1);
+        }
+        assert_eq!(player_input.undo_len(), 10);
+
+        player_input.set_max_undo(4);
+        assert_eq!(player_input.undo_len(), 4);
+        player_input.set_max_undo(0);
+        player_input.add(10, 11);
+        assert_eq!(player_input.undo_len(), 5);
+    }
+
+    #[test]
+    fn undo_redo_past_trim_point() {
+        let mut player_input: PlayerInput = PlayerInput::new();
+        player_input.set_max_undo(2);
+        for cell_id in 0..4 {
+            player_input.add(cell_id, cell_id + 1);
+        }
+        let solved: Vec<(usize, usize)> = values(&player_input);
+
+        // Undoing more steps than the list holds stops at the trim point
+        for _ in 0..5 {
+            player_input.undo();
+        }
+        assert_eq!(values(&player_input), vec![(0, 1), (1, 2)]);
+        assert_eq!(player_input.undo_len(), 0);
+        assert_eq!(player_input.redo_len(), 2);
+
+        // Redoing everything restores the latest state
+        for _ in 0..5 {
+            player_input.redo();
+        }
+        assert_eq!(values(&player_input), solved);
+        assert_eq!(player_input.undo_len(), 2);
+        assert_eq!(player_input.redo_len(), 0);
+    }
+
+    #[test]
+    fn trimmed_values_become_the_starting_state() {
+        let mut player_input: PlayerInput = PlayerInput::new();
+        player_input.set_max_undo(2);
+        player_input.add(0, 1);
+        player_input.add(1, 5);
+        player_input.remove(1);
+        player_input.add(1, 2);
+
+        // The first two operations are dropped: the starting state has the value of the first
+        // cell and the wrong value of the second cell
+        player_input.undo();
+        player_input.undo();
+        assert_eq!(values(&player_input), vec![(0, 1), (1, 5)]);
+        assert_eq!(player_input.undo_len(), 0);
+
+        player_input.redo();
+        player_input.redo();
+        assert_eq!(values(&player_input), vec![(0, 1), (1, 2)]);
+    }
+}
@@ -0,0 +1,145 @@
+/*
+player_profiles.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Player profiles, so that the players who share a computer keep their own high score boards,
+//! game history, and statistics.
+//!
+//! Each profile has an ID, which Hexkudo generates and which names the files of the profile,
+//! and a name, which players choose and can change. The profiles are stored in the
+//! `player-profiles` GSettings key, a dictionary of the names indexed by the IDs:
+//!
+//! ```text
+//! {'5f0c2a9e1b7d4c38': 'Alice', 'c81e3b0a94f2d765': 'Bob'}
+//! ```
+//!
+//! The `player-profile` GSettings key stores the ID of the current player. The default player
+//! has an empty ID and is not stored in the dictionary.
+
+use log::debug;
+use std::collections::HashMap;
+
+use gtk::{gio, prelude::*};
+
+/// GSettings key that stores the profiles.
+const PROFILES_KEY: &str = "player-profiles";
+
+/// GSettings key that stores the ID of the current player.
+const CURRENT_KEY: &str = "player-profile";
+
+/// Player profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerProfile {
+    /// ID that names the files of the profile.
+    pub id: String,
+
+    /// Name that the player chose.
+    pub name: String,
+}
+
+/// Return the profiles, without the default player, sorted by name.
+pub fn list(settings: &gio::Settings) -> Vec<PlayerProfile> {
+    let mut profiles: Vec<PlayerProfile> = load(settings)
+        .into_iter()
+        .map(|(id, name)| PlayerProfile { id, name })
+        .collect();
+    profiles.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+    profiles
+}
+
+/// Return the IDs of all the profiles, starting with the default player (empty ID).
+pub fn all_ids(settings: &gio::Settings) -> Vec<String> {
+    std::iter::once(String::new())
+        .chain(list(settings).into_iter().map(|p| p.id))
+        .collect()
+}
+
+/// Return the ID of the current player, empty for the default player.
+///
+/// If the profile of the current player does not exist anymore, then the default player is
+/// the current player.
+pub fn current(settings: &gio::Settings) -> String {
+    let id: String = settings.string(CURRENT_KEY).to_string();
+    if id.is_empty() || load(settings).contains_key(&id) {
+        id
+    } else {
+        String::new()
+    }
+}
+
+/// Select the current player.
+pub fn set_current(settings: &gio::Settings, id: &str) {
+    if let Err(error) = settings.set_string(CURRENT_KEY, id) {
+        debug!("Cannot save the current player: {error}");
+    }
+}
+
+/// Whether a profile already uses the given name.
+pub fn name_exists(settings: &gio::Settings, name: &str) -> bool {
+    load(settings).values().any(|n| n == name)
+}
+
+/// Create a profile with the given name and return its ID.
+pub fn add(settings: &gio::Settings, name: &str) -> String {
+    let mut profiles: HashMap<String, String> = load(settings);
+    let id: String = loop {
+        let id: String = format!("{:016x}", rand::random::<u64>());
+        if !profiles.contains_key(&id) {
+            break id;
+        }
+    };
+    profiles.insert(id.clone(), String::from(name));
+    save(settings, &profiles);
+    id
+}
+
+/// Change the name of the given profile. The files of the profile do not change.
+pub fn rename(settings: &gio::Settings, id: &str, name: &str) {
+    let mut profiles: HashMap<String, String> = load(settings);
+    if let Some(n) = profiles.get_mut(id) {
+        *n = String::from(name);
+        save(settings, &profiles);
+    }
+}
+
+/// Remove the given profile. The default player becomes the current player if the profile was
+/// the current one. The caller deletes the files of the profile.
+pub fn remove(settings: &gio::Settings, id: &str) {
+    let mut profiles: HashMap<String, String> = load(settings);
+    if profiles.remove(id).is_some() {
+        save(settings, &profiles);
+    }
+    if settings.string(CURRENT_KEY) == id {
+        set_current(settings, "");
+    }
+}
+
+/// Read the profiles from GSettings.
+fn load(settings: &gio::Settings) -> HashMap<String, String> {
+    settings.value(PROFILES_KEY).get().unwrap_or_default()
+}
+
+/// Store the profiles in GSettings.
+fn save(settings: &gio::Settings, profiles: &HashMap<String, String>) {
+    if let Err(error) = settings.set_value(PROFILES_KEY, &profiles.to_variant()) {
+        debug!("Cannot save the player profiles: {error}");
+    }
+}
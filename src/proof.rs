@@ -0,0 +1,569 @@
+/*
+proof.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Proof strings for solved puzzles.
+//!
+//! When players complete a puzzle, they can share a proof string alongside their time.
+//! The proof string embeds the puzzle with its solution path and its clues, the seed code that
+//! generated the puzzle, the game duration, the number of mistakes, and the log of all the
+//! changes of the cell values, with the game duration at each change.
+//!
+//! Other Hexkudo installations can verify the proof string: they regenerate the puzzle from its
+//! name, its difficulty level, and its seed code, and verify that it gives the same solution
+//! path and the same clues. They then replay the move log from the initial board, and verify
+//! that the moves solve the puzzle, that the last move happened before the claimed time, and
+//! that the mistake counter gives the claimed number of mistakes. Only the games on the puzzles
+//! that Hexkudo provides, with a seed code that reproduces the game, have a proof string.
+//!
+//! There is no server and no secret key involved. The checksum only detects damaged strings,
+//! and anybody can build a proof string with a consistent, but invented, move log. The proof
+//! string is therefore only meant for informal competitions.
+//!
+//! The format of the proof string is `HEXKUDO3:<payload>:<checksum>`, where `<payload>` is the
+//! base64 encoding of the JSON serialization of the proof content, and `<checksum>` is the
+//! SHA-256 checksum of the payload.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use gettextrs::gettext;
+use gtk::glib;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Game, Move, MoveKind};
+use crate::generation_cache::{self, GeneratedGame};
+use crate::generator::puzzles::{self, Puzzle, PuzzleExport};
+use crate::generator::regions::{self, Region};
+use crate::generator::seed_code::SeedCode;
+use crate::input_errors::InputErrors;
+
+/// Prefix of the proof strings. The number is the version of the format.
+const PROOF_PREFIX: &str = "HEXKUDO3";
+
+/// Errors returned when verifying a proof string.
+#[derive(Debug)]
+pub enum ProofError {
+    /// The string is not a proof string.
+    Format,
+
+    /// The checksum does not match the content.
+    Checksum,
+
+    /// The puzzle in the proof string is not valid.
+    Puzzle,
+
+    /// The proof string has no seed code, or Hexkudo does not provide its puzzle.
+    NoSeed,
+
+    /// The seed code does not regenerate the puzzle in the proof string.
+    Seed,
+
+    /// The path in the proof string does not solve the puzzle.
+    InvalidSolution,
+
+    /// The move log is not consistent, or does not solve the puzzle.
+    Moves,
+
+    /// The claimed time is shorter than the time of the last move.
+    Time,
+
+    /// The replay of the move log gives a different number of mistakes.
+    Errors,
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg: String = match self {
+            ProofError::Format => gettext("This is not a valid proof string."),
+            ProofError::Checksum => gettext("The proof string is damaged."),
+            ProofError::Puzzle => gettext("The puzzle in the proof string is not valid."),
+            ProofError::NoSeed => gettext("The puzzle of the proof string cannot be regenerated."),
+            ProofError::Seed => {
+                gettext("The seed does not generate the puzzle of the proof string.")
+            }
+            ProofError::InvalidSolution => gettext("The solution does not solve the puzzle."),
+            ProofError::Moves => gettext("The moves do not solve the puzzle."),
+            ProofError::Time => gettext("The time does not match the moves."),
+            ProofError::Errors => gettext("The number of mistakes does not match the moves."),
+        };
+        write!(f, "{msg}")
+    }
+}
+
+/// Serialized content of a proof string.
+#[derive(Serialize, Deserialize, Debug)]
+struct ProofData {
+    /// Puzzle, solution path, and clues, in the puzzle text format.
+    puzzle: String,
+
+    /// Seed code that generated the puzzle, if any.
+    seed: Option<String>,
+
+    /// Game duration, in milliseconds.
+    time_ms: u64,
+
+    /// Number of mistakes while resolving the puzzle.
+    errors: usize,
+
+    /// Log of the changes of the cell values.
+    moves: Vec<Move>,
+}
+
+/// Content of a proof string.
+#[derive(Debug, Clone)]
+pub struct Proof {
+    /// Puzzle, with its solution path and its clues.
+    pub export: PuzzleExport,
+
+    /// Seed code that generated the puzzle. The imported puzzles have no seed code.
+    pub seed: Option<SeedCode>,
+
+    /// Game duration.
+    pub time: Duration,
+
+    /// Number of mistakes while resolving the puzzle.
+    pub errors: usize,
+
+    /// Log of the changes of the cell values.
+    moves: Vec<Move>,
+}
+
+impl Proof {
+    /// Create a [`Proof`] object from a solved game.
+    pub fn new(game: &Game) -> Self {
+        Self {
+            export: game.to_export(),
+            seed: game.seed_code(),
+            time: game.get_duration(),
+            errors: game.get_errors(),
+            moves: game.get_moves().clone(),
+        }
+    }
+
+    /// Return the SHA-256 checksum of the given string.
+    fn checksum(data: &str) -> String {
+        glib::compute_checksum_for_string(glib::ChecksumType::Sha256, data)
+            .map(|c| c.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Build a [`Proof`] object from a proof string and verify the checksum.
+    ///
+    /// # Errors
+    ///
+    /// The method returns an error if the string is not a proof string, if the checksum does
+    /// not match, or if the puzzle is not valid.
+    pub fn parse(proof: &str) -> Result<Self, ProofError> {
+        let fields: Vec<&str> = proof.trim().split(':').collect();
+        if fields.len() != 3 || fields[0] != PROOF_PREFIX {
+            return Err(ProofError::Format);
+        }
+        if Self::checksum(fields[1]) != fields[2] {
+            return Err(ProofError::Checksum);
+        }
+        let json: Vec<u8> = glib::base64_decode(fields[1]);
+        let data: ProofData = serde_json::from_slice(&json).map_err(|_| ProofError::Format)?;
+
+        Ok(Self {
+            export: PuzzleExport::from_str(&data.puzzle).map_err(|_| ProofError::Puzzle)?,
+            seed: data
+                .seed
+                .map(|s| s.parse::<SeedCode>().map_err(|_| ProofError::Format))
+                .transpose()?,
+            time: Duration::from_millis(data.time_ms),
+            errors: data.errors,
+            moves: data.moves,
+        })
+    }
+
+    /// Regenerate the puzzle from its seed code, verify that the path solves the puzzle, and
+    /// then replay the move log.
+    ///
+    /// The generation can take some time, and therefore the method should not run in the main
+    /// thread.
+    ///
+    /// # Errors
+    ///
+    /// The method returns an error if the seed code does not regenerate the puzzle, if the path
+    /// is not a solution for the puzzle, if the moves do not solve the puzzle, or if the time or
+    /// the number of mistakes do not match the moves.
+    pub fn verify(&self) -> Result<(), ProofError> {
+        self.verify_seed()?;
+        self.verify_moves()
+    }
+
+    /// Verify that the path solves the puzzle, and then replay the move log, without
+    /// regenerating the puzzle.
+    ///
+    /// Use this method for the proofs that [`Proof::new`] just created from a game, which
+    /// already has the generated puzzle.
+    ///
+    /// # Errors
+    ///
+    /// See [`Proof::verify`].
+    pub fn verify_moves(&self) -> Result<(), ProofError> {
+        self.verify_solution()?;
+        self.replay()
+    }
+
+    /// Regenerate the puzzle from its name, its difficulty level, and its seed code, and verify
+    /// that it has the same cells, solution path, and clues as the puzzle in the proof.
+    fn verify_seed(&self) -> Result<(), ProofError> {
+        let export: &PuzzleExport = &self.export;
+        let Some(code) = self.seed else {
+            return Err(ProofError::NoSeed);
+        };
+        if !export.puzzle.is_builtin() {
+            return Err(ProofError::NoSeed);
+        }
+        let generated: PuzzleExport =
+            regenerate(&export.puzzle.name, export.puzzle.difficulty, code)
+                .ok_or(ProofError::NoSeed)?;
+
+        // The clue lists can come in a different order
+        let pairs = |list: &[(usize, usize)]| -> BTreeSet<(usize, usize)> {
+            list.iter().map(|(a, b)| (*a.min(b), *a.max(b))).collect()
+        };
+        let cells = |list: &[usize]| -> BTreeSet<usize> { list.iter().copied().collect() };
+        let sums = |list: &[Region]| -> BTreeSet<(Vec<usize>, usize)> {
+            list.iter()
+                .map(|r| {
+                    let mut cells: Vec<usize> = r.cells.clone();
+                    cells.sort_unstable();
+                    (cells, r.sum)
+                })
+                .collect()
+        };
+        if generated.path != export.path
+            || cells(&generated.map) != cells(&export.map)
+            || pairs(&generated.diamonds) != pairs(&export.diamonds)
+            || pairs(&generated.anti_diamonds) != pairs(&export.anti_diamonds)
+            || sums(&generated.regions) != sums(&export.regions)
+        {
+            return Err(ProofError::Seed);
+        }
+        Ok(())
+    }
+
+    /// Verify that the path in the proof solves the puzzle and respects its clues.
+    ///
+    /// The import of the puzzle already verified that the path goes through all the cells,
+    /// only once, and that consecutive cells in the path are adjacent.
+    fn verify_solution(&self) -> Result<(), ProofError> {
+        let export: &PuzzleExport = &self.export;
+        let vertexes = &export.puzzle.matrix.vertexes;
+        let position: HashMap<usize, usize> = export
+            .path
+            .iter()
+            .enumerate()
+            .map(|(i, cell_id)| (*cell_id, i))
+            .collect();
+        let consecutive = |a: &usize, b: &usize| -> bool {
+            matches!((position.get(a), position.get(b)), (Some(i), Some(j)) if i.abs_diff(*j) == 1)
+        };
+
+        // Some puzzles have a required starting cell
+        if let Some(v) = vertexes.required_starting_vertex
+            && export.path.first() != Some(&v)
+        {
+            return Err(ProofError::InvalidSolution);
+        }
+
+        // The diamonds link consecutive values, and the anti-diamonds never do
+        if export.diamonds.iter().any(|(a, b)| !consecutive(a, b))
+            || export.anti_diamonds.iter().any(|(a, b)| consecutive(a, b))
+        {
+            return Err(ProofError::InvalidSolution);
+        }
+
+        // The values of the regions add up to the sums
+        for region in &export.regions {
+            let sum: usize = region
+                .cells
+                .iter()
+                .filter_map(|c| position.get(c))
+                .map(|i| i + 1)
+                .sum();
+            if sum != region.sum {
+                return Err(ProofError::InvalidSolution);
+            }
+        }
+        Ok(())
+    }
+
+    /// Replay the move log from the initial board, where only the hint cells have a value.
+    ///
+    /// The player's entries go through the mistake counter, as during the game. The other
+    /// moves (undo, redo, checkpoints...) can only restore a value that the cell had before, or
+    /// clear the cell. Proof strings are only available for the games without assistance, and
+    /// therefore the log cannot have [`MoveKind::Assist`] moves.
+    fn replay(&self) -> Result<(), ProofError> {
+        let export: &PuzzleExport = &self.export;
+        let num_vertexes: usize = export.path.len();
+        let mut board: HashMap<usize, usize> = HashMap::new();
+        let mut seen: HashSet<(usize, usize)> = HashSet::new();
+        let mut input_errors: InputErrors = InputErrors::new();
+        let mut last_time: Duration = Duration::ZERO;
+
+        for cell_id in &export.map {
+            let value: usize = export
+                .path
+                .iter()
+                .position(|c| c == cell_id)
+                .ok_or(ProofError::InvalidSolution)?
+                + 1;
+            board.insert(*cell_id, value);
+        }
+
+        for m in &self.moves {
+            let time: Duration = Duration::from_millis(m.time_ms);
+            if time < last_time
+                || m.cell_id >= num_vertexes
+                || export.map.contains(&m.cell_id)
+                || m.value.is_some_and(|v| v == 0 || v > num_vertexes)
+            {
+                return Err(ProofError::Moves);
+            }
+            last_time = time;
+
+            match (m.kind, m.value) {
+                (MoveKind::Entry, Some(v)) => {
+                    let in_error: bool = export.path.get(v - 1) != Some(&m.cell_id);
+                    input_errors.add_cell(m.cell_id, in_error, time);
+                }
+                (MoveKind::Entry, None) => input_errors.clear_cell(m.cell_id, time),
+                (MoveKind::Restore, Some(v)) if !seen.contains(&(m.cell_id, v)) => {
+                    return Err(ProofError::Moves);
+                }
+                (MoveKind::Restore, _) => (),
+                (MoveKind::Assist, _) => return Err(ProofError::Moves),
+            }
+            match m.value {
+                Some(v) => {
+                    board.insert(m.cell_id, v);
+                    seen.insert((m.cell_id, v));
+                }
+                None => {
+                    board.remove(&m.cell_id);
+                }
+            }
+        }
+
+        // The final board must be the solution
+        if board.len() != num_vertexes
+            || export
+                .path
+                .iter()
+                .enumerate()
+                .any(|(i, cell_id)| board.get(cell_id) != Some(&(i + 1)))
+        {
+            return Err(ProofError::Moves);
+        }
+        if last_time > self.time {
+            return Err(ProofError::Time);
+        }
+        if input_errors.get_errors() != self.errors {
+            return Err(ProofError::Errors);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Proof {
+    /// Format the object as a proof string.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let data = ProofData {
+            puzzle: self.export.to_string(),
+            seed: self.seed.map(|s| s.to_string()),
+            time_ms: u64::try_from(self.time.as_millis()).unwrap_or(u64::MAX),
+            errors: self.errors,
+            moves: self.moves.clone(),
+        };
+        let json: String = serde_json::to_string(&data).map_err(|_| fmt::Error)?;
+        let payload: glib::GString = glib::base64_encode(json.as_bytes());
+        write!(f, "{PROOF_PREFIX}:{payload}:{}", Self::checksum(&payload))
+    }
+}
+
+/// Generate the game that the seed code gives for the puzzle that Hexkudo provides with the
+/// given name and difficulty level, without the pool samples, which differ between computers.
+///
+/// Return None if Hexkudo does not provide the puzzle, or if the seed code does not reproduce
+/// a game.
+fn regenerate(name: &str, difficulty: puzzles::Difficulty, code: SeedCode) -> Option<PuzzleExport> {
+    let mut puzzle: Puzzle = puzzles::puzzle_map().remove(&(name.to_string(), difficulty))?;
+    puzzle.matrix.build_edges().ok()?;
+    let generated: GeneratedGame = generation_cache::generate(
+        &puzzle,
+        code.seed,
+        code.options.endpoints,
+        code.options.negative_clues,
+        code.options.strict_diamonds,
+        &[],
+    );
+    if !generated.reproducible {
+        return None;
+    }
+    let (diamonds, map) = generated.diamond_and_map.get_diamond_and_map();
+    let regions: Vec<Region> = if code.options.sum_regions {
+        regions::generate(&generated.path, &puzzle.matrix.vertexes, code.seed)
+    } else {
+        Vec::new()
+    };
+    Some(PuzzleExport {
+        path: generated.path.get().clone(),
+        map,
+        diamonds,
+        anti_diamonds: generated.diamond_and_map.get_anti_diamonds(),
+        regions,
+        puzzle,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::diamond_and_map::Endpoints;
+    use crate::generator::seed_code::SeedOptions;
+
+    /// Seed code of the games in the tests.
+    const CODE: SeedCode = SeedCode {
+        seed: 42,
+        options: SeedOptions {
+            endpoints: Endpoints::Both,
+            negative_clues: false,
+            strict_diamonds: false,
+            sum_regions: false,
+        },
+    };
+
+    /// Return the proof of a solved game. The player fixed their only mistake immediately, and
+    /// therefore the mistake is not counted.
+    fn solved_proof() -> Proof {
+        let export: PuzzleExport = regenerate("Classic", puzzles::Difficulty::Easy, CODE).unwrap();
+        let mut game: Game = Game::new();
+        game.set_export(&export);
+        game.seed = Some(CODE.seed);
+        game.seed_options = Some(CODE.options);
+
+        // Wrong value in the second cell, and then an undo
+        let wrong_cell: usize = *export.path[2..]
+            .iter()
+            .find(|c| !export.map.contains(c))
+            .unwrap();
+        game.add_value_to_cell(wrong_cell, 2);
+        game.undo();
+        for (i, cell_id) in export.path.iter().enumerate() {
+            game.add_value_to_cell(*cell_id, i + 1);
+        }
+        assert!(game.is_solved());
+        Proof::new(&game)
+    }
+
+    #[test]
+    fn proof_string_round_trip() {
+        let proof: Proof = solved_proof();
+        let parsed: Proof = Proof::parse(&proof.to_string()).unwrap();
+
+        assert_eq!(parsed.seed, Some(CODE));
+        assert_eq!(parsed.export.path, proof.export.path);
+        assert!(parsed.verify().is_ok());
+    }
+
+    #[test]
+    fn verify_wrong_seed() {
+        let mut proof: Proof = solved_proof();
+
+        proof.seed = Some(SeedCode { seed: 43, ..CODE });
+        assert!(matches!(proof.verify(), Err(ProofError::Seed)));
+        proof.seed = None;
+        assert!(matches!(proof.verify(), Err(ProofError::NoSeed)));
+    }
+
+    #[test]
+    fn verify_invented_puzzle() {
+        let mut proof: Proof = solved_proof();
+
+        // The moves still solve the puzzle, but the seed does not give these hints
+        let first: usize = proof.export.path[0];
+        proof.export.map.retain(|c| *c != first);
+        assert!(matches!(proof.verify(), Err(ProofError::Seed)));
+    }
+
+    #[test]
+    fn verify_solved_game() {
+        let proof: Proof = solved_proof();
+
+        assert_eq!(proof.errors, 0);
+        assert!(proof.verify().is_ok());
+    }
+
+    #[test]
+    fn verify_wrong_errors() {
+        let mut proof: Proof = solved_proof();
+
+        proof.errors = 1;
+        assert!(matches!(proof.verify(), Err(ProofError::Errors)));
+    }
+
+    #[test]
+    fn verify_counted_mistake() {
+        let mut proof: Proof = solved_proof();
+
+        // The player fixed the mistake after the toleration delay
+        for m in proof.moves.iter_mut().skip(1) {
+            m.time_ms += 5000;
+        }
+        proof.time += Duration::from_secs(5);
+        assert!(matches!(proof.verify(), Err(ProofError::Errors)));
+        proof.errors = 1;
+        assert!(proof.verify().is_ok());
+    }
+
+    #[test]
+    fn verify_wrong_time() {
+        let mut proof: Proof = solved_proof();
+
+        proof.moves.last_mut().unwrap().time_ms = proof.time.as_millis() as u64 + 1000;
+        assert!(matches!(proof.verify(), Err(ProofError::Time)));
+    }
+
+    #[test]
+    fn verify_invented_restore() {
+        let mut proof: Proof = solved_proof();
+
+        // A restore cannot place a value that the cell never had
+        let m: &mut Move = proof.moves.last_mut().unwrap();
+        m.kind = MoveKind::Restore;
+        assert!(matches!(proof.verify(), Err(ProofError::Moves)));
+    }
+
+    #[test]
+    fn verify_missing_moves() {
+        let mut proof: Proof = solved_proof();
+
+        proof.moves.pop();
+        assert!(matches!(proof.verify(), Err(ProofError::Moves)));
+    }
+}
@@ -0,0 +1,533 @@
+/*
+qr_decode.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Decode the QR code images that Hexkudo saves.
+//!
+//! Hexkudo does not scan photos: it only reads back the images that
+//! [`Draw::qr_code`](crate::draw::Draw::qr_code) renders. These images are straight, black on
+//! white, surrounded by a quiet zone, and without any noise. The decoder locates the code from
+//! the bounding box of the dark pixels, samples the center of every module, and reads the data
+//! codewords.
+//!
+//! The decoder does not correct errors. Instead, it encodes the decoded text again, with the
+//! same version, error correction level, and mask, and rejects the image when the resulting
+//! modules differ from the sampled ones.
+
+use qrcodegen::{Mask, QrCode, QrCodeEcc, QrSegment, Version};
+
+/// Number of error correction codewords in each block, indexed by the error correction level
+/// (low, medium, quartile, high) and then by the version. Index 0 is unused.
+static ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+/// Number of error correction blocks, indexed by the error correction level (low, medium,
+/// quartile, high) and then by the version. Index 0 is unused.
+static NUM_ERROR_CORRECTION_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// Error correction levels, in the order of the tables.
+const ECC_LEVELS: [QrCodeEcc; 4] = [
+    QrCodeEcc::Low,
+    QrCodeEcc::Medium,
+    QrCodeEcc::Quartile,
+    QrCodeEcc::High,
+];
+
+/// Characters of the alphanumeric mode, in the order of their values.
+const ALPHANUMERIC_CHARSET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// Decode the QR code in the image of the given size, and return its text.
+///
+/// The `is_dark` closure returns whether the pixel at the given coordinates is dark.
+/// The function returns `None` if the image is not a QR code that Hexkudo saved, or if the image
+/// is damaged.
+pub fn decode(
+    width: usize,
+    height: usize,
+    is_dark: impl Fn(usize, usize) -> bool,
+) -> Option<String> {
+    // Bounding box of the dark pixels. The quiet zone around the code is light.
+    let (mut left, mut right, mut top, mut bottom) = (width, 0, height, 0);
+    for y in 0..height {
+        for x in 0..width {
+            if is_dark(x, y) {
+                left = left.min(x);
+                right = right.max(x);
+                top = top.min(y);
+                bottom = bottom.max(y);
+            }
+        }
+    }
+    if left > right || top > bottom {
+        return None;
+    }
+    let box_width: usize = right - left + 1;
+    let box_height: usize = bottom - top + 1;
+
+    // The top edge of the top left finder pattern is seven modules wide, and is followed by a
+    // light separator
+    let run: usize = (left..=right).take_while(|&x| is_dark(x, top)).count();
+    let estimate: f64 = box_width as f64 * 7.0 / run as f64;
+    let version: i32 = ((estimate - 17.0) / 4.0).round() as i32;
+
+    // The estimate can be off by one version for the small images
+    [version, version - 1, version + 1]
+        .into_iter()
+        .filter(|v| (1..=40).contains(v))
+        .find_map(|version| {
+            let size: usize = 4 * version as usize + 17;
+            let modules: Vec<bool> = (0..size * size)
+                .map(|i| {
+                    let x: f64 = (i % size) as f64 + 0.5;
+                    let y: f64 = (i / size) as f64 + 0.5;
+                    is_dark(
+                        left + (x * box_width as f64 / size as f64) as usize,
+                        top + (y * box_height as f64 / size as f64) as usize,
+                    )
+                })
+                .collect();
+            decode_modules(version as u8, &modules)
+        })
+}
+
+/// Decode the modules of a QR code of the given version, and return its text.
+///
+/// The `modules` vector lists the modules row by row, `true` for the dark modules.
+fn decode_modules(version: u8, modules: &[bool]) -> Option<String> {
+    let size: usize = 4 * version as usize + 17;
+    let (ecc_index, mask) = read_format(size, modules)?;
+    let function: Vec<bool> = function_modules(version);
+
+    // Read the unmasked bits in the zigzag order, from the bottom right corner, by columns of two
+    // modules that go upward and then downward
+    let num_codewords: usize = num_raw_data_modules(version) / 8;
+    let mut bits: Vec<bool> = Vec::with_capacity(num_codewords * 8);
+    let mut right: usize = size - 1;
+    while right >= 1 {
+        // Skip the vertical timing pattern
+        if right == 6 {
+            right = 5;
+        }
+        let upward: bool = (right + 1) & 2 == 0;
+        for vert in 0..size {
+            let y: usize = if upward { size - 1 - vert } else { vert };
+            for x in [right, right - 1] {
+                if !function[y * size + x] && bits.len() < num_codewords * 8 {
+                    bits.push(modules[y * size + x] ^ mask_bit(mask, x, y));
+                }
+            }
+        }
+        if right < 2 {
+            break;
+        }
+        right -= 2;
+    }
+    let codewords: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |acc, &bit| acc << 1 | bit as u8))
+        .collect();
+
+    let data: Vec<u8> = deinterleave(version, ecc_index, &codewords);
+    let text: String = parse_segments(version, &data)?;
+
+    // Encode the text again to verify the sampled modules
+    let segments: Vec<QrSegment> = QrSegment::make_segments(&text);
+    let qr: QrCode = QrCode::encode_segments_advanced(
+        &segments,
+        ECC_LEVELS[ecc_index],
+        Version::new(version),
+        Version::new(version),
+        Some(Mask::new(mask)),
+        false,
+    )
+    .ok()?;
+    let matches: bool = (0..size)
+        .all(|y| (0..size).all(|x| qr.get_module(x as i32, y as i32) == modules[y * size + x]));
+    matches.then_some(text)
+}
+
+/// Read the format information, and return the index of the error correction level in the
+/// tables and the mask.
+///
+/// The function compares both copies of the format information with all the valid values, and
+/// keeps the closest value.
+fn read_format(size: usize, modules: &[bool]) -> Option<(usize, u8)> {
+    let get = |x: usize, y: usize| modules[y * size + x] as u32;
+
+    let mut first: u32 = 0;
+    let mut second: u32 = 0;
+    for i in 0..15 {
+        let (x1, y1) = match i {
+            0..6 => (8, i),
+            6 => (8, 7),
+            7 => (8, 8),
+            8 => (7, 8),
+            _ => (14 - i, 8),
+        };
+        let (x2, y2) = if i < 8 {
+            (size - 1 - i, 8)
+        } else {
+            (8, size - 15 + i)
+        };
+        first |= get(x1, y1) << i;
+        second |= get(x2, y2) << i;
+    }
+
+    (0..4)
+        .flat_map(|ecc_index| (0..8).map(move |mask| (ecc_index, mask)))
+        .map(|(ecc_index, mask)| {
+            let bits: u32 = format_bits(ECC_LEVELS[ecc_index], mask);
+            let distance: u32 = (bits ^ first)
+                .count_ones()
+                .min((bits ^ second).count_ones());
+            (distance, ecc_index, mask)
+        })
+        .min_by_key(|&(distance, _, _)| distance)
+        .filter(|&(distance, _, _)| distance <= 3)
+        .map(|(_, ecc_index, mask)| (ecc_index, mask))
+}
+
+/// Return the 15 bits of the format information for the given error correction level and mask.
+fn format_bits(ecc: QrCodeEcc, mask: u8) -> u32 {
+    let level: u32 = match ecc {
+        QrCodeEcc::Low => 1,
+        QrCodeEcc::Medium => 0,
+        QrCodeEcc::Quartile => 3,
+        QrCodeEcc::High => 2,
+    };
+    let data: u32 = level << 3 | mask as u32;
+    let mut rem: u32 = data;
+    for _ in 0..10 {
+        rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+    }
+    (data << 10 | rem) ^ 0x5412
+}
+
+/// Return the modules that the function patterns use, row by row: the finder patterns and their
+/// separators, the timing patterns, the alignment patterns, and the format and version
+/// information.
+fn function_modules(version: u8) -> Vec<bool> {
+    let size: usize = 4 * version as usize + 17;
+    let mut function: Vec<bool> = vec![false; size * size];
+    let mut fill = |x0: usize, y0: usize, width: usize, height: usize| {
+        for y in y0..y0 + height {
+            for x in x0..x0 + width {
+                function[y * size + x] = true;
+            }
+        }
+    };
+
+    // Timing patterns
+    fill(6, 0, 1, size);
+    fill(0, 6, size, 1);
+
+    // Finder patterns, separators, and format information
+    fill(0, 0, 9, 9);
+    fill(size - 8, 0, 8, 9);
+    fill(0, size - 8, 9, 8);
+
+    // Alignment patterns, except where they overlap the finder patterns
+    let positions: Vec<usize> = alignment_positions(version);
+    let last: usize = positions.len().saturating_sub(1);
+    for (i, &y) in positions.iter().enumerate() {
+        for (j, &x) in positions.iter().enumerate() {
+            if (i, j) != (0, 0) && (i, j) != (0, last) && (i, j) != (last, 0) {
+                fill(x - 2, y - 2, 5, 5);
+            }
+        }
+    }
+
+    // Version information
+    if version >= 7 {
+        fill(size - 11, 0, 3, 6);
+        fill(0, size - 11, 6, 3);
+    }
+    function
+}
+
+/// Return the center coordinates of the alignment patterns for the given version.
+fn alignment_positions(version: u8) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let version: usize = version as usize;
+    let size: usize = 4 * version + 17;
+    let num_align: usize = version / 7 + 2;
+    let step: usize = (version * 8 + num_align * 3 + 5) / (num_align * 4 - 4) * 2;
+    let mut positions: Vec<usize> = (0..num_align - 1).map(|i| size - 7 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Return the number of modules that store data and error correction codewords, including the
+/// remainder bits.
+fn num_raw_data_modules(version: u8) -> usize {
+    let version: usize = version as usize;
+    let mut result: usize = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let num_align: usize = version / 7 + 2;
+        result -= (25 * num_align - 10) * num_align - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+/// Return whether the given mask inverts the module at the given coordinates.
+fn mask_bit(mask: u8, x: usize, y: usize) -> bool {
+    match mask {
+        0 => (x + y).is_multiple_of(2),
+        1 => y.is_multiple_of(2),
+        2 => x.is_multiple_of(3),
+        3 => (x + y).is_multiple_of(3),
+        4 => (x / 3 + y / 2).is_multiple_of(2),
+        5 => x * y % 2 + x * y % 3 == 0,
+        6 => (x * y % 2 + x * y % 3).is_multiple_of(2),
+        _ => ((x + y) % 2 + x * y % 3).is_multiple_of(2),
+    }
+}
+
+/// Extract the data codewords from the interleaved blocks.
+///
+/// The codewords alternate between the blocks. The short blocks have one data codeword less than
+/// the long blocks, which come last.
+fn deinterleave(version: u8, ecc_index: usize, codewords: &[u8]) -> Vec<u8> {
+    let num_blocks: usize = NUM_ERROR_CORRECTION_BLOCKS[ecc_index][version as usize] as usize;
+    let block_ecc_len: usize = ECC_CODEWORDS_PER_BLOCK[ecc_index][version as usize] as usize;
+    let num_short_blocks: usize = num_blocks - codewords.len() % num_blocks;
+    let short_block_len: usize = codewords.len() / num_blocks;
+    let short_data_len: usize = short_block_len - block_ecc_len;
+
+    let mut blocks: Vec<Vec<u8>> = vec![Vec::new(); num_blocks];
+    let mut iter = codewords.iter();
+    for i in 0..=short_block_len {
+        for (j, block) in blocks.iter_mut().enumerate() {
+            if (i != short_data_len || j >= num_short_blocks)
+                && let Some(&codeword) = iter.next()
+            {
+                block.push(codeword);
+            }
+        }
+    }
+    blocks
+        .iter()
+        .enumerate()
+        .flat_map(|(j, block)| {
+            let data_len: usize = short_data_len + usize::from(j >= num_short_blocks);
+            block.iter().take(data_len).copied()
+        })
+        .collect()
+}
+
+/// Reader of the bits of the data codewords, most significant bit first.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl BitReader<'_> {
+    /// Return the number of bits that are not read yet.
+    fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.position
+    }
+
+    /// Read the given number of bits, or return `None` if there are not enough bits left.
+    fn read(&mut self, count: usize) -> Option<u32> {
+        if count > self.remaining() {
+            return None;
+        }
+        let value: u32 = (self.position..self.position + count).fold(0, |acc, i| {
+            acc << 1 | ((self.data[i / 8] >> (7 - i % 8)) & 1) as u32
+        });
+        self.position += count;
+        Some(value)
+    }
+}
+
+/// Parse the numeric, alphanumeric, and byte segments in the data codewords, and return the text.
+fn parse_segments(version: u8, data: &[u8]) -> Option<String> {
+    // Width of the character count, by version range
+    let range: usize = match version {
+        1..=9 => 0,
+        10..=26 => 1,
+        _ => 2,
+    };
+    let mut reader = BitReader { data, position: 0 };
+    let mut text: String = String::new();
+
+    // The terminator (four zero bits) can be shortened at the end of the codewords
+    while reader.remaining() >= 4 {
+        match reader.read(4)? {
+            0 => break,
+            // Numeric mode: three digits in ten bits
+            1 => {
+                let mut count: usize = reader.read([10, 12, 14][range])? as usize;
+                while count > 0 {
+                    let digits: usize = count.min(3);
+                    let value: u32 = reader.read([0, 4, 7, 10][digits])?;
+                    if value >= 10_u32.pow(digits as u32) {
+                        return None;
+                    }
+                    text.push_str(&format!("{value:0digits$}"));
+                    count -= digits;
+                }
+            }
+            // Alphanumeric mode: two characters in eleven bits
+            2 => {
+                let mut count: usize = reader.read([9, 11, 13][range])? as usize;
+                while count > 0 {
+                    let chars: usize = count.min(2);
+                    let value: usize = reader.read([0, 6, 11][chars])? as usize;
+                    let indexes: Vec<usize> = if chars == 2 {
+                        vec![value / 45, value % 45]
+                    } else {
+                        vec![value]
+                    };
+                    for index in indexes {
+                        text.push(*ALPHANUMERIC_CHARSET.get(index)? as char);
+                    }
+                    count -= chars;
+                }
+            }
+            // Byte mode, which Hexkudo uses for UTF-8 text
+            4 => {
+                let count: usize = reader.read([8, 16, 16][range])? as usize;
+                let bytes: Vec<u8> = (0..count)
+                    .map(|_| reader.read(8).map(|byte| byte as u8))
+                    .collect::<Option<_>>()?;
+                text.push_str(&String::from_utf8(bytes).ok()?);
+            }
+            _ => return None,
+        }
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Render the QR code with the given module size in pixels and a quiet zone of four modules,
+    /// and decode the result.
+    fn render_and_decode(qr: &QrCode, module_size: f64) -> Option<String> {
+        let image_size: usize = ((qr.size() + 8) as f64 * module_size) as usize;
+        decode(image_size, image_size, |x, y| {
+            let mx: i32 = (x as f64 / module_size) as i32 - 4;
+            let my: i32 = (y as f64 / module_size) as i32 - 4;
+            qr.get_module(mx, my)
+        })
+    }
+
+    #[test]
+    fn decode_saved_qr_codes() {
+        let texts: [&str; 4] = [
+            "hexkudo:Classic:hard:1234567-sar",
+            "HEXKUDO:CLASSIC:EASY:42",
+            "1234567890123",
+            "hexkudo:Très grand hexagone:expert:18446744073709551615-ena, \
+             with a longer text that needs several error correction blocks",
+        ];
+        for text in texts {
+            for ecc in ECC_LEVELS {
+                let qr: QrCode = QrCode::encode_text(text, ecc).unwrap();
+                for module_size in [1.0, 3.0, 4.7] {
+                    assert_eq!(
+                        render_and_decode(&qr, module_size).as_deref(),
+                        Some(text),
+                        "version {}, {ecc:?}, module size {module_size}",
+                        qr.version().value()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn decode_all_masks_and_large_versions() {
+        let text: String = "hexkudo:Classic:hard:1234567-sar ".repeat(20);
+        let segments: Vec<QrSegment> = QrSegment::make_segments(&text);
+        for mask in 0..8 {
+            let qr: QrCode = QrCode::encode_segments_advanced(
+                &segments,
+                QrCodeEcc::Medium,
+                Version::new(14),
+                Version::new(40),
+                Some(Mask::new(mask)),
+                false,
+            )
+            .unwrap();
+            assert_eq!(render_and_decode(&qr, 2.0), Some(text.clone()));
+        }
+    }
+
+    #[test]
+    fn reject_other_images() {
+        // Blank image
+        assert_eq!(decode(100, 100, |_, _| false), None);
+
+        // Checkerboard
+        assert_eq!(decode(100, 100, |x, y| (x / 5 + y / 5) % 2 == 0), None);
+
+        // Damaged QR code
+        let qr: QrCode = QrCode::encode_text("hexkudo:Classic:hard:42", QrCodeEcc::Medium).unwrap();
+        let image_size: usize = (qr.size() + 8) as usize * 3;
+        let damaged = decode(image_size, image_size, |x, y| {
+            let (mx, my) = (x as i32 / 3 - 4, y as i32 / 3 - 4);
+            qr.get_module(mx, my) ^ ((mx, my) == (12, 14))
+        });
+        assert_eq!(damaged, None);
+    }
+}
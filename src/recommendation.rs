@@ -0,0 +1,194 @@
+/*
+recommendation.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Suggest a difficulty level after each game.
+//!
+//! The suggestion uses the recent games of the history at the difficulty level of the completed
+//! game: the time per cell, the number of mistakes per cell, and the proportion of the games
+//! where the player got help.
+//! Players who solve the puzzles quickly and without mistakes are invited to try the next
+//! difficulty level, or a bigger board when they already play the hardest level. Players who
+//! make many mistakes, or who often need help, are invited to try the previous level.
+//!
+//! The history is only recorded when the player allows it (`record-history` GSettings key). No
+//! suggestion is made otherwise. The limits are grouped in the [`Thresholds`] object.
+
+use std::collections::HashMap;
+
+use crate::generator::puzzles::{Difficulty, Puzzle};
+use crate::highscores::GameRecord;
+
+/// Direction of the suggested change.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Suggestion {
+    /// Play the same puzzle at the next difficulty level.
+    Harder,
+
+    /// Play the same puzzle at the previous difficulty level.
+    Easier,
+
+    /// Play a puzzle with more cells at the same difficulty level. Suggested instead of
+    /// [`Suggestion::Harder`] at the hardest level.
+    BiggerBoard,
+}
+
+/// Averages over the recent games at a difficulty level.
+#[derive(Debug, Copy, Clone)]
+pub struct RecentStats {
+    /// Average solving time per cell, in seconds.
+    pub secs_per_cell: f64,
+
+    /// Average number of mistakes per cell.
+    pub errors_per_cell: f64,
+
+    /// Proportion of the games where the player got help, between 0 and 1.
+    pub assisted_rate: f64,
+}
+
+/// Tunable limits for the suggestions.
+#[derive(Debug, Copy, Clone)]
+pub struct Thresholds {
+    /// Number of recent games at the difficulty level that the suggestion considers.
+    pub recent_games: usize,
+
+    /// Minimum number of recent games before making a suggestion.
+    pub min_games: usize,
+
+    /// Maximum average time per cell, in seconds, to suggest a harder game.
+    pub fast_secs_per_cell: f64,
+
+    /// Maximum average number of mistakes per cell to suggest a harder game.
+    pub low_errors_per_cell: f64,
+
+    /// Minimum average time per cell, in seconds, to suggest an easier game.
+    pub slow_secs_per_cell: f64,
+
+    /// Minimum average number of mistakes per cell to suggest an easier game.
+    pub high_errors_per_cell: f64,
+
+    /// Minimum proportion of assisted games to suggest an easier game.
+    pub high_assisted_rate: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            recent_games: 5,
+            min_games: 3,
+            fast_secs_per_cell: 3.0,
+            low_errors_per_cell: 0.02,
+            slow_secs_per_cell: 15.0,
+            high_errors_per_cell: 0.15,
+            high_assisted_rate: 0.5,
+        }
+    }
+}
+
+impl Thresholds {
+    /// Return the averages over the recent games at the given difficulty level, or None when
+    /// the history does not have enough games.
+    ///
+    /// The games recorded before the history stored the number of cells are ignored.
+    pub fn recent_stats(
+        &self,
+        history: &[GameRecord],
+        difficulty: Difficulty,
+    ) -> Option<RecentStats> {
+        let records: Vec<&GameRecord> = history
+            .iter()
+            .rev()
+            .filter(|r| r.difficulty == difficulty && r.cells > 0)
+            .take(self.recent_games)
+            .collect();
+
+        if records.len() < self.min_games.max(1) {
+            return None;
+        }
+        let games: f64 = records.len() as f64;
+        let cells: f64 = records.iter().map(|r| r.cells as f64).sum();
+        Some(RecentStats {
+            secs_per_cell: records.iter().map(|r| r.time.as_secs_f64()).sum::<f64>() / cells,
+            errors_per_cell: records.iter().map(|r| r.errors as f64).sum::<f64>() / cells,
+            assisted_rate: records.iter().filter(|r| r.assisted).count() as f64 / games,
+        })
+    }
+
+    /// Return the suggested change after a game at the given difficulty level, or None when
+    /// the player should keep the same level.
+    pub fn suggest(&self, history: &[GameRecord], difficulty: Difficulty) -> Option<Suggestion> {
+        let stats: RecentStats = self.recent_stats(history, difficulty)?;
+
+        if stats.assisted_rate >= self.high_assisted_rate
+            || stats.errors_per_cell >= self.high_errors_per_cell
+            || stats.secs_per_cell >= self.slow_secs_per_cell
+        {
+            return (difficulty != Difficulty::Easy).then_some(Suggestion::Easier);
+        }
+        if stats.assisted_rate == 0.0
+            && stats.errors_per_cell <= self.low_errors_per_cell
+            && stats.secs_per_cell <= self.fast_secs_per_cell
+        {
+            return Some(if difficulty == Difficulty::Hard {
+                Suggestion::BiggerBoard
+            } else {
+                Suggestion::Harder
+            });
+        }
+        None
+    }
+}
+
+/// Return the number of cells of the given puzzle, from its source.
+pub fn puzzle_cells(puzzle: &Puzzle) -> usize {
+    puzzle
+        .matrix
+        .vertexes
+        .source()
+        .chars()
+        .filter(|c| *c == 'o')
+        .count()
+}
+
+/// Return the puzzle that applies the suggestion after a game with the given puzzle, or None if
+/// no puzzle matches.
+pub fn suggested_puzzle<'a>(
+    suggestion: Suggestion,
+    puzzle_list: &'a HashMap<(String, Difficulty), Puzzle>,
+    puzzle: &Puzzle,
+) -> Option<&'a Puzzle> {
+    match suggestion {
+        Suggestion::Harder => Difficulty::from_repr(puzzle.difficulty as i32 + 1)
+            .and_then(|d| puzzle_list.get(&(puzzle.name.clone(), d))),
+        Suggestion::Easier => Difficulty::from_repr(puzzle.difficulty as i32 - 1)
+            .and_then(|d| puzzle_list.get(&(puzzle.name.clone(), d))),
+        Suggestion::BiggerBoard => {
+            let cells: usize = puzzle_cells(puzzle);
+            puzzle_list
+                .values()
+                .filter(|p| p.difficulty == puzzle.difficulty)
+                .map(|p| (puzzle_cells(p), p))
+                .filter(|(c, _)| *c > cells)
+                .min_by_key(|(c, p)| (*c, p.name_i18n.clone()))
+                .map(|(_, p)| p)
+        }
+    }
+}
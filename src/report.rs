@@ -0,0 +1,238 @@
+/*
+report.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Monthly personal report.
+//!
+//! At the start of a month, Hexkudo summarizes the games that the player completed during the
+//! previous month: number of games, best time per difficulty level, longest streak of days with
+//! at least one game, and most played puzzle. The report is built from the history of the games
+//! that the [`HighScores`] object records, and can be exported as a text or an HTML file.
+
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use formatx::formatx;
+use gettextrs::{gettext, ngettext};
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+
+use gtk::glib;
+
+use crate::generator::puzzles;
+use crate::highscores::{GameRecord, HighScores};
+
+/// Summary of the games completed during a month.
+#[derive(Debug, Clone)]
+pub struct MonthlyReport {
+    /// First day of the month.
+    month: NaiveDate,
+
+    /// Number of completed games.
+    games: usize,
+
+    /// Best time for each difficulty level, for the games that the player completed without
+    /// help.
+    best_times: Vec<(puzzles::Difficulty, Duration)>,
+
+    /// Longest number of consecutive days with at least one completed game.
+    streak: usize,
+
+    /// Most played puzzle, with the number of games.
+    favorite: Option<(String, usize)>,
+}
+
+impl MonthlyReport {
+    /// Build the report of the given month from the history of the games.
+    ///
+    /// The `month` parameter can be any day in the month.
+    pub fn new(
+        highscores: &HighScores,
+        puzzle_list: &HashMap<(String, puzzles::Difficulty), puzzles::Puzzle>,
+        month: NaiveDate,
+    ) -> Self {
+        let month: NaiveDate = month.with_day(1).unwrap_or(month);
+        let records: Vec<&GameRecord> = highscores
+            .get_history()
+            .iter()
+            .filter(|r| {
+                let when: DateTime<Local> = DateTime::from(r.when);
+                when.year() == month.year() && when.month() == month.month()
+            })
+            .collect();
+
+        // Best time per difficulty level
+        let mut best_times: Vec<(puzzles::Difficulty, Duration)> = Vec::new();
+        for difficulty in [
+            puzzles::Difficulty::Easy,
+            puzzles::Difficulty::Medium,
+            puzzles::Difficulty::Hard,
+        ] {
+            if let Some(best) = records
+                .iter()
+                .filter(|r| r.difficulty == difficulty && !r.assisted)
+                .map(|r| r.time)
+                .min()
+            {
+                best_times.push((difficulty, best));
+            }
+        }
+
+        // Longest streak of consecutive days
+        let days: BTreeSet<NaiveDate> = records
+            .iter()
+            .map(|r| DateTime::<Local>::from(r.when).date_naive())
+            .collect();
+        let mut streak: usize = 0;
+        let mut current: usize = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for day in &days {
+            current = match previous {
+                Some(p) if p.succ_opt() == Some(*day) => current + 1,
+                _ => 1,
+            };
+            streak = streak.max(current);
+            previous = Some(*day);
+        }
+
+        // Most played puzzle
+        let mut counts: HashMap<(&String, puzzles::Difficulty), usize> = HashMap::new();
+        for r in &records {
+            *counts.entry((&r.puzzle_name, r.difficulty)).or_insert(0) += 1;
+        }
+        let favorite: Option<(String, usize)> = counts
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|((name, difficulty), count)| {
+                let name_i18n: &String = puzzle_list
+                    .get(&(name.clone(), difficulty))
+                    .map_or(name, |p| &p.name_i18n);
+                (format!("{difficulty} - {name_i18n}"), count)
+            });
+
+        Self {
+            month,
+            games: records.len(),
+            best_times,
+            streak,
+            favorite,
+        }
+    }
+
+    /// Return the first day of the month that precedes the month of the given date.
+    pub fn previous_month(date: NaiveDate) -> NaiveDate {
+        let first: NaiveDate = date.with_day(1).unwrap_or(date);
+        first
+            .pred_opt()
+            .and_then(|d| d.with_day(1))
+            .unwrap_or(first)
+    }
+
+    /// Whether the player completed games during the month.
+    pub fn is_empty(&self) -> bool {
+        self.games == 0
+    }
+
+    /// Return the title of the report, such as "September 2026".
+    pub fn title(&self) -> String {
+        formatx!(
+            gettext("Your Month in Hexkudo: {month}"),
+            month = self.month.format("%B %Y").to_string()
+        )
+        .unwrap()
+    }
+
+    /// Return the lines of the report.
+    fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = vec![
+            formatx!(
+                ngettext(
+                    "{count} game completed",
+                    "{count} games completed",
+                    self.games as u32
+                ),
+                count = self.games
+            )
+            .unwrap(),
+        ];
+        for (difficulty, time) in &self.best_times {
+            let secs: u64 = time.as_secs();
+            let (h, m, s) = (secs / 3600, (secs % 3600) / 60, (secs % 3600) % 60);
+            let time_str: String = if h > 0 {
+                format!("{h:02}:{m:02}:{s:02}")
+            } else {
+                format!("{m:02}:{s:02}")
+            };
+            lines.push(
+                formatx!(
+                    gettext("Best time in {difficulty}: {time}"),
+                    difficulty = difficulty,
+                    time = time_str
+                )
+                .unwrap(),
+            );
+        }
+        lines.push(
+            formatx!(
+                ngettext(
+                    "Longest streak: {count} day",
+                    "Longest streak: {count} days",
+                    self.streak as u32
+                ),
+                count = self.streak
+            )
+            .unwrap(),
+        );
+        if let Some((puzzle, count)) = &self.favorite {
+            lines.push(
+                formatx!(
+                    ngettext(
+                        "Favorite puzzle: {puzzle} ({count} game)",
+                        "Favorite puzzle: {puzzle} ({count} games)",
+                        *count as u32
+                    ),
+                    puzzle = puzzle,
+                    count = count
+                )
+                .unwrap(),
+            );
+        }
+        lines
+    }
+
+    /// Return the report as plain text.
+    pub fn to_text(&self) -> String {
+        self.lines().join("\n")
+    }
+
+    /// Return the report as an HTML document.
+    pub fn to_html(&self) -> String {
+        let title: String = glib::markup_escape_text(&self.title()).to_string();
+        let items: String = self
+            .lines()
+            .iter()
+            .map(|l| format!("    <li>{}</li>\n", glib::markup_escape_text(l)))
+            .collect();
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n  <meta charset=\"utf-8\">\n  <title>{title}</title>\n\
+             </head>\n<body>\n  <h1>{title}</h1>\n  <ul>\n{items}  </ul>\n</body>\n</html>\n"
+        )
+    }
+}
@@ -0,0 +1,278 @@
+/*
+sample_pool.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Local pool of sample games.
+//!
+//! When generating the path or the clues takes too long, the game falls back to a sample game
+//! from the puzzle definition (see [`puzzles::PuzzleSampleGame`]). Each puzzle only defines a
+//! few samples, so the player might see the same fallback games again and again.
+//!
+//! The [`SamplePool`] object keeps the games that the generator successfully produced, for each
+//! puzzle and difficulty level, so that the fallback can also select from these games. The pool
+//! grows over time, up to [`MAX_SAMPLES`] games per puzzle. When the pool is full, a new game
+//! replaces the oldest one.
+//!
+//! The pool is only updated when the player allows it (`grow-sample-pool` GSettings key).
+//! See the [`crate::saver::sample_pool`] module that saves and restores the [`SamplePool`]
+//! object.
+
+use serde::{Deserialize, Serialize};
+
+use crate::generator::diamond_and_map::DiamondAndMap;
+use crate::generator::path::Path;
+use crate::generator::puzzles;
+use crate::generator::vertexes::Vertexes;
+
+/// Maximum number of puzzles in the pool. A restored object with more puzzles comes from a
+/// corrupted or a tampered file.
+const MAX_ENTRIES: usize = 1000;
+
+/// Maximum number of sample games for each puzzle and difficulty level.
+const MAX_SAMPLES: usize = 50;
+
+/// Sample games for a puzzle at a difficulty level.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Entry {
+    /// Name of the puzzle.
+    puzzle_name: String,
+
+    /// Difficulty level of the puzzle.
+    difficulty: puzzles::Difficulty,
+
+    /// Sample games, from the oldest to the newest.
+    samples: Vec<puzzles::PuzzleSampleGame>,
+}
+
+/// Sample games for all the puzzles.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SamplePool {
+    /// Sample games for each puzzle and difficulty level.
+    entries: Vec<Entry>,
+}
+
+impl SamplePool {
+    /// Create an empty [`SamplePool`] object.
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Verify that the number of puzzles does not exceed [`MAX_ENTRIES`], and that the number
+    /// of samples for each puzzle does not exceed [`MAX_SAMPLES`].
+    pub fn validate(&self) -> Result<(), String> {
+        if self.entries.len() > MAX_ENTRIES {
+            return Err(format!(
+                "Too many puzzles in the sample pool: {}",
+                self.entries.len()
+            ));
+        }
+        for entry in &self.entries {
+            if entry.samples.len() > MAX_SAMPLES {
+                return Err(format!(
+                    "Too many samples for {} {:?}: {}",
+                    entry.puzzle_name,
+                    entry.difficulty,
+                    entry.samples.len()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a sample game for the given puzzle. The oldest sample is dropped when the pool
+    /// already has [`MAX_SAMPLES`] games for the puzzle.
+    ///
+    /// Return false when the pool already has the same path for the puzzle.
+    pub fn add(
+        &mut self,
+        puzzle_name: &str,
+        difficulty: puzzles::Difficulty,
+        sample: puzzles::PuzzleSampleGame,
+    ) -> bool {
+        let index: usize = match self
+            .entries
+            .iter()
+            .position(|e| e.puzzle_name == puzzle_name && e.difficulty == difficulty)
+        {
+            Some(i) => i,
+            None => {
+                self.entries.push(Entry {
+                    puzzle_name: puzzle_name.to_string(),
+                    difficulty,
+                    samples: Vec::new(),
+                });
+                self.entries.len() - 1
+            }
+        };
+        let samples: &mut Vec<puzzles::PuzzleSampleGame> = &mut self.entries[index].samples;
+
+        if samples.iter().any(|s| s.path == sample.path) {
+            return false;
+        }
+        if samples.len() >= MAX_SAMPLES {
+            samples.remove(0);
+        }
+        samples.push(sample);
+        true
+    }
+
+    /// Return the sample games of the given puzzle.
+    ///
+    /// Only the samples that are consistent with the puzzle board are returned, so that a
+    /// tampered file cannot produce an invalid game.
+    pub fn get(
+        &self,
+        puzzle_name: &str,
+        difficulty: puzzles::Difficulty,
+        vertexes: &Vertexes,
+    ) -> Vec<puzzles::PuzzleSampleGame> {
+        self.entries
+            .iter()
+            .find(|e| e.puzzle_name == puzzle_name && e.difficulty == difficulty)
+            .map(|e| {
+                e.samples
+                    .iter()
+                    .filter(|s| is_valid(s, vertexes))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Build a sample game from a generated path and its clues.
+///
+/// Return None when a cell ID does not fit in the sample format, for the puzzles with more than
+/// 256 cells.
+pub fn to_sample(
+    path: &Path,
+    diamond_and_map: &DiamondAndMap,
+) -> Option<puzzles::PuzzleSampleGame> {
+    let (diamonds, map) = diamond_and_map.get_diamond_and_map();
+    let cell = |v: &usize| u8::try_from(*v).ok();
+
+    Some(puzzles::PuzzleSampleGame {
+        path: path.get().iter().map(cell).collect::<Option<Vec<u8>>>()?,
+        diamonds: diamonds
+            .iter()
+            .map(|(v1, v2)| Some((cell(v1)?, cell(v2)?)))
+            .collect::<Option<Vec<(u8, u8)>>>()?,
+        map: map.iter().map(cell).collect::<Option<Vec<u8>>>()?,
+    })
+}
+
+/// Whether the sample game is a valid game for the given puzzle board: the path goes through
+/// all the cells once, from a cell to an adjacent cell, and the clues refer to existing cells.
+fn is_valid(sample: &puzzles::PuzzleSampleGame, vertexes: &Vertexes) -> bool {
+    let num_vertexes: usize = vertexes.num_vertexes;
+    let mut path: Vec<u8> = sample.path.clone();
+    path.sort_unstable();
+    path.dedup();
+
+    sample.path.len() == num_vertexes
+        && path.len() == num_vertexes
+        && sample.path.iter().all(|v| (*v as usize) < num_vertexes)
+        && sample
+            .path
+            .windows(2)
+            .all(|w| vertexes.is_adjacent(w[0] as usize, w[1] as usize))
+        && sample.diamonds.iter().all(|(v1, v2)| {
+            (*v1 as usize) < num_vertexes
+                && (*v2 as usize) < num_vertexes
+                && vertexes.is_adjacent(*v1 as usize, *v2 as usize)
+        })
+        && sample.map.iter().all(|v| (*v as usize) < num_vertexes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Path of the easy Classic puzzle, from the documentation of [`puzzles::PuzzleExport`].
+    const PATH: [u8; 22] = [
+        3, 6, 7, 8, 4, 1, 0, 2, 5, 9, 13, 10, 14, 17, 18, 20, 21, 19, 15, 11, 12, 16,
+    ];
+
+    /// Return the easy Classic puzzle.
+    fn puzzle() -> puzzles::Puzzle {
+        let mut puzzle: puzzles::Puzzle = puzzles::puzzle_map()
+            .remove(&(String::from("Classic"), puzzles::Difficulty::Easy))
+            .unwrap();
+        puzzle.matrix.build_edges().unwrap();
+        puzzle
+    }
+
+    /// Return a pool with the given sample for the easy Classic puzzle.
+    fn pool(sample: puzzles::PuzzleSampleGame) -> SamplePool {
+        let mut pool: SamplePool = SamplePool::new();
+        pool.add("Classic", puzzles::Difficulty::Easy, sample);
+        pool
+    }
+
+    #[test]
+    fn valid_sample_is_returned() {
+        let puzzle: puzzles::Puzzle = puzzle();
+        let pool: SamplePool = pool(puzzles::PuzzleSampleGame {
+            path: PATH.to_vec(),
+            diamonds: vec![(18, 20), (9, 13)],
+            map: vec![3, 6, 8, 16],
+        });
+
+        let samples = pool.get(
+            "Classic",
+            puzzles::Difficulty::Easy,
+            &puzzle.matrix.vertexes,
+        );
+        assert_eq!(samples.len(), 1);
+    }
+
+    #[test]
+    fn path_with_non_adjacent_cells_is_rejected() {
+        let puzzle: puzzles::Puzzle = puzzle();
+        // All the cells once, but the first cell and the last cell are not adjacent
+        let mut path: Vec<u8> = PATH.to_vec();
+        path.swap(0, 21);
+        let pool: SamplePool = pool(puzzles::PuzzleSampleGame {
+            path,
+            diamonds: Vec::new(),
+            map: Vec::new(),
+        });
+
+        let samples = pool.get(
+            "Classic",
+            puzzles::Difficulty::Easy,
+            &puzzle.matrix.vertexes,
+        );
+        assert!(samples.is_empty());
+    }
+
+    #[test]
+    fn large_cell_ids_are_not_truncated() {
+        let mut path: Path = Path::new(2);
+        path.push(255);
+        path.push(256);
+        let d_and_m: DiamondAndMap = DiamondAndMap::new(2, 255, 256);
+
+        assert!(to_sample(&path, &d_and_m).is_none());
+    }
+}
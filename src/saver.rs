@@ -21,6 +21,63 @@ SPDX-License-Identifier: GPL-3.0-or-later
 */
 
 //! Hexkudo saving objects.
+//!
+//! The restored objects come from files that users can modify. The files are size-limited,
+//! and the restored objects are verified before use, so that a corrupted or a tampered file
+//! cannot cause large allocations or crashes.
+
+use std::error::Error;
+use std::fs::File;
 
 pub mod game;
 pub mod highscores;
+pub mod sample_pool;
+pub mod statistics;
+pub mod themes;
+
+/// Maximum size, in bytes, of the files that Hexkudo restores.
+pub const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Return the name of the file that stores the data of the given player profile.
+///
+/// The default player, with an empty profile ID, uses the `<stem>.json` file, and the other
+/// profiles the `<stem>-<id>.json` file. Hexkudo generates the profile IDs with ASCII letters
+/// and digits only. The IDs come from GSettings, which users can modify, so that any other ID
+/// selects the file of the default player instead of escaping the data directory.
+pub fn profile_file_name(stem: &str, profile_id: &str) -> String {
+    if profile_id.is_empty() || !profile_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        format!("{stem}.json")
+    } else {
+        format!("{stem}-{profile_id}.json")
+    }
+}
+
+/// Return an error if the given file is larger than [`MAX_FILE_SIZE`].
+pub fn check_file_size(file: &File) -> Result<(), Box<dyn Error>> {
+    let size: u64 = file.metadata()?.len();
+    if size > MAX_FILE_SIZE {
+        return Err(format!("The file is too large ({size} bytes)").into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_files_stay_in_the_data_directory() {
+        assert_eq!(profile_file_name("highscores", ""), "highscores.json");
+        assert_eq!(
+            profile_file_name("highscores", "0f3a9c"),
+            "highscores-0f3a9c.json"
+        );
+        for id in ["..", "../x", "a/b", "a\\b", ".", "é"] {
+            assert_eq!(
+                profile_file_name("statistics", id),
+                "statistics.json",
+                "{id}"
+            );
+        }
+    }
+}
@@ -25,49 +25,41 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! When a game is in progress and the user quits Hexkudo, the game status is saved in the
 //! `savegame.json` file.
 //! When Hexkudo is restarted, the saved gave is loaded, and the user can continue the puzzle.
+//! The game in progress is also saved periodically and at every checkpoint, so that a crash
+//! does not lose the progress.
 //!
 //! The saved object is a serialization of the [`Game`] object in JSON format by using [`serde`].
+//! The restored object is verified with [`Game::validate`].
+//! The puzzles that Hexkudo provides are saved by name and difficulty, but the imported puzzles
+//! are saved with their matrix, so that they can be restored.
+//!
+//! Players can also keep several games going at the same time by saving them in named slots.
+//! Each slot is a file with the same format in the `slots` subdirectory, and the slot name is
+//! the file name without the `.json` extension.
+//!
+//! To move a game in progress to another computer, players export it to a file with the
+//! `.hexkudo-save` extension. The file wraps the saved game with the version of its format, so
+//! that the import rejects the files that a newer version of Hexkudo produced.
+//!
+//! Puzzle files, with the `.hexkudo` extension, use the puzzle text format that
+//! [`puzzles::PuzzleExport`] describes, or the same format as the saved game. Opening such a
+//! file from the file manager starts Hexkudo with that puzzle.
 
 use log::debug;
 use std::error::Error;
 use std::fmt;
-use std::fs::{File, remove_file};
-use std::io::{BufReader, BufWriter, ErrorKind, Write};
-use std::path::PathBuf;
+use std::fs::{File, create_dir_all, read_dir, remove_file, rename};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
 
 use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 
+use super::check_file_size;
+use crate::config;
 use crate::game::Game;
-use crate::generator::puzzles;
-
-/// Serialize and deserialize [`std::time::Instant`] objects with Serde.
-pub mod instant {
-    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
-    use std::time::{Duration, Instant};
-
-    /// Serialize an [`std::time::Instant`] object.
-    pub fn serialize<S>(instant: &Instant, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let duration: Duration = instant.elapsed();
-        duration.serialize(serializer)
-    }
-
-    /// Deserialize an [`std::time::Instant`] object.
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<Instant, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let duration: Duration = Duration::deserialize(deserializer)?;
-        let now: Instant = Instant::now();
-        let instant: Instant = now
-            .checked_sub(duration)
-            .ok_or_else(|| Error::custom("Cannot compute the saved game duration"))?;
-        Ok(instant)
-    }
-}
+use crate::generator::{puzzle_parse, puzzles, vertexes};
 
 /// Serialize a [`puzzles::Puzzle`] object.
 impl Serialize for puzzles::Puzzle {
@@ -75,13 +67,19 @@ impl Serialize for puzzles::Puzzle {
     where
         S: Serializer,
     {
-        // `2` is the number of fields to serialize
-        let mut state = serializer.serialize_struct("Puzzle", 2)?;
+        // `3` is the number of fields to serialize
+        let mut state = serializer.serialize_struct("Puzzle", 3)?;
 
-        // Only serialize the puzzle name and difficulty. During deserialization, a complete
-        // Puzzle object is retrieved from these two fields.
+        // For the puzzles that Hexkudo provides, only serialize the puzzle name and difficulty.
+        // During deserialization, a complete Puzzle object is retrieved from these two fields.
+        // The imported puzzles also need their matrix, because Hexkudo cannot rebuild it.
         state.serialize_field("name", &self.name)?;
         state.serialize_field("difficulty", &self.difficulty)?;
+        if self.is_builtin() {
+            state.skip_field("matrix")?;
+        } else {
+            state.serialize_field("matrix", self.matrix.vertexes.source())?;
+        }
         state.end()
     }
 }
@@ -95,6 +93,7 @@ impl<'de> Deserialize<'de> for puzzles::Puzzle {
         enum Field {
             Name,
             Difficulty,
+            Matrix,
         }
 
         impl<'de> Deserialize<'de> for Field {
@@ -108,7 +107,7 @@ impl<'de> Deserialize<'de> for puzzles::Puzzle {
                     type Value = Field;
 
                     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                        formatter.write_str("`name` or `difficulty`")
+                        formatter.write_str("`name`, `difficulty`, or `matrix`")
                     }
 
                     fn visit_str<E>(self, value: &str) -> Result<Field, E>
@@ -118,6 +117,7 @@ impl<'de> Deserialize<'de> for puzzles::Puzzle {
                         match value {
                             "name" => Ok(Field::Name),
                             "difficulty" => Ok(Field::Difficulty),
+                            "matrix" => Ok(Field::Matrix),
                             _ => Err(de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -146,15 +146,8 @@ impl<'de> Deserialize<'de> for puzzles::Puzzle {
                 let difficulty: puzzles::Difficulty = seq
                     .next_element()?
                     .ok_or_else(|| de::Error::invalid_length(1, &self))?;
-
-                // Retrieve the list (HashMap) of puzzles
-                let puzzle_hash = puzzles::puzzle_map();
-                // From this list, retrieve the Puzzle object that matches the serialized name and
-                // difficulty, and then return that Puzzle.
-                match puzzle_hash.get(&(name, difficulty)) {
-                    Some(p) => Ok(p.clone()),
-                    None => Err(de::Error::duplicate_field("puzzle")),
-                }
+                let matrix: Option<String> = seq.next_element()?;
+                build_puzzle(name, difficulty, matrix)
             }
 
             fn visit_map<V>(self, mut map: V) -> Result<puzzles::Puzzle, V::Error>
@@ -163,6 +156,7 @@ impl<'de> Deserialize<'de> for puzzles::Puzzle {
             {
                 let mut name: Option<String> = None;
                 let mut difficulty: Option<puzzles::Difficulty> = None;
+                let mut matrix: Option<String> = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::Name => {
@@ -177,31 +171,150 @@ impl<'de> Deserialize<'de> for puzzles::Puzzle {
                             }
                             difficulty = Some(map.next_value()?);
                         }
+                        Field::Matrix => {
+                            if matrix.is_some() {
+                                return Err(de::Error::duplicate_field("matrix"));
+                            }
+                            matrix = Some(map.next_value()?);
+                        }
                     }
                 }
                 let name: String = name.ok_or_else(|| de::Error::missing_field("name"))?;
                 let difficulty: puzzles::Difficulty =
                     difficulty.ok_or_else(|| de::Error::missing_field("difficulty"))?;
-                // Retrieve the list (HashMap) of puzzles
-                let puzzle_hash = puzzles::puzzle_map();
-                // From this list, retrieve the Puzzle object that matches the serialized name and
-                // difficulty, and then return that Puzzle.
-                match puzzle_hash.get(&(name, difficulty)) {
-                    Some(p) => Ok(p.clone()),
-                    None => Err(de::Error::duplicate_field("puzzle")),
-                }
+                build_puzzle(name, difficulty, matrix)
             }
         }
 
-        const FIELDS: &[&str] = &["secs", "nanos"];
+        /// Return the [`puzzles::Puzzle`] object from the deserialized fields.
+        ///
+        /// Without a matrix, the puzzle must be one of the puzzles that Hexkudo provides. With a
+        /// matrix, the puzzle is an imported puzzle, which reuses the colors and the icons of the
+        /// puzzle that Hexkudo provides, if any, like [`puzzles::PuzzleExport`] does.
+        fn build_puzzle<E: de::Error>(
+            name: String,
+            difficulty: puzzles::Difficulty,
+            matrix: Option<String>,
+        ) -> Result<puzzles::Puzzle, E> {
+            // Retrieve the list (HashMap) of puzzles, and then the Puzzle object that matches the
+            // serialized name and difficulty
+            let puzzle: Option<puzzles::Puzzle> =
+                puzzles::puzzle_map().remove(&(name.clone(), difficulty));
+            let Some(matrix) = matrix else {
+                return puzzle
+                    .ok_or_else(|| E::custom(format!("unknown puzzle {name} {difficulty:?}")));
+            };
+            vertexes::check_size(&matrix).map_err(E::custom)?;
+            let mut puzzle: puzzles::Puzzle = puzzle.unwrap_or_else(|| puzzles::Puzzle {
+                name: name.clone(),
+                name_i18n: name,
+                difficulty,
+                ..Default::default()
+            });
+            puzzle.matrix = puzzle_parse::PuzzleParse::new(&matrix);
+            puzzle.matrix.build_edges().map_err(E::custom)?;
+            Ok(puzzle)
+        }
+
+        const FIELDS: &[&str] = &["name", "difficulty", "matrix"];
         deserializer.deserialize_struct("Puzzle", FIELDS, PuzzleVisitor)
     }
 }
 
+/// Lock that serializes the writes of the save files. The automatic saves write the game in a
+/// background thread, which must not interleave with the save on exit.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Subdirectory, in the data directory, of the save slots.
+const SLOTS_DIR: &str = "slots";
+
+/// Maximum number of save slots.
+pub const MAX_SLOTS: usize = 100;
+
+/// Version of the format of the exported games.
+///
+/// - Version 1: initial format.
+const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// Exported game, as written to the file.
+#[derive(serde::Serialize)]
+struct ExportedGameRef<'a> {
+    /// Version of the format. See [`EXPORT_FORMAT_VERSION`].
+    format_version: u32,
+
+    /// Version of the application that exported the game.
+    version: &'a str,
+
+    /// Game in progress.
+    game: &'a Game,
+}
+
+/// Header of the exported games, to verify the format version before reading the game.
+#[derive(serde::Deserialize)]
+struct ExportHeader {
+    /// Version of the format. See [`EXPORT_FORMAT_VERSION`].
+    format_version: u32,
+
+    /// Version of the application that exported the game.
+    #[serde(default)]
+    version: String,
+}
+
+/// Exported game, as read from the file.
+#[derive(serde::Deserialize)]
+struct ExportedGame {
+    /// Game in progress.
+    game: Game,
+}
+
+/// Type of errors when importing a game.
+#[derive(Debug)]
+pub enum ImportError {
+    /// A newer version of the application, which version is provided, exported the game.
+    NewerVersion(String),
+
+    /// The file is not a valid exported game.
+    Invalid(Box<dyn Error>),
+}
+
+impl ImportError {
+    /// Build an [`ImportError::Invalid`] error from the given error.
+    fn invalid<E: Into<Box<dyn Error>>>(error: E) -> Self {
+        ImportError::Invalid(error.into())
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::NewerVersion(version) => {
+                write!(f, "The game was exported by a newer version ({version})")
+            }
+            ImportError::Invalid(error) => write!(f, "Invalid exported game: {error}"),
+        }
+    }
+}
+
+impl Error for ImportError {}
+/// Error when saving a game in a new save slot, and all the [`MAX_SLOTS`] slots are used.
+#[derive(Debug)]
+pub struct TooManySlots;
+
+impl fmt::Display for TooManySlots {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "There are already {MAX_SLOTS} save slots")
+    }
+}
+
+impl Error for TooManySlots {}
+
 /// Object to save and restore a puzzle in progress.
 pub struct SaverGame {
     /// Absolute path to the save file.
     save_file: PathBuf,
+
+    /// Whether the save file is a save slot, which number is limited to [`MAX_SLOTS`].
+    slot: bool,
 }
 
 impl SaverGame {
@@ -213,7 +326,83 @@ impl SaverGame {
         debug!("Save game file: {data_dir:?}");
         SaverGame {
             save_file: data_dir,
+            slot: false,
+        }
+    }
+
+    /// Create a [`SaverGame`] object for the save slot with the given name.
+    ///
+    /// The provided [`PathBuf`] is the path to the data directory. Return None if the name is
+    /// not a valid slot name.
+    pub fn new_slot(mut data_dir: PathBuf, name: &str) -> Option<Self> {
+        let name: &str = name.trim();
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return None;
         }
+        data_dir.push(SLOTS_DIR);
+        data_dir.push(format!("{name}.json"));
+        debug!("Save slot file: {data_dir:?}");
+        Some(SaverGame {
+            save_file: data_dir,
+            slot: true,
+        })
+    }
+
+    /// Return the path to the directory of the save slots.
+    ///
+    /// The provided [`PathBuf`] is the path to the data directory.
+    pub fn slots_dir(mut data_dir: PathBuf) -> PathBuf {
+        data_dir.push(SLOTS_DIR);
+        data_dir
+    }
+
+    /// Return the paths to the save slot files in the given directory, sorted by name.
+    fn slot_files(slots_dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = read_dir(slots_dir) else {
+            return Vec::new();
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "json"))
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Delete all the save slots.
+    ///
+    /// The provided [`PathBuf`] is the path to the data directory.
+    pub fn delete_slots(data_dir: PathBuf) {
+        for path in Self::slot_files(&Self::slots_dir(data_dir)) {
+            let _ = remove_file(path);
+        }
+    }
+
+    /// Return the name and the [`Game`] object of the first [`MAX_SLOTS`] save slots, sorted by
+    /// name.
+    ///
+    /// The slots that cannot be read are skipped.
+    pub fn list_slots(data_dir: PathBuf) -> Vec<(String, Game)> {
+        Self::slot_files(&Self::slots_dir(data_dir))
+            .into_iter()
+            .take(MAX_SLOTS)
+            .filter_map(|p| {
+                let name: String = p.file_stem()?.to_string_lossy().to_string();
+                let saver: SaverGame = SaverGame {
+                    save_file: p,
+                    slot: true,
+                };
+                match saver.get_game() {
+                    Ok(Some(game)) => Some((name, game)),
+                    Ok(None) => None,
+                    Err(error) => {
+                        debug!("Error reading the {name} save slot: {error}");
+                        None
+                    }
+                }
+            })
+            .collect()
     }
 
     /// Retrieve the [`Game`] object for the saved puzzle.
@@ -228,18 +417,105 @@ impl SaverGame {
                 _ => return Err(Box::new(error)),
             },
         }
+        check_file_size(&file)?;
         let reader: BufReader<File> = BufReader::new(file);
         let game: Game = serde_json::from_reader(reader)?;
+        game.validate()?;
         Ok(Some(game))
     }
 
+    /// Retrieve the [`Game`] object from the given puzzle file.
+    ///
+    /// The file is in the puzzle text format, or in the saved game format.
+    pub fn load_game_file(path: &Path) -> Result<Game, Box<dyn Error>> {
+        let mut file: File = File::open(path)?;
+        check_file_size(&file)?;
+        let mut content: String = String::new();
+        file.read_to_string(&mut content)?;
+
+        match content.parse::<puzzles::PuzzleExport>() {
+            Ok(export) => {
+                let mut game: Game = Game::default();
+                game.set_export(&export);
+                Ok(game)
+            }
+            Err(msg) => {
+                debug!("Not in the puzzle text format: {msg}");
+                let game: Game = serde_json::from_str(&content).map_err(|_| msg)?;
+                game.validate()?;
+                Ok(game)
+            }
+        }
+    }
+
+    /// Export the provided [`Game`] object to the given file, so that the game can be imported
+    /// on another computer.
+    pub fn export_game(path: &Path, game: &Game) -> Result<(), Box<dyn Error>> {
+        let file: File = File::create(path)?;
+        let mut writer: BufWriter<File> = BufWriter::new(file);
+        let export: ExportedGameRef = ExportedGameRef {
+            format_version: EXPORT_FORMAT_VERSION,
+            version: config::VERSION,
+            game,
+        };
+
+        serde_json::to_writer(&mut writer, &export)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Retrieve the [`Game`] object from the given exported game file.
+    pub fn import_game(path: &Path) -> Result<Game, ImportError> {
+        let mut file: File = File::open(path).map_err(ImportError::invalid)?;
+        check_file_size(&file).map_err(ImportError::Invalid)?;
+        let mut content: String = String::new();
+        file.read_to_string(&mut content)
+            .map_err(ImportError::invalid)?;
+
+        let header: ExportHeader = serde_json::from_str(&content).map_err(ImportError::invalid)?;
+        if header.format_version > EXPORT_FORMAT_VERSION {
+            return Err(ImportError::NewerVersion(header.version));
+        }
+        let export: ExportedGame = serde_json::from_str(&content).map_err(ImportError::invalid)?;
+        export.game.validate().map_err(ImportError::invalid)?;
+        Ok(export.game)
+    }
+
+    /// Return the path to the save file.
+    pub fn path(&self) -> &Path {
+        &self.save_file
+    }
+
     /// Save the provided [`Game`] object.
+    ///
+    /// The game is written to a temporary file that then replaces the save file, so that an
+    /// interrupted save does not corrupt the previous save.
+    ///
+    /// Saving in a new save slot fails with [`TooManySlots`] when all the slots are used.
     pub fn save_game(&self, game: &Game) -> Result<(), Box<dyn Error>> {
-        let file: File = File::create(&self.save_file)?;
+        self.write_game(&serde_json::to_vec(game)?)
+    }
+
+    /// Write a game, already serialized in JSON, to the save file. See
+    /// [`SaverGame::save_game`].
+    ///
+    /// The method does not access the game, and can therefore run in a background thread.
+    pub fn write_game(&self, content: &[u8]) -> Result<(), Box<dyn Error>> {
+        let _lock = WRITE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(dir) = self.save_file.parent() {
+            if self.slot && !self.save_file.exists() && Self::slot_files(dir).len() >= MAX_SLOTS {
+                return Err(Box::new(TooManySlots));
+            }
+            create_dir_all(dir)?;
+        }
+        let tmp_file: PathBuf = self.save_file.with_extension("json.tmp");
+        let file: File = File::create(&tmp_file)?;
         let mut writer: BufWriter<File> = BufWriter::new(file);
 
-        serde_json::to_writer(&mut writer, game)?;
+        writer.write_all(content)?;
         writer.flush()?;
+        writer.get_ref().sync_all()?;
+        rename(&tmp_file, &self.save_file)?;
         Ok(())
     }
 
@@ -248,3 +524,219 @@ impl SaverGame {
         let _ = remove_file(&self.save_file);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs::{create_dir_all, remove_dir_all};
+    use std::str::FromStr;
+
+    /// Imported puzzle, which Hexkudo does not provide.
+    const PUZZLE: &str = "format: 1
+name: Triangle
+difficulty: easy
+path: 0 1 3 4 2 5
+map: 0 5
+diamonds:
+anti-diamonds:
+regions:
+matrix:
+  o
+ o o
+o o o
+";
+
+    /// Return a new temporary data directory for the test.
+    fn data_dir(test: &str) -> PathBuf {
+        let mut dir: PathBuf = temp_dir();
+        dir.push(format!("hexkudo-test-{}-{test}", std::process::id()));
+        let _ = remove_dir_all(&dir);
+        dir
+    }
+
+    /// Return a new game on the easy Classic puzzle.
+    fn classic_game() -> Game {
+        let mut game: Game = Game::new();
+        game.set_puzzle(
+            &puzzles::puzzle_map()[&(String::from("Classic"), puzzles::Difficulty::Easy)],
+        );
+        game
+    }
+
+    /// Write the given content to a save file, and then restore the game from that file.
+    fn restore(test: &str, content: &[u8]) -> Result<Option<Game>, Box<dyn Error>> {
+        let dir: PathBuf = data_dir(test);
+        create_dir_all(&dir).unwrap();
+        let saver: SaverGame = SaverGame::new(dir.clone());
+        File::create(&saver.save_file)
+            .unwrap()
+            .write_all(content)
+            .unwrap();
+        let game: Result<Option<Game>, Box<dyn Error>> = saver.get_game();
+        let _ = remove_dir_all(&dir);
+        game
+    }
+
+    #[test]
+    fn save_and_restore_imported_puzzle() {
+        let export: puzzles::PuzzleExport = puzzles::PuzzleExport::from_str(PUZZLE).unwrap();
+        let mut game: Game = Game::new();
+        game.set_export(&export);
+        game.add_value_to_cell(3, 3);
+
+        let dir: PathBuf = data_dir("imported");
+        let saver: SaverGame = SaverGame::new(dir.clone());
+        saver.save_game(&game).unwrap();
+        let restored: Game = saver.get_game().unwrap().unwrap();
+        let _ = remove_dir_all(&dir);
+
+        assert_eq!(restored.puzzle.name, "Triangle");
+        assert_eq!(
+            restored.puzzle.matrix.vertexes.source(),
+            game.puzzle.matrix.vertexes.source()
+        );
+        assert_eq!(restored.puzzle.matrix.vertexes.num_vertexes, 6);
+        assert_eq!(restored.path.get(), &export.path);
+        assert_eq!(restored.map, game.map);
+        assert_eq!(
+            restored.to_export().to_string(),
+            game.to_export().to_string()
+        );
+    }
+
+    #[test]
+    fn builtin_puzzle_is_saved_by_name() {
+        let game: Game = classic_game();
+        let json: String = serde_json::to_string(&game.puzzle).unwrap();
+        assert!(!json.contains("matrix"));
+        let puzzle: puzzles::Puzzle = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            puzzle.matrix.vertexes.source(),
+            game.puzzle.matrix.vertexes.source()
+        );
+    }
+
+    #[test]
+    fn unknown_puzzle_without_matrix_is_rejected() {
+        let json: &str = r#"{"name":"Triangle","difficulty":"Easy"}"#;
+        assert!(serde_json::from_str::<puzzles::Puzzle>(json).is_err());
+    }
+
+    #[test]
+    fn valid_fixture_is_restored() {
+        let content: String = serde_json::to_string(&classic_game()).unwrap();
+        assert!(restore("valid", content.as_bytes()).unwrap().is_some());
+    }
+
+    #[test]
+    fn truncated_file_is_rejected() {
+        let content: String = serde_json::to_string(&classic_game()).unwrap();
+        let truncated: &[u8] = &content.as_bytes()[..content.len() / 2];
+        assert!(restore("truncated", truncated).is_err());
+        assert!(restore("empty", b"").is_err());
+    }
+
+    #[test]
+    fn oversized_file_is_rejected() {
+        let dir: PathBuf = data_dir("oversized");
+        create_dir_all(&dir).unwrap();
+        let saver: SaverGame = SaverGame::new(dir.clone());
+        // Sparse file, to not write the whole content
+        File::create(&saver.save_file)
+            .unwrap()
+            .set_len(super::super::MAX_FILE_SIZE + 1)
+            .unwrap();
+        let game: Result<Option<Game>, Box<dyn Error>> = saver.get_game();
+        let file_game: Result<Game, Box<dyn Error>> = SaverGame::load_game_file(&saver.save_file);
+        let _ = remove_dir_all(&dir);
+        assert!(game.is_err());
+        assert!(file_game.is_err());
+    }
+
+    #[test]
+    fn out_of_range_cells_are_rejected() {
+        let game: Game = classic_game();
+        let num_vertexes: usize = game.puzzle.matrix.vertexes.num_vertexes;
+        let saved: serde_json::Value = serde_json::to_value(&game).unwrap();
+
+        let mut content: serde_json::Value = saved.clone();
+        content["map"] = serde_json::json!([1_000_000]);
+        assert!(restore("map", content.to_string().as_bytes()).is_err());
+
+        let mut content: serde_json::Value = saved.clone();
+        content["diamonds"] = serde_json::json!([[0, 1_000_000]]);
+        assert!(restore("diamonds", content.to_string().as_bytes()).is_err());
+
+        let mut content: serde_json::Value = saved;
+        content["selected_cell"] = serde_json::json!(num_vertexes);
+        assert!(restore("selected", content.to_string().as_bytes()).is_err());
+
+        let mut game: Game = classic_game();
+        game.player_input.add_no_undo(1, num_vertexes + 1);
+        let content: String = serde_json::to_string(&game).unwrap();
+        assert!(restore("value", content.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn invalid_imported_puzzles_are_rejected() {
+        let mut export: puzzles::PuzzleExport = puzzles::PuzzleExport::from_str(PUZZLE).unwrap();
+        export.path = vec![0, 3, 1, 4, 2, 5];
+        let mut game: Game = Game::new();
+        game.set_export(&export);
+        let content: String = serde_json::to_string(&game).unwrap();
+        assert!(restore("adjacency", content.as_bytes()).is_err());
+
+        let mut content: serde_json::Value = serde_json::to_value(&game).unwrap();
+        content["puzzle"]["matrix"] = serde_json::json!("o o\n".repeat(vertexes::MAX_HEIGHT + 1));
+        assert!(restore("matrix", content.to_string().as_bytes()).is_err());
+    }
+
+    #[test]
+    fn non_utf8_file_is_rejected() {
+        let mut content: Vec<u8> = serde_json::to_string(&classic_game()).unwrap().into_bytes();
+        content.splice(2..2, [0xFF, 0xFE, 0xC3]);
+        assert!(restore("utf8", &content).is_err());
+
+        let dir: PathBuf = data_dir("utf8-file");
+        create_dir_all(&dir).unwrap();
+        let mut path: PathBuf = dir.clone();
+        path.push("puzzle.hexkudo");
+        let mut content: Vec<u8> = PUZZLE.as_bytes().to_vec();
+        content.splice(10..10, [0xFF, 0xFE, 0xC3]);
+        File::create(&path).unwrap().write_all(&content).unwrap();
+        let game: Result<Game, Box<dyn Error>> = SaverGame::load_game_file(&path);
+        let imported: Result<Game, ImportError> = SaverGame::import_game(&path);
+        let _ = remove_dir_all(&dir);
+        assert!(game.is_err());
+        assert!(imported.is_err());
+    }
+
+    #[test]
+    fn slots_are_limited() {
+        let dir: PathBuf = data_dir("slots");
+        let slots_dir: PathBuf = SaverGame::slots_dir(dir.clone());
+        create_dir_all(&slots_dir).unwrap();
+        let content: String = serde_json::to_string(&classic_game()).unwrap();
+        // Write the files in the reverse order of their names
+        for i in (0..=MAX_SLOTS).rev() {
+            std::fs::write(slots_dir.join(format!("slot{i:03}.json")), &content).unwrap();
+        }
+
+        let slots: Vec<(String, Game)> = SaverGame::list_slots(dir.clone());
+        let game: &Game = &slots[0].1;
+        let new_slot: Result<(), Box<dyn Error>> = SaverGame::new_slot(dir.clone(), "new")
+            .unwrap()
+            .save_game(game);
+        let existing_slot: Result<(), Box<dyn Error>> = SaverGame::new_slot(dir.clone(), "slot000")
+            .unwrap()
+            .save_game(game);
+        let _ = remove_dir_all(&dir);
+
+        assert_eq!(slots.len(), MAX_SLOTS);
+        assert_eq!(slots[0].0, "slot000");
+        assert_eq!(slots[MAX_SLOTS - 1].0, format!("slot{:03}", MAX_SLOTS - 1));
+        assert!(new_slot.is_err_and(|e| e.is::<TooManySlots>()));
+        assert!(existing_slot.is_ok());
+    }
+}
@@ -24,13 +24,15 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //!
 //! The saved object is a serialization of the [`HighScores`] object in JSON format by
 //! using [`serde`].
+//! The restored object is verified with [`HighScores::validate`].
 
 use log::debug;
 use std::error::Error;
 use std::fs::{File, remove_file};
 use std::io::{BufReader, BufWriter, ErrorKind, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use super::{check_file_size, profile_file_name};
 use crate::highscores::HighScores;
 
 /// Object to save and restore a high scores.
@@ -43,8 +45,10 @@ impl SaverHighScores {
     /// Create a [`SaverHighScores`] object.
     ///
     /// The provided [`PathBuf`] is the path to the directory where the high scores must be saved.
-    pub fn new(mut data_dir: PathBuf) -> Self {
-        data_dir.push("highscores.json");
+    /// Each player profile has its own file (see [`profile_file_name`]). The default player, with
+    /// an empty profile ID, uses the `highscores.json` file.
+    pub fn new(mut data_dir: PathBuf, profile_id: &str) -> Self {
+        data_dir.push(profile_file_name("highscores", profile_id));
         debug!("High scores file: {data_dir:?}");
         Self {
             save_file: data_dir,
@@ -63,11 +67,19 @@ impl SaverHighScores {
                 _ => return Err(Box::new(error)),
             },
         }
+        check_file_size(&file)?;
         let reader: BufReader<File> = BufReader::new(file);
-        let highscores: HighScores = serde_json::from_reader(reader)?;
+        let mut highscores: HighScores = serde_json::from_reader(reader)?;
+        highscores.validate()?;
+        highscores.migrate();
         Ok(Some(highscores))
     }
 
+    /// Return the path to the save file.
+    pub fn path(&self) -> &Path {
+        &self.save_file
+    }
+
     /// Save the provided [`HighScores`] object.
     pub fn save_highscores(&self, highscores: &HighScores) -> Result<(), Box<dyn Error>> {
         let file: File = File::create(&self.save_file)?;
@@ -0,0 +1,100 @@
+/*
+sample_pool.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Save and restore the local pool of sample games.
+//!
+//! The saved object is a serialization of the [`SamplePool`] object in JSON format by
+//! using [`serde`].
+//! The restored object is verified with [`SamplePool::validate`].
+
+use log::debug;
+use std::error::Error;
+use std::fs::{File, remove_file, rename};
+use std::io::{BufReader, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use super::check_file_size;
+use crate::sample_pool::SamplePool;
+
+/// Object to save and restore the sample pool.
+pub struct SaverSamplePool {
+    /// Absolute path to the save file.
+    save_file: PathBuf,
+}
+
+impl SaverSamplePool {
+    /// Create a [`SaverSamplePool`] object.
+    ///
+    /// The provided [`PathBuf`] is the path to the directory where the sample pool must be
+    /// saved.
+    pub fn new(mut data_dir: PathBuf) -> Self {
+        data_dir.push("sample_pool.json");
+        debug!("Sample pool file: {data_dir:?}");
+        Self {
+            save_file: data_dir,
+        }
+    }
+
+    /// Retrieve the [`SamplePool`] object from the sample pool file.
+    ///
+    /// Return the [`SamplePool`] object or None if the sample pool file does not exist.
+    pub fn get_sample_pool(&self) -> Result<Option<SamplePool>, Box<dyn Error>> {
+        let file: File = match File::open(&self.save_file) {
+            Ok(f) => f,
+            Err(error) => match error.kind() {
+                ErrorKind::NotFound => return Ok(None),
+                _ => return Err(Box::new(error)),
+            },
+        };
+        check_file_size(&file)?;
+        let reader: BufReader<File> = BufReader::new(file);
+        let sample_pool: SamplePool = serde_json::from_reader(reader)?;
+        sample_pool.validate()?;
+        Ok(Some(sample_pool))
+    }
+
+    /// Save the provided [`SamplePool`] object.
+    ///
+    /// The pool is written to a temporary file that then replaces the sample pool file, so that
+    /// an interrupted save does not corrupt the pool.
+    pub fn save_sample_pool(&self, sample_pool: &SamplePool) -> Result<(), Box<dyn Error>> {
+        let tmp_file: PathBuf = self.save_file.with_extension("json.tmp");
+        let file: File = File::create(&tmp_file)?;
+        let mut writer: BufWriter<File> = BufWriter::new(file);
+
+        serde_json::to_writer(&mut writer, sample_pool)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        rename(&tmp_file, &self.save_file)?;
+        Ok(())
+    }
+
+    /// Return the path to the sample pool file.
+    pub fn path(&self) -> &Path {
+        &self.save_file
+    }
+
+    /// Delete the sample pool file.
+    pub fn delete_save(&self) {
+        let _ = remove_file(&self.save_file);
+    }
+}
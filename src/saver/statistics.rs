@@ -0,0 +1,95 @@
+/*
+statistics.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Save and restore the lifetime statistics for the puzzles.
+//!
+//! The saved object is a serialization of the [`Statistics`] object in JSON format by
+//! using [`serde`].
+//! The restored object is verified with [`Statistics::validate`].
+
+use log::debug;
+use std::error::Error;
+use std::fs::{File, remove_file};
+use std::io::{BufReader, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use super::{check_file_size, profile_file_name};
+use crate::statistics::Statistics;
+
+/// Object to save and restore the statistics.
+pub struct SaverStatistics {
+    /// Absolute path to the save file.
+    save_file: PathBuf,
+}
+
+impl SaverStatistics {
+    /// Create a [`SaverStatistics`] object.
+    ///
+    /// The provided [`PathBuf`] is the path to the directory where the statistics must be saved.
+    /// Each player profile has its own file (see [`profile_file_name`]). The default player, with
+    /// an empty profile ID, uses the `statistics.json` file.
+    pub fn new(mut data_dir: PathBuf, profile_id: &str) -> Self {
+        data_dir.push(profile_file_name("statistics", profile_id));
+        debug!("Statistics file: {data_dir:?}");
+        Self {
+            save_file: data_dir,
+        }
+    }
+
+    /// Retrieve the [`Statistics`] object from the statistics file.
+    ///
+    /// Return the [`Statistics`] object or None if the statistics file does not exist.
+    pub fn get_statistics(&self) -> Result<Option<Statistics>, Box<dyn Error>> {
+        let file: File = match File::open(&self.save_file) {
+            Ok(f) => f,
+            Err(error) => match error.kind() {
+                ErrorKind::NotFound => return Ok(None),
+                _ => return Err(Box::new(error)),
+            },
+        };
+        check_file_size(&file)?;
+        let reader: BufReader<File> = BufReader::new(file);
+        let statistics: Statistics = serde_json::from_reader(reader)?;
+        statistics.validate()?;
+        Ok(Some(statistics))
+    }
+
+    /// Save the provided [`Statistics`] object.
+    pub fn save_statistics(&self, statistics: &Statistics) -> Result<(), Box<dyn Error>> {
+        let file: File = File::create(&self.save_file)?;
+        let mut writer: BufWriter<File> = BufWriter::new(file);
+
+        serde_json::to_writer(&mut writer, statistics)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Return the path to the statistics file.
+    pub fn path(&self) -> &Path {
+        &self.save_file
+    }
+
+    /// Delete the statistics file.
+    pub fn delete_save(&self) {
+        let _ = remove_file(&self.save_file);
+    }
+}
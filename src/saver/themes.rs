@@ -0,0 +1,116 @@
+/*
+themes.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Save and restore the color themes.
+//!
+//! The list of the themes in the theme manager is a serialization of the [`Themes`] object in
+//! JSON format by using [`serde`]. The theme files that players share are a serialization of a
+//! [`Theme`] object.
+//! The restored objects are verified with [`Themes::validate`] and [`Theme::validate`].
+
+use log::debug;
+use std::error::Error;
+use std::fs::{File, create_dir_all, rename};
+use std::io::{BufReader, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use super::check_file_size;
+use crate::theme::{Theme, Themes};
+
+/// File extension of the theme files.
+pub const THEME_EXTENSION: &str = "hexkudo-theme";
+
+/// Object to save and restore the color themes.
+pub struct SaverThemes {
+    /// Absolute path to the save file.
+    save_file: PathBuf,
+}
+
+impl SaverThemes {
+    /// Create a [`SaverThemes`] object.
+    ///
+    /// The provided [`PathBuf`] is the path to the directory where the themes must be saved.
+    pub fn new(mut data_dir: PathBuf) -> Self {
+        data_dir.push("themes.json");
+        debug!("Themes file: {data_dir:?}");
+        Self {
+            save_file: data_dir,
+        }
+    }
+
+    /// Retrieve the [`Themes`] object from the themes file.
+    ///
+    /// Return the [`Themes`] object or None if the themes file does not exist.
+    pub fn get_themes(&self) -> Result<Option<Themes>, Box<dyn Error>> {
+        let file: File = match File::open(&self.save_file) {
+            Ok(f) => f,
+            Err(error) => match error.kind() {
+                ErrorKind::NotFound => return Ok(None),
+                _ => return Err(Box::new(error)),
+            },
+        };
+        check_file_size(&file)?;
+        let reader: BufReader<File> = BufReader::new(file);
+        let themes: Themes = serde_json::from_reader(reader)?;
+        themes.validate()?;
+        Ok(Some(themes))
+    }
+
+    /// Save the provided [`Themes`] object.
+    ///
+    /// The themes are written to a temporary file that then replaces the save file, so that an
+    /// interrupted save does not corrupt the previous save.
+    pub fn save_themes(&self, themes: &Themes) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = self.save_file.parent() {
+            create_dir_all(dir)?;
+        }
+        let tmp_file: PathBuf = self.save_file.with_extension("json.tmp");
+        let file: File = File::create(&tmp_file)?;
+        let mut writer: BufWriter<File> = BufWriter::new(file);
+
+        serde_json::to_writer(&mut writer, themes)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        rename(&tmp_file, &self.save_file)?;
+        Ok(())
+    }
+
+    /// Write the given [`Theme`] object to a theme file that players can share.
+    pub fn export_theme(path: &Path, theme: &Theme) -> Result<(), Box<dyn Error>> {
+        let file: File = File::create(path)?;
+        let mut writer: BufWriter<File> = BufWriter::new(file);
+
+        serde_json::to_writer_pretty(&mut writer, theme)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Retrieve the [`Theme`] object from the given theme file.
+    pub fn import_theme(path: &Path) -> Result<Theme, Box<dyn Error>> {
+        let file: File = File::open(path)?;
+        check_file_size(&file)?;
+        let reader: BufReader<File> = BufReader::new(file);
+        let theme: Theme = serde_json::from_reader(reader)?;
+        theme.validate()?;
+        Ok(theme)
+    }
+}
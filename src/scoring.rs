@@ -0,0 +1,125 @@
+/*
+scoring.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Compute the optional points-based score of the game.
+//!
+//! The player earns points for each correct value they place, plus bonuses for placing values
+//! quickly and for streaks of consecutive correct values. Each mistake costs points.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Points for each correct value.
+const CELL_POINTS: u32 = 10;
+
+/// Bonus points for a correct value placed less than [`SPEED_BONUS_SEC`] seconds after the
+/// previous correct value.
+const SPEED_BONUS_POINTS: u32 = 5;
+const SPEED_BONUS_SEC: u64 = 5;
+
+/// Bonus points for each consecutive correct value in the current streak, up to
+/// [`MAX_STREAK_BONUS_POINTS`].
+const STREAK_BONUS_POINTS: u32 = 2;
+const MAX_STREAK_BONUS_POINTS: u32 = 20;
+
+/// Points that each mistake costs.
+const ERROR_PENALTY_POINTS: u32 = 15;
+
+/// Manage the points that the player earns.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Scoring {
+    // Points earned so far, before the penalties for the mistakes.
+    earned: u32,
+
+    // Number of consecutive correct values that the player placed.
+    streak: usize,
+
+    // Longest streak of the game.
+    best_streak: usize,
+
+    // Game duration when the player placed the last correct value.
+    last_correct: Option<Duration>,
+
+    // Cells that already earned points. Clearing and setting a cell again does not earn more
+    // points.
+    scored_cells: HashSet<usize>,
+}
+
+impl Scoring {
+    /// Create a [`Scoring`] object.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset the object.
+    pub fn clear(&mut self) {
+        self.earned = 0;
+        self.streak = 0;
+        self.best_streak = 0;
+        self.last_correct = None;
+        self.scored_cells.clear();
+    }
+
+    /// Process the value that the player just set in the given cell.
+    ///
+    /// The `elapsed` parameter is the game duration at that time.
+    pub fn add_cell(&mut self, cell_id: usize, correct: bool, elapsed: Duration) {
+        if !correct {
+            self.streak = 0;
+            return;
+        }
+        self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+        if !self.scored_cells.insert(cell_id) {
+            return;
+        }
+
+        let mut points: u32 = CELL_POINTS;
+        if self
+            .last_correct
+            .is_some_and(|t| elapsed.saturating_sub(t).as_secs() < SPEED_BONUS_SEC)
+        {
+            points += SPEED_BONUS_POINTS;
+        }
+        points += (STREAK_BONUS_POINTS * (self.streak as u32 - 1)).min(MAX_STREAK_BONUS_POINTS);
+        self.earned += points;
+        self.last_correct = Some(elapsed);
+    }
+
+    /// Return the number of consecutive correct values that the player placed.
+    pub fn get_streak(&self) -> usize {
+        self.streak
+    }
+
+    /// Return the longest streak of correct values of the game.
+    pub fn get_best_streak(&self) -> usize {
+        self.best_streak
+    }
+
+    /// Return the score, given the number of mistakes that the player made.
+    pub fn get_points(&self, errors: usize) -> u32 {
+        self.earned
+            .saturating_sub(ERROR_PENALTY_POINTS.saturating_mul(errors as u32))
+    }
+}
@@ -0,0 +1,198 @@
+/*
+seasonal.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Seasonal color themes.
+//!
+//! During some periods of the year, the puzzles use seasonal colors and logos instead of their
+//! own colors and logo.
+//! The theme is selected once, when the application starts, so that the colors do not change in
+//! the middle of a game. Players can disable the seasonal themes in the preferences, in which
+//! case the puzzles keep their own colors.
+//!
+//! The colors that players customize in the preferences take precedence over the seasonal
+//! colors.
+//!
+//! For developers: to add a theme, add a [`SeasonalTheme`] object to the [`THEMES`] list, and
+//! place its 128x128 PNG logo in the `data/media/icons/` directory. Declare the logo in the
+//! `data/hexkudo.gresource.xml` file.
+
+use gtk::glib;
+use log::debug;
+use std::sync::OnceLock;
+
+use crate::generator::puzzles::{Puzzle, PuzzleColor};
+
+/// Seasonal theme.
+#[derive(Debug)]
+pub struct SeasonalTheme {
+    /// Name of the theme, for debugging.
+    pub name: &'static str,
+
+    /// First day of the period, as a month and a day of the month.
+    start: (i32, i32),
+
+    /// Last day of the period, as a month and a day of the month. The period can span the end
+    /// of the year.
+    end: (i32, i32),
+
+    /// Colors for the light color theme.
+    light: PuzzleColor,
+
+    /// Colors for the dark color theme.
+    dark: PuzzleColor,
+
+    /// File name of the logo (Gio resource).
+    logo: &'static str,
+}
+
+/// Seasonal themes. When periods overlap, the first theme in the list wins.
+static THEMES: [SeasonalTheme; 3] = [
+    SeasonalTheme {
+        name: "winter",
+        start: (12, 20),
+        end: (1, 6),
+        light: PuzzleColor {
+            border: (0x0D, 0x47, 0xA1, 0xFF),
+            bg: (0xE3, 0xF2, 0xFD, 0xFF),
+            bg_map: (0xBB, 0xDE, 0xFB, 0xFF),
+            text: (0, 0, 0, 0xFF),
+            diamond: (0x0D, 0x47, 0xA1, 0xFF),
+            text_wrong: (0xEE, 0, 0, 0xFF),
+            selection: (0x90, 0xCA, 0xF9, 0xFF),
+            path: (0, 0, 0, 0x60),
+            bg_css: "background: radial-gradient(circle,rgba(144, 202, 249, 1) 0%, rgba(144, 202, 249, 0) 75%);",
+        },
+        dark: PuzzleColor {
+            border: (0xE3, 0xF2, 0xFD, 0xFF),
+            bg: (0x1A, 0x33, 0x4D, 0xFF),
+            bg_map: (0x0F, 0x22, 0x36, 0xFF),
+            text: (0xFF, 0xFF, 0xFF, 0xFF),
+            diamond: (0xE3, 0xF2, 0xFD, 0xFF),
+            text_wrong: (0xFF, 0x52, 0x52, 0xFF),
+            selection: (0x15, 0x65, 0xC0, 0xFF),
+            path: (0xFF, 0xFF, 0xFF, 0x60),
+            bg_css: "background: radial-gradient(circle,rgba(144, 202, 249, 1) 0%, rgba(144, 202, 249, 0) 75%);",
+        },
+        logo: "logo-winter.png",
+    },
+    SeasonalTheme {
+        name: "halloween",
+        start: (10, 24),
+        end: (10, 31),
+        light: PuzzleColor {
+            border: (0x4A, 0x14, 0x8C, 0xFF),
+            bg: (0xFF, 0xE0, 0xB2, 0xFF),
+            bg_map: (0xFF, 0xB7, 0x4D, 0xFF),
+            text: (0, 0, 0, 0xFF),
+            diamond: (0x4A, 0x14, 0x8C, 0xFF),
+            text_wrong: (0xC6, 0x28, 0x28, 0xFF),
+            selection: (0xCE, 0x93, 0xD8, 0xFF),
+            path: (0, 0, 0, 0x60),
+            bg_css: "background: radial-gradient(circle,rgba(255, 143, 0, 1) 0%, rgba(255, 143, 0, 0) 75%);",
+        },
+        dark: PuzzleColor {
+            border: (0xFF, 0xB7, 0x4D, 0xFF),
+            bg: (0x3E, 0x27, 0x23, 0xFF),
+            bg_map: (0x2A, 0x1A, 0x17, 0xFF),
+            text: (0xFF, 0xFF, 0xFF, 0xFF),
+            diamond: (0xFF, 0xB7, 0x4D, 0xFF),
+            text_wrong: (0xFF, 0x52, 0x52, 0xFF),
+            selection: (0x6A, 0x1B, 0x9A, 0xFF),
+            path: (0xFF, 0xFF, 0xFF, 0x60),
+            bg_css: "background: radial-gradient(circle,rgba(255, 143, 0, 1) 0%, rgba(255, 143, 0, 0) 75%);",
+        },
+        logo: "logo-halloween.png",
+    },
+    SeasonalTheme {
+        name: "spring",
+        start: (3, 20),
+        end: (4, 2),
+        light: PuzzleColor {
+            border: (0x33, 0x69, 0x1E, 0xFF),
+            bg: (0xF1, 0xF8, 0xE9, 0xFF),
+            bg_map: (0xDC, 0xED, 0xC8, 0xFF),
+            text: (0, 0, 0, 0xFF),
+            diamond: (0x33, 0x69, 0x1E, 0xFF),
+            text_wrong: (0xEE, 0, 0, 0xFF),
+            selection: (0xF8, 0xBB, 0xD0, 0xFF),
+            path: (0, 0, 0, 0x60),
+            bg_css: "background: radial-gradient(circle,rgba(139, 195, 74, 1) 0%, rgba(139, 195, 74, 0) 75%);",
+        },
+        dark: PuzzleColor {
+            border: (0xDC, 0xED, 0xC8, 0xFF),
+            bg: (0x2E, 0x3B, 0x24, 0xFF),
+            bg_map: (0x1F, 0x29, 0x18, 0xFF),
+            text: (0xFF, 0xFF, 0xFF, 0xFF),
+            diamond: (0xDC, 0xED, 0xC8, 0xFF),
+            text_wrong: (0xFF, 0x52, 0x52, 0xFF),
+            selection: (0xAD, 0x14, 0x57, 0xFF),
+            path: (0xFF, 0xFF, 0xFF, 0x60),
+            bg_css: "background: radial-gradient(circle,rgba(139, 195, 74, 1) 0%, rgba(139, 195, 74, 0) 75%);",
+        },
+        logo: "logo-spring.png",
+    },
+];
+
+/// Theme selected when the application started, if any.
+static ACTIVE_THEME: OnceLock<Option<&'static SeasonalTheme>> = OnceLock::new();
+
+impl SeasonalTheme {
+    /// Whether the theme is active on the given day.
+    fn is_active(&self, month: i32, day: i32) -> bool {
+        let date: (i32, i32) = (month, day);
+
+        if self.start <= self.end {
+            self.start <= date && date <= self.end
+        } else {
+            date >= self.start || date <= self.end
+        }
+    }
+
+    /// Use the theme colors and logo for the given puzzle.
+    pub fn apply(&self, puzzle: &mut Puzzle) {
+        puzzle
+            .colors
+            .set_palettes(self.light.clone(), self.dark.clone());
+        puzzle.logo = String::from(self.logo);
+    }
+}
+
+/// Select the theme for the current date. The function is called once, when the application
+/// starts.
+pub fn init() {
+    let theme: Option<&'static SeasonalTheme> = glib::DateTime::now_local().ok().and_then(|now| {
+        THEMES
+            .iter()
+            .find(|t| t.is_active(now.month(), now.day_of_month()))
+    });
+
+    if let Some(t) = theme {
+        debug!("Seasonal theme: {}", t.name);
+    }
+    let _ = ACTIVE_THEME.set(theme);
+}
+
+/// Return the theme that was selected when the application started, or None outside of the
+/// seasonal periods.
+pub fn active_theme() -> Option<&'static SeasonalTheme> {
+    ACTIVE_THEME.get().copied().flatten()
+}
@@ -0,0 +1,97 @@
+/*
+shared_game.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Guarded access to the game data that the widgets share.
+//!
+//! The widgets store the [`Game`] object in a `Rc<RefCell<Game>>`. Signal handlers can run while
+//! another handler still holds a borrow of the game, for example when a button in the number
+//! picker closes the popover, which redraws the puzzle. Instead of panicking, the functions in
+//! this module skip the work or run it again later, when the main loop is idle.
+
+use log::debug;
+use std::cell::{Ref, RefCell, RefMut};
+use std::rc::Rc;
+
+use gtk::glib;
+use gtk::glib::prelude::*;
+
+use crate::game::Game;
+
+/// Game data shared between the widgets.
+pub type SharedGame = Rc<RefCell<Game>>;
+
+/// Borrow the game for reading, or return `None` if the game is being updated.
+pub fn try_read(game: &SharedGame) -> Option<Ref<'_, Game>> {
+    match game.try_borrow() {
+        Ok(g) => Some(g),
+        Err(_) => {
+            debug!("Game data in use: skipping the read access");
+            None
+        }
+    }
+}
+
+/// Run the given function with mutable access to the game.
+///
+/// If the game is in use, then the function runs later, when the main loop is idle.
+pub fn with_game_mut<F>(game: &SharedGame, f: F)
+where
+    F: FnOnce(&mut Game) + 'static,
+{
+    match game.try_borrow_mut() {
+        Ok(mut g) => f(&mut g),
+        Err(_) => {
+            debug!("Game data in use: retrying when idle");
+            let game: SharedGame = Rc::clone(game);
+            glib::idle_add_local_once(move || with_game_mut(&game, f));
+        }
+    }
+}
+
+/// Borrow the game for writing from a callback of the given object.
+///
+/// If the game is in use, then return `None` and call `retry` with the object when the main loop
+/// is idle. The object must not be destroyed in the meantime, otherwise the call is dropped.
+/// The `retry` function can be a closure that captures the parameters of the callback.
+pub fn try_write_or_retry<'a, W, R>(
+    object: &W,
+    game: &'a SharedGame,
+    retry: R,
+) -> Option<RefMut<'a, Game>>
+where
+    W: ObjectType,
+    R: FnOnce(&W) + 'static,
+{
+    match game.try_borrow_mut() {
+        Ok(g) => Some(g),
+        Err(_) => {
+            debug!("Game data in use: retrying when idle");
+            let weak: glib::WeakRef<W> = object.downgrade();
+            glib::idle_add_local_once(move || {
+                if let Some(o) = weak.upgrade() {
+                    retry(&o);
+                }
+            });
+            None
+        }
+    }
+}
@@ -0,0 +1,417 @@
+/*
+solvability.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Check whether the player can still solve the puzzle.
+//!
+//! The check verifies that the values that the player placed can be completed into a valid path:
+//! consecutive values in adjacent cells, consecutive values across the diamonds, no consecutive
+//! values across the anti-diamonds (negative clues), and the sums of the regions.
+//! The values are not compared to the solution of the puzzle, so that the check only reports
+//! real dead ends.
+//!
+//! The check runs in a background task and gives up after a delay, so that the large boards
+//! do not keep the processor busy.
+//!
+//! The same search also verifies that a puzzle has a unique solution, for the puzzle authors
+//! (see [`Constraints::analyze`]).
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::chain::distance;
+use crate::game::Game;
+use crate::generator::regions::{self, Region};
+use crate::generator::vertexes::Vertexes;
+
+/// Number of explored cells between two verifications of the delay.
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
+/// Result of the solvability check.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Solvability {
+    /// The placed values can be completed into a valid path.
+    Solvable,
+
+    /// No valid path goes through the placed values.
+    Unsolvable,
+
+    /// The check gave up before reaching a conclusion.
+    Unknown,
+}
+
+/// Result of the puzzle analysis.
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    /// Number of solutions found. The search stops at two solutions.
+    pub solutions: usize,
+
+    /// First solution found, as the list of the cells in the path order.
+    pub solution: Option<Vec<usize>>,
+
+    /// Number of cells that the search explored, which measures the puzzle difficulty.
+    pub explored: usize,
+
+    /// Whether the search reached a conclusion before the delay.
+    pub complete: bool,
+}
+
+/// Board constraints that the check verifies.
+///
+/// The object is a copy of the game data, so that the check can run in a background task.
+#[derive(Debug, Clone)]
+pub struct Constraints {
+    /// Board cells.
+    vertexes: Vertexes,
+
+    /// Pairs of cells that must have consecutive values.
+    diamonds: Vec<(usize, usize)>,
+
+    /// Pairs of cells that must not have consecutive values.
+    anti_diamonds: Vec<(usize, usize)>,
+
+    /// Regions and the sums of their values.
+    regions: Vec<Region>,
+}
+
+impl Constraints {
+    /// Create a [`Constraints`] object from the game in progress.
+    pub fn from_game(game: &Game) -> Self {
+        Self {
+            vertexes: game.puzzle.matrix.vertexes.clone(),
+            diamonds: game.diamonds.clone(),
+            anti_diamonds: game.anti_diamonds.clone(),
+            regions: game.regions.clone(),
+        }
+    }
+
+    /// Verify whether the given values, indexed by the cell IDs, can be completed into a valid
+    /// path. The check gives up and returns [`Solvability::Unknown`] after the given delay.
+    pub fn check(&self, values: &HashMap<usize, usize>, timeout: Duration) -> Solvability {
+        let Some(mut search) = self.search(values, timeout, 1) else {
+            return Solvability::Unsolvable;
+        };
+        match search.explore() {
+            Some(true) => Solvability::Solvable,
+            Some(false) => Solvability::Unsolvable,
+            None => Solvability::Unknown,
+        }
+    }
+
+    /// Search for the solutions that complete the given values, indexed by the cell IDs. The
+    /// search stops at the second solution, or after the given delay.
+    pub fn analyze(&self, values: &HashMap<usize, usize>, timeout: Duration) -> Analysis {
+        let Some(mut search) = self.search(values, timeout, 2) else {
+            return Analysis {
+                solutions: 0,
+                solution: None,
+                explored: 0,
+                complete: true,
+            };
+        };
+        let complete: bool = search.explore().is_some();
+        Analysis {
+            solutions: search.solutions,
+            solution: search.first_solution,
+            explored: search.explored,
+            complete,
+        }
+    }
+
+    /// Return the cell, among the given cells with their values, that gives the player the most
+    /// information when revealed, or None if the list is empty.
+    ///
+    /// For each cell, the search completes the known values, indexed by the cell IDs, and the
+    /// value of that cell. The most informative cell is the one that leaves the least work: the
+    /// search explores the fewest cells to find the solution and to verify that it is unique.
+    /// Each search gives up after the given delay, and the cells of the incomplete searches rank
+    /// last.
+    pub fn most_informative_cell(
+        &self,
+        values: &HashMap<usize, usize>,
+        candidates: &[(usize, usize)],
+        timeout: Duration,
+    ) -> Option<usize> {
+        let mut values: HashMap<usize, usize> = values.clone();
+
+        candidates
+            .iter()
+            .min_by_key(|(cell_id, value)| {
+                values.insert(*cell_id, *value);
+                let analysis: Analysis = self.analyze(&values, timeout);
+                values.remove(cell_id);
+                (!analysis.complete, analysis.explored)
+            })
+            .map(|(cell_id, _)| *cell_id)
+    }
+
+    /// Prepare a search that stops after finding the given number of solutions. Return None if
+    /// the same value is in several cells, in which case there is no solution.
+    fn search(
+        &self,
+        values: &HashMap<usize, usize>,
+        timeout: Duration,
+        limit: usize,
+    ) -> Option<Search<'_>> {
+        let num_vertexes: usize = self.vertexes.num_vertexes;
+        let mut fixed: Vec<Option<usize>> = vec![None; num_vertexes];
+        let mut has_value: Vec<bool> = vec![false; num_vertexes];
+
+        for (cell_id, value) in values {
+            if *value == 0 || *value > num_vertexes || *cell_id >= num_vertexes {
+                continue;
+            }
+            // The same value in two cells
+            if fixed[*value - 1].is_some() {
+                return None;
+            }
+            fixed[*value - 1] = Some(*cell_id);
+            has_value[*cell_id] = true;
+        }
+
+        let mut region_of: Vec<Option<usize>> = vec![None; num_vertexes];
+        for (i, region) in self.regions.iter().enumerate() {
+            for cell_id in &region.cells {
+                if let Some(r) = region_of.get_mut(*cell_id) {
+                    *r = Some(i);
+                }
+            }
+        }
+
+        Some(Search {
+            constraints: self,
+            num_vertexes,
+            fixed,
+            has_value,
+            used: vec![false; num_vertexes],
+            region_of,
+            region_sums: vec![(0, 0); self.regions.len()],
+            path: Vec::with_capacity(num_vertexes),
+            deadline: Instant::now() + timeout,
+            explored: 0,
+            limit,
+            solutions: 0,
+            first_solution: None,
+        })
+    }
+
+    /// Return the cells that a diamond links to the given cell.
+    fn diamond_partners(&self, cell_id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.diamonds.iter().filter_map(move |(a, b)| {
+            if *a == cell_id {
+                Some(*b)
+            } else if *b == cell_id {
+                Some(*a)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Whether an anti-diamond separates the two given cells.
+    fn is_anti_diamond(&self, cell_id_1: usize, cell_id_2: usize) -> bool {
+        self.anti_diamonds.iter().any(|(a, b)| {
+            (*a == cell_id_1 && *b == cell_id_2) || (*a == cell_id_2 && *b == cell_id_1)
+        })
+    }
+}
+
+/// Search state for a valid path.
+struct Search<'a> {
+    /// Board constraints.
+    constraints: &'a Constraints,
+
+    /// Number of cells in the board.
+    num_vertexes: usize,
+
+    /// Cell of each placed value, indexed by the value minus one.
+    fixed: Vec<Option<usize>>,
+
+    /// Whether the player placed a value in the cell, indexed by the cell ID.
+    has_value: Vec<bool>,
+
+    /// Whether the path being built goes through the cell, indexed by the cell ID.
+    used: Vec<bool>,
+
+    /// Region of each cell, indexed by the cell ID.
+    region_of: Vec<Option<usize>>,
+
+    /// Sum of the values and number of cells that the path being built covers in each region.
+    region_sums: Vec<(usize, usize)>,
+
+    /// Cells of the path being built. The cell at index `i` has the value `i + 1`.
+    path: Vec<usize>,
+
+    /// Time after which the search gives up.
+    deadline: Instant,
+
+    /// Number of cells explored so far.
+    explored: usize,
+
+    /// Number of solutions after which the search stops.
+    limit: usize,
+
+    /// Number of solutions found so far.
+    solutions: usize,
+
+    /// First solution found.
+    first_solution: Option<Vec<usize>>,
+}
+
+impl Search<'_> {
+    /// Extend the path with the next value. Return whether the search found enough valid paths,
+    /// or None if the search gave up.
+    fn explore(&mut self) -> Option<bool> {
+        self.explored += 1;
+        if self.explored.is_multiple_of(DEADLINE_CHECK_INTERVAL) && Instant::now() > self.deadline {
+            return None;
+        }
+
+        let value: usize = self.path.len() + 1;
+        if value > self.num_vertexes {
+            if !self.last_diamonds_match() {
+                return Some(false);
+            }
+            self.solutions += 1;
+            if self.first_solution.is_none() {
+                self.first_solution = Some(self.path.clone());
+            }
+            return Some(self.solutions >= self.limit);
+        }
+
+        let mut candidates: Vec<usize> = match (self.fixed[value - 1], self.path.last()) {
+            (Some(cell_id), _) => vec![cell_id],
+            (None, None) => (0..self.num_vertexes)
+                .filter(|c| self.is_free(*c))
+                .collect(),
+            (None, Some(prev)) => regions::adjacent_cells(&self.constraints.vertexes, *prev)
+                .into_iter()
+                .filter(|c| self.is_free(*c))
+                .collect(),
+        };
+        // Try the cells with the fewest exits first, which reaches the dead ends faster
+        candidates.sort_by_cached_key(|c| {
+            regions::adjacent_cells(&self.constraints.vertexes, *c)
+                .into_iter()
+                .filter(|n| self.is_free(*n))
+                .count()
+        });
+
+        for cell_id in candidates {
+            if !self.is_allowed(cell_id, value) {
+                continue;
+            }
+            self.push(cell_id, value);
+            let found: Option<bool> = self.explore();
+            self.pop(cell_id, value);
+            if found != Some(false) {
+                return found;
+            }
+        }
+        Some(false)
+    }
+
+    /// Whether the path can go through the given cell without a placed value.
+    fn is_free(&self, cell_id: usize) -> bool {
+        !self.used[cell_id] && !self.has_value[cell_id]
+    }
+
+    /// Whether the given cell can receive the given value, which extends the path.
+    fn is_allowed(&self, cell_id: usize, value: usize) -> bool {
+        let vertexes: &Vertexes = &self.constraints.vertexes;
+
+        if self.used[cell_id] {
+            return false;
+        }
+        if let Some(prev) = self.path.last() {
+            if !vertexes.is_adjacent(*prev, cell_id)
+                || self.constraints.is_anti_diamond(*prev, cell_id)
+            {
+                return false;
+            }
+
+            // The cells that a diamond links to the previous cell must be next to it in the path
+            let prev_prev: Option<usize> = self.path.iter().rev().nth(1).copied();
+            if self
+                .constraints
+                .diamond_partners(*prev)
+                .any(|p| p != cell_id && Some(p) != prev_prev)
+            {
+                return false;
+            }
+        }
+
+        // The next placed value must remain reachable
+        if let Some((next_value, next_cell)) = self.fixed[value..]
+            .iter()
+            .enumerate()
+            .find_map(|(i, c)| c.map(|c| (value + i + 1, c)))
+            && next_cell != cell_id
+            && distance(vertexes, cell_id, next_cell) > next_value - value
+        {
+            return false;
+        }
+
+        // The region sum must not be exceeded, and must be reached when the region is complete
+        if let Some(r) = self.region_of[cell_id] {
+            let region: &Region = &self.constraints.regions[r];
+            let (sum, count) = self.region_sums[r];
+            if sum + value > region.sum
+                || (count + 1 == region.cells.len() && sum + value != region.sum)
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Whether the cells that a diamond links to the last cell of the complete path are just
+    /// before that cell.
+    fn last_diamonds_match(&self) -> bool {
+        let Some(last) = self.path.last() else {
+            return true;
+        };
+        let prev: Option<usize> = self.path.iter().rev().nth(1).copied();
+        self.constraints
+            .diamond_partners(*last)
+            .all(|p| Some(p) == prev)
+    }
+
+    /// Add the given cell, with the given value, to the path.
+    fn push(&mut self, cell_id: usize, value: usize) {
+        self.path.push(cell_id);
+        self.used[cell_id] = true;
+        if let Some(r) = self.region_of[cell_id] {
+            self.region_sums[r].0 += value;
+            self.region_sums[r].1 += 1;
+        }
+    }
+
+    /// Remove the given cell, with the given value, from the end of the path.
+    fn pop(&mut self, cell_id: usize, value: usize) {
+        self.path.pop();
+        self.used[cell_id] = false;
+        if let Some(r) = self.region_of[cell_id] {
+            self.region_sums[r].0 -= value;
+            self.region_sums[r].1 -= 1;
+        }
+    }
+}
@@ -0,0 +1,228 @@
+/*
+statistics.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Lifetime statistics for the puzzles.
+//!
+//! The main object, [`Statistics`], keeps aggregates for each puzzle, difficulty level, and
+//! revealed endpoints option: the number of games won and abandoned, the total solving time,
+//! the best streak of correct values, and the number of hints used. Unlike the scoreboards, the
+//! aggregates cover all the games, including the assisted games and the games that the player
+//! did not complete.
+//!
+//! The statistics are only recorded when the player allows it (`record-history` GSettings key).
+//! See the [`crate::saver::statistics`] module that saves and restores the [`Statistics`]
+//! object.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::generator::diamond_and_map::Endpoints;
+use crate::generator::puzzles;
+
+/// Maximum number of puzzles in the statistics. A restored object with more puzzles comes from
+/// a corrupted or a tampered file.
+const MAX_ENTRIES: usize = 1000;
+
+/// Aggregates for a puzzle, or for all the puzzles of a difficulty level.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PuzzleStats {
+    /// Number of completed games.
+    pub won: usize,
+
+    /// Number of games that the player left before completing the puzzle.
+    pub abandoned: usize,
+
+    /// Total solving time of the completed games.
+    pub total_time: Duration,
+
+    /// Longest streak of correct values placed in a row.
+    pub best_streak: usize,
+
+    /// Number of hints used, in all the games.
+    pub hints: usize,
+}
+
+impl PuzzleStats {
+    /// Return the number of games played, completed or abandoned.
+    pub fn played(&self) -> usize {
+        self.won + self.abandoned
+    }
+
+    /// Return the average solving time of the completed games, or None if no game was
+    /// completed.
+    pub fn average_time(&self) -> Option<Duration> {
+        (self.won > 0).then(|| self.total_time / self.won as u32)
+    }
+
+    /// Return the proportion of the games that the player abandoned, between 0 and 1.
+    pub fn abandonment_rate(&self) -> f64 {
+        if self.played() == 0 {
+            0.0
+        } else {
+            self.abandoned as f64 / self.played() as f64
+        }
+    }
+
+    /// Add the aggregates of another object to this object.
+    fn merge(&mut self, other: &PuzzleStats) {
+        self.won += other.won;
+        self.abandoned += other.abandoned;
+        self.total_time += other.total_time;
+        self.best_streak = self.best_streak.max(other.best_streak);
+        self.hints += other.hints;
+    }
+}
+
+/// Aggregates for a puzzle at a difficulty level, with the given revealed endpoints.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Entry {
+    /// Name of the puzzle.
+    puzzle_name: String,
+
+    /// Difficulty level of the puzzle.
+    difficulty: puzzles::Difficulty,
+
+    /// Endpoints of the path that the games revealed.
+    #[serde(default)]
+    endpoints: Endpoints,
+
+    /// Aggregates.
+    stats: PuzzleStats,
+}
+
+/// Lifetime statistics for all the puzzles.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Statistics {
+    /// Aggregates for each puzzle, difficulty level, and revealed endpoints option.
+    entries: Vec<Entry>,
+}
+
+impl Statistics {
+    /// Create an empty [`Statistics`] object.
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Verify that the number of puzzles does not exceed [`MAX_ENTRIES`].
+    pub fn validate(&self) -> Result<(), String> {
+        if self.entries.len() > MAX_ENTRIES {
+            return Err(format!(
+                "Too many puzzles in the statistics: {}",
+                self.entries.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Return the aggregates of the given puzzle, creating them if needed.
+    fn entry_mut(
+        &mut self,
+        puzzle_name: &str,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+    ) -> &mut PuzzleStats {
+        let index: usize = match self.entries.iter().position(|e| {
+            e.puzzle_name == puzzle_name && e.difficulty == difficulty && e.endpoints == endpoints
+        }) {
+            Some(i) => i,
+            None => {
+                self.entries.push(Entry {
+                    puzzle_name: puzzle_name.to_string(),
+                    difficulty,
+                    endpoints,
+                    stats: PuzzleStats::default(),
+                });
+                self.entries.len() - 1
+            }
+        };
+        &mut self.entries[index].stats
+    }
+
+    /// Record a completed game.
+    pub fn add_won(
+        &mut self,
+        puzzle_name: &str,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+        time: Duration,
+        best_streak: usize,
+        hints: usize,
+    ) {
+        let stats: &mut PuzzleStats = self.entry_mut(puzzle_name, difficulty, endpoints);
+
+        stats.won += 1;
+        stats.total_time += time;
+        stats.best_streak = stats.best_streak.max(best_streak);
+        stats.hints += hints;
+    }
+
+    /// Record a game that the player left before completing the puzzle.
+    pub fn add_abandoned(
+        &mut self,
+        puzzle_name: &str,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+        best_streak: usize,
+        hints: usize,
+    ) {
+        let stats: &mut PuzzleStats = self.entry_mut(puzzle_name, difficulty, endpoints);
+
+        stats.abandoned += 1;
+        stats.best_streak = stats.best_streak.max(best_streak);
+        stats.hints += hints;
+    }
+
+    /// Return the aggregates of the given puzzle, or None if no game was recorded.
+    pub fn get(
+        &self,
+        puzzle_name: &str,
+        difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+    ) -> Option<&PuzzleStats> {
+        self.entries
+            .iter()
+            .find(|e| {
+                e.puzzle_name == puzzle_name
+                    && e.difficulty == difficulty
+                    && e.endpoints == endpoints
+            })
+            .map(|e| &e.stats)
+    }
+
+    /// Return the aggregates of all the puzzles of the given difficulty level.
+    pub fn get_difficulty(&self, difficulty: puzzles::Difficulty) -> PuzzleStats {
+        let mut total: PuzzleStats = PuzzleStats::default();
+
+        for entry in self.entries.iter().filter(|e| e.difficulty == difficulty) {
+            total.merge(&entry.stats);
+        }
+        total
+    }
+
+    /// Return whether no game was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
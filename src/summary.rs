@@ -0,0 +1,91 @@
+/*
+summary.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Shareable summary of a solved puzzle.
+//!
+//! When players complete a puzzle, they can copy a short text that describes their game, such as
+//! `Hexkudo Heart (Hard) — 14:32, 2 mistakes`, and share it with their friends.
+//!
+//! The summary can include a grid of emoji, one per number of the path, in the path order:
+//!
+//! * 🔷 for the numbers that the puzzle gives as hints.
+//! * 🟩 for the numbers that the player found without mistakes.
+//! * 🟥 for the numbers where the player made mistakes.
+//!
+//! The grid does not reveal the solution, because the shape of the path is not included.
+
+use formatx::formatx;
+use gettextrs::{gettext, ngettext};
+
+use crate::game::Game;
+
+/// Number of emoji per line in the grid.
+const GRID_WIDTH: usize = 10;
+
+/// Return the shareable summary of the given game.
+pub fn summary(game: &Game, emoji_grid: bool) -> String {
+    let (h, m, s) = game.get_duration_hms();
+    let time_str: String = if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m:02}:{s:02}")
+    };
+    let errors: usize = game.get_errors();
+    let errors_str: String = formatx!(
+        ngettext("{count} mistake", "{count} mistakes", errors as u32),
+        count = errors
+    )
+    .unwrap();
+
+    let mut text: String = formatx!(
+        gettext("Hexkudo {puzzle} ({difficulty}) — {time}, {errors}"),
+        puzzle = game.puzzle.name_i18n.clone(),
+        difficulty = game.puzzle.difficulty,
+        time = time_str,
+        errors = errors_str
+    )
+    .unwrap();
+
+    if emoji_grid {
+        let error_numbers: Vec<usize> = game.get_error_numbers();
+        let cells: Vec<&str> = game
+            .path
+            .get()
+            .iter()
+            .enumerate()
+            .map(|(i, cell_id)| {
+                if game.map.contains(cell_id) {
+                    "🔷"
+                } else if error_numbers.contains(&(i + 1)) {
+                    "🟥"
+                } else {
+                    "🟩"
+                }
+            })
+            .collect();
+        for line in cells.chunks(GRID_WIDTH) {
+            text.push('\n');
+            text.push_str(&line.concat());
+        }
+    }
+    text
+}
@@ -0,0 +1,154 @@
+/*
+theme.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Color themes that players can share.
+//!
+//! A [`Theme`] is a named set of custom colors. The theme manager in the Preferences dialog
+//! lists the themes that the player saved from the current colors or imported from theme files,
+//! and applies them to the GSettings keys of the custom colors.
+//! See the [`crate::saver::themes`] module that saves the list of themes and that reads and
+//! writes the theme files.
+
+use gtk::gio;
+use gtk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Maximum number of themes in the theme manager.
+const MAX_THEMES: usize = 100;
+
+/// Maximum length of a theme name, in characters.
+const MAX_NAME_LENGTH: usize = 64;
+
+/// GSettings keys of the custom colors, with the keys that select the default color instead.
+pub const COLOR_KEYS: [(&str, &str); 7] = [
+    ("color-cell-values", "use-default-color-cell-values"),
+    ("color-cell-wrong", "use-default-color-cell-wrong"),
+    ("color-cell-bg", "use-default-color-bg"),
+    ("color-cell-hint-bg", "use-default-color-hint-bg"),
+    ("color-sel-cell-bg", "use-default-sel-color-bg"),
+    ("color-cell-borders", "use-default-color-borders"),
+    ("color-path", "use-default-color-path"),
+];
+
+/// Named set of custom colors.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Theme {
+    /// Name that the theme manager displays.
+    pub name: String,
+
+    /// Colors (red, green, blue, alpha) indexed by their GSettings key. The colors that the
+    /// theme does not include use the default puzzle colors.
+    pub colors: BTreeMap<String, (f64, f64, f64, f64)>,
+}
+
+impl Theme {
+    /// Create a [`Theme`] object from the custom colors that the player currently uses.
+    pub fn from_settings(name: &str, settings: &gio::Settings) -> Self {
+        let colors: BTreeMap<String, (f64, f64, f64, f64)> = COLOR_KEYS
+            .iter()
+            .filter(|(_, default_key)| !settings.boolean(default_key))
+            .filter_map(|(key, _)| {
+                settings
+                    .value(key)
+                    .get::<(f64, f64, f64, f64)>()
+                    .map(|color| (String::from(*key), color))
+            })
+            .collect();
+
+        Self {
+            name: String::from(name),
+            colors,
+        }
+    }
+
+    /// Replace the custom colors in GSettings with the colors of the theme.
+    pub fn apply(&self, settings: &gio::Settings) {
+        for (key, default_key) in COLOR_KEYS {
+            match self.colors.get(key) {
+                Some(color) => {
+                    settings
+                        .set_value(key, &color.to_variant())
+                        .expect("Cannot save the color in GSettings");
+                    let _ = settings.set_boolean(default_key, false);
+                }
+                None => {
+                    let _ = settings.set_boolean(default_key, true);
+                }
+            }
+        }
+    }
+
+    /// Verify the name and the colors of the theme.
+    /// A theme that fails the verification comes from a corrupted or a tampered file.
+    pub fn validate(&self) -> Result<(), String> {
+        let length: usize = self.name.chars().count();
+        if self.name.trim().is_empty() || length > MAX_NAME_LENGTH {
+            return Err(format!("Invalid theme name length: {length}"));
+        }
+        for (key, color) in &self.colors {
+            if !COLOR_KEYS.iter().any(|(k, _)| k == key) {
+                return Err(format!("Unknown color in the theme: {key}"));
+            }
+            if [color.0, color.1, color.2, color.3]
+                .iter()
+                .any(|c| !(0.0..=1.0).contains(c))
+            {
+                return Err(format!("Color component out of range for {key}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// List of the themes in the theme manager.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Themes {
+    /// Themes, in the order that the player added them.
+    pub themes: Vec<Theme>,
+}
+
+impl Themes {
+    /// Verify that the list does not exceed its maximum size and that the themes are valid.
+    /// A restored object that fails the verification comes from a corrupted or a tampered file.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.themes.len() > MAX_THEMES {
+            return Err(format!("Too many themes: {}", self.themes.len()));
+        }
+        self.themes.iter().try_for_each(Theme::validate)
+    }
+
+    /// Add the given theme to the list. The theme replaces the theme with the same name.
+    ///
+    /// Return an error when the list is full.
+    pub fn add(&mut self, theme: Theme) -> Result<(), String> {
+        if let Some(existing) = self.themes.iter_mut().find(|t| t.name == theme.name) {
+            *existing = theme;
+            return Ok(());
+        }
+        if self.themes.len() >= MAX_THEMES {
+            return Err(format!("Too many themes: {}", self.themes.len()));
+        }
+        self.themes.push(theme);
+        Ok(())
+    }
+}
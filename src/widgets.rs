@@ -22,19 +22,30 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Hexkudo widget objects.
 
+pub mod cheat_sheet;
 pub mod done_dialog;
 pub mod drawing_area;
 pub mod game_view;
+pub mod history_dialog;
+pub mod image_dialog;
 pub mod layout_manager;
 pub mod menu_button;
+pub mod number_pad;
+pub mod number_sheet;
 pub mod popover_number;
 pub mod preferences_dialog;
 pub mod print_dialog;
 pub mod print_job;
 pub mod print_progress;
 pub mod puzzle_list_item;
+pub mod remaining_numbers;
+pub mod replay_dialog;
+pub mod saved_games_dialog;
 pub mod scores_dialog;
 pub mod scores_dialog_item;
 pub mod select_puzzle_view;
+pub mod shortcuts_dialog;
 pub mod start_view;
+pub mod stats_dialog;
+pub mod verify_dialog;
 pub mod window;
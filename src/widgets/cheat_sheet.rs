@@ -0,0 +1,311 @@
+/*
+cheat_sheet.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! `GtkPrintOperation` object to print the solving technique cheat sheet.
+//!
+//! The cheat sheet is a single page that illustrates the solving techniques with small example
+//! boards. The boards are rendered with the same [`draw::Draw`] pipeline as the printed
+//! puzzles. The print dialog can also save the page as a PDF document.
+
+use gettextrs::gettext;
+
+use adw::prelude::*;
+use gtk::cairo::{Context, Surface};
+use gtk::glib;
+use gtk::subclass::prelude::*;
+
+use crate::draw;
+use crate::generator::path;
+use crate::generator::puzzle_parse::PuzzleParse;
+use crate::generator::puzzles;
+
+/// Board of the examples. The cell IDs go from 0 to 9, from left to right and from top to
+/// bottom.
+const EXAMPLE_MATRIX: &str = "
+ O O O
+O O O O
+ O O O";
+
+/// Solution of the example board.
+const EXAMPLE_SOLUTION: [u8; 10] = [3, 0, 1, 2, 6, 5, 4, 7, 8, 9];
+
+/// Numbers of the dead end example, in the order of the cells. Only the first three cells
+/// receive a number; the other cells complete the list so that the first cell is the only
+/// endpoint.
+const DEAD_END_CELLS: [u8; 10] = [3, 4, 8, 9, 5, 6, 2, 1, 0, 7];
+
+/// Example board that illustrates a technique.
+struct Example {
+    /// Title of the technique.
+    title: String,
+
+    /// Explanation of the technique.
+    text: String,
+
+    /// Order of the cells, which gives the number of each cell.
+    path: path::Path,
+
+    /// Cells that display their number.
+    map: Vec<usize>,
+
+    /// Diamonds between cells.
+    diamonds: Vec<(usize, usize)>,
+
+    /// Cells to highlight.
+    highlighted: Vec<usize>,
+
+    /// Whether to draw the path line.
+    draw_path: bool,
+}
+
+/// Return the examples of the cheat sheet.
+fn examples() -> Vec<Example> {
+    vec![
+        Example {
+            title: gettext("Diamonds"),
+            text: gettext(
+                "A diamond on the border between two cells links consecutive numbers. The chain \
+                 always goes through the diamonds: when one of the two cells gets a number, the \
+                 other cell gets the number just before or just after.",
+            ),
+            path: path::Path::from_vec(&EXAMPLE_SOLUTION),
+            map: vec![3, 1, 9],
+            diamonds: vec![(2, 6)],
+            highlighted: vec![2, 6],
+            draw_path: false,
+        },
+        Example {
+            title: gettext("Endpoints"),
+            text: gettext(
+                "The circled cells hold the first and the last numbers. The chain enters or \
+                 leaves these cells through a single neighbor, whereas every other cell has two \
+                 neighbors in the chain. Start from the endpoints and follow the cells that have \
+                 only one way out.",
+            ),
+            path: path::Path::from_vec(&EXAMPLE_SOLUTION),
+            map: vec![3, 9],
+            diamonds: Vec::new(),
+            highlighted: Vec::new(),
+            draw_path: true,
+        },
+        Example {
+            title: gettext("Dead Ends"),
+            text: gettext(
+                "An empty cell that the chain can enter but not leave can only hold the last \
+                 number. Here, the highlighted cell can only follow 3, and all its neighbors are \
+                 taken afterward: the numbers already placed lead to a dead end and must change.",
+            ),
+            path: path::Path::from_vec(&DEAD_END_CELLS),
+            map: vec![3, 4, 8],
+            diamonds: Vec::new(),
+            highlighted: vec![7],
+            draw_path: false,
+        },
+    ]
+}
+
+/// Split the given text into lines that fit the given width.
+fn wrap_text(ctx: &Context, text: &str, width: f64) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line: String = String::new();
+
+    for word in text.split_whitespace() {
+        let candidate: String = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{line} {word}")
+        };
+        let fits: bool = ctx
+            .text_extents(&candidate)
+            .map(|e| e.x_advance() <= width)
+            .unwrap_or(true);
+        if fits || line.is_empty() {
+            line = candidate;
+        } else {
+            lines.push(line);
+            line = word.to_string();
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct HexkudoCheatSheet {}
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HexkudoCheatSheet {
+        const NAME: &'static str = "HexkudoCheatSheet";
+        type Type = super::HexkudoCheatSheet;
+        type ParentType = gtk::PrintOperation;
+    }
+
+    impl ObjectImpl for HexkudoCheatSheet {}
+    impl PrintOperationImpl for HexkudoCheatSheet {
+        fn begin_print(&self, _context: &gtk::PrintContext) {
+            self.obj().set_n_pages(1);
+        }
+        fn draw_page(&self, context: &gtk::PrintContext, _page_nr: i32) {
+            self.obj().draw_page(context);
+        }
+    }
+    impl PrintOperationPreviewImpl for HexkudoCheatSheet {
+        fn render_page(&self, _: i32) {}
+        fn is_selected(&self, _: i32) -> bool {
+            false
+        }
+        fn end_preview(&self) {}
+    }
+}
+
+glib::wrapper! {
+    pub struct HexkudoCheatSheet(ObjectSubclass<imp::HexkudoCheatSheet>)
+        @extends gtk::PrintOperation, @implements gtk::PrintOperationPreview;
+}
+
+impl Default for HexkudoCheatSheet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HexkudoCheatSheet {
+    /// Create the print operation object.
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    /// Display the print dialog and print the cheat sheet.
+    pub fn print(&self, window: &gtk::Window) {
+        if let Err(e) = self.run(gtk::PrintOperationAction::PrintDialog, Some(window)) {
+            let dialog: adw::AlertDialog = adw::AlertDialog::new(
+                Some(&gettext("Error Printing the Cheat Sheet")),
+                Some(e.message()),
+            );
+            dialog.add_response("close", &gettext("Close"));
+            dialog.present(Some(window));
+        }
+    }
+
+    /// Draw the page: a title, and a row for each example with the board on the left and the
+    /// explanation on the right.
+    fn draw_page(&self, context: &gtk::PrintContext) {
+        let mut puzzle: puzzles::Puzzle = puzzles::Puzzle {
+            matrix: PuzzleParse::new(EXAMPLE_MATRIX),
+            ..Default::default()
+        };
+        puzzle
+            .matrix
+            .build_edges()
+            .expect("The example board definition has an error");
+        let mut draw: draw::Draw = draw::Draw::new(&puzzle);
+
+        // Always print with the light color scheme
+        draw.set_dark(false);
+
+        let ctx: Context = context.cairo_context();
+        let width: f64 = context.width();
+        let height: f64 = context.height();
+        let margin: f64 = width * 0.05;
+        let examples: Vec<Example> = examples();
+
+        // Title
+        ctx.set_source_rgba(0.0, 0.0, 0.0, 1.0);
+        ctx.set_font_size(20.0);
+        let title: String = gettext("Hexkudo Solving Techniques");
+        let title_height: f64 = ctx.font_extents().map(|e| e.height()).unwrap_or(20.0);
+        ctx.move_to(margin, margin + title_height);
+        let _ = ctx.show_text(&title);
+
+        // Each example gets the same share of the remaining height
+        let top: f64 = margin + title_height * 2.0;
+        let row_height: f64 = (height - top - margin) / examples.len() as f64;
+        let board_size: f64 = (row_height * 0.9).min(width * 0.35);
+        let scaling_factor: f64 = board_size / draw.surface_size();
+        let text_x: f64 = margin + board_size + margin;
+        let text_width: f64 = width - text_x - margin;
+
+        for (i, example) in examples.iter().enumerate() {
+            let y: f64 = top + i as f64 * row_height;
+
+            draw.puzzle_frame()
+                .expect("Cannot draw the example board frame");
+            draw.puzzle_maps_and_diamonds(
+                &example.path,
+                &example.map,
+                &example.diamonds,
+                &Vec::new(),
+                &Vec::new(),
+            )
+            .expect("Cannot draw the hints and the diamonds");
+            let number_surface: Surface = draw
+                .puzzle_cell_numbers(&example.path, &example.map, draw::ZoomLevel::Medium)
+                .expect("Cannot draw the cell numbers");
+            let highlight_surface: Surface = draw
+                .highlighted_cells(&example.highlighted)
+                .expect("Cannot draw the highlighted cells");
+            let path_surface: Option<Surface> = example
+                .draw_path
+                .then(|| draw.path(&example.path, 1.0).expect("Cannot draw the path"));
+
+            // Paint the board layers
+            let _ = ctx.save();
+            ctx.translate(margin, y);
+            ctx.scale(scaling_factor, scaling_factor);
+            let _ = ctx.set_source_surface(draw.background_surface(), 0.0, 0.0);
+            let _ = ctx.paint();
+            let _ = ctx.set_source_surface(highlight_surface, 0.0, 0.0);
+            let _ = ctx.paint();
+            let _ = ctx.set_source_surface(draw.border_surface(), 0.0, 0.0);
+            let _ = ctx.paint();
+            let _ = ctx.set_source_surface(number_surface, 0.0, 0.0);
+            let _ = ctx.paint();
+            if let Some(p) = path_surface {
+                let _ = ctx.set_source_surface(p, 0.0, 0.0);
+                let _ = ctx.paint();
+            }
+            let _ = ctx.restore();
+
+            // Title and explanation of the technique
+            ctx.set_source_rgba(0.0, 0.0, 0.0, 1.0);
+            ctx.set_font_size(14.0);
+            let line_height: f64 = ctx.font_extents().map(|e| e.height()).unwrap_or(14.0);
+            let mut text_y: f64 = y + line_height;
+            ctx.move_to(text_x, text_y);
+            let _ = ctx.show_text(&example.title);
+
+            ctx.set_font_size(11.0);
+            let line_height: f64 = ctx.font_extents().map(|e| e.height()).unwrap_or(11.0);
+            text_y += line_height * 0.5;
+            for line in wrap_text(&ctx, &example.text, text_width) {
+                text_y += line_height;
+                ctx.move_to(text_x, text_y);
+                let _ = ctx.show_text(&line);
+            }
+        }
+    }
+}
@@ -24,10 +24,15 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Dialog for when the player successfully completed the puzzle.
 
-use gettextrs::gettext;
+use formatx::formatx;
+use gettextrs::{gettext, ngettext};
+use std::time::Duration;
 
 use adw::{prelude::*, subclass::prelude::*};
-use gtk::glib;
+use gtk::{gdk, glib};
+
+use crate::generator::puzzles;
+use crate::recommendation::Suggestion;
 
 mod imp {
     use super::*;
@@ -37,6 +42,8 @@ mod imp {
     pub struct HexkudoDoneDialog {
         // Template widgets
         #[template_child]
+        pub thumbnail: TemplateChild<gtk::Picture>,
+        #[template_child]
         pub highscore_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub highscore_button_content: TemplateChild<adw::ButtonContent>,
@@ -71,7 +78,21 @@ glib::wrapper! {
 
 impl HexkudoDoneDialog {
     /// Create the dialog.
-    pub fn new(cheated: bool, clock_visible: bool, highscore_position: Option<usize>) -> Self {
+    ///
+    /// The `pauses` parameter provides the number of times the game was paused and the total
+    /// pause time.
+    /// In mystery mode, the `mistakes` parameter provides the number of mistakes and the numbers
+    /// of the cells where the player made them.
+    /// The `thumbnail` parameter provides a snapshot of the solved board, with the solution
+    /// path.
+    pub fn new(
+        cheated: bool,
+        clock_visible: bool,
+        highscore_position: Option<usize>,
+        pauses: (usize, Duration),
+        mistakes: Option<(usize, Vec<usize>)>,
+        thumbnail: Option<gdk::Texture>,
+    ) -> Self {
         let obj: HexkudoDoneDialog = glib::Object::builder().build();
         let imp: &imp::HexkudoDoneDialog = obj.imp();
 
@@ -83,13 +104,145 @@ impl HexkudoDoneDialog {
 
         obj.set_heading(Some(&msg));
 
+        let mut body: Vec<String> = Vec::new();
+
+        let (pause_count, pause_total) = pauses;
+        if pause_count > 0 {
+            let secs: u64 = pause_total.as_secs();
+            let (h, m, s) = (secs / 3600, (secs % 3600) / 60, (secs % 3600) % 60);
+            let time_str: String = if h > 0 {
+                format!("{h:02}:{m:02}:{s:02}")
+            } else {
+                format!("{m:02}:{s:02}")
+            };
+            body.push(
+                formatx!(
+                    ngettext(
+                        "The game was paused {count} time, for a total of {time}.",
+                        "The game was paused {count} times, for a total of {time}.",
+                        pause_count as u32
+                    ),
+                    count = pause_count,
+                    time = time_str
+                )
+                .unwrap(),
+            );
+        }
+
+        // Review of the mistakes that the mystery mode kept hidden during the game
+        if let Some((count, numbers)) = mistakes {
+            if count == 0 {
+                body.push(gettext("You made no mistakes."));
+            } else {
+                let cells: Vec<String> = numbers.iter().map(|n| format!("{n}")).collect();
+                body.push(
+                    formatx!(
+                        ngettext(
+                            "You made {count} mistake, on the cells {cells}.",
+                            "You made {count} mistakes, on the cells {cells}.",
+                            count as u32
+                        ),
+                        count = count,
+                        cells = cells.join(", ")
+                    )
+                    .unwrap(),
+                );
+            }
+        }
+        if !body.is_empty() {
+            obj.set_body(&body.join("\n"));
+        }
+
+        if let Some(texture) = thumbnail {
+            imp.thumbnail.set_paintable(Some(&texture));
+            imp.thumbnail.set_visible(true);
+        }
+
         if clock_visible {
             if let Some(pos) = highscore_position {
                 imp.highscore_button_content.set_label(&format!("{pos}"));
             }
+            if cheated {
+                imp.highscore_button
+                    .set_tooltip_text(Some(&gettext("Assisted High Scores")));
+            }
         } else {
             imp.highscore_button.set_visible(false);
         }
         obj
     }
+
+    /// Suggest the next puzzle to play, and add the response that starts it.
+    pub fn set_next_challenge(&self, puzzle: &puzzles::Puzzle) {
+        let msg: String = formatx!(
+            gettext("Try {puzzle} {difficulty}: you have not beaten it yet."),
+            puzzle = &puzzle.name_i18n,
+            difficulty = puzzle.difficulty
+        )
+        .unwrap();
+
+        self.append_body(&msg);
+        self.add_response("next-challenge", &gettext("_Next Challenge"));
+    }
+
+    /// Suggest a harder or an easier puzzle, and add the response that starts it.
+    pub fn set_suggestion(&self, suggestion: Suggestion, puzzle: &puzzles::Puzzle) {
+        let (msg, label): (String, String) = match suggestion {
+            Suggestion::Harder => (
+                formatx!(
+                    gettext("You solve these puzzles quickly and accurately. Ready for {puzzle} {difficulty}?"),
+                    puzzle = &puzzle.name_i18n,
+                    difficulty = puzzle.difficulty
+                )
+                .unwrap(),
+                gettext("Try _Harder"),
+            ),
+            Suggestion::Easier => (
+                formatx!(
+                    gettext("These puzzles seem challenging lately. {puzzle} {difficulty} might be more relaxing."),
+                    puzzle = &puzzle.name_i18n,
+                    difficulty = puzzle.difficulty
+                )
+                .unwrap(),
+                gettext("Try _Easier"),
+            ),
+            Suggestion::BiggerBoard => (
+                formatx!(
+                    gettext("You master this difficulty level. Try a bigger board with {puzzle}."),
+                    puzzle = &puzzle.name_i18n
+                )
+                .unwrap(),
+                gettext("Try a _Bigger Board"),
+            ),
+        };
+
+        self.append_body(&msg);
+        self.add_response("suggestion", &label);
+    }
+
+    /// Display the points that the player earned with the points-based scoring.
+    pub fn set_points(&self, points: u32) {
+        self.append_body(
+            &formatx!(
+                ngettext(
+                    "You scored {points} point.",
+                    "You scored {points} points.",
+                    points
+                ),
+                points = points
+            )
+            .unwrap(),
+        );
+    }
+
+    /// Add a line to the body of the dialog.
+    fn append_body(&self, msg: &str) {
+        let body: String = self.body().to_string();
+
+        if body.is_empty() {
+            self.set_body(msg);
+        } else {
+            self.set_body(&format!("{body}\n{msg}"));
+        }
+    }
 }
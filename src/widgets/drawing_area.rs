@@ -23,27 +23,98 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! Manage drawings and events in the drawing area.
 
 use log::{Level, debug, log_enabled};
+use std::collections::HashMap;
+use std::error::Error;
 use std::ops::DerefMut;
 
+use qrcodegen::{QrCode, QrCodeEcc};
+
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use glib::{Properties, clone};
 use gtk::cairo::{ImageSurface, Surface};
 use gtk::{gdk, gio, glib};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
+use super::number_sheet::HexkudoNumberSheet;
 use super::popover_number::HexkudoPopoverNumber;
 use super::preferences_dialog::get_rgba;
 use super::print_job::{HexkudoPrintJob, PrintJobParameters};
 use crate::draw;
+use crate::energy_saver;
 use crate::game::{CellStatus, Game};
 use crate::generator::diamond_and_map;
 use crate::generator::path;
 use crate::generator::puzzles;
+use crate::generator::regions;
+use crate::generator::share_code::ShareCode;
 use crate::generator::vertexes;
+use crate::keybindings;
+use crate::player_input::PlayerInput;
+use crate::seasonal;
+use crate::shared_game;
 use crate::widgets::game_view::HexkudoGameView;
 
+/// Duration, in milliseconds, of the animation that brings the board back to its position
+/// after the player spun it.
+const SPIN_BACK_DURATION: u32 = 400;
+
+/// Duration, in microseconds of frame clock time, of the animation that shakes and flashes the
+/// cell where the player entered a wrong value.
+const WRONG_ENTRY_DURATION: i64 = 500_000;
+
+/// Duration, in microseconds of frame clock time, of the animation that flashes the cells
+/// where a missing value could go.
+const CANDIDATE_FLASH_DURATION: i64 = 1_200_000;
+
+/// Duration, in microseconds of frame clock time, of the animation that traces the solution
+/// path when the player solves the puzzle.
+const COMPLETION_TRACE_DURATION: i64 = 2_000_000;
+
+/// Maximum duration, in microseconds, of a two-finger tap. Longer two-finger gestures are
+/// rotations.
+const TWO_FINGER_TAP_DURATION: i64 = 250_000;
+
+/// Maximum angle, in radians, by which the fingers can turn during a two-finger tap.
+const TWO_FINGER_TAP_ANGLE: f64 = 0.1;
+
+/// Maximum change of the distance between the fingers during a two-finger tap. Larger changes
+/// are pinches.
+const TWO_FINGER_TAP_SCALE: f64 = 0.1;
+
+/// Maximum distance, in pixels, by which the fingers can move during a two-finger tap.
+const TWO_FINGER_TAP_DISTANCE: f64 = 16.0;
+
+/// Factor by which one step of the mouse wheel magnifies the board.
+const SCROLL_ZOOM_STEP: f64 = 1.1;
+
+/// Distance, in pixels, by which one step of the mouse wheel moves the magnified board.
+const SCROLL_PAN_STEP: f64 = 40.0;
+
+/// Margin, in pixels, that the magnified board keeps around the cell that the player selects
+/// with the keyboard.
+const FOLLOW_SELECTION_MARGIN: f64 = 24.0;
+
+/// How the number picker opens when the player clicks a cell.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, glib::Enum)]
+#[enum_type(name = "HexkudoPopoverTrigger")]
+pub enum PopoverTrigger {
+    /// When the player presses the mouse button.
+    Press,
+
+    /// When the player releases the mouse button in the cell where they pressed it.
+    #[default]
+    Release,
+
+    /// When the player double-clicks the cell. A single click selects the cell.
+    DoubleClick,
+
+    /// When the player clicks the cell that is already selected. Clicking another cell only
+    /// selects that cell.
+    SecondClick,
+}
+
 /// Currently dragged cell
 #[derive(Debug, Clone, Default)]
 pub struct Drag {
@@ -55,6 +126,10 @@ pub struct Drag {
 
     /// List of the cell that have been visited by the drag motion.
     pub cells: Vec<vertexes::CellType>,
+
+    /// Viewport when the drag operation started, if the drag operation moves the magnified
+    /// board instead of filling cells.
+    pub pan_from: Option<draw::Viewport>,
 }
 
 mod imp {
@@ -73,6 +148,41 @@ mod imp {
         pub draw: RefCell<draw::Draw>,
         pub game: OnceCell<Rc<RefCell<Game>>>,
         pub drag: RefCell<Drag>,
+        pub show_moves: Cell<bool>,
+        /// Cell and candidate number of the last note that the player toggled with the keyboard.
+        /// The next digit extends that number.
+        pub pending_note: Cell<Option<(usize, usize)>>,
+        /// Angle, in radians, by which the board is rotated around the center of the widget.
+        /// The rotation is only visual: the board is back to its position when the player
+        /// interacts with the cells.
+        pub rotation: Cell<f64>,
+        /// Cells that the guided reveal highlights.
+        pub highlighted_cells: RefCell<Vec<usize>>,
+        /// Whether to show the heat map of the time spent on each cell, after the game.
+        pub show_heat_map: Cell<bool>,
+        /// Cell where the player entered a wrong value, with the frame time when the animation
+        /// started. None when no animation is running.
+        pub wrong_entry: Cell<Option<(usize, i64)>>,
+        /// Cells that flash to show where a missing value could go, with the frame time when
+        /// the animation started. None when no animation is running.
+        pub candidate_flash: RefCell<Option<(Vec<usize>, i64)>>,
+        /// Frame time when the animation that traces the solution path started. None when no
+        /// animation is running.
+        pub completion_trace: Cell<Option<i64>>,
+        /// Monotonic time and surface coordinates of the center of the fingers when the current
+        /// two-finger gesture started. None when no two-finger gesture is running.
+        pub two_finger_start: Cell<Option<(i64, f64, f64)>>,
+        /// Magnification and position of the board.
+        pub viewport: Cell<draw::Viewport>,
+        /// Viewport and widget coordinates of the center of the fingers when the current pinch
+        /// started. None when no pinch is running.
+        pub pinch_start: Cell<Option<(draw::Viewport, f64, f64)>>,
+        /// Position of the mouse pointer in the widget, for zooming around it.
+        pub pointer: Cell<Option<(f64, f64)>>,
+        /// Number pad that replaces the popover on narrow screens.
+        pub number_sheet: OnceCell<HexkudoNumberSheet>,
+        /// Whether the window is narrow. The number pad replaces the popover in that case.
+        pub compact: Cell<bool>,
 
         // Properties
         #[property(get, set)]
@@ -81,6 +191,16 @@ mod imp {
         pub show_duplicates: Cell<bool>,
         #[property(get, set)]
         pub draw_path: Cell<bool>,
+        #[property(get, set)]
+        pub number_words: Cell<bool>,
+        #[property(get, set)]
+        pub mystery_mode: Cell<bool>,
+        #[property(get, set)]
+        pub pause_thumbnail: Cell<bool>,
+        #[property(get, set)]
+        pub notes_mode: Cell<bool>,
+        #[property(get, set)]
+        pub board_spin: Cell<bool>,
 
         // Color properties
         #[property(get, set)]
@@ -99,10 +219,20 @@ mod imp {
         pub use_default_color_path: Cell<bool>,
         #[property(get, set)]
         pub sel_thick_border: Cell<bool>,
+        #[property(get, set)]
+        pub select_hint_cells: Cell<bool>,
+        #[property(get, set)]
+        pub debug_layers: Cell<draw::DebugLayers>,
+        #[property(get, set, builder(PopoverTrigger::Release))]
+        pub popover_trigger: Cell<PopoverTrigger>,
 
         // Template widgets
         #[template_child]
         pub popover_number: TemplateChild<HexkudoPopoverNumber>,
+        #[template_child]
+        pub rotate_gesture: TemplateChild<gtk::GestureRotate>,
+        #[template_child]
+        pub zoom_gesture: TemplateChild<gtk::GestureZoom>,
     }
 
     #[glib::object_subclass]
@@ -177,6 +307,12 @@ impl HexkudoDrawingArea {
             .bind("show-duplicates", self, "show-duplicates")
             .build();
         settings.bind("draw-path", self, "draw-path").build();
+        settings.bind("number-words", self, "number-words").build();
+        settings.bind("mystery-mode", self, "mystery-mode").build();
+        settings
+            .bind("pause-thumbnail", self, "pause-thumbnail")
+            .build();
+        settings.bind("board-spin", self, "board-spin").build();
 
         settings
             .bind(
@@ -218,6 +354,12 @@ impl HexkudoDrawingArea {
         settings
             .bind("sel-thick-border", self, "sel-thick-border")
             .build();
+        settings
+            .bind("select-hint-cells", self, "select-hint-cells")
+            .build();
+        settings
+            .bind("popover-trigger", self, "popover-trigger")
+            .build();
 
         // React to color changes from the Preferences dialog
         settings.connect_changed(
@@ -237,6 +379,20 @@ impl HexkudoDrawingArea {
             .expect("Cannot store the settings in the object");
     }
 
+    /// Show or hide a debug overlay layer. See [`draw::DebugLayers`] for the layer names.
+    pub fn toggle_debug_layer(&self, nick: &str) {
+        match draw::DebugLayers::from_nick(nick) {
+            Some(layer) => self.set_debug_layers(self.debug_layers() ^ layer),
+            None => debug!("Unknown debug layer: {nick}"),
+        }
+    }
+
+    /// Highlight the given cells, or remove the highlight when the list is empty.
+    pub fn set_highlighted_cells(&self, cells: Vec<usize>) {
+        self.imp().highlighted_cells.replace(cells);
+        self.queue_draw();
+    }
+
     pub fn set_zoom_level(&self, zoom_level: draw::ZoomLevel) {
         self.imp().zoom_level.set(zoom_level);
     }
@@ -248,6 +404,137 @@ impl HexkudoDrawingArea {
         self.queue_draw();
     }
 
+    /// Show or hide the heat map of the solved game. Return whether the heat map is visible.
+    pub fn switch_heat_map(&self) -> bool {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+
+        imp.show_heat_map.set(!imp.show_heat_map.get());
+        self.queue_draw();
+        imp.show_heat_map.get()
+    }
+
+    pub fn hide_heat_map(&self) {
+        self.imp().show_heat_map.set(false);
+        self.queue_draw();
+    }
+
+    /// Shake and flash the given cell to show that the player entered a wrong value.
+    ///
+    /// The animation only runs when the wrong values are highlighted, and not with the energy
+    /// saver. A tick callback drives the animation from the frame clock.
+    pub fn flash_wrong_entry(&self, cell_id: usize) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        if !imp.show_warnings.get() || imp.mystery_mode.get() {
+            return;
+        }
+        if let Some(settings) = imp.settings.get()
+            && energy_saver::is_active(settings)
+        {
+            return;
+        }
+        let Some(frame_clock) = self.frame_clock() else {
+            return;
+        };
+
+        // A running animation restarts with the new cell
+        let running: bool = imp.wrong_entry.get().is_some();
+        imp.wrong_entry
+            .set(Some((cell_id, frame_clock.frame_time())));
+        if running {
+            return;
+        }
+        self.add_tick_callback(|mself, frame_clock| {
+            let imp: &imp::HexkudoDrawingArea = mself.imp();
+            mself.queue_draw();
+            if imp
+                .wrong_entry
+                .get()
+                .is_some_and(|(_, start)| frame_clock.frame_time() - start < WRONG_ENTRY_DURATION)
+            {
+                return glib::ControlFlow::Continue;
+            }
+            imp.wrong_entry.set(None);
+            glib::ControlFlow::Break
+        });
+    }
+
+    /// Flash the given cells to show where a missing value could go.
+    ///
+    /// With the energy saver, the cells are highlighted without animation, until the player
+    /// completes a cell.
+    pub fn flash_candidate_cells(&self, cells: Vec<usize>) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        if let Some(settings) = imp.settings.get()
+            && energy_saver::is_active(settings)
+        {
+            self.set_highlighted_cells(cells);
+            return;
+        }
+        let Some(frame_clock) = self.frame_clock() else {
+            return;
+        };
+
+        // A running animation restarts with the new cells
+        let running: bool = imp
+            .candidate_flash
+            .replace(Some((cells, frame_clock.frame_time())))
+            .is_some();
+        if running {
+            return;
+        }
+        self.add_tick_callback(|mself, frame_clock| {
+            let imp: &imp::HexkudoDrawingArea = mself.imp();
+            mself.queue_draw();
+            if imp
+                .candidate_flash
+                .borrow()
+                .as_ref()
+                .is_some_and(|(_, start)| {
+                    frame_clock.frame_time() - start < CANDIDATE_FLASH_DURATION
+                })
+            {
+                return glib::ControlFlow::Continue;
+            }
+            imp.candidate_flash.replace(None);
+            glib::ControlFlow::Break
+        });
+    }
+
+    /// Trace the solution path from the start cell to the end cell, and then run the given
+    /// function.
+    ///
+    /// With the energy saver, the function runs immediately. When a new puzzle replaces the
+    /// solved one during the animation, the function does not run.
+    pub fn trace_solution<F: FnOnce() + 'static>(&self, done: F) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let frame_clock: Option<gdk::FrameClock> = self.frame_clock();
+        let saving: bool = imp.settings.get().is_some_and(energy_saver::is_active);
+
+        let Some(frame_clock) = frame_clock.filter(|_| !saving) else {
+            done();
+            return;
+        };
+        imp.completion_trace.set(Some(frame_clock.frame_time()));
+
+        let done: Cell<Option<F>> = Cell::new(Some(done));
+        self.add_tick_callback(move |mself, frame_clock| {
+            let imp: &imp::HexkudoDrawingArea = mself.imp();
+            mself.queue_draw();
+            let Some(start) = imp.completion_trace.get() else {
+                // A new puzzle replaced the solved one
+                return glib::ControlFlow::Break;
+            };
+            if frame_clock.frame_time() - start < COMPLETION_TRACE_DURATION {
+                return glib::ControlFlow::Continue;
+            }
+            imp.completion_trace.set(None);
+            if let Some(done) = done.take() {
+                done();
+            }
+            glib::ControlFlow::Break
+        });
+    }
+
     pub fn switch_duplicates(&self) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
 
@@ -255,33 +542,70 @@ impl HexkudoDrawingArea {
         self.queue_draw();
     }
 
+    /// Set whether to highlight the wrong and the duplicated values for the current game.
+    /// Unlike the properties, the GSettings values do not change.
+    pub fn set_highlights(&self, show_warnings: bool, show_duplicates: bool) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+
+        imp.show_warnings.set(show_warnings);
+        imp.show_duplicates.set(show_duplicates);
+        self.queue_draw();
+    }
+
+    /// Switch between entering values and entering notes (pencil marks). Return whether the
+    /// notes mode is active.
+    pub fn switch_notes_mode(&self) -> bool {
+        self.imp().pending_note.set(None);
+        self.set_notes_mode(!self.notes_mode());
+        self.notes_mode()
+    }
+
     fn dark(&self, style_manager: &adw::StyleManager) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let retry_style_manager: adw::StyleManager = style_manager.clone();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            move |obj: &Self| obj.dark(&retry_style_manager),
+        ) else {
+            return;
+        };
         let is_dark: bool = style_manager.is_dark();
 
         imp.is_dark.set(is_dark);
 
         // Redraw the puzzle with the new color set
         self.init_puzzle(&mut game.puzzle);
-        self.set_path_from_diamonds_and_map(&game.path, &game.diamonds, &game.map);
+        self.set_path_from_diamonds_and_map(
+            &game.path,
+            &game.diamonds,
+            &game.map,
+            &game.anti_diamonds,
+            &game.regions,
+        );
     }
 
     fn draw(&self, _da: &gtk::DrawingArea, ctx: &gtk::cairo::Context, w: i32, h: i32) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
         let draw = imp.draw.borrow();
-        let game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow();
 
-        // Hide the puzzle when in pause by not drawing the puzzle
-        if !draw.initialized() || game.paused {
+        // The game is being updated: redraw the puzzle when the update is complete
+        let Some(game) = shared_game::try_read(
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        ) else {
+            glib::idle_add_local_once(clone!(
+                #[weak(rename_to = mself)]
+                self,
+                move || mself.queue_draw()
+            ));
+            return;
+        };
+
+        if !draw.initialized() {
             return;
         }
 
@@ -291,6 +615,24 @@ impl HexkudoDrawingArea {
             h as f64 / draw.surface_size()
         };
 
+        // Hide the puzzle when in pause by not drawing the puzzle, or by only drawing a blurred
+        // thumbnail
+        if game.paused {
+            if imp.pause_thumbnail.get()
+                && let Ok(thumbnail) = draw.pause_thumbnail()
+            {
+                let scale: f64 = scaling_factor * draw.surface_size() / thumbnail.width() as f64;
+
+                let _ = ctx.save();
+                ctx.scale(scale, scale);
+                let _ = ctx.set_source_surface(&thumbnail, 0.0, 0.0);
+                ctx.source().set_filter(gtk::cairo::Filter::Good);
+                let _ = ctx.paint_with_alpha(0.5);
+                let _ = ctx.restore();
+            }
+            return;
+        }
+
         // Save the scaling factor
         imp.scaling_factor.set(scaling_factor);
 
@@ -303,40 +645,142 @@ impl HexkudoDrawingArea {
         // - The cell background
 
         let _ = ctx.save();
+
+        // Rotate the board around the center of the widget while the player spins it
+        let rotation: f64 = imp.rotation.get();
+        if rotation != 0.0 {
+            ctx.translate(w as f64 / 2.0, h as f64 / 2.0);
+            ctx.rotate(rotation);
+            ctx.translate(-w as f64 / 2.0, -h as f64 / 2.0);
+        }
+
+        // Magnify and move the board when the player zoomed in. The viewport is clamped in case
+        // the widget was resized.
+        let mut viewport: draw::Viewport = imp.viewport.get();
+        viewport.clamp(w as f64, h as f64);
+        imp.viewport.set(viewport);
+        ctx.translate(viewport.offset_x, viewport.offset_y);
+        ctx.scale(viewport.magnification, viewport.magnification);
         ctx.scale(scaling_factor, scaling_factor);
 
+        // Layers that are hidden for debugging rendering issues
+        let debug_layers: draw::DebugLayers = imp.debug_layers.get();
+
         // Paint the background
-        let _ = ctx.set_source_surface(draw.background_surface(), 0.0, 0.0);
-        let _ = ctx.paint();
+        if !debug_layers.contains(draw::DebugLayers::HIDE_BACKGROUND) {
+            let _ = ctx.set_source_surface(draw.background_surface(), 0.0, 0.0);
+            let _ = ctx.paint();
+        }
 
         // Paint the selected cell background
-        let selection_surface: Surface = draw
-            .selected_cell(game.get_selected_cell(), imp.sel_thick_border.get())
-            .expect("Cannot create a surface to draw the selected cell background");
-        let _ = ctx.set_source_surface(selection_surface, 0.0, 0.0);
-        let _ = ctx.paint();
+        if !debug_layers.contains(draw::DebugLayers::HIDE_SELECTION) {
+            let selection_surface: Surface = draw
+                .selected_cell(game.get_selected_cell(), imp.sel_thick_border.get())
+                .expect("Cannot create a surface to draw the selected cell background");
+            let _ = ctx.set_source_surface(selection_surface, 0.0, 0.0);
+            let _ = ctx.paint();
+        }
+
+        // Paint the area that the guided reveal highlights
+        let highlighted_cells = imp.highlighted_cells.borrow();
+        if !highlighted_cells.is_empty()
+            && !debug_layers.contains(draw::DebugLayers::HIDE_SELECTION)
+        {
+            let highlight_surface: Surface = draw
+                .highlighted_cells(&highlighted_cells)
+                .expect("Cannot create a surface to draw the highlighted cells");
+            let _ = ctx.set_source_surface(highlight_surface, 0.0, 0.0);
+            let _ = ctx.paint();
+        }
+
+        // Flash the cells where a missing value could go
+        if let Some((cells, start)) = imp.candidate_flash.borrow().as_ref()
+            && let Some(frame_clock) = self.frame_clock()
+        {
+            let progress: f64 =
+                (frame_clock.frame_time() - start) as f64 / CANDIDATE_FLASH_DURATION as f64;
+            let flash_surface: Surface = draw
+                .flash_cells(cells, progress)
+                .expect("Cannot create a surface to draw the candidate cells");
+            let _ = ctx.set_source_surface(flash_surface, 0.0, 0.0);
+            let _ = ctx.paint();
+        }
+
+        // Paint the heat map of the solved game
+        if imp.show_heat_map.get() && game.solved {
+            let heat_surface: Surface = draw
+                .heat_map(&game.get_heat_map())
+                .expect("Cannot create a surface to draw the heat map");
+            let _ = ctx.set_source_surface(heat_surface, 0.0, 0.0);
+            let _ = ctx.paint();
+        }
+
+        // Shake and flash the cell where the player just entered a wrong value
+        if let Some((cell_id, start)) = imp.wrong_entry.get()
+            && let Some(frame_clock) = self.frame_clock()
+        {
+            let progress: f64 =
+                (frame_clock.frame_time() - start) as f64 / WRONG_ENTRY_DURATION as f64;
+            let wrong_surface: Surface = draw
+                .wrong_entry(cell_id, progress)
+                .expect("Cannot create a surface to draw the wrong entry animation");
+            let _ = ctx.set_source_surface(wrong_surface, 0.0, 0.0);
+            let _ = ctx.paint();
+        }
 
         // Paint the cell borders and the diamonds
-        let _ = ctx.set_source_surface(draw.border_surface(), 0.0, 0.0);
-        let _ = ctx.paint();
+        if !debug_layers.contains(draw::DebugLayers::HIDE_BORDERS) {
+            let _ = ctx.set_source_surface(draw.border_surface(), 0.0, 0.0);
+            let _ = ctx.paint();
+        }
 
         // Paint the cell numbers that the user entered
         // let selection = game.selection.get_cells();
-        let player_input: Vec<CellStatus> = game.get_cells();
-        let zoom: draw::ZoomLevel = imp.zoom_level.get();
-        let user_surface: Surface = draw
-            .user_cell_numbers(
-                player_input,
-                imp.show_duplicates.get(),
-                imp.show_warnings.get(),
-                zoom,
-            )
-            .expect("Cannot create a surface to draw the user cell numbers");
-        let _ = ctx.set_source_surface(user_surface, 0.0, 0.0);
-        let _ = ctx.paint();
+        if !debug_layers.contains(draw::DebugLayers::HIDE_NUMBERS) {
+            let player_input: Vec<CellStatus> = game.get_cells();
+            let zoom: draw::ZoomLevel = imp
+                .zoom_level
+                .get()
+                .resolve(draw.cell_size() * scaling_factor * viewport.magnification);
+
+            // In mystery mode, the mistakes stay hidden until the end of the game
+            let mystery: bool = imp.mystery_mode.get();
+            let user_surface: Surface = draw
+                .user_cell_numbers(
+                    player_input,
+                    imp.show_duplicates.get() && !mystery,
+                    imp.show_warnings.get() && !mystery,
+                    zoom,
+                )
+                .expect("Cannot create a surface to draw the user cell numbers");
+            let _ = ctx.set_source_surface(user_surface, 0.0, 0.0);
+            let _ = ctx.paint();
+
+            // Cross the edges between consecutive values without a diamond, in the strict
+            // diamonds variant
+            let conflicts: Vec<(usize, usize)> = game.get_strict_conflicts_for(&game.player_input);
+            if imp.show_duplicates.get() && !mystery && !conflicts.is_empty() {
+                let conflict_surface: Surface = draw
+                    .strict_conflicts(&conflicts)
+                    .expect("Cannot create a surface to draw the strict diamond conflicts");
+                let _ = ctx.set_source_surface(conflict_surface, 0.0, 0.0);
+                let _ = ctx.paint();
+            }
+
+            // Paint the notes (pencil marks) of the empty cells. In mystery mode, the notes are
+            // visible because they are not checked.
+            let notes: HashMap<usize, Vec<usize>> = game.get_notes();
+            if !notes.is_empty() {
+                let notes_surface: Surface = draw
+                    .user_cell_notes(&notes)
+                    .expect("Cannot create a surface to draw the cell notes");
+                let _ = ctx.set_source_surface(notes_surface, 0.0, 0.0);
+                let _ = ctx.paint();
+            }
+        }
 
         // Paint the path line over the selected numbers
-        if imp.draw_path.get() {
+        if imp.draw_path.get() && !debug_layers.contains(draw::DebugLayers::HIDE_PATH) {
             let path = draw
                 .path_from_player_input(&game.player_input)
                 .expect("Cannot create a surface to draw the user cell numbers");
@@ -344,6 +788,48 @@ impl HexkudoDrawingArea {
             let _ = ctx.paint();
         }
 
+        // Trace the solution path after the player solved the puzzle
+        if let Some(start) = imp.completion_trace.get()
+            && let Some(frame_clock) = self.frame_clock()
+        {
+            let progress: f64 =
+                (frame_clock.frame_time() - start) as f64 / COMPLETION_TRACE_DURATION as f64;
+            let trace: Surface = draw
+                .path(&game.path, progress)
+                .expect("Cannot create a surface to draw the solution path");
+            let _ = ctx.set_source_surface(trace, 0.0, 0.0);
+            let _ = ctx.paint();
+        }
+
+        // Paint the arrows that show where the movement keys lead from the selected cell
+        if imp.show_moves.get()
+            && let Some(cell_id) = game.get_selected_cell()
+        {
+            let targets: Vec<usize> = [
+                Self::move_selection_right(&game, Some(cell_id)),
+                Self::move_selection_left(&game, Some(cell_id)),
+                Self::move_selection_up(&game, Some(cell_id)),
+                Self::move_selection_down(&game, Some(cell_id)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            let arrows = draw
+                .movement_arrows(cell_id, &targets)
+                .expect("Cannot create a surface to draw the movement arrows");
+            let _ = ctx.set_source_surface(arrows, 0.0, 0.0);
+            let _ = ctx.paint();
+        }
+
+        // Paint the cell bounding boxes
+        if debug_layers.contains(draw::DebugLayers::BOUNDING_BOXES) {
+            let boxes = draw
+                .cell_bounding_boxes()
+                .expect("Cannot create a surface to draw the cell bounding boxes");
+            let _ = ctx.set_source_surface(boxes, 0.0, 0.0);
+            let _ = ctx.paint();
+        }
+
         let _ = ctx.restore();
         self.grab_focus();
     }
@@ -353,6 +839,12 @@ impl HexkudoDrawingArea {
 
         // Update the puzzle colors when the player customized the colors in the Preferences dialog
         if let Some(settings) = imp.settings.get() {
+            if settings.boolean("seasonal-themes")
+                && let Some(theme) = seasonal::active_theme()
+            {
+                theme.apply(puzzle);
+            }
+
             let mut rgba: gdk::RGBA = get_rgba(settings, "color-cell-values");
             puzzle.colors.custom.set_text(
                 rgba.red() as f64,
@@ -438,13 +930,22 @@ impl HexkudoDrawingArea {
                 .set_custom_path(!settings.boolean("use-default-color-path"));
         }
 
+        // Stop the animations of the previous puzzle
+        imp.completion_trace.set(None);
+        imp.candidate_flash.replace(None);
+        imp.viewport.set(draw::Viewport::default());
+
         let mut draw: draw::Draw = draw::Draw::new(puzzle);
 
         puzzle.set_dark(imp.is_dark.get());
         draw.set_dark(imp.is_dark.get());
+        draw.set_number_words(self.number_words());
         draw.puzzle_frame().expect("Cannot draw the puzzle frame");
         imp.draw.replace(draw);
         imp.popover_number.set_puzzle(puzzle);
+        if let Some(sheet) = imp.number_sheet.get() {
+            sheet.set_puzzle(puzzle);
+        }
     }
 
     pub fn set_path_from_diamonds_and_map(
@@ -452,6 +953,8 @@ impl HexkudoDrawingArea {
         path: &path::Path,
         diamonds: &Vec<(usize, usize)>,
         map: &Vec<usize>,
+        anti_diamonds: &Vec<(usize, usize)>,
+        regions: &Vec<regions::Region>,
     ) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
         let draw = imp.draw.borrow();
@@ -460,28 +963,52 @@ impl HexkudoDrawingArea {
             return;
         }
 
-        draw.puzzle_maps_and_diamonds(path, map, diamonds)
+        draw.puzzle_maps_and_diamonds(path, map, diamonds, anti_diamonds, regions)
             .expect("Cannot draw the hints and the diamonds");
         imp.popover_number.set_path(path, map);
+        if let Some(sheet) = imp.number_sheet.get() {
+            sheet.set_path(path, map);
+        }
         self.queue_draw();
     }
 
-    pub fn set_path(&self, path: &path::Path, diamond_and_map: &diamond_and_map::DiamondAndMap) {
+    pub fn set_path(
+        &self,
+        path: &path::Path,
+        diamond_and_map: &diamond_and_map::DiamondAndMap,
+        regions: &Vec<regions::Region>,
+    ) {
         let (diamonds, map) = diamond_and_map.get_diamond_and_map();
 
-        self.set_path_from_diamonds_and_map(path, &diamonds, &map);
+        self.set_path_from_diamonds_and_map(
+            path,
+            &diamonds,
+            &map,
+            &diamond_and_map.get_anti_diamonds(),
+            regions,
+        );
     }
 
     pub fn print_current(&self) {
-        let imp: &imp::HexkudoDrawingArea = self.imp();
-        let game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow();
-        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        if let Some(job) = self.print_job(true) {
+            job.print();
+        }
+    }
 
-        let print_job: HexkudoPrintJob = HexkudoPrintJob::new(PrintJobParameters {
+    /// Create a print operation for the current puzzle. The solution is printed on a separate
+    /// page when `solutions` is true.
+    ///
+    /// Return None if the game is being updated.
+    fn print_job(&self, solutions: bool) -> Option<HexkudoPrintJob> {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let game = shared_game::try_read(
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        )?;
+        let window: Option<gtk::Window> = self.root().and_downcast::<gtk::Window>();
+
+        Some(HexkudoPrintJob::new(PrintJobParameters {
             window,
             puzzle: game.puzzle.clone(),
             paths: vec![game.path.clone()],
@@ -489,27 +1016,333 @@ impl HexkudoDrawingArea {
             diamonds: vec![game.diamonds.clone()],
             n_puzzles: 1,
             n_puzzles_per_page: 1,
-            solutions: true,
-        });
-        print_job.print();
+            solutions,
+        }))
+    }
+
+    /// Save the puzzle as a PNG image of `size` x `size` pixels. The image only shows the clues,
+    /// or all the values of the solution when `solution` is true.
+    pub fn save_png(
+        &self,
+        path: &std::path::Path,
+        size: i32,
+        solution: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let draw = imp.draw.borrow();
+        let game = shared_game::try_read(
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        )
+        .ok_or("The game data is in use")?;
+
+        let initial: PlayerInput = game.get_initial_input();
+        let cells: Vec<CellStatus> = if solution {
+            game.path
+                .get()
+                .iter()
+                .enumerate()
+                .map(|(i, cell_id)| CellStatus {
+                    cell_id: *cell_id,
+                    cell_value: i + 1,
+                    duplicated: false,
+                    error: false,
+                })
+                .collect()
+        } else {
+            game.get_cells_for(&initial)
+        };
+        let surface: ImageSurface = draw.render_frame(
+            cells,
+            &initial,
+            false,
+            imp.zoom_level
+                .get()
+                .resolve(draw.cell_size() * size as f64 / draw.surface_size()),
+            size,
+        )?;
+        let texture: gdk::MemoryTexture = draw::Draw::to_texture(surface)?;
+        texture.save_to_png(path)?;
+        Ok(())
+    }
+
+    /// Save the puzzle as a PDF document. The solution is on a separate page when `solution` is
+    /// true.
+    pub fn save_pdf(&self, path: &std::path::Path, solution: bool) -> Result<(), glib::Error> {
+        match self.print_job(solution) {
+            Some(job) => job.export_pdf(path),
+            None => Err(glib::Error::new(
+                glib::FileError::Failed,
+                "The game data is in use",
+            )),
+        }
+    }
+
+    /// Save the QR code of the game share code as a PNG image of `size` x `size` pixels.
+    ///
+    /// Players scan the code with their phone, or open the image in Hexkudo, to start the same
+    /// game.
+    pub fn save_qr_code(&self, path: &std::path::Path, size: i32) -> Result<(), Box<dyn Error>> {
+        let share_code: ShareCode = shared_game::try_read(
+            self.imp()
+                .game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        )
+        .ok_or("The game data is in use")?
+        .share_code()
+        .ok_or("The game has no share code")?;
+
+        let qr: QrCode = QrCode::encode_text(&share_code.to_string(), QrCodeEcc::Medium)?;
+        let surface: ImageSurface = draw::Draw::qr_code(&qr, size)?;
+        let texture: gdk::MemoryTexture = draw::Draw::to_texture(surface)?;
+        texture.save_to_png(path)?;
+        Ok(())
+    }
+
+    /// Render a snapshot of the board, with the path that the player drew, into a texture of
+    /// `size` x `size` pixels.
+    ///
+    /// The game is given as a parameter because the caller might already hold a borrow on it.
+    pub fn board_texture(&self, game: &Game, size: i32) -> Option<gdk::Texture> {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let draw = imp.draw.borrow();
+
+        let surface: ImageSurface = match draw.render_frame(
+            game.get_cells(),
+            &game.player_input,
+            true,
+            imp.zoom_level
+                .get()
+                .resolve(draw.cell_size() * size as f64 / draw.surface_size()),
+            size,
+        ) {
+            Ok(surface) => surface,
+            Err(error) => {
+                debug!("Cannot render the board snapshot: {error}");
+                return None;
+            }
+        };
+        match draw::Draw::to_texture(surface) {
+            Ok(texture) => Some(texture.upcast()),
+            Err(error) => {
+                debug!("Cannot convert the board snapshot: {error}");
+                None
+            }
+        }
+    }
+
+    /// Export the replay of the game as a sequence of PNG images in the given directory.
+    ///
+    /// Each image shows the puzzle after a player's move. The function also creates a
+    /// `replay.ffconcat` playlist that gives the duration of each image, so that tools such as
+    /// FFmpeg can assemble the images into a video or an animated GIF:
+    ///
+    /// ```text
+    /// $ ffmpeg -f concat -i replay.ffconcat replay.webm
+    /// ```
+    ///
+    /// The frames are rendered one at a time, and the PNG encoding runs in a separate thread, so
+    /// that the interface stays responsive during long replays.
+    ///
+    /// Return the number of images.
+    pub async fn export_replay(
+        &self,
+        directory: &std::path::Path,
+        size: i32,
+        frame_duration: f64,
+    ) -> Result<usize, Box<dyn Error>> {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let game_rc = imp
+            .game
+            .get()
+            .expect("Cannot retrieve the game data from the object");
+        let ((mut player_input, changes), path): ((PlayerInput, _), Vec<usize>) = {
+            let game = shared_game::try_read(game_rc).ok_or("The game data is in use")?;
+            (game.get_replay(), game.path.get().clone())
+        };
+        let mut playlist: String = String::from("ffconcat version 1.0\n");
+
+        for i in 0..=changes.len() {
+            if i > 0 {
+                match changes[i - 1] {
+                    (cell_id, Some(value)) => {
+                        player_input.remove_no_undo(cell_id);
+                        player_input.add_no_undo(cell_id, value);
+                    }
+                    (cell_id, None) => {
+                        player_input.remove_no_undo(cell_id);
+                    }
+                }
+            }
+
+            // The borrows are released before waiting for the PNG encoding
+            let texture: gdk::MemoryTexture = {
+                let draw = imp.draw.borrow();
+                let game = shared_game::try_read(game_rc).ok_or("The game data is in use")?;
+
+                // The player started another game during the export
+                if *game.path.get() != path {
+                    return Err("The game changed during the export".into());
+                }
+                let surface: ImageSurface = draw.render_frame(
+                    game.get_cells_for(&player_input),
+                    &player_input,
+                    imp.draw_path.get(),
+                    imp.zoom_level
+                        .get()
+                        .resolve(draw.cell_size() * size as f64 / draw.surface_size()),
+                    size,
+                )?;
+
+                // Convert the Cairo surface into a texture that can be saved as a PNG file
+                draw::Draw::to_texture(surface)?
+            };
+
+            let file_name: String = format!("frame-{i:04}.png");
+            let file_path: std::path::PathBuf = directory.join(&file_name);
+            gio::spawn_blocking(move || texture.save_to_png(file_path))
+                .await
+                .map_err(|_| "The PNG encoding failed")??;
+            playlist.push_str(&format!(
+                "file '{file_name}'\nduration {frame_duration:.3}\n"
+            ));
+        }
+
+        // The last image must be repeated for its duration to be taken into account
+        playlist.push_str(&format!("file 'frame-{:04}.png'\n", changes.len()));
+        std::fs::write(directory.join("replay.ffconcat"), playlist)?;
+        Ok(changes.len() + 1)
     }
 
     fn show_popover(&self, cell_id: usize, cell_x: usize, cell_y: usize) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
 
+        if imp.compact.get()
+            && let Some(sheet) = imp.number_sheet.get()
+        {
+            sheet.show(cell_id);
+            self.queue_draw();
+            return;
+        }
+
         // Compute the rectangle that the popover must point to
         let (s_x, s_y, w, h) =
             imp.draw
                 .borrow()
                 .inscribed_rectangle(imp.scaling_factor.get(), cell_x, cell_y);
+        let viewport: draw::Viewport = imp.viewport.get();
+        let (s_x, s_y) = viewport.to_widget(s_x, s_y);
+        let (w, h) = (w * viewport.magnification, h * viewport.magnification);
         let r: gdk::Rectangle = gdk::Rectangle::new(s_x as i32, s_y as i32, w as i32, h as i32);
 
         imp.popover_number.show(r, cell_id);
         self.queue_draw();
     }
 
+    /// Show the popover for the given cell if the player can select it. Otherwise, hide the
+    /// popover.
+    fn popover_for_cell(&self, cell_type: vertexes::CellType, cell_x: usize, cell_y: usize) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+
+        match cell_type {
+            vertexes::CellType::Vertex(v) => {
+                if shared_game::try_read(
+                    imp.game
+                        .get()
+                        .expect("Cannot retrieve the game data from the object"),
+                )
+                .is_some_and(|game| game.is_selectable(v))
+                {
+                    self.show_popover(v, cell_x, cell_y);
+                } else {
+                    self.hide_popover();
+                }
+            }
+            _ => self.hide_popover(),
+        }
+    }
+
     pub fn hide_popover(&self) {
-        self.imp().popover_number.hide();
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+
+        imp.popover_number.hide();
+        if let Some(sheet) = imp.number_sheet.get() {
+            sheet.hide();
+        }
+    }
+
+    /// Attach the number pad that replaces the popover on narrow screens.
+    pub fn set_number_sheet(&self, sheet: &HexkudoNumberSheet) {
+        self.imp()
+            .number_sheet
+            .set(sheet.clone())
+            .expect("Cannot store the number pad into the object");
+    }
+
+    /// Switch between the popover and the number pad, depending on the width of the window.
+    pub fn set_compact(&self, compact: bool) {
+        self.imp().compact.set(compact);
+        self.hide_popover();
+    }
+
+    /// Return the coordinates and the type of the cell at the given widget coordinates.
+    fn cell_at(&self, x_surface: f64, y_surface: f64) -> (usize, usize, vertexes::CellType) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let (x_board, y_board) = imp.viewport.get().to_board(x_surface, y_surface);
+        imp.draw
+            .borrow()
+            .surface_to_cell_coordinates(imp.scaling_factor.get(), x_board, y_board)
+    }
+
+    /// Whether the player zoomed in on the board.
+    pub fn is_magnified(&self) -> bool {
+        self.imp().viewport.get().is_magnified()
+    }
+
+    /// Display the whole board again.
+    pub fn reset_viewport(&self) {
+        self.set_viewport(draw::Viewport::default());
+    }
+
+    /// Change the magnification and the position of the board.
+    fn set_viewport(&self, viewport: draw::Viewport) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let was_magnified: bool = imp.viewport.get().is_magnified();
+
+        imp.viewport.set(viewport);
+        // The popover would point to the previous position of the cell
+        self.hide_popover();
+        if viewport.is_magnified() != was_magnified {
+            self.get_game_view().enable_zoom_actions();
+        }
+        self.queue_draw();
+    }
+
+    /// Move the magnified board so that the cell that the player selected with the keyboard
+    /// stays visible.
+    fn follow_selection(&self, game: &Game, cell_id: usize) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let mut viewport: draw::Viewport = imp.viewport.get();
+
+        if !viewport.is_magnified() {
+            return;
+        }
+        let Some((cell_x, cell_y)) = game.puzzle.matrix.vertexes.get_coordinates(cell_id) else {
+            return;
+        };
+        let rectangle: (f64, f64, f64, f64) =
+            imp.draw
+                .borrow()
+                .inscribed_rectangle(imp.scaling_factor.get(), cell_x, cell_y);
+        viewport.ensure_visible(
+            rectangle,
+            FOLLOW_SELECTION_MARGIN,
+            self.width() as f64,
+            self.height() as f64,
+        );
+        self.set_viewport(viewport);
     }
 
     fn get_game_view(&self) -> HexkudoGameView {
@@ -526,11 +1359,17 @@ impl HexkudoDrawingArea {
     // Callback for the GSettings changed event
     fn color_changed(&self, settings: &gio::Settings, key: &str) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let retry_settings: gio::Settings = settings.clone();
+        let retry_key: String = key.to_string();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            move |obj: &Self| obj.color_changed(&retry_settings, &retry_key),
+        ) else {
+            return;
+        };
 
         match key {
             "color-cell-values" => {
@@ -596,15 +1435,23 @@ impl HexkudoDrawingArea {
                     rgba.alpha() as f64,
                 );
             }
+            "number-words" => (),
             _ => return,
         }
 
         let mut draw = imp.draw.borrow_mut();
 
+        draw.set_number_words(settings.boolean("number-words"));
         draw.replace_puzzle(&game.puzzle);
         draw.puzzle_frame().expect("Cannot draw the puzzle frame");
-        draw.puzzle_maps_and_diamonds(&game.path, &game.map, &game.diamonds)
-            .expect("Cannot draw the hints and the diamonds");
+        draw.puzzle_maps_and_diamonds(
+            &game.path,
+            &game.map,
+            &game.diamonds,
+            &game.anti_diamonds,
+            &game.regions,
+        )
+        .expect("Cannot draw the hints and the diamonds");
         self.queue_draw();
     }
 
@@ -613,14 +1460,34 @@ impl HexkudoDrawingArea {
         self.queue_draw();
     }
 
+    #[template_callback]
+    fn select_hint_cells_cb(&self) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::select_hint_cells_cb,
+        ) else {
+            return;
+        };
+
+        game.select_map_cells = imp.select_hint_cells.get();
+    }
+
     #[template_callback]
     fn use_default_color_cell_values_cb(&self) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::use_default_color_cell_values_cb,
+        ) else {
+            return;
+        };
         let mut draw = imp.draw.borrow_mut();
 
         game.puzzle
@@ -634,11 +1501,15 @@ impl HexkudoDrawingArea {
     #[template_callback]
     fn use_default_color_cell_wrong_cb(&self) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::use_default_color_cell_wrong_cb,
+        ) else {
+            return;
+        };
         let mut draw = imp.draw.borrow_mut();
 
         game.puzzle
@@ -652,11 +1523,15 @@ impl HexkudoDrawingArea {
     #[template_callback]
     fn use_default_color_bg_cb(&self) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::use_default_color_bg_cb,
+        ) else {
+            return;
+        };
         let mut draw = imp.draw.borrow_mut();
 
         game.puzzle
@@ -665,19 +1540,29 @@ impl HexkudoDrawingArea {
             .set_custom_bg(!imp.use_default_color_bg.get());
         draw.replace_puzzle(&game.puzzle);
         draw.puzzle_frame().expect("Cannot draw the puzzle frame");
-        draw.puzzle_maps_and_diamonds(&game.path, &game.map, &game.diamonds)
-            .expect("Cannot draw the hints and the diamonds");
+        draw.puzzle_maps_and_diamonds(
+            &game.path,
+            &game.map,
+            &game.diamonds,
+            &game.anti_diamonds,
+            &game.regions,
+        )
+        .expect("Cannot draw the hints and the diamonds");
         self.queue_draw();
     }
 
     #[template_callback]
     fn use_default_color_hint_bg_cb(&self) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::use_default_color_hint_bg_cb,
+        ) else {
+            return;
+        };
         let mut draw = imp.draw.borrow_mut();
 
         game.puzzle
@@ -685,19 +1570,29 @@ impl HexkudoDrawingArea {
             .custom
             .set_custom_bg_map(!imp.use_default_color_hint_bg.get());
         draw.replace_puzzle(&game.puzzle);
-        draw.puzzle_maps_and_diamonds(&game.path, &game.map, &game.diamonds)
-            .expect("Cannot draw the hints and the diamonds");
+        draw.puzzle_maps_and_diamonds(
+            &game.path,
+            &game.map,
+            &game.diamonds,
+            &game.anti_diamonds,
+            &game.regions,
+        )
+        .expect("Cannot draw the hints and the diamonds");
         self.queue_draw();
     }
 
     #[template_callback]
     fn use_default_sel_color_bg_cb(&self) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::use_default_sel_color_bg_cb,
+        ) else {
+            return;
+        };
         let mut draw = imp.draw.borrow_mut();
 
         game.puzzle
@@ -711,11 +1606,15 @@ impl HexkudoDrawingArea {
     #[template_callback]
     fn use_default_color_borders_cb(&self) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::use_default_color_borders_cb,
+        ) else {
+            return;
+        };
         let mut draw = imp.draw.borrow_mut();
 
         game.puzzle
@@ -724,19 +1623,29 @@ impl HexkudoDrawingArea {
             .set_custom_border(!imp.use_default_color_borders.get());
         draw.replace_puzzle(&game.puzzle);
         draw.puzzle_frame().expect("Cannot draw the puzzle frame");
-        draw.puzzle_maps_and_diamonds(&game.path, &game.map, &game.diamonds)
-            .expect("Cannot draw the hints and the diamonds");
+        draw.puzzle_maps_and_diamonds(
+            &game.path,
+            &game.map,
+            &game.diamonds,
+            &game.anti_diamonds,
+            &game.regions,
+        )
+        .expect("Cannot draw the hints and the diamonds");
         self.queue_draw();
     }
 
     #[template_callback]
     fn use_default_color_path_cb(&self) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::use_default_color_path_cb,
+        ) else {
+            return;
+        };
         let mut draw = imp.draw.borrow_mut();
 
         game.puzzle
@@ -756,11 +1665,25 @@ impl HexkudoDrawingArea {
     #[template_callback]
     fn drag_begin_cb(&self, x_surface: f64, y_surface: f64, gesture: &gtk::GestureDrag) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
-        let draw = imp.draw.borrow();
-        let (_x_, _y, cell_type) =
-            draw.surface_to_cell_coordinates(imp.scaling_factor.get(), x_surface, y_surface);
+        let (x, y, cell_type) = self.cell_at(x_surface, y_surface);
         let button: u32 = gesture.current_button();
 
+        // When the board is magnified, the middle mouse button, or a drag that starts outside
+        // the cells, moves the board
+        let viewport: draw::Viewport = imp.viewport.get();
+        if viewport.is_magnified()
+            && (button == 2 || (button == 1 && !matches!(cell_type, vertexes::CellType::Vertex(_))))
+        {
+            imp.drag.replace(Drag {
+                start_x: x_surface,
+                start_y: y_surface,
+                cells: vec![cell_type],
+                pan_from: Some(viewport),
+            });
+            self.hide_popover();
+            return;
+        }
+
         // Expect the left or right mouse button for drag motions
         if button != 1 && button != 3 {
             return;
@@ -770,8 +1693,40 @@ impl HexkudoDrawingArea {
             start_x: x_surface,
             start_y: y_surface,
             cells: vec![cell_type],
+            pan_from: None,
         });
-        self.hide_popover();
+        if button == 1 && self.popover_trigger() == PopoverTrigger::Press {
+            self.popover_for_cell(cell_type, x, y);
+        } else {
+            self.hide_popover();
+        }
+    }
+
+    // Callback for the click event, which detects the double-clicks
+    #[template_callback]
+    fn click_pressed_cb(
+        &self,
+        n_press: i32,
+        x_surface: f64,
+        y_surface: f64,
+        gesture: &gtk::GestureClick,
+    ) {
+        if n_press != 2
+            || gesture.current_button() != 1
+            || self.popover_trigger() != PopoverTrigger::DoubleClick
+        {
+            return;
+        }
+
+        let (x, y, cell_type) = self.cell_at(x_surface, y_surface);
+        self.popover_for_cell(cell_type, x, y);
+    }
+
+    // Callback for the long press on touchscreens, which opens the number picker
+    #[template_callback]
+    fn long_press_cb(&self, x_surface: f64, y_surface: f64, _gesture: &gtk::GestureLongPress) {
+        let (x, y, cell_type) = self.cell_at(x_surface, y_surface);
+        self.popover_for_cell(cell_type, x, y);
     }
 
     // Callback for drag update event
@@ -784,14 +1739,31 @@ impl HexkudoDrawingArea {
     ) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
         let mut drag = imp.drag.borrow_mut();
-        let draw = imp.draw.borrow();
-        let mut game = imp
+
+        // Move the magnified board
+        if let Some(mut viewport) = drag.pan_from {
+            viewport.pan(
+                offset_x_surface,
+                offset_y_surface,
+                self.width() as f64,
+                self.height() as f64,
+            );
+            drop(drag);
+            self.set_viewport(viewport);
+            return;
+        }
+
+        let Some(mut game) = imp
             .game
             .get()
             .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
-        let (_x, _y, current_cell) = draw.surface_to_cell_coordinates(
-            imp.scaling_factor.get(),
+            .try_borrow_mut()
+            .ok()
+        else {
+            debug!("Game data in use: ignoring the drag motion");
+            return;
+        };
+        let (_x, _y, current_cell) = self.cell_at(
             drag.start_x + offset_x_surface,
             drag.start_y + offset_y_surface,
         );
@@ -843,6 +1815,8 @@ impl HexkudoDrawingArea {
                     let next_value: usize = if button == 1 { value + 1 } else { value - 1 };
                     drag.cells.push(current_cell);
                     drop(drag);
+                    // The popover that the button press opened is not needed for drag motions
+                    self.hide_popover();
                     // Set the value only if the current cell is not a map (hint) cell
                     // and value is not the ending value.
                     if !game.map.contains(&current_cid)
@@ -868,41 +1842,227 @@ impl HexkudoDrawingArea {
     ) {
         let imp: &imp::HexkudoDrawingArea = self.imp();
         let drag = imp.drag.borrow();
-        let draw = imp.draw.borrow();
-        let (x, y, cell_type) = draw.surface_to_cell_coordinates(
-            imp.scaling_factor.get(),
+        let (x, y, cell_type) = self.cell_at(
             drag.start_x + offset_x_surface,
             drag.start_y + offset_y_surface,
         );
         let button: u32 = gesture.current_button();
 
-        // Expect the left mouse button for mouse release events
-        if button != 1 {
+        // Expect the left mouse button for mouse release events, and ignore the end of the
+        // board moves
+        if button != 1 || drag.pan_from.is_some() {
             return;
         }
 
-        // The use released the button in the same cell as the starting cell. Show the popover.
-        if drag.cells[0] == cell_type {
-            match cell_type {
-                vertexes::CellType::Vertex(v) => {
-                    if imp
+        // The use released the button in the same cell as the starting cell
+        if drag.cells[0] != cell_type {
+            return;
+        }
+        match self.popover_trigger() {
+            // The popover is already displayed
+            PopoverTrigger::Press => (),
+            // The number picker selects the cell on the first click
+            PopoverTrigger::Release | PopoverTrigger::SecondClick => {
+                self.popover_for_cell(cell_type, x, y)
+            }
+            // A single click only selects the cell
+            PopoverTrigger::DoubleClick => {
+                if let vertexes::CellType::Vertex(v) = cell_type
+                    && let Ok(mut game) = imp
                         .game
                         .get()
                         .expect("Cannot retrieve the game data from the object")
-                        .borrow()
-                        .map
-                        .contains(&v)
-                    {
-                        self.hide_popover();
-                    } else {
-                        self.show_popover(v, x, y);
-                    }
+                        .try_borrow_mut()
+                    && game.is_selectable(v)
+                {
+                    game.set_selected_cell(Some(v));
+                    self.queue_draw();
                 }
-                _ => self.hide_popover(),
             }
         }
     }
 
+    // Callback for the start of the two-finger gestures. Record the time and the position of
+    // the fingers for detecting the two-finger taps.
+    #[template_callback]
+    fn rotate_begin_cb(&self, #[rest] _values: &[glib::Value]) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        imp.two_finger_start.set(
+            imp.rotate_gesture
+                .bounding_box_center()
+                .map(|(x, y)| (glib::monotonic_time(), x, y)),
+        );
+    }
+
+    // Callback for the two-finger rotate gesture
+    #[template_callback]
+    fn rotate_cb(&self, angle: f64, _angle_delta: f64, _gesture: &gtk::GestureRotate) {
+        if !self.board_spin() {
+            return;
+        }
+        self.imp().rotation.set(angle);
+        self.queue_draw();
+    }
+
+    // Callback for the end of the rotate gesture. The board spins back to its position.
+    #[template_callback]
+    fn rotate_end_cb(&self, #[rest] _values: &[glib::Value]) {
+        let rotation: f64 = self.imp().rotation.get();
+
+        // A short two-finger gesture that does not spin the board is a tap
+        if let Some((start, x_surface, y_surface)) = self.imp().two_finger_start.take()
+            && glib::monotonic_time() - start < TWO_FINGER_TAP_DURATION
+            && rotation.abs() < TWO_FINGER_TAP_ANGLE
+        {
+            self.two_finger_tap(x_surface, y_surface);
+        }
+
+        if rotation == 0.0 {
+            return;
+        }
+
+        // No animation with the energy saver
+        if let Some(settings) = self.imp().settings.get()
+            && energy_saver::is_active(settings)
+        {
+            self.imp().rotation.set(0.0);
+            self.queue_draw();
+            return;
+        }
+
+        // Spin back by the shortest way
+        let from: f64 = rotation.sin().atan2(rotation.cos());
+        let target = adw::CallbackAnimationTarget::new(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |angle| {
+                mself.imp().rotation.set(angle);
+                mself.queue_draw();
+            }
+        ));
+        adw::TimedAnimation::builder()
+            .widget(self)
+            .value_from(from)
+            .value_to(0.0)
+            .duration(SPIN_BACK_DURATION)
+            .easing(adw::Easing::EaseOutBack)
+            .target(&target)
+            .build()
+            .play();
+    }
+
+    // Callback for the start of the pinch gesture
+    #[template_callback]
+    fn zoom_begin_cb(&self, #[rest] _values: &[glib::Value]) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        imp.pinch_start.set(
+            imp.zoom_gesture
+                .bounding_box_center()
+                .map(|(x, y)| (imp.viewport.get(), x, y)),
+        );
+    }
+
+    // Callback for the pinch gesture. The board is magnified around the center of the fingers,
+    // and follows the fingers when they move.
+    #[template_callback]
+    fn zoom_scale_cb(&self, scale: f64, _gesture: &gtk::GestureZoom) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let Some((x, y)) = imp.zoom_gesture.bounding_box_center() else {
+            return;
+        };
+
+        // The fingers moved: the gesture is not a two-finger tap
+        if let Some((_start, start_x, start_y)) = imp.two_finger_start.get()
+            && ((scale - 1.0).abs() > TWO_FINGER_TAP_SCALE
+                || (x - start_x).hypot(y - start_y) > TWO_FINGER_TAP_DISTANCE)
+        {
+            imp.two_finger_start.set(None);
+        }
+
+        let Some((mut viewport, start_x, start_y)) = imp.pinch_start.get() else {
+            return;
+        };
+        let width: f64 = self.width() as f64;
+        let height: f64 = self.height() as f64;
+        viewport.zoom_at(scale, start_x, start_y, width, height);
+        viewport.pan(x - start_x, y - start_y, width, height);
+        self.set_viewport(viewport);
+    }
+
+    // Callback for the end of the pinch gesture
+    #[template_callback]
+    fn zoom_end_cb(&self, #[rest] _values: &[glib::Value]) {
+        self.imp().pinch_start.set(None);
+    }
+
+    // Callback for the mouse wheel. With the Ctrl key, the wheel magnifies the board around the
+    // mouse pointer. Without the Ctrl key, the wheel moves the magnified board.
+    #[template_callback]
+    fn scroll_cb(
+        &self,
+        dx: f64,
+        dy: f64,
+        controller: &gtk::EventControllerScroll,
+    ) -> glib::Propagation {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let width: f64 = self.width() as f64;
+        let height: f64 = self.height() as f64;
+        let mut viewport: draw::Viewport = imp.viewport.get();
+
+        if controller
+            .current_event_state()
+            .contains(gdk::ModifierType::CONTROL_MASK)
+        {
+            let (x, y) = imp.pointer.get().unwrap_or((width / 2.0, height / 2.0));
+            viewport.zoom_at(SCROLL_ZOOM_STEP.powf(-dy), x, y, width, height);
+        } else if viewport.is_magnified() {
+            viewport.pan(-dx * SCROLL_PAN_STEP, -dy * SCROLL_PAN_STEP, width, height);
+        } else {
+            return glib::Propagation::Proceed;
+        }
+        self.set_viewport(viewport);
+        glib::Propagation::Stop
+    }
+
+    // Callback for the pointer motions. Record the position of the pointer for zooming around it.
+    #[template_callback]
+    fn motion_cb(&self, x: f64, y: f64, _controller: &gtk::EventControllerMotion) {
+        self.imp().pointer.set(Some((x, y)));
+    }
+
+    #[template_callback]
+    fn motion_leave_cb(&self, _controller: &gtk::EventControllerMotion) {
+        self.imp().pointer.set(None);
+    }
+
+    /// Remove the value of the cell under the two-finger tap.
+    fn two_finger_tap(&self, x_surface: f64, y_surface: f64) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let (_x, _y, cell_type) = self.cell_at(x_surface, y_surface);
+        let vertexes::CellType::Vertex(v) = cell_type else {
+            return;
+        };
+        let Ok(mut game) = imp
+            .game
+            .get()
+            .expect("Cannot retrieve the game data from the object")
+            .try_borrow_mut()
+        else {
+            debug!("Game data in use: ignoring the two-finger tap");
+            return;
+        };
+        if !game.started || game.player_input.get_value_from_id(v).is_none() {
+            return;
+        }
+
+        self.hide_popover();
+        self.get_game_view().remove_cell_value(game.deref_mut(), v);
+        if game.get_selected_cell() == Some(v) {
+            game.set_selected_cell_value_updated(false);
+        }
+        self.queue_draw();
+    }
+
     fn move_selection_right(game: &Game, cell_id: Option<usize>) -> Option<usize> {
         let mut cell: usize;
         match cell_id {
@@ -914,7 +2074,7 @@ impl HexkudoDrawingArea {
         if let Some(cell_type) = adjacent.e
             && let vertexes::CellType::Vertex(v) = cell_type
         {
-            if !game.map.contains(&v) {
+            if game.is_selectable(v) {
                 return Some(v);
             }
             return Self::move_selection_right(game, Some(v));
@@ -923,7 +2083,7 @@ impl HexkudoDrawingArea {
         if cell >= game.puzzle.matrix.vertexes.num_vertexes {
             cell = 0;
         }
-        if !game.map.contains(&cell) {
+        if game.is_selectable(cell) {
             return Some(cell);
         }
 
@@ -941,7 +2101,7 @@ impl HexkudoDrawingArea {
         if let Some(cell_type) = adjacent.w
             && let vertexes::CellType::Vertex(v) = cell_type
         {
-            if !game.map.contains(&v) {
+            if game.is_selectable(v) {
                 return Some(v);
             }
 
@@ -951,7 +2111,7 @@ impl HexkudoDrawingArea {
             cell = game.puzzle.matrix.vertexes.num_vertexes;
         }
         cell -= 1;
-        if !game.map.contains(&cell) {
+        if game.is_selectable(cell) {
             return Some(cell);
         }
 
@@ -974,9 +2134,9 @@ impl HexkudoDrawingArea {
         };
 
         // Move the selection to the upper-left or the upper-right cell if it is a vertex (not a
-        // background or a logo), and if it is not a hint (map)
+        // background or a logo), and if it is not a hint (map) that the player cannot select
         if let Some(v) = new_vertex {
-            if !game.map.contains(&v) {
+            if game.is_selectable(v) {
                 return Some(v);
             }
             // If the selection cannot be moved to the previous row, then skip that row by
@@ -993,14 +2153,14 @@ impl HexkudoDrawingArea {
                 for idx in 0..6 {
                     if let vertexes::CellType::Vertex(v) =
                         game.puzzle.matrix.vertexes.get_cell(x + idx, y)
-                        && !game.map.contains(&v)
+                        && game.is_selectable(v)
                     {
                         return Some(v);
                     }
                     if x >= idx
                         && let vertexes::CellType::Vertex(v) =
                             game.puzzle.matrix.vertexes.get_cell(x - idx, y)
-                        && !game.map.contains(&v)
+                        && game.is_selectable(v)
                     {
                         return Some(v);
                     }
@@ -1029,7 +2189,7 @@ impl HexkudoDrawingArea {
         // Move the selection to the bottom-right or the bottom-left cell if it is a vertex (not a
         // background or a logo), and if it is not a hint (map)
         if let Some(v) = new_vertex {
-            if !game.map.contains(&v) {
+            if game.is_selectable(v) {
                 return Some(v);
             }
             // If the selection cannot be moved to the next row, then skip that row by
@@ -1043,14 +2203,14 @@ impl HexkudoDrawingArea {
                 for idx in 0..6 {
                     if let vertexes::CellType::Vertex(v) =
                         game.puzzle.matrix.vertexes.get_cell(x + idx, y)
-                        && !game.map.contains(&v)
+                        && game.is_selectable(v)
                     {
                         return Some(v);
                     }
                     if x >= idx
                         && let vertexes::CellType::Vertex(v) =
                             game.puzzle.matrix.vertexes.get_cell(x - idx, y)
-                        && !game.map.contains(&v)
+                        && game.is_selectable(v)
                     {
                         return Some(v);
                     }
@@ -1066,6 +2226,10 @@ impl HexkudoDrawingArea {
             Some(cid) => cid,
             None => return,
         };
+        if self.notes_mode() {
+            self.note_key(game, selected_cell_id, number);
+            return;
+        }
         let mut new_value: usize = number;
 
         if game.is_selected_cell_value_updated()
@@ -1085,6 +2249,32 @@ impl HexkudoDrawingArea {
         self.queue_draw();
     }
 
+    /// Toggle a note in the selected cell.
+    ///
+    /// When the player just toggled a note in the same cell, the digit extends that note, which
+    /// is replaced by the new number. For example, typing 1 and then 2 toggles the note 12.
+    fn note_key(&self, game: &mut Game, cell_id: usize, number: usize) {
+        let imp: &imp::HexkudoDrawingArea = self.imp();
+        let mut new_note: usize = number;
+
+        if game.is_selected_cell_value_updated()
+            && let Some((pending_cell_id, pending_note)) = imp.pending_note.get()
+            && pending_cell_id == cell_id
+            && pending_note * 10 + number <= game.puzzle.matrix.vertexes.num_vertexes
+        {
+            // Cancel the previous toggle
+            game.toggle_note(cell_id, pending_note);
+            new_note = pending_note * 10 + number;
+        }
+        if game.toggle_note(cell_id, new_note) {
+            imp.pending_note.set(Some((cell_id, new_note)));
+            game.set_selected_cell_value_updated(true);
+            self.queue_draw();
+        } else {
+            imp.pending_note.set(None);
+        }
+    }
+
     fn backspace_key(&self, game: &mut Game) {
         let selected_cell_id: usize = match game.get_selected_cell() {
             Some(cid) => cid,
@@ -1114,11 +2304,16 @@ impl HexkudoDrawingArea {
         _controller: &gtk::EventControllerKey,
     ) -> glib::Propagation {
         let imp: &imp::HexkudoDrawingArea = self.imp();
-        let mut game = imp
+        let Some(mut game) = imp
             .game
             .get()
             .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+            .try_borrow_mut()
+            .ok()
+        else {
+            debug!("Game data in use: ignoring the key press");
+            return glib::Propagation::Stop;
+        };
 
         // Ctrl shortcuts are managed by the game-view widget
         if !game.started || modifier == gdk::ModifierType::CONTROL_MASK {
@@ -1132,6 +2327,10 @@ impl HexkudoDrawingArea {
             debug!("      keycode = {keycode}");
         }
 
+        let settings: &gio::Settings = imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object");
         match keyval {
             gdk::Key::Return | gdk::Key::space => {
                 let selected_cell_id: usize = match game.get_selected_cell() {
@@ -1170,6 +2369,7 @@ impl HexkudoDrawingArea {
                 if modifier == gdk::ModifierType::SHIFT_MASK {
                     if let Some(cid) = Self::move_selection_left(&game, game.get_selected_cell()) {
                         game.set_selected_cell(Some(cid));
+                        self.follow_selection(&game, cid);
                         self.hide_popover();
                         self.queue_draw();
                         return glib::Propagation::Stop;
@@ -1178,30 +2378,34 @@ impl HexkudoDrawingArea {
                     Self::move_selection_right(&game, game.get_selected_cell())
                 {
                     game.set_selected_cell(Some(cid));
+                    self.follow_selection(&game, cid);
                     self.hide_popover();
                     self.queue_draw();
                     return glib::Propagation::Stop;
                 }
             }
-            gdk::Key::Right | gdk::Key::KP_Right | gdk::Key::d => {
+            _ if keybindings::matches(settings, "move-right", keyval, modifier) => {
                 if let Some(cid) = Self::move_selection_right(&game, game.get_selected_cell()) {
                     game.set_selected_cell(Some(cid));
+                    self.follow_selection(&game, cid);
                     self.hide_popover();
                     self.queue_draw();
                     return glib::Propagation::Stop;
                 }
             }
-            gdk::Key::Left | gdk::Key::KP_Left | gdk::Key::a => {
+            _ if keybindings::matches(settings, "move-left", keyval, modifier) => {
                 if let Some(cid) = Self::move_selection_left(&game, game.get_selected_cell()) {
                     game.set_selected_cell(Some(cid));
+                    self.follow_selection(&game, cid);
                     self.hide_popover();
                     self.queue_draw();
                     return glib::Propagation::Stop;
                 }
             }
-            gdk::Key::Up | gdk::Key::KP_Up | gdk::Key::w => {
+            _ if keybindings::matches(settings, "move-up", keyval, modifier) => {
                 if let Some(cid) = Self::move_selection_up(&game, game.get_selected_cell()) {
                     game.set_selected_cell(Some(cid));
+                    self.follow_selection(&game, cid);
                     self.hide_popover();
                     self.queue_draw();
                     return glib::Propagation::Stop;
@@ -1210,24 +2414,40 @@ impl HexkudoDrawingArea {
                 // title bar actions
                 return glib::Propagation::Stop;
             }
-            gdk::Key::Down | gdk::Key::KP_Down | gdk::Key::s => {
+            _ if keybindings::matches(settings, "move-down", keyval, modifier) => {
                 if let Some(cid) = Self::move_selection_down(&game, game.get_selected_cell()) {
                     game.set_selected_cell(Some(cid));
+                    self.follow_selection(&game, cid);
                     self.hide_popover();
                     self.queue_draw();
                     return glib::Propagation::Stop;
                 }
             }
             gdk::Key::Delete | gdk::Key::KP_Delete => {
-                if let Some(cid) = game.get_selected_cell() {
+                if let Some(cid) = game.get_selected_cell()
+                    && self.notes_mode()
+                {
+                    game.player_input.clear_notes(cid);
+                    imp.pending_note.set(None);
+                    self.queue_draw();
+                } else if let Some(cid) = game.get_selected_cell() {
                     self.get_game_view()
                         .remove_cell_value(game.deref_mut(), cid);
                     game.set_selected_cell_value_updated(false);
                     self.queue_draw();
                 }
             }
+            gdk::Key::F2 => {
+                imp.show_moves.set(!imp.show_moves.get());
+                self.queue_draw();
+                return glib::Propagation::Stop;
+            }
             gdk::Key::Escape => {
                 self.hide_popover();
+                if imp.show_moves.get() {
+                    imp.show_moves.set(false);
+                    self.queue_draw();
+                }
             }
             _ => (),
         }
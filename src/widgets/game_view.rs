@@ -28,10 +28,10 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::ops::DerefMut;
 use std::rc::Rc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use formatx::formatx;
-use gettextrs::gettext;
+use gettextrs::{gettext, ngettext};
 use log::debug;
 
 use adw::prelude::*;
@@ -43,17 +43,78 @@ use gtk::{gdk, gio, glib, pango};
 use super::drawing_area::HexkudoDrawingArea;
 use super::layout_manager::HexkudoLayoutManager;
 use super::menu_button::HexkudoMenuButton;
+use super::number_pad::HexkudoNumberPad;
+use super::number_sheet::HexkudoNumberSheet;
+use super::remaining_numbers::HexkudoRemainingNumbers;
+use crate::difficulty_defaults::DifficultyDefaults;
 use crate::draw;
-use crate::game::Game;
+use crate::energy_saver;
+use crate::game::hint::{self, Hint};
+use crate::game::{Game, SPLIT_PERCENTS};
+use crate::generation_cache::{self, CacheKey, GenerationCache};
 use crate::generator::diamond_and_map;
-use crate::generator::diamonds;
-use crate::generator::path;
 use crate::generator::puzzles::{self, Difficulty};
 use crate::generator::random_path;
-use crate::highscores::HighScores;
+use crate::generator::regions;
+use crate::generator::seed_code::{SeedCode, SeedOptions};
+use crate::generator::share_code::ShareCode;
+use crate::highscores::{HighScores, Score};
+use crate::keybindings;
+use crate::network::{self, GameResult};
+use crate::player_profiles;
+use crate::proof::Proof;
+use crate::qr_decode;
+use crate::recommendation;
+use crate::sample_pool::SamplePool;
+use crate::saver::game::{SaverGame, TooManySlots};
 use crate::saver::highscores::SaverHighScores;
+use crate::saver::sample_pool::SaverSamplePool;
+use crate::saver::statistics::SaverStatistics;
+use crate::shared_game;
+use crate::solvability::{Constraints, Solvability};
+use crate::statistics::Statistics;
+use crate::summary;
 use crate::widgets::done_dialog::HexkudoDoneDialog;
+use crate::widgets::history_dialog::HexkudoHistoryDialog;
+use crate::widgets::image_dialog::{HexkudoImageDialog, ImageFormat};
+use crate::widgets::replay_dialog::HexkudoReplayDialog;
 use crate::widgets::scores_dialog::HexkudoScoresDialog;
+use crate::widgets::stats_dialog::HexkudoStatsDialog;
+
+/// Number of messages that the notification history keeps.
+const NOTIFICATION_HISTORY_LEN: usize = 20;
+
+/// The streak widget is displayed from this number of correct values placed in a row.
+const STREAK_MIN_DISPLAYED: usize = 3;
+
+/// The streak widget pulses every time the streak reaches a multiple of this number.
+const STREAK_MILESTONE: usize = 10;
+
+/// Duration, in milliseconds, of the pulse animation of the streak widget (see `style.css`).
+const STREAK_PULSE_MS: u64 = 600;
+
+/// Size, in pixels, of the snapshot of the solved board that the done dialog displays.
+const DONE_THUMBNAIL_SIZE: i32 = 240;
+
+/// Maximum number of remaining cells for offering the guided reveal.
+const GUIDED_REVEAL_CELLS: usize = 8;
+
+/// Delay after which the solvability check gives up.
+const SOLVABILITY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Delay during which the solver looks for the most informative cell to reveal.
+const REVEAL_HINT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Interval, in seconds, between two automatic saves of the game in progress.
+const AUTOSAVE_INTERVAL: u32 = 30;
+
+/// Number of rounds of the timer, which runs every 100 ms, between two refreshes of the widgets
+/// when the energy saver is active.
+const ENERGY_SAVER_TIMER_ROUNDS: u32 = 10;
+
+/// Number of [`AUTOSAVE_INTERVAL`] rounds between two automatic saves when the energy saver is
+/// active.
+const ENERGY_SAVER_AUTOSAVE_ROUNDS: u32 = 4;
 
 mod imp {
     use super::*;
@@ -64,14 +125,98 @@ mod imp {
     #[template(resource = "/io/github/herve4m/Hexkudo/ui/game_view.ui")]
     pub struct HexkudoGameView {
         pub style_css_provider: OnceCell<gtk::CssProvider>,
+        pub settings: OnceCell<gio::Settings>,
         pub game: OnceCell<Rc<RefCell<Game>>>,
         pub puzzle_list: OnceCell<HashMap<(String, Difficulty), puzzles::Puzzle>>,
 
+        /// Number of split times that the split widget displays.
+        pub displayed_splits: Cell<usize>,
+        pub ghost_timeline: RefCell<Vec<Duration>>,
+
+        /// Streak that the streak widget displays.
+        pub displayed_streak: Cell<usize>,
+
+        /// Number of remaining cells that was last announced to the screen readers.
+        pub announced_remaining: Cell<usize>,
+
+        /// Number of completed cells and game duration when the player last made progress. The
+        /// guided reveal uses these values to detect that the player is stuck.
+        pub last_progress: Cell<(usize, Duration)>,
+        /// Whether the guided reveal was offered since the player last made progress.
+        pub guided_reveal_offered: Cell<bool>,
+
+        /// Next game for the same puzzle, generated in the background.
+        pub generation_cache: RefCell<GenerationCache>,
+
+        /// Monitor of the system power saver mode, for the energy saver.
+        pub power_monitor: OnceCell<gio::PowerProfileMonitor>,
+        /// Whether the energy saver is active. See [`crate::energy_saver`].
+        pub energy_saver: Cell<bool>,
+        /// Number of rounds of the timer. With the energy saver, the widgets are only refreshed
+        /// every [`ENERGY_SAVER_TIMER_ROUNDS`] rounds.
+        pub timer_rounds: Cell<u32>,
+        /// Number of rounds of the automatic save. With the energy saver, the game is only saved
+        /// every [`ENERGY_SAVER_AUTOSAVE_ROUNDS`] rounds.
+        pub autosave_rounds: Cell<u32>,
+
+        /// Channel through which the background tasks report the solvability of the board.
+        pub solvability_sender: OnceCell<async_channel::Sender<(u64, Solvability)>>,
+        /// Serial number of the last requested solvability check. The results of the older
+        /// checks are ignored.
+        pub solvability_serial: Cell<u64>,
+        /// Whether a solvability check is running.
+        pub solvability_running: Cell<bool>,
+        /// Whether the board changed while a solvability check was running.
+        pub solvability_pending: Cell<bool>,
+
+        /// Cookie returned by `gtk::Application::inhibit()` while the screen is kept on.
+        pub inhibit_cookie: Cell<Option<u32>>,
+
+        /// Whether the window is narrow. The number pad panel is hidden in that case.
+        pub compact: Cell<bool>,
+
         // Properties
         #[property(get, set, builder(draw::ZoomLevel::Medium))]
         pub zoom_level: Cell<draw::ZoomLevel>,
+        pub zoom_key: Cell<&'static str>,
         #[property(get, set)]
         pub show_puzzle_bg: Cell<bool>,
+        #[property(get, set)]
+        pub inhibit_idle: Cell<bool>,
+        #[property(get, set)]
+        pub show_timer_tenths: Cell<bool>,
+        #[property(get, set)]
+        pub freeze_timer: Cell<bool>,
+        #[property(get, set)]
+        pub show_splits: Cell<bool>,
+        #[property(get, set)]
+        pub show_ghost: Cell<bool>,
+        #[property(get, set)]
+        pub show_streak: Cell<bool>,
+        #[property(get, set)]
+        pub warn_unsolvable: Cell<bool>,
+        #[property(get, set)]
+        pub undo_depth: Cell<u32>,
+        #[property(get, set)]
+        pub show_next_number: Cell<bool>,
+        #[property(get, set)]
+        pub show_last_number: Cell<bool>,
+        #[property(get, set)]
+        pub show_number_pad: Cell<bool>,
+        #[property(get, set)]
+        pub show_remaining_numbers: Cell<bool>,
+        #[property(get, set)]
+        pub auto_complete_chains: Cell<bool>,
+        #[property(get, set)]
+        pub automation_actions: Cell<bool>,
+        #[property(get, set)]
+        pub practice_mode: Cell<bool>,
+        #[property(get, set)]
+        pub mystery_mode: Cell<bool>,
+        #[property(get, set)]
+        pub sum_regions: Cell<bool>,
+        #[property(get, set)]
+        pub unlimited_assist: Cell<bool>,
 
         // Template widgets
         #[template_child]
@@ -79,6 +224,10 @@ mod imp {
         #[template_child]
         pub menu_button: TemplateChild<HexkudoMenuButton>,
         #[template_child]
+        pub bottom_sheet: TemplateChild<adw::BottomSheet>,
+        #[template_child]
+        pub number_sheet: TemplateChild<HexkudoNumberSheet>,
+        #[template_child]
         pub draw_bin: TemplateChild<adw::Bin>,
         #[template_child]
         pub toast_overlay: TemplateChild<adw::ToastOverlay>,
@@ -87,12 +236,18 @@ mod imp {
         #[template_child]
         pub drawing_area: TemplateChild<HexkudoDrawingArea>,
         #[template_child]
+        pub number_pad: TemplateChild<HexkudoNumberPad>,
+        #[template_child]
         pub spinner: TemplateChild<adw::Spinner>,
         #[template_child]
         pub undo_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub redo_button: TemplateChild<gtk::Button>,
         #[template_child]
+        pub notifications_button: TemplateChild<gtk::MenuButton>,
+        #[template_child]
+        pub notifications_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
         pub play_pause_stack: TemplateChild<gtk::Stack>,
         #[template_child]
         pub pause_button: TemplateChild<gtk::Button>,
@@ -103,15 +258,37 @@ mod imp {
         #[template_child]
         pub error_label: TemplateChild<gtk::Label>,
         #[template_child]
+        pub points_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub points_label: TemplateChild<gtk::Label>,
+        #[template_child]
         pub clock_box: TemplateChild<gtk::Box>,
         #[template_child]
         pub clock_label: TemplateChild<gtk::Label>,
         #[template_child]
+        pub split_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub ghost_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub energy_saver_image: TemplateChild<gtk::Image>,
+        #[template_child]
+        pub streak_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub next_number_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub last_number_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub remaining_numbers: TemplateChild<HexkudoRemainingNumbers>,
+        #[template_child]
+        pub unsolvable_banner: TemplateChild<adw::Banner>,
+        #[template_child]
         pub paused_label: TemplateChild<gtk::Label>,
         #[template_child]
         pub resume_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub box_paused: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub shortcut_controller: TemplateChild<gtk::ShortcutController>,
     }
 
     #[glib::object_subclass]
@@ -169,431 +346,2430 @@ impl HexkudoGameView {
         let imp: &imp::HexkudoGameView = self.imp();
 
         // GSettings bindings
-        settings.bind("zoom-level", self, "zoom-level").build();
         settings
             .bind("show-timer", &*imp.play_pause_stack, "visible")
             .build();
+        // The zen games hide these widgets without changing the settings
         settings
             .bind("show-errors", &*imp.error_box, "visible")
+            .get()
+            .build();
+        settings
+            .bind("points-scoring", &*imp.points_box, "visible")
+            .get()
             .build();
         settings
             .bind("show-timer", &*imp.clock_box, "visible")
+            .get()
             .build();
+        for key in ["show-errors", "points-scoring", "show-timer"] {
+            settings.connect_changed(
+                Some(key),
+                clone!(
+                    #[weak(rename_to = mself)]
+                    self,
+                    move |_, _| {
+                        let zen: bool = mself
+                            .imp()
+                            .game
+                            .get()
+                            .and_then(|g| g.try_borrow().ok().map(|g| g.zen))
+                            .unwrap_or_default();
+                        if zen {
+                            mself.apply_zen_mode(true);
+                        }
+                    }
+                ),
+            );
+        }
         settings
             .bind("show-puzzle-bg", self, "show-puzzle-bg")
             .build();
+        settings.bind("inhibit-idle", self, "inhibit-idle").build();
+        settings
+            .bind("show-timer-tenths", self, "show-timer-tenths")
+            .build();
+        settings.bind("freeze-timer", self, "freeze-timer").build();
+        settings.bind("show-splits", self, "show-splits").build();
+        settings.bind("show-ghost", self, "show-ghost").build();
+        settings.bind("show-streak", self, "show-streak").build();
+        settings
+            .bind("auto-complete-chains", self, "auto-complete-chains")
+            .build();
+        settings
+            .bind("show-next-number", self, "show-next-number")
+            .build();
+        settings
+            .bind("show-number-pad", self, "show-number-pad")
+            .build();
+        settings
+            .bind("show-remaining-numbers", self, "show-remaining-numbers")
+            .build();
+        settings
+            .bind("warn-unsolvable", self, "warn-unsolvable")
+            .build();
+        settings.bind("undo-depth", self, "undo-depth").build();
+        settings
+            .bind("show-last-number", self, "show-last-number")
+            .build();
+        settings
+            .bind("automation-actions", self, "automation-actions")
+            .build();
+        settings
+            .bind("practice-mode", self, "practice-mode")
+            .build();
+        settings.bind("sum-regions", self, "sum-regions").build();
+        settings
+            .bind("unlimited-assist", self, "unlimited-assist")
+            .build();
+        settings.bind("mystery-mode", self, "mystery-mode").build();
+        self.connect_practice_mode_notify(|mself| {
+            let Some(game) = shared_game::try_read(
+                mself
+                    .imp()
+                    .game
+                    .get()
+                    .expect("Cannot retrieve the game data from the object"),
+            ) else {
+                return;
+            };
+            mself.action_set_enabled(
+                "game-view.reveal-hint",
+                mself.practice_mode() && game.started && !game.paused && !game.solved,
+            );
+        });
+        self.connect_unlimited_assist_notify(|mself| {
+            let Some(game) = shared_game::try_read(
+                mself
+                    .imp()
+                    .game
+                    .get()
+                    .expect("Cannot retrieve the game data from the object"),
+            ) else {
+                return;
+            };
+            mself.update_solve_cell_budget(game.started && !game.paused && !game.solved, &game);
+        });
+        self.connect_mystery_mode_notify(|mself| {
+            let Some(game) = shared_game::try_read(
+                mself
+                    .imp()
+                    .game
+                    .get()
+                    .expect("Cannot retrieve the game data from the object"),
+            ) else {
+                return;
+            };
+            let sensitive: bool = game.started && !game.paused && !game.solved;
+            mself.action_set_enabled(
+                "game-view.show-warnings",
+                sensitive && !mself.mystery_mode(),
+            );
+            mself.action_set_enabled(
+                "game-view.show-duplicates",
+                sensitive && !mself.mystery_mode(),
+            );
+            mself.update_error_widget(game.get_errors());
+            mself.update_points_widget(game.get_points());
+            mself.update_streak_widget(&game);
+            mself.check_solvability(&game);
+        });
+        self.connect_show_next_number_notify(|mself| {
+            if let Some(game) = mself.imp().game.get().and_then(shared_game::try_read) {
+                mself.update_missing_value_widgets(&game);
+            }
+        });
+        self.connect_show_last_number_notify(|mself| {
+            if let Some(game) = mself.imp().game.get().and_then(shared_game::try_read) {
+                mself.update_missing_value_widgets(&game);
+            }
+        });
+        self.connect_show_remaining_numbers_notify(|mself| {
+            if let Some(game) = mself.imp().game.get().and_then(shared_game::try_read) {
+                mself.update_missing_value_widgets(&game);
+            }
+        });
+        self.connect_show_number_pad_notify(|mself| mself.update_number_pad_visibility());
+        self.update_number_pad_visibility();
+        self.connect_undo_depth_notify(|mself| {
+            if let Some(g) = mself.imp().game.get() {
+                shared_game::with_game_mut(
+                    g,
+                    clone!(
+                        #[weak]
+                        mself,
+                        move |game: &mut Game| {
+                            game.player_input.set_max_undo(mself.undo_depth() as usize);
+                            mself.action_set_enabled(
+                                "game-view.undo",
+                                game.started && game.player_input.undo_len() > 0,
+                            );
+                        }
+                    ),
+                );
+            }
+        });
+        self.connect_warn_unsolvable_notify(|mself| {
+            if let Some(game) = mself.imp().game.get().and_then(shared_game::try_read) {
+                mself.check_solvability(&game);
+            }
+        });
+        self.setup_solvability_channel();
 
         imp.drawing_area.init(settings, game);
-        imp.drawing_area.set_zoom_level(
-            draw::ZoomLevel::from_repr(settings.enum_("zoom-level"))
-                .expect("Cannot get the current zoom level"),
-        );
+        imp.number_sheet.init(settings, game);
+        imp.number_pad.init(game);
+        imp.remaining_numbers.init(game);
+        imp.drawing_area.set_number_sheet(&imp.number_sheet);
+        imp.menu_button.init_quick_actions(settings);
         imp.game
             .set(Rc::clone(game))
             .expect("Cannot store the game data into the object");
         imp.puzzle_list
             .set(puzzle_list.clone())
             .expect("Cannot store the puzzle list into the object");
+        imp.settings
+            .set(settings.clone())
+            .expect("Cannot store the settings into the object");
 
-        // Manage the timer widget
+        // Manage the timer widget. The timer is refreshed often enough to display tenths of
+        // seconds.
         glib::timeout_add_local(
-            Duration::new(1, 0),
+            Duration::from_millis(100),
             clone!(
                 #[weak]
                 imp,
                 #[upgrade_or]
                 glib::ControlFlow::Break,
                 move || {
-                    let game = imp
+                    // Skip this round if the game data is in use
+                    let Ok(mut game) = imp
                         .game
                         .get()
                         .expect("Cannot retrieve the game data from the object")
-                        .borrow();
+                        .try_borrow_mut()
+                    else {
+                        return glib::ControlFlow::Continue;
+                    };
+                    let playing: bool = game.started && !game.paused && !game.solved;
+
+                    // Freeze the clock while a dialog or the number picker is open
+                    if playing {
+                        if imp.obj().freeze_timer() && imp.obj().is_dialog_open() {
+                            game.freeze_clock();
+                        } else {
+                            game.start_clock();
+                        }
+                    }
+
+                    // Refresh the widgets less often with the energy saver
+                    let rounds: u32 = imp.timer_rounds.get().wrapping_add(1);
+                    imp.timer_rounds.set(rounds);
+                    if imp.energy_saver.get() && !rounds.is_multiple_of(ENERGY_SAVER_TIMER_ROUNDS) {
+                        return glib::ControlFlow::Continue;
+                    }
                     if imp.clock_box.is_visible() && !game.paused && !game.solved {
-                        let (h, m, s) = game.get_duration_hms();
-                        Self::update_clock_widget(&imp, h, m, s);
+                        Self::update_clock_widget(&imp, game.get_duration());
+                    }
+                    imp.obj().update_split_widget(&game);
+                    imp.obj().update_ghost_widget(&game);
+                    imp.obj().update_inhibit(playing);
+                    if playing {
+                        imp.obj().detect_stuck(&game);
                     }
                     glib::ControlFlow::Continue
                 }
             ),
         );
-    }
-
-    fn update_clock_widget(imp: &imp::HexkudoGameView, hour: u64, minute: u64, second: u64) {
-        let time_str: String = if hour > 0 {
-            format!("{hour:02}:{minute:02}:{second:02}")
-        } else {
-            format!("{minute:02}:{second:02}")
-        };
-        imp.clock_label.set_text(&time_str);
-    }
-
-    fn update_error_widget(&self, errors: usize) {
-        self.imp().error_label.set_text(&format!("{errors}"));
-    }
-
-    fn setup_gactions(&self) {
-        let group = gio::SimpleActionGroup::new();
-
-        let print_current_action = gio::SimpleAction::new("print-current", None);
-        print_current_action.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.print_current_action()
-        ));
-        group.add_action(&print_current_action);
 
-        let zoom_out_action = gio::SimpleAction::new("zoom-out", None);
-        zoom_out_action.connect_activate(clone!(
+        // Follow the power saver mode of the system for the energy saver
+        let power_monitor: gio::PowerProfileMonitor = gio::PowerProfileMonitor::get_default();
+        power_monitor.connect_power_saver_enabled_notify(clone!(
             #[weak(rename_to = mself)]
             self,
-            move |_, _| mself.zoom_out_action()
+            move |_| mself.update_energy_saver()
         ));
-        group.add_action(&zoom_out_action);
+        imp.power_monitor
+            .set(power_monitor)
+            .expect("Cannot store the power monitor into the object");
+        settings.connect_changed(
+            Some("energy-saver"),
+            clone!(
+                #[weak(rename_to = mself)]
+                self,
+                move |_, _| mself.update_energy_saver()
+            ),
+        );
+        self.update_energy_saver();
 
-        let zoom_in_action = gio::SimpleAction::new("zoom-in", None);
-        zoom_in_action.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.zoom_in_action()
-        ));
-        group.add_action(&zoom_in_action);
+        // Keyboard shortcuts of the game view actions
+        keybindings::fill_controller(&imp.shortcut_controller, settings);
+        settings.connect_changed(
+            Some("custom-accels"),
+            clone!(
+                #[weak]
+                imp,
+                move |settings, _| keybindings::fill_controller(&imp.shortcut_controller, settings)
+            ),
+        );
 
-        let undo_action = gio::SimpleAction::new("undo", None);
-        undo_action.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.undo_action()
-        ));
-        group.add_action(&undo_action);
+        // Save the game in progress periodically, so that a crash does not lose the progress
+        glib::timeout_add_seconds_local(
+            AUTOSAVE_INTERVAL,
+            clone!(
+                #[weak]
+                imp,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    // Save less often with the energy saver
+                    let rounds: u32 = imp.autosave_rounds.get().wrapping_add(1);
+                    imp.autosave_rounds.set(rounds);
+                    if imp.energy_saver.get()
+                        && !rounds.is_multiple_of(ENERGY_SAVER_AUTOSAVE_ROUNDS)
+                    {
+                        return glib::ControlFlow::Continue;
+                    }
 
-        let redo_action = gio::SimpleAction::new("redo", None);
-        redo_action.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.redo_action()
-        ));
-        group.add_action(&redo_action);
+                    // Skip this round if the game data is in use
+                    if let Some(game) = shared_game::try_read(
+                        imp.game
+                            .get()
+                            .expect("Cannot retrieve the game data from the object"),
+                    ) {
+                        imp.obj().autosave(&game);
+                    }
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+    }
 
-        let reset_puzzle_action = gio::SimpleAction::new("reset-puzzle", None);
-        reset_puzzle_action.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.reset_puzzle_action()
-        ));
-        group.add_action(&reset_puzzle_action);
+    /// Update the energy saver state and its indicator, after a change of the system power
+    /// saver mode or of the `energy-saver` GSettings key.
+    fn update_energy_saver(&self) {
+        let active: bool = energy_saver::is_active(
+            self.imp()
+                .settings
+                .get()
+                .expect("Cannot retrieve the settings from the object"),
+        );
 
-        let set_checkpoint = gio::SimpleAction::new("set-checkpoint", None);
-        set_checkpoint.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.set_checkpoint_action()
-        ));
-        group.add_action(&set_checkpoint);
+        debug!("Energy saver active: {active}");
+        self.imp().energy_saver.set(active);
+        self.imp().energy_saver_image.set_visible(active);
+    }
 
-        let undo_checkpoint = gio::SimpleAction::new("undo-checkpoint", None);
-        undo_checkpoint.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.undo_checkpoint_action()
-        ));
-        group.add_action(&undo_checkpoint);
+    /// Save the game in progress. The game is also saved when the application quits, but saving
+    /// it during play protects the progress against crashes.
+    ///
+    /// The game is only saved when the board changed since the last save. The file is written in
+    /// a background thread.
+    fn autosave(&self, game: &Game) {
+        if !game.started || game.solved || !game.is_unsaved() {
+            return;
+        }
+        let content: Vec<u8> = match serde_json::to_vec(game) {
+            Ok(c) => c,
+            Err(error) => {
+                debug!("Error serializing the game: {error}");
+                return;
+            }
+        };
+        game.mark_saved();
 
-        let solve_cell = gio::SimpleAction::new("solve-current-cell", None);
-        solve_cell.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.solve_current_cell_action()
-        ));
-        group.add_action(&solve_cell);
+        debug!("Autosaving the game");
+        let saver: SaverGame = SaverGame::new(glib::user_data_dir());
+        gio::spawn_blocking(move || {
+            if let Err(error) = saver.write_game(&content) {
+                debug!("Error saving the game: {error}");
+            }
+        });
+    }
 
-        let solve_puzzle = gio::SimpleAction::new("solve-puzzle", None);
-        solve_puzzle.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.solve_puzzle_action()
-        ));
-        group.add_action(&solve_puzzle);
+    /// Offer the guided reveal when only a few cells remain and the player has not completed a
+    /// cell for the delay that the `guided-reveal-delay` GSettings key defines. The delay is
+    /// measured with the game clock, so that the pauses do not count.
+    fn detect_stuck(&self, game: &Game) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let (completed, to_complete) = game.get_progress();
+        let duration: Duration = game.get_duration();
+        let (last_completed, last_duration) = imp.last_progress.get();
+
+        // The player made progress, or started a new game
+        if completed != last_completed || duration < last_duration {
+            imp.last_progress.set((completed, duration));
+            imp.guided_reveal_offered.set(false);
+            if !imp.drawing_area.imp().highlighted_cells.borrow().is_empty() {
+                imp.drawing_area.set_highlighted_cells(Vec::new());
+            }
+            return;
+        }
 
-        let pause_action = gio::SimpleAction::new("pause-resume", None);
-        pause_action.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.pause_resume_action()
-        ));
-        group.add_action(&pause_action);
+        let delay: u64 = imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object")
+            .uint("guided-reveal-delay") as u64;
+        if delay == 0
+            || imp.guided_reveal_offered.get()
+            || to_complete.saturating_sub(completed) > GUIDED_REVEAL_CELLS
+            || duration - last_duration < Duration::from_secs(delay)
+        {
+            return;
+        }
+        imp.guided_reveal_offered.set(true);
 
-        let show_warnings = gio::SimpleAction::new("show-warnings", None);
-        show_warnings.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.show_warnings_action()
-        ));
-        group.add_action(&show_warnings);
+        let toast: adw::Toast = adw::Toast::builder()
+            .title(gettext("Stuck? Highlight the area of the next forced cell"))
+            .button_label(gettext("Highlight"))
+            .action_name("game-view.guided-reveal")
+            .timeout(0)
+            .build();
+        imp.toast_overlay.add_toast(toast);
+    }
 
-        let show_duplicates = gio::SimpleAction::new("show-duplicates", None);
-        show_duplicates.connect_activate(clone!(
-            #[weak(rename_to = mself)]
-            self,
-            move |_, _| mself.show_duplicates_action()
-        ));
-        group.add_action(&show_duplicates);
+    /// Whether a dialog or the number picker is displayed over the game.
+    fn is_dialog_open(&self) -> bool {
+        let imp: &imp::HexkudoGameView = self.imp();
 
-        self.insert_action_group("game-view", Some(&group));
+        if imp.drawing_area.imp().popover_number.is_visible() || imp.bottom_sheet.is_open() {
+            return true;
+        }
+        self.root()
+            .and_downcast::<adw::ApplicationWindow>()
+            .is_some_and(|window| window.visible_dialog().is_some())
     }
 
-    #[template_callback]
-    fn show_puzzle_bg_cb(&self) {
-        if let Some(g) = self.imp().game.get() {
-            self.set_background_css(g.borrow().puzzle.colors.get_bg_css());
+    /// Prevent the screen from blanking while the game is in progress, or release the inhibitor
+    /// when the game is paused or completed.
+    fn update_inhibit(&self, playing: bool) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(window) = self.root().and_downcast::<gtk::Window>() else {
+            return;
+        };
+        let Some(application) = window.application() else {
+            return;
+        };
+
+        if self.inhibit_idle() && playing {
+            if imp.inhibit_cookie.get().is_none() {
+                debug!("Inhibiting the screen saver");
+                let cookie: u32 = application.inhibit(
+                    Some(&window),
+                    gtk::ApplicationInhibitFlags::IDLE | gtk::ApplicationInhibitFlags::SUSPEND,
+                    Some(&gettext("Playing a game")),
+                );
+                // A cookie of 0 means that the request failed
+                if cookie != 0 {
+                    imp.inhibit_cookie.set(Some(cookie));
+                }
+            }
+        } else if let Some(cookie) = imp.inhibit_cookie.take() {
+            debug!("Releasing the screen saver inhibitor");
+            application.uninhibit(cookie);
+        }
+    }
+
+    fn update_clock_widget(imp: &imp::HexkudoGameView, duration: Duration) {
+        let secs: u64 = duration.as_secs();
+        let hour: u64 = secs / 3600;
+        let minute: u64 = (secs % 3600) / 60;
+        let second: u64 = (secs % 3600) % 60;
+        let mut time_str: String = if hour > 0 {
+            format!("{hour:02}:{minute:02}:{second:02}")
+        } else {
+            format!("{minute:02}:{second:02}")
+        };
+        if imp.obj().show_timer_tenths() {
+            time_str.push_str(&format!(".{}", duration.subsec_millis() / 100));
+        }
+        imp.clock_label.set_text(&time_str);
+    }
+
+    /// Display the last split time and compare it with the player's best split time.
+    fn update_split_widget(&self, game: &Game) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let splits: &Vec<Duration> = game.get_splits();
+
+        imp.split_label
+            .set_visible(self.show_splits() && imp.clock_box.is_visible() && !splits.is_empty());
+
+        // Only refresh the widget when a new split time is recorded
+        if splits.len() == imp.displayed_splits.get() {
+            return;
+        }
+        imp.displayed_splits.set(splits.len());
+        imp.split_label.remove_css_class("success");
+        imp.split_label.remove_css_class("error");
+
+        let Some(split) = splits.last() else {
+            imp.split_label.set_text("");
+            return;
+        };
+        let index: usize = splits.len() - 1;
+        let mut split_str: String = format!(
+            "{}% {}",
+            SPLIT_PERCENTS[index],
+            Self::format_split_time(*split)
+        );
+
+        let highscores: HighScores = self.get_highscores();
+        if let Some(best) = highscores
+            .get_best_splits(
+                &game.puzzle.name,
+                game.puzzle.difficulty,
+                game.get_endpoints(),
+            )
+            .and_then(|b| b.get(index))
+        {
+            if split <= best {
+                split_str.push_str(&format!(" (−{})", Self::format_split_time(*best - *split)));
+                imp.split_label.add_css_class("success");
+            } else {
+                split_str.push_str(&format!(" (+{})", Self::format_split_time(*split - *best)));
+                imp.split_label.add_css_class("error");
+            }
+        }
+        imp.split_label.set_text(&split_str);
+    }
+
+    /// Load the timeline of the player's best game for the puzzle of the given game, with the
+    /// same revealed endpoints. The ghost of that game races against the player.
+    fn load_ghost(&self, game: &Game) {
+        let timeline: Vec<Duration> = self
+            .get_highscores()
+            .get_best_timeline(
+                &game.puzzle.name,
+                game.puzzle.difficulty,
+                game.get_endpoints(),
+            )
+            .cloned()
+            .unwrap_or_default();
+
+        self.imp().ghost_timeline.replace(timeline);
+    }
+
+    /// Display how many numbers the player's best game had placed at the same game duration,
+    /// and whether the player is ahead of that ghost game.
+    fn update_ghost_widget(&self, game: &Game) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let timeline = imp.ghost_timeline.borrow();
+
+        let visible: bool = self.show_ghost()
+            && imp.clock_box.is_visible()
+            && !timeline.is_empty()
+            && game.started
+            && !game.solved;
+        imp.ghost_label.set_visible(visible);
+        if !visible {
+            return;
+        }
+
+        let elapsed: Duration = game.get_duration();
+        let ghost: usize = timeline.iter().take_while(|t| **t <= elapsed).count();
+        let (completed, to_complete) = game.get_progress();
+
+        imp.ghost_label.set_text(
+            &formatx!(
+                gettext("Best run: {ghost}/{total}"),
+                ghost = ghost,
+                total = to_complete
+            )
+            .unwrap(),
+        );
+        if completed >= ghost {
+            imp.ghost_label.remove_css_class("error");
+            imp.ghost_label.add_css_class("success");
+        } else {
+            imp.ghost_label.remove_css_class("success");
+            imp.ghost_label.add_css_class("error");
+        }
+    }
+
+    /// Display the number of correct values that the player placed in a row, and celebrate the
+    /// streak milestones with a short pulse.
+    fn update_streak_widget(&self, game: &Game) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let streak: usize = game.get_streak();
+        let previous: usize = imp.displayed_streak.replace(streak);
+
+        // The streak would reveal the mistakes in mystery mode
+        let visible: bool = self.show_streak()
+            && !self.mystery_mode()
+            && game.started
+            && !game.solved
+            && streak >= STREAK_MIN_DISPLAYED;
+        imp.streak_label.set_visible(visible);
+        if !visible {
+            return;
+        }
+
+        imp.streak_label.set_text(
+            &formatx!(
+                ngettext("{count} in a row", "{count} in a row", streak as u32),
+                count = streak
+            )
+            .unwrap(),
+        );
+        if streak > previous && streak.is_multiple_of(STREAK_MILESTONE) && !imp.energy_saver.get() {
+            imp.streak_label.add_css_class("streak-pulse");
+            glib::timeout_add_local_once(
+                Duration::from_millis(STREAK_PULSE_MS),
+                glib::clone!(
+                    #[weak(rename_to = label)]
+                    imp.streak_label,
+                    move || label.remove_css_class("streak-pulse")
+                ),
+            );
+        }
+    }
+
+    /// Display the smallest value that the player has not placed yet, and optionally the largest
+    /// one and the list of all the missing values. Grey out the values already in the board in
+    /// the number pad panel.
+    fn update_missing_value_widgets(&self, game: &Game) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        imp.number_pad.update(game);
+        let playing: bool = game.started && !game.solved;
+        let next_value: Option<usize> = game.get_next_missing_value().filter(|_| playing);
+        let last_value: Option<usize> = game
+            .get_last_missing_value()
+            .filter(|v| playing && next_value != Some(*v));
+
+        imp.next_number_button
+            .set_visible(self.show_next_number() && next_value.is_some());
+        if let Some(value) = next_value {
+            imp.next_number_button
+                .set_label(&formatx!(gettext("Next: {value}"), value = value).unwrap());
+        }
+        imp.last_number_button
+            .set_visible(self.show_last_number() && last_value.is_some());
+        if let Some(value) = last_value {
+            imp.last_number_button
+                .set_label(&formatx!(gettext("Last: {value}"), value = value).unwrap());
+        }
+        imp.remaining_numbers
+            .set_visible(self.show_remaining_numbers() && next_value.is_some());
+        imp.remaining_numbers.update(game);
+    }
+
+    /// Select a cell next to the end of the chain of placed values that leads to the smallest
+    /// (`ascending` set) or the largest missing value.
+    fn select_near_chain_end(&self, ascending: bool) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            move |mself: &Self| mself.select_near_chain_end(ascending),
+        ) else {
+            return;
+        };
+
+        if !game.started || game.solved || game.paused {
+            return;
+        }
+        let value: Option<usize> = if ascending {
+            game.get_next_missing_value()
+        } else {
+            game.get_last_missing_value()
+        };
+        if let Some(v) = value
+            && let Some(cell_id) = game.get_cell_near_chain_end(v, ascending)
+        {
+            game.set_selected_cell(Some(cell_id));
+            self.hide_popover();
+            imp.drawing_area.queue_draw();
+            imp.drawing_area.grab_focus();
+        }
+    }
+
+    /// Create the channel through which the background tasks report the solvability of the
+    /// board, and process the results.
+    fn setup_solvability_channel(&self) {
+        let (sender, receiver) = async_channel::bounded::<(u64, Solvability)>(1);
+
+        self.imp()
+            .solvability_sender
+            .set(sender)
+            .expect("Cannot store the solvability channel into the object");
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            async move {
+                while let Ok((serial, solvability)) = receiver.recv().await {
+                    let imp: &imp::HexkudoGameView = mself.imp();
+
+                    imp.solvability_running.set(false);
+                    if imp.solvability_pending.replace(false) {
+                        // The board changed during the check, which must run again
+                        if let Some(game) = shared_game::try_read(
+                            imp.game
+                                .get()
+                                .expect("Cannot retrieve the game data from the object"),
+                        ) {
+                            mself.check_solvability(&game);
+                        }
+                    } else if serial == imp.solvability_serial.get() {
+                        imp.unsolvable_banner
+                            .set_revealed(solvability == Solvability::Unsolvable);
+                    }
+                }
+            }
+        ));
+    }
+
+    /// Verify in a background task whether the player can still solve the puzzle, and display
+    /// a banner when no solution is possible.
+    ///
+    /// Only one check runs at a time. When the board changes during a check, a new check starts
+    /// when the running one completes.
+    fn check_solvability(&self, game: &Game) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let serial: u64 = imp.solvability_serial.get() + 1;
+
+        imp.solvability_serial.set(serial);
+        // In mystery mode, the dead-end warning would reveal the mistakes
+        if !self.warn_unsolvable() || self.mystery_mode() || !game.started || game.solved {
+            imp.unsolvable_banner.set_revealed(false);
+            return;
+        }
+        if imp.solvability_running.get() {
+            imp.solvability_pending.set(true);
+            return;
+        }
+        let Some(sender) = imp.solvability_sender.get().cloned() else {
+            return;
+        };
+
+        imp.solvability_running.set(true);
+        let constraints: Constraints = Constraints::from_game(game);
+        let values: HashMap<usize, usize> = game.player_input.get_values().clone();
+        glib::spawn_future_local(async move {
+            let solvability: Solvability =
+                gio::spawn_blocking(move || constraints.check(&values, SOLVABILITY_TIMEOUT))
+                    .await
+                    .unwrap_or(Solvability::Unknown);
+            sender
+                .send((serial, solvability))
+                .await
+                .expect("The channel needs to be open");
+        });
+    }
+
+    fn format_split_time(duration: Duration) -> String {
+        let secs: u64 = duration.as_secs();
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+
+    fn update_error_widget(&self, errors: usize) {
+        // In mystery mode, the mistake counter is only revealed at the end of the game
+        if self.mystery_mode() {
+            self.imp().error_label.set_text("?");
+        } else {
+            self.imp().error_label.set_text(&format!("{errors}"));
+        }
+    }
+
+    fn update_points_widget(&self, points: u32) {
+        // The penalties for the mistakes would reveal them in mystery mode
+        if self.mystery_mode() {
+            self.imp().points_label.set_text("?");
+        } else {
+            self.imp().points_label.set_text(&format!("{points}"));
+        }
+    }
+
+    fn setup_gactions(&self) {
+        let group = gio::SimpleActionGroup::new();
+
+        let print_current_action = gio::SimpleAction::new("print-current", None);
+        print_current_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.print_current_action()
+        ));
+        group.add_action(&print_current_action);
+
+        let export_replay_action = gio::SimpleAction::new("export-replay", None);
+        export_replay_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.export_replay_action()
+        ));
+        group.add_action(&export_replay_action);
+
+        let heat_map_action = gio::SimpleAction::new("heat-map", None);
+        heat_map_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.heat_map_action()
+        ));
+        group.add_action(&heat_map_action);
+
+        let save_image_action = gio::SimpleAction::new("save-image", None);
+        save_image_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.save_image_action()
+        ));
+        group.add_action(&save_image_action);
+
+        let export_puzzle_action = gio::SimpleAction::new("export-puzzle", None);
+        export_puzzle_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.export_puzzle_action()
+        ));
+        group.add_action(&export_puzzle_action);
+
+        let zoom_out_action = gio::SimpleAction::new("zoom-out", None);
+        zoom_out_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.zoom_out_action()
+        ));
+        group.add_action(&zoom_out_action);
+
+        let zoom_in_action = gio::SimpleAction::new("zoom-in", None);
+        zoom_in_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.zoom_in_action()
+        ));
+        group.add_action(&zoom_in_action);
+
+        let zoom_fit_action = gio::SimpleAction::new("zoom-fit", None);
+        zoom_fit_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.zoom_fit_action()
+        ));
+        group.add_action(&zoom_fit_action);
+
+        let undo_action = gio::SimpleAction::new("undo", None);
+        undo_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.undo_action()
+        ));
+        group.add_action(&undo_action);
+
+        let redo_action = gio::SimpleAction::new("redo", None);
+        redo_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.redo_action()
+        ));
+        group.add_action(&redo_action);
+
+        let reset_puzzle_action = gio::SimpleAction::new("reset-puzzle", None);
+        reset_puzzle_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.reset_puzzle_action()
+        ));
+        group.add_action(&reset_puzzle_action);
+
+        let set_checkpoint = gio::SimpleAction::new("set-checkpoint", None);
+        set_checkpoint.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.set_checkpoint_action()
+        ));
+        group.add_action(&set_checkpoint);
+
+        let undo_checkpoint = gio::SimpleAction::new("undo-checkpoint", None);
+        undo_checkpoint.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.undo_checkpoint_action()
+        ));
+        group.add_action(&undo_checkpoint);
+
+        let set_named_checkpoint = gio::SimpleAction::new("set-named-checkpoint", None);
+        set_named_checkpoint.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.set_named_checkpoint_action()
+        ));
+        group.add_action(&set_named_checkpoint);
+
+        let jump_checkpoint =
+            gio::SimpleAction::new("jump-checkpoint", Some(&i32::static_variant_type()));
+        jump_checkpoint.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, parameter| mself.jump_checkpoint_action(parameter)
+        ));
+        group.add_action(&jump_checkpoint);
+
+        let solve_cell = gio::SimpleAction::new("solve-current-cell", None);
+        solve_cell.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.solve_current_cell_action()
+        ));
+        group.add_action(&solve_cell);
+
+        let solve_puzzle = gio::SimpleAction::new("solve-puzzle", None);
+        solve_puzzle.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.solve_puzzle_action()
+        ));
+        group.add_action(&solve_puzzle);
+
+        let reveal_hint = gio::SimpleAction::new("reveal-hint", None);
+        reveal_hint.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.reveal_hint_action()
+        ));
+        group.add_action(&reveal_hint);
+
+        let guided_reveal_action = gio::SimpleAction::new("guided-reveal", None);
+        guided_reveal_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.guided_reveal_action()
+        ));
+        group.add_action(&guided_reveal_action);
+
+        let hint_action = gio::SimpleAction::new("hint", None);
+        hint_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.hint_action()
+        ));
+        group.add_action(&hint_action);
+
+        let pause_action = gio::SimpleAction::new("pause-resume", None);
+        pause_action.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.pause_resume_action()
+        ));
+        group.add_action(&pause_action);
+
+        let show_warnings = gio::SimpleAction::new("show-warnings", None);
+        show_warnings.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.show_warnings_action()
+        ));
+        group.add_action(&show_warnings);
+
+        let toggle_notes = gio::SimpleAction::new("toggle-notes", None);
+        toggle_notes.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.toggle_notes_action()
+        ));
+        group.add_action(&toggle_notes);
+
+        let copy_seed = gio::SimpleAction::new("copy-seed", None);
+        copy_seed.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.copy_seed_action()
+        ));
+        group.add_action(&copy_seed);
+
+        let history = gio::SimpleAction::new("history", None);
+        history.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.history_action()
+        ));
+        group.add_action(&history);
+
+        let new_game_seed = gio::SimpleAction::new("new-game-seed", None);
+        new_game_seed.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.new_game_seed_action()
+        ));
+        group.add_action(&new_game_seed);
+
+        let new_game_qr_code = gio::SimpleAction::new("new-game-qr-code", None);
+        new_game_qr_code.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.new_game_qr_code_action()
+        ));
+        group.add_action(&new_game_qr_code);
+
+        let save_slot = gio::SimpleAction::new("save-slot", None);
+        save_slot.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.save_slot_action()
+        ));
+        group.add_action(&save_slot);
+
+        let export_save = gio::SimpleAction::new("export-save", None);
+        export_save.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.export_save_action()
+        ));
+        group.add_action(&export_save);
+
+        let show_duplicates = gio::SimpleAction::new("show-duplicates", None);
+        show_duplicates.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.show_duplicates_action()
+        ));
+        group.add_action(&show_duplicates);
+
+        let announce_remaining = gio::SimpleAction::new("announce-remaining", None);
+        announce_remaining.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.announce_remaining_action()
+        ));
+        group.add_action(&announce_remaining);
+
+        let revert_cell = gio::SimpleAction::new("revert-cell", None);
+        revert_cell.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.revert_cell_action()
+        ));
+        group.add_action(&revert_cell);
+
+        let complete_chains = gio::SimpleAction::new("complete-chains", None);
+        complete_chains.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, _| mself.complete_chains_action()
+        ));
+        group.add_action(&complete_chains);
+
+        // Actions for automation tests. They are ignored unless the `automation-actions`
+        // GSettings key is set. The application also exports them, as `app.select-cell` and
+        // `app.set-cell`, for driving the game through D-Bus.
+        let select_cell = gio::SimpleAction::new("select-cell", Some(&i32::static_variant_type()));
+        select_cell.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, parameter| mself.select_cell_action(parameter)
+        ));
+        group.add_action(&select_cell);
+
+        let set_cell =
+            gio::SimpleAction::new("set-cell", Some(&<(i32, i32)>::static_variant_type()));
+        set_cell.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, parameter| mself.set_cell_action(parameter)
+        ));
+        group.add_action(&set_cell);
+
+        // Debug action that shows or hides a rendering layer given by name
+        let debug_layer =
+            gio::SimpleAction::new("debug-layer", Some(&String::static_variant_type()));
+        debug_layer.connect_activate(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_, parameter| {
+                if let Some(nick) = parameter.and_then(|p| p.str()) {
+                    mself.imp().drawing_area.toggle_debug_layer(nick);
+                }
+            }
+        ));
+        group.add_action(&debug_layer);
+
+        self.insert_action_group("game-view", Some(&group));
+    }
+
+    #[template_callback]
+    fn next_number_cb(&self) {
+        self.select_near_chain_end(true);
+    }
+
+    #[template_callback]
+    fn last_number_cb(&self) {
+        self.select_near_chain_end(false);
+    }
+
+    #[template_callback]
+    fn show_puzzle_bg_cb(&self) {
+        if let Some(game) = self.imp().game.get().and_then(shared_game::try_read) {
+            self.set_background_css(game.puzzle.colors.get_bg_css());
         }
     }
 
     #[template_callback]
     fn timer_visible_cb(&self) {
         let imp: &imp::HexkudoGameView = self.imp();
-        if !imp.play_pause_stack.is_visible()
-            && let Some(g) = imp.game.get()
+        if !imp.play_pause_stack.is_visible()
+            && let Some(g) = imp.game.get()
+            && let Some(mut game) = shared_game::try_write_or_retry(self, g, Self::timer_visible_cb)
+        {
+            self.resume(&mut game);
+        }
+    }
+
+    // Return the ID of the current player profile, which selects the high scores and statistics
+    fn profile_id(&self) -> String {
+        player_profiles::current(
+            self.imp()
+                .settings
+                .get()
+                .expect("Cannot retrieve the settings from the object"),
+        )
+    }
+
+    // Load the high score boards from the disk
+    fn get_highscores(&self) -> HighScores {
+        let saver: SaverHighScores =
+            SaverHighScores::new(glib::user_data_dir(), &self.profile_id());
+        if let Ok(o) = saver.get_highscores() {
+            if let Some(h) = o {
+                return h;
+            }
+        } else {
+            // Delete the file in error for trying to resolve the issue for the next start
+            saver.delete_save();
+        }
+        HighScores::new()
+    }
+
+    // Load the statistics from the disk
+    fn get_statistics(&self) -> Statistics {
+        let saver: SaverStatistics =
+            SaverStatistics::new(glib::user_data_dir(), &self.profile_id());
+        if let Ok(o) = saver.get_statistics() {
+            if let Some(s) = o {
+                return s;
+            }
+        } else {
+            // Delete the file in error for trying to resolve the issue for the next start
+            saver.delete_save();
+        }
+        Statistics::new()
+    }
+
+    // Add the game to the statistics, if the player allows recording the history of the games
+    fn record_statistics(&self, game: &Game, won: bool) {
+        if !self
+            .imp()
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object")
+            .boolean("record-history")
+        {
+            return;
+        }
+
+        let mut statistics: Statistics = self.get_statistics();
+        if won {
+            statistics.add_won(
+                &game.puzzle.name,
+                game.puzzle.difficulty,
+                game.get_endpoints(),
+                game.get_duration(),
+                game.get_best_streak(),
+                game.get_hints_used(),
+            );
+        } else {
+            statistics.add_abandoned(
+                &game.puzzle.name,
+                game.puzzle.difficulty,
+                game.get_endpoints(),
+                game.get_best_streak(),
+                game.get_hints_used(),
+            );
+        }
+
+        let saver: SaverStatistics =
+            SaverStatistics::new(glib::user_data_dir(), &self.profile_id());
+        if let Err(error) = saver.save_statistics(&statistics) {
+            debug!("Error saving statistics: {error}");
+            // Delete the file in error for trying to resolve the issue for the next start
+            saver.delete_save();
+        }
+    }
+
+    fn print_current_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(game) = shared_game::try_read(
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        ) else {
+            return;
+        };
+
+        if !game.paused {
+            self.action_set_enabled("game-view.print-current", false);
+            self.action_set_enabled("app.print-multiple", false);
+            imp.drawing_area.print_current();
+            self.action_set_enabled("game-view.print-current", true);
+            self.action_set_enabled("app.print-multiple", true);
+        }
+    }
+
+    fn export_replay_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let settings: &gio::Settings = imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object");
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+
+        let replay_dialog: HexkudoReplayDialog = HexkudoReplayDialog::new(settings, self);
+        replay_dialog.present(Some(&window));
+    }
+
+    fn save_image_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let settings: &gio::Settings = imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object");
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+
+        let image_dialog: HexkudoImageDialog = HexkudoImageDialog::new(settings, self);
+        image_dialog.present(Some(&window));
+    }
+
+    /// Return the name, without extension, of the files that export the current puzzle.
+    ///
+    /// The file stem is only used to fill the file and save dialogs, so a generic name is
+    /// returned when the game is being updated.
+    pub fn export_file_stem(&self) -> String {
+        shared_game::try_read(
+            self.imp()
+                .game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        )
+        .map(|game| format!("{}-{:?}", game.puzzle.name, game.puzzle.difficulty).to_lowercase())
+        .unwrap_or_else(|| String::from("hexkudo"))
+    }
+
+    /// Whether the game has a share code, which the QR code encodes.
+    pub fn has_share_code(&self) -> bool {
+        shared_game::try_read(
+            self.imp()
+                .game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        )
+        .is_some_and(|game| game.share_code().is_some())
+    }
+
+    /// Save the puzzle as a PNG image, as a PDF document, or as a QR code, and notify the
+    /// player.
+    pub fn save_image(
+        &self,
+        path: &std::path::Path,
+        format: ImageFormat,
+        size: i32,
+        solution: bool,
+    ) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let result: Result<(), String> = match format {
+            ImageFormat::Png => imp
+                .drawing_area
+                .save_png(path, size, solution)
+                .map_err(|e| e.to_string()),
+            ImageFormat::Pdf => imp
+                .drawing_area
+                .save_pdf(path, solution)
+                .map_err(|e| e.to_string()),
+            ImageFormat::QrCode => imp
+                .drawing_area
+                .save_qr_code(path, size)
+                .map_err(|e| e.to_string()),
+        };
+
+        let msg: String = match result {
+            Ok(()) => gettext("Puzzle saved"),
+            Err(error) => {
+                debug!("Error saving the puzzle image: {error}");
+                gettext("Cannot save the puzzle")
+            }
+        };
+        self.show_toast(&msg);
+    }
+
+    /// Save the puzzle, with its solution and clues, to a file that players can share.
+    fn export_puzzle_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(game) = shared_game::try_read(
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        ) else {
+            return;
+        };
+        let export: puzzles::PuzzleExport = game.to_export();
+        drop(game);
+
+        let initial_name: String = format!(
+            "{}-{:?}.hexkudo",
+            export.puzzle.name, export.puzzle.difficulty
+        )
+        .to_lowercase();
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let file_dialog: gtk::FileDialog = gtk::FileDialog::builder()
+            .title(gettext("Export Puzzle"))
+            .initial_name(initial_name)
+            .modal(true)
+            .build();
+        file_dialog.save(
+            Some(&window),
+            gio::Cancellable::NONE,
+            clone!(
+                #[weak(rename_to = mself)]
+                self,
+                move |result| {
+                    if let Ok(file) = result
+                        && let Some(path) = file.path()
+                    {
+                        let msg: String = match std::fs::write(&path, export.to_string()) {
+                            Ok(()) => gettext("Puzzle exported"),
+                            Err(error) => {
+                                debug!("Error exporting the puzzle: {error}");
+                                gettext("Cannot export the puzzle")
+                            }
+                        };
+                        mself.show_toast(&msg);
+                    }
+                }
+            ),
+        );
+    }
+
+    /// Export the game in progress to a file, so that the player can continue it on another
+    /// computer.
+    fn export_save_action(&self) {
+        let initial_name: String = format!("{}.hexkudo-save", self.export_file_stem());
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let file_dialog: gtk::FileDialog = gtk::FileDialog::builder()
+            .title(gettext("Export Save"))
+            .initial_name(initial_name)
+            .modal(true)
+            .build();
+        file_dialog.save(
+            Some(&window),
+            gio::Cancellable::NONE,
+            clone!(
+                #[weak(rename_to = mself)]
+                self,
+                move |result| {
+                    if let Ok(file) = result
+                        && let Some(path) = file.path()
+                    {
+                        let Some(game) = shared_game::try_read(
+                            mself
+                                .imp()
+                                .game
+                                .get()
+                                .expect("Cannot retrieve the game data from the object"),
+                        ) else {
+                            mself.show_toast(&gettext("Cannot export the game"));
+                            return;
+                        };
+                        let msg: String = match SaverGame::export_game(&path, &game) {
+                            Ok(()) => gettext("Game exported"),
+                            Err(error) => {
+                                debug!("Error exporting the game: {error}");
+                                gettext("Cannot export the game")
+                            }
+                        };
+                        drop(game);
+                        mself.show_toast(&msg);
+                    }
+                }
+            ),
+        );
+    }
+
+    /// Export the replay images to the given directory and notify the player.
+    pub fn export_replay(&self, directory: &std::path::Path, size: i32, frame_duration: f64) {
+        let directory: std::path::PathBuf = directory.to_path_buf();
+
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let msg: String = match obj
+                    .imp()
+                    .drawing_area
+                    .export_replay(&directory, size, frame_duration)
+                    .await
+                {
+                    Ok(n) => {
+                        formatx!(gettext("Replay exported ({count} images)"), count = n).unwrap()
+                    }
+                    Err(error) => {
+                        debug!("Error exporting the replay: {error}");
+                        gettext("Cannot export the replay")
+                    }
+                };
+                obj.show_toast(&msg);
+            }
+        ));
+    }
+
+    /// Display a toast and record the message in the notification history.
+    ///
+    /// The history lists the most recent messages with their time, so that players can read the
+    /// toasts that they missed.
+    pub fn show_toast(&self, msg: &str) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let timeout: u32 = imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object")
+            .uint("toast-timeout");
+
+        let toast: adw::Toast = adw::Toast::new(msg);
+        toast.set_timeout(timeout);
+        imp.toast_overlay.add_toast(toast);
+
+        let time: String = glib::DateTime::now_local()
+            .and_then(|t| t.format("%X"))
+            .map(|t| t.to_string())
+            .unwrap_or_default();
+        let row: adw::ActionRow = adw::ActionRow::builder().title(msg).subtitle(time).build();
+        imp.notifications_list.prepend(&row);
+        if let Some(oldest) = imp
+            .notifications_list
+            .row_at_index(NOTIFICATION_HISTORY_LEN as i32)
         {
-            self.resume(&mut g.borrow_mut());
+            imp.notifications_list.remove(&oldest);
+        }
+        imp.notifications_button.set_visible(true);
+    }
+
+    fn zoom_out_action(&self) {
+        let imp = self.imp();
+        let current_zoom: draw::ZoomLevel = self.zoom_level();
+        let new_zoom: draw::ZoomLevel = current_zoom.zoom_out();
+
+        if new_zoom != current_zoom {
+            self.set_zoom_level(new_zoom);
+            if new_zoom.is_fully_zoomed_out() {
+                self.action_set_enabled("game-view.zoom-out", false);
+            } else {
+                self.action_set_enabled("game-view.zoom-out", true);
+            }
+            self.action_set_enabled("game-view.zoom-in", true);
+            self.action_set_enabled("game-view.zoom-fit", true);
+            imp.drawing_area.set_zoom_level(new_zoom);
+            imp.drawing_area.queue_draw();
+            self.save_zoom_level(new_zoom);
+        }
+    }
+
+    fn zoom_in_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let current_zoom: draw::ZoomLevel = self.zoom_level();
+        let new_zoom: draw::ZoomLevel = current_zoom.zoom_in();
+
+        if new_zoom != current_zoom {
+            self.set_zoom_level(new_zoom);
+            if new_zoom.is_fully_zoomed_in() {
+                self.action_set_enabled("game-view.zoom-in", false);
+            } else {
+                self.action_set_enabled("game-view.zoom-in", true);
+            }
+            self.action_set_enabled("game-view.zoom-out", true);
+            self.action_set_enabled("game-view.zoom-fit", true);
+            imp.drawing_area.set_zoom_level(new_zoom);
+            imp.drawing_area.queue_draw();
+            self.save_zoom_level(new_zoom);
+        }
+    }
+
+    fn zoom_fit_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+
+        // Display the whole board
+        imp.drawing_area.reset_viewport();
+        if self.zoom_level() != draw::ZoomLevel::Auto {
+            self.set_zoom_level(draw::ZoomLevel::Auto);
+            self.enable_zoom_actions();
+            imp.drawing_area.set_zoom_level(draw::ZoomLevel::Auto);
+            imp.drawing_area.queue_draw();
+            self.save_zoom_level(draw::ZoomLevel::Auto);
+        }
+    }
+
+    fn undo_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::undo_action,
+        ) else {
+            return;
+        };
+
+        if !game.solved && !game.paused {
+            game.undo();
+            self.action_set_enabled("game-view.undo", game.player_input.undo_len() > 0);
+            self.action_set_enabled("game-view.redo", game.player_input.redo_len() > 0);
+            self.hide_popover();
+            self.update_missing_value_widgets(&game);
+            self.check_solvability(&game);
+            imp.drawing_area.queue_draw();
+        }
+    }
+
+    fn redo_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::redo_action,
+        ) else {
+            return;
+        };
+
+        if !game.solved && !game.paused {
+            game.redo();
+            self.action_set_enabled("game-view.undo", game.player_input.undo_len() > 0);
+            self.action_set_enabled("game-view.redo", game.player_input.redo_len() > 0);
+            self.hide_popover();
+            self.update_missing_value_widgets(&game);
+            self.check_solvability(&game);
+            imp.drawing_area.queue_draw();
+        }
+    }
+
+    /// Display the history of the moves, from which the player can jump to a previous state.
+    fn history_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(game) = shared_game::try_read(
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        ) else {
+            return;
+        };
+
+        if game.solved || game.paused {
+            return;
+        }
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let dialog: HexkudoHistoryDialog = HexkudoHistoryDialog::new(
+            self,
+            &game.player_input.history(),
+            game.player_input.undo_steps(),
+        );
+        drop(game);
+        dialog.present(Some(&window));
+    }
+
+    /// Undo or redo the moves until the board is in the state after the given step of the
+    /// history.
+    pub fn jump_to_history(&self, step: usize) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            move |mself: &Self| mself.jump_to_history(step),
+        ) else {
+            return;
+        };
+
+        if !game.solved && !game.paused {
+            game.jump_to_history(step);
+            self.action_set_enabled("game-view.undo", game.player_input.undo_len() > 0);
+            self.action_set_enabled("game-view.redo", game.player_input.redo_len() > 0);
+            self.hide_popover();
+            self.update_missing_value_widgets(&game);
+            self.check_solvability(&game);
+            imp.drawing_area.queue_draw();
+        }
+    }
+
+    fn reset_puzzle_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::reset_puzzle_action,
+        ) else {
+            return;
+        };
+
+        if !game.paused {
+            game.reset();
+            self.sensitive(true, &game);
+            self.update_missing_value_widgets(&game);
+            self.check_solvability(&game);
+            self.action_set_enabled("game-view.pause-resume", true);
+            imp.drawing_area.queue_draw();
+        }
+    }
+
+    fn set_checkpoint_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::set_checkpoint_action,
+        ) else {
+            return;
+        };
+
+        if !game.solved && !game.paused {
+            self.add_checkpoint(&mut game, "");
+        }
+    }
+
+    /// Add a checkpoint with the given name. Without a name, the checkpoint is numbered.
+    fn add_checkpoint(&self, game: &mut Game, label: &str) {
+        let label: String = if label.trim().is_empty() {
+            formatx!(
+                gettext("Checkpoint {number}"),
+                number = game.checkpoints_len() + 1
+            )
+            .unwrap()
+        } else {
+            label.trim().to_string()
+        };
+
+        game.set_checkpoint(&label);
+        self.action_set_enabled("game-view.undo-checkpoint", true);
+        self.action_set_enabled("game-view.jump-checkpoint", true);
+        self.imp()
+            .menu_button
+            .set_checkpoints(&game.get_checkpoint_labels());
+        self.show_toast(&gettext("Checkpoint set"));
+        self.autosave(game);
+    }
+
+    fn set_named_checkpoint_action(&self) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(
+            Some(&gettext("Set Checkpoint")),
+            Some(&gettext(
+                "Give the checkpoint a name to find it later in the main menu.",
+            )),
+        );
+        let entry: gtk::Entry = gtk::Entry::builder()
+            .placeholder_text(gettext("Checkpoint Name"))
+            .activates_default(true)
+            .build();
+
+        dialog.set_extra_child(Some(&entry));
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("set", &gettext("_Set"));
+        dialog.set_response_appearance("set", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("set"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = mself)]
+                self,
+                #[weak]
+                entry,
+                move |_w, response_id| {
+                    if response_id != "set" {
+                        return;
+                    }
+                    let label: String = entry.text().to_string();
+                    shared_game::with_game_mut(
+                        mself
+                            .imp()
+                            .game
+                            .get()
+                            .expect("Cannot retrieve the game data from the object"),
+                        clone!(
+                            #[weak]
+                            mself,
+                            move |game: &mut Game| {
+                                if !game.solved && !game.paused {
+                                    mself.add_checkpoint(game, &label);
+                                }
+                            }
+                        ),
+                    );
+                }
+            ),
+        );
+        dialog.present(Some(&window));
+    }
+
+    /// Save the game in progress in a named slot, so that the player can continue it later from
+    /// the Saved Games dialog.
+    fn save_slot_action(&self) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(
+            Some(&gettext("Save Game As")),
+            Some(&gettext(
+                "Give the game a name to continue it later from the Saved Games dialog. A saved \
+                 game with the same name is replaced.",
+            )),
+        );
+        let entry: gtk::Entry = gtk::Entry::builder()
+            .placeholder_text(gettext("Game Name"))
+            .text(self.export_file_stem())
+            .activates_default(true)
+            .build();
+
+        dialog.set_extra_child(Some(&entry));
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("save", &gettext("_Save"));
+        dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("save"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = mself)]
+                self,
+                #[weak]
+                entry,
+                move |_w, response_id| {
+                    if response_id != "save" {
+                        return;
+                    }
+                    let Some(saver) = SaverGame::new_slot(glib::user_data_dir(), &entry.text())
+                    else {
+                        mself.show_toast(&gettext("Invalid game name"));
+                        return;
+                    };
+                    let Some(game) = shared_game::try_read(
+                        mself
+                            .imp()
+                            .game
+                            .get()
+                            .expect("Cannot retrieve the game data from the object"),
+                    ) else {
+                        mself.show_toast(&gettext("Cannot save the game"));
+                        return;
+                    };
+                    match saver.save_game(&game) {
+                        Ok(()) => mself.show_toast(&gettext("Game saved")),
+                        Err(error) if error.is::<TooManySlots>() => {
+                            mself.show_toast(&gettext(
+                                "Too many saved games. Delete some saved games first",
+                            ));
+                        }
+                        Err(error) => {
+                            debug!("Error saving the game: {error}");
+                            mself.show_toast(&gettext("Cannot save the game"));
+                        }
+                    }
+                }
+            ),
+        );
+        dialog.present(Some(&window));
+    }
+
+    /// Copy the seed code of the current puzzle to the clipboard, so that players can share it.
+    fn copy_seed_action(&self) {
+        let seed: Option<SeedCode> = shared_game::try_read(
+            self.imp()
+                .game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        )
+        .and_then(|game| game.seed_code());
+
+        if let Some(seed) = seed {
+            self.clipboard().set_text(&seed.to_string());
+            self.show_toast(&gettext("Seed copied to the clipboard"));
         }
     }
 
-    // Load the high score boards from the disk
-    fn get_highscores(&self) -> HighScores {
-        let saver: SaverHighScores = SaverHighScores::new(glib::user_data_dir());
-        if let Ok(o) = saver.get_highscores() {
-            if let Some(h) = o {
-                return h;
+    /// Ask for a seed code and start a new game of the current puzzle with that code.
+    ///
+    /// The seed code includes the clue options (revealed endpoints, negative clues, and sum
+    /// regions), so that the same code on the same puzzle produces the same path and clues,
+    /// whatever the clue preferences.
+    ///
+    /// The dialog also accepts the share codes, which the QR codes encode, to start the game on
+    /// the puzzle that the code names.
+    fn new_game_seed_action(&self) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(
+            Some(&gettext("New Game by Seed")),
+            Some(&gettext(
+                "Enter the seed of a puzzle to play the same puzzle again, or enter the code \
+                 that another player shared.",
+            )),
+        );
+        let entry: gtk::Entry = gtk::Entry::builder()
+            .placeholder_text(gettext("Seed"))
+            .activates_default(true)
+            .build();
+
+        dialog.set_extra_child(Some(&entry));
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("play", &gettext("_Play"));
+        dialog.set_response_appearance("play", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("play"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = mself)]
+                self,
+                #[weak]
+                entry,
+                move |_w, response_id| {
+                    if response_id != "play" {
+                        return;
+                    }
+                    let text: glib::GString = entry.text();
+                    if let Ok(share_code) = text.parse::<ShareCode>() {
+                        mself.play_share_code(share_code);
+                        return;
+                    }
+                    let Ok(seed) = text.parse::<SeedCode>() else {
+                        mself.show_toast(&gettext("The seed is not valid"));
+                        return;
+                    };
+                    let Some(puzzle) = shared_game::try_read(
+                        mself
+                            .imp()
+                            .game
+                            .get()
+                            .expect("Cannot retrieve the game data from the object"),
+                    )
+                    .map(|game| game.puzzle.clone()) else {
+                        return;
+                    };
+                    mself.set_puzzle(puzzle, Some(seed));
+                }
+            ),
+        );
+        dialog.present(Some(&window));
+    }
+
+    /// Select a QR code image that Hexkudo saved, and start the game of its share code.
+    fn new_game_qr_code_action(&self) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let filter: gtk::FileFilter = gtk::FileFilter::new();
+        filter.set_name(Some(&gettext("PNG Images")));
+        filter.add_mime_type("image/png");
+        let filters: gio::ListStore = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let file_dialog: gtk::FileDialog = gtk::FileDialog::builder()
+            .title(gettext("Open QR Code"))
+            .filters(&filters)
+            .modal(true)
+            .build();
+        file_dialog.open(
+            Some(&window),
+            gio::Cancellable::NONE,
+            clone!(
+                #[weak(rename_to = mself)]
+                self,
+                move |result| {
+                    if let Ok(file) = result {
+                        mself.open_qr_code(&file);
+                    }
+                }
+            ),
+        );
+    }
+
+    /// Decode the QR code in the given image file, and start the game of its share code.
+    fn open_qr_code(&self, file: &gio::File) {
+        let texture: gdk::Texture = match gdk::Texture::from_file(file) {
+            Ok(t) => t,
+            Err(error) => {
+                debug!("Cannot load the {} image: {error}", file.uri());
+                self.show_toast(&gettext("Cannot open the image"));
+                return;
+            }
+        };
+
+        // Read the pixels as 8-bit RGBA values
+        let mut downloader: gdk::TextureDownloader = gdk::TextureDownloader::new(&texture);
+        downloader.set_format(gdk::MemoryFormat::R8g8b8a8);
+        let (pixels, stride) = downloader.download_bytes();
+        let is_dark = |x: usize, y: usize| {
+            let pixel: &[u8] = &pixels[y * stride + x * 4..][..4];
+            let luminance: u32 =
+                (299 * pixel[0] as u32 + 587 * pixel[1] as u32 + 114 * pixel[2] as u32) / 1000;
+            // The transparent pixels belong to the light background
+            pixel[3] >= 128 && luminance < 128
+        };
+
+        let text: Option<String> =
+            qr_decode::decode(texture.width() as usize, texture.height() as usize, is_dark);
+        match text.as_deref().map(str::parse::<ShareCode>) {
+            Some(Ok(share_code)) => self.play_share_code(share_code),
+            _ => {
+                debug!("No share code in the {} image: {text:?}", file.uri());
+                self.show_toast(&gettext("The image is not a QR code that Hexkudo saved"));
             }
-        } else {
-            // Delete the file in error for trying to resolve the issue for the next start
-            saver.delete_save();
         }
-        HighScores::new()
     }
 
-    fn print_current_action(&self) {
+    /// Start the game that the given share code describes.
+    fn play_share_code(&self, share_code: ShareCode) {
+        let Some(puzzle) = puzzles::puzzle_map().remove(&(share_code.name, share_code.difficulty))
+        else {
+            self.show_toast(&gettext("The puzzle of this code is not available"));
+            return;
+        };
+        self.set_puzzle(puzzle, Some(share_code.code));
+    }
+
+    fn undo_checkpoint_action(&self) {
+        self.confirm_checkpoint_revert(
+            &gettext("Undo to Last Checkpoint?"),
+            &gettext("Are you sure that you want to undo all actions since the last checkpoint?"),
+            None,
+        );
+    }
+
+    /// Revert back to the checkpoint given as parameter.
+    fn jump_checkpoint_action(&self, parameter: Option<&glib::Variant>) {
+        let Some(index) = parameter
+            .and_then(|p| p.get::<i32>())
+            .and_then(|i| usize::try_from(i).ok())
+        else {
+            return;
+        };
+        let Some(label) = shared_game::try_read(
+            self.imp()
+                .game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        )
+        .and_then(|game| game.get_checkpoint_labels().get(index).cloned()) else {
+            return;
+        };
+
+        self.confirm_checkpoint_revert(
+            &gettext("Jump to Checkpoint?"),
+            &formatx!(
+                gettext(
+                    "Are you sure that you want to undo all actions since the “{label}” checkpoint?"
+                ),
+                label = label
+            )
+            .unwrap(),
+            Some(index),
+        );
+    }
+
+    /// Ask for confirmation, and then revert back to the checkpoint at the given index, or to
+    /// the last checkpoint.
+    fn confirm_checkpoint_revert(&self, heading: &str, body: &str, index: Option<usize>) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(Some(heading), Some(body));
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("undo", &gettext("Undo"));
+        dialog.set_response_appearance("undo", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = mself)]
+                self,
+                move |_w, response_id| {
+                    if response_id == "undo" {
+                        mself.revert_checkpoint(index);
+                    }
+                }
+            ),
+        );
+        dialog.present(Some(&window));
+    }
+
+    /// Revert back to the checkpoint at the given index, or to the last checkpoint.
+    fn revert_checkpoint(&self, index: Option<usize>) {
         let imp: &imp::HexkudoGameView = self.imp();
-        let game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            move |mself: &Self| mself.revert_checkpoint(index),
+        ) else {
+            return;
+        };
 
-        if !game.paused {
-            self.action_set_enabled("game-view.print-current", false);
-            self.action_set_enabled("app.print-multiple", false);
-            imp.drawing_area.print_current();
-            self.action_set_enabled("game-view.print-current", true);
-            self.action_set_enabled("app.print-multiple", true);
+        match index {
+            Some(i) => game.jump_to_checkpoint(i),
+            None => game.undo_checkpoint(),
         }
+        imp.menu_button
+            .set_checkpoints(&game.get_checkpoint_labels());
+        self.action_set_enabled("game-view.undo-checkpoint", game.checkpoints_len() > 0);
+        self.action_set_enabled("game-view.jump-checkpoint", game.checkpoints_len() > 0);
+        self.action_set_enabled("game-view.undo", game.player_input.undo_len() > 0);
+        self.action_set_enabled("game-view.redo", game.player_input.redo_len() > 0);
+        self.update_missing_value_widgets(&game);
+        self.check_solvability(&game);
+        imp.drawing_area.queue_draw();
     }
 
-    fn zoom_out_action(&self) {
-        let imp = self.imp();
-        let current_zoom: draw::ZoomLevel = self.zoom_level();
-        let new_zoom: draw::ZoomLevel = current_zoom.zoom_out();
-
-        if new_zoom != current_zoom {
-            self.set_zoom_level(new_zoom);
-            if new_zoom.is_fully_zoomed_out() {
-                self.action_set_enabled("game-view.zoom-out", false);
-            } else {
-                self.action_set_enabled("game-view.zoom-out", true);
+    /// Select the cell given as parameter.
+    pub fn select_cell_action(&self, parameter: Option<&glib::Variant>) {
+        if !self.automation_actions() {
+            debug!("Automation actions are disabled: ignoring select-cell");
+            return;
+        }
+        let imp: &imp::HexkudoGameView = self.imp();
+        let retry_parameter: Option<glib::Variant> = parameter.cloned();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            move |mself: &Self| mself.select_cell_action(retry_parameter.as_ref()),
+        ) else {
+            return;
+        };
+        let cell_id: usize = match parameter.and_then(|p| p.get::<i32>()) {
+            Some(c) if c >= 0 && (c as usize) < game.puzzle.matrix.vertexes.num_vertexes => {
+                c as usize
             }
-            self.action_set_enabled("game-view.zoom-in", true);
-            imp.drawing_area.set_zoom_level(new_zoom);
+            _ => return,
+        };
+
+        if game.started && !game.paused {
+            game.set_selected_cell(Some(cell_id));
+            self.hide_popover();
             imp.drawing_area.queue_draw();
         }
     }
 
-    fn zoom_in_action(&self) {
+    /// Set the value of a cell. The parameter is the cell ID and the value. A zero value clears
+    /// the cell.
+    pub fn set_cell_action(&self, parameter: Option<&glib::Variant>) {
+        if !self.automation_actions() {
+            debug!("Automation actions are disabled: ignoring set-cell");
+            return;
+        }
         let imp: &imp::HexkudoGameView = self.imp();
-        let current_zoom: draw::ZoomLevel = self.zoom_level();
-        let new_zoom: draw::ZoomLevel = current_zoom.zoom_in();
-
-        if new_zoom != current_zoom {
-            self.set_zoom_level(new_zoom);
-            if new_zoom.is_fully_zoomed_in() {
-                self.action_set_enabled("game-view.zoom-in", false);
-            } else {
-                self.action_set_enabled("game-view.zoom-in", true);
+        let retry_parameter: Option<glib::Variant> = parameter.cloned();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            move |mself: &Self| mself.set_cell_action(retry_parameter.as_ref()),
+        ) else {
+            return;
+        };
+        let num_vertexes: usize = game.puzzle.matrix.vertexes.num_vertexes;
+        let (cell_id, value): (usize, usize) = match parameter.and_then(|p| p.get::<(i32, i32)>()) {
+            Some((c, v))
+                if c >= 0
+                    && (c as usize) < num_vertexes
+                    && v >= 0
+                    && (v as usize) <= num_vertexes =>
+            {
+                (c as usize, v as usize)
             }
-            self.action_set_enabled("game-view.zoom-out", true);
-            imp.drawing_area.set_zoom_level(new_zoom);
-            imp.drawing_area.queue_draw();
+            _ => return,
+        };
+
+        if !game.started || game.solved || game.paused {
+            return;
+        }
+        if value == 0 {
+            self.remove_cell_value(game.deref_mut(), cell_id);
+        } else {
+            self.set_cell_value(game.deref_mut(), cell_id, value);
         }
+        imp.drawing_area.queue_draw();
     }
 
-    fn undo_action(&self) {
+    fn solve_current_cell_action(&self) {
         let imp: &imp::HexkudoGameView = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
-
-        if !game.solved && !game.paused {
-            let player_input = &mut game.player_input;
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::solve_current_cell_action,
+        ) else {
+            return;
+        };
 
-            player_input.undo();
-            self.action_set_enabled("game-view.undo", player_input.undo_len() > 0);
-            self.action_set_enabled("game-view.redo", player_input.redo_len() > 0);
+        let unlimited: bool = self.unlimited_assist();
+        if !game.solved
+            && !game.paused
+            && (unlimited || game.solve_cell_budget() > 0)
+            && let Some((cid, value)) = game.get_selected_cell_value()
+            && !game.is_given(cid)
+        {
+            game.user_has_cheated = true;
+            if !unlimited {
+                game.use_solve_cell_budget();
+            }
+            self.set_cell_value(game.deref_mut(), cid, value);
+            self.update_solve_cell_budget(!game.solved, &game);
             self.hide_popover();
             imp.drawing_area.queue_draw();
         }
     }
 
-    fn redo_action(&self) {
+    /// Enable the Solve Current Cell action while the game has some budget left, and display
+    /// that budget in the main menu. The budget does not apply when the player enables the
+    /// unlimited assistance.
+    fn update_solve_cell_budget(&self, sensitive: bool, game: &Game) {
+        let budget: Option<usize> = if self.unlimited_assist() {
+            None
+        } else {
+            Some(game.solve_cell_budget())
+        };
+
+        self.action_set_enabled(
+            "game-view.solve-current-cell",
+            sensitive && budget != Some(0),
+        );
+        self.imp().menu_button.set_solve_cell_budget(budget);
+    }
+
+    fn solve_puzzle_action(&self) {
         let imp: &imp::HexkudoGameView = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::solve_puzzle_action,
+        ) else {
+            return;
+        };
 
         if !game.solved && !game.paused {
-            let player_input = &mut game.player_input;
-
-            player_input.redo();
-            self.action_set_enabled("game-view.undo", player_input.undo_len() > 0);
-            self.action_set_enabled("game-view.redo", player_input.redo_len() > 0);
+            game.solve();
+            self.check_completed(game.deref_mut());
+            self.update_missing_value_widgets(&game);
+            self.check_solvability(&game);
             self.hide_popover();
             imp.drawing_area.queue_draw();
         }
     }
 
-    fn reset_puzzle_action(&self) {
+    /// Reveal an additional hint cell, which the solver chooses in a background task.
+    fn reveal_hint_action(&self) {
         let imp: &imp::HexkudoGameView = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(game) = shared_game::try_read(
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        ) else {
+            return;
+        };
 
-        if !game.paused {
-            game.reset();
-            self.sensitive(true, &game);
-            self.action_set_enabled("game-view.pause-resume", true);
-            imp.drawing_area.queue_draw();
+        if game.solved || game.paused || !self.practice_mode() {
+            return;
+        }
+        let constraints: Constraints = Constraints::from_game(&game);
+        let (values, candidates) = game.get_hint_candidates();
+        let path: Vec<usize> = game.path.get().clone();
+        drop(game);
+        if candidates.is_empty() {
+            return;
         }
+
+        // Prevent a second request while the solver is running
+        self.action_set_enabled("game-view.reveal-hint", false);
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let cell_id: Option<usize> = gio::spawn_blocking(move || {
+                    let timeout: Duration =
+                        REVEAL_HINT_TIMEOUT / u32::try_from(candidates.len()).unwrap_or(u32::MAX);
+                    constraints.most_informative_cell(&values, &candidates, timeout)
+                })
+                .await
+                .unwrap_or_default();
+
+                obj.action_set_enabled("game-view.reveal-hint", obj.practice_mode());
+                if let Some(cell_id) = cell_id {
+                    obj.reveal_cell(cell_id, path);
+                }
+            }
+        ));
     }
 
-    fn set_checkpoint_action(&self) {
+    /// Reveal the given cell as an additional hint, unless the player started another game in
+    /// the meantime. The `path` parameter is the solution path of the game.
+    fn reveal_cell(&self, cell_id: usize, path: Vec<usize>) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let obj: HexkudoGameView = self.clone();
+
+        shared_game::with_game_mut(
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            move |game| {
+                if game.solved
+                    || game.paused
+                    || *game.path.get() != path
+                    || !game.reveal_cell(cell_id)
+                {
+                    return;
+                }
+                let imp: &imp::HexkudoGameView = obj.imp();
+                imp.drawing_area.set_path_from_diamonds_and_map(
+                    &game.path,
+                    &game.diamonds,
+                    &game.map,
+                    &game.anti_diamonds,
+                    &game.regions,
+                );
+                obj.action_set_enabled("game-view.undo", game.player_input.undo_len() > 0);
+                obj.action_set_enabled("game-view.redo", game.player_input.redo_len() > 0);
+                obj.check_completed(game);
+                obj.update_missing_value_widgets(game);
+                obj.check_solvability(game);
+                obj.hide_popover();
+                imp.drawing_area.queue_draw();
+            },
+        );
+    }
+
+    /// Select a cell whose value can be deduced, and explain the deduction.
+    ///
+    /// When the `hint-fills-cell` GSettings key is set, the value is also placed in the cell.
+    /// The first [`hint::FREE_HINTS`] hints do not make the game assisted.
+    fn hint_action(&self) {
         let imp: &imp::HexkudoGameView = self.imp();
-        let mut game = imp
-            .game
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::hint_action,
+        ) else {
+            return;
+        };
+
+        if game.solved || game.paused {
+            return;
+        }
+        let Some(forced) = game.find_forced_cell() else {
+            self.show_toast(&gettext(
+                "No cell can be deduced from the correct values. Try fixing the mistakes",
+            ));
+            return;
+        };
+
+        let fill: bool = imp
+            .settings
             .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+            .expect("Cannot retrieve the settings from the object")
+            .boolean("hint-fills-cell");
+        let assisted: bool = game.use_hint();
+        game.set_selected_cell(Some(forced.cell_id));
+        if fill {
+            self.set_cell_value(game.deref_mut(), forced.cell_id, forced.value);
+        }
+        self.hide_popover();
+        imp.drawing_area.queue_draw();
+        drop(game);
 
-        if !game.solved && !game.paused {
-            game.set_checkpoint();
-            self.action_set_enabled("game-view.undo-checkpoint", true);
-            let toast: adw::Toast = adw::Toast::new(&gettext("Checkpoint set"));
-            toast.set_timeout(2);
-            imp.toast_overlay.add_toast(toast);
+        self.show_toast(&Self::hint_message(&forced, fill));
+        if assisted {
+            self.show_assisted_toast();
         }
     }
 
-    fn undo_checkpoint_action(&self) {
-        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
-        let dialog: adw::AlertDialog = adw::AlertDialog::new(
-            Some(&gettext("Undo to Last Checkpoint?")),
-            Some(&gettext(
-                "Are you sure that you want to undo all actions since the last checkpoint?",
-            )),
-        );
-        dialog.add_response("cancel", &gettext("Cancel"));
-        dialog.add_response("undo", &gettext("Undo"));
-        dialog.set_response_appearance("undo", adw::ResponseAppearance::Destructive);
-        dialog.set_default_response(Some("cancel"));
-        dialog.set_close_response("cancel");
-        dialog.connect_response(
-            None,
-            glib::clone!(
-                #[weak(rename_to = mself)]
-                self,
-                move |_w, response_id| {
-                    if response_id == "undo" {
-                        let imp: &imp::HexkudoGameView = mself.imp();
-                        let mut game = imp
-                            .game
-                            .get()
-                            .expect("Cannot retrieve the game data from the object")
-                            .borrow_mut();
-                        game.undo_checkpoint();
-                        mself.action_set_enabled(
-                            "game-view.undo-checkpoint",
-                            game.checkpoints_len() > 0,
-                        );
-                        mself
-                            .action_set_enabled("game-view.undo", game.player_input.undo_len() > 0);
-                        mself
-                            .action_set_enabled("game-view.redo", game.player_input.redo_len() > 0);
-                        imp.drawing_area.queue_draw();
-                    }
-                }
-            ),
+    /// Notify the player that the game is now assisted because of the hints.
+    fn show_assisted_toast(&self) {
+        self.show_toast(
+            &formatx!(
+                ngettext(
+                    "You used more than {count} hint: the game is now assisted",
+                    "You used more than {count} hints: the game is now assisted",
+                    hint::FREE_HINTS as u32,
+                ),
+                count = hint::FREE_HINTS
+            )
+            .unwrap(),
         );
-        dialog.present(Some(&window));
     }
 
-    fn solve_current_cell_action(&self) {
+    /// Highlight the area around the next forced cell, without revealing the cell or its value.
+    /// The highlight counts as a hint, and disappears when the player completes a cell.
+    fn guided_reveal_action(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::guided_reveal_action,
+        ) else {
+            return;
+        };
+
+        if game.solved || game.paused {
+            return;
+        }
+        let Some(forced) = game.find_forced_cell() else {
+            self.show_toast(&gettext(
+                "No cell can be deduced from the correct values. Try fixing the mistakes",
+            ));
+            return;
+        };
+
+        let mut area: Vec<usize> =
+            regions::adjacent_cells(&game.puzzle.matrix.vertexes, forced.cell_id);
+        area.push(forced.cell_id);
+        let assisted: bool = game.use_hint();
+        drop(game);
+
+        imp.drawing_area.set_highlighted_cells(area);
+        if assisted {
+            self.show_assisted_toast();
+        }
+    }
+
+    /// Return the explanation of a hint. The value is only given when the hint fills the cell.
+    fn hint_message(hint: &Hint, fill: bool) -> String {
+        match (hint.reason, fill) {
+            (hint::Reason::OnlyCell, true) => formatx!(
+                gettext("{value} cannot go in any other cell"),
+                value = hint.value
+            )
+            .unwrap(),
+            (hint::Reason::OnlyValue, true) => formatx!(
+                gettext("No other missing number than {value} fits in this cell"),
+                value = hint.value
+            )
+            .unwrap(),
+            (hint::Reason::OnlyCell, false) => {
+                gettext("A missing number cannot go in any other cell than the selected one")
+            }
+            (hint::Reason::OnlyValue, false) => {
+                gettext("Only one of the missing numbers fits in the selected cell")
+            }
+        }
+    }
+
+    fn show_warnings_action(&self) {
+        self.imp().drawing_area.switch_warnings();
+    }
+
+    fn heat_map_action(&self) {
+        if self.imp().drawing_area.switch_heat_map() {
+            self.show_toast(&gettext("Green cells were fast, red cells were slow"));
+        }
+    }
+
+    fn show_duplicates_action(&self) {
+        self.imp().drawing_area.switch_duplicates();
+    }
+
+    fn toggle_notes_action(&self) {
+        if self.imp().drawing_area.switch_notes_mode() {
+            self.show_toast(&gettext("Notes mode on"));
+        } else {
+            self.show_toast(&gettext("Notes mode off"));
+        }
+    }
+
+    fn revert_cell_action(&self) {
         let imp: &imp::HexkudoGameView = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::revert_cell_action,
+        ) else {
+            return;
+        };
 
         if !game.solved
             && !game.paused
-            && let Some((cid, value)) = game.get_selected_cell_value()
+            && let Some(cell_id) = game.get_selected_cell()
+            && game.revert_cell(cell_id)
         {
-            game.user_has_cheated = true;
-            self.set_cell_value(game.deref_mut(), cid, value);
+            self.action_set_enabled("game-view.undo", true);
+            self.action_set_enabled("game-view.redo", false);
+            self.check_completed(game.deref_mut());
+            self.update_missing_value_widgets(&game);
+            self.check_solvability(&game);
             self.hide_popover();
             imp.drawing_area.queue_draw();
         }
     }
 
-    fn solve_puzzle_action(&self) {
+    fn complete_chains_action(&self) {
         let imp: &imp::HexkudoGameView = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::complete_chains_action,
+        ) else {
+            return;
+        };
 
         if !game.solved && !game.paused {
-            game.user_has_cheated = true;
-            game.player_input.clear();
-            for (i, cid) in game.path.get().clone().iter().enumerate() {
-                game.player_input.add_no_undo(*cid, i + 1);
+            if game.complete_chains() > 0 {
+                self.action_set_enabled("game-view.undo", true);
+                self.action_set_enabled("game-view.redo", false);
+                self.check_completed(game.deref_mut());
+                self.update_missing_value_widgets(&game);
+                self.check_solvability(&game);
+                self.announce_remaining(&game, false);
+            } else {
+                self.show_toast(&gettext("No chain can be completed"));
             }
-            self.check_completed(game.deref_mut());
             self.hide_popover();
             imp.drawing_area.queue_draw();
         }
     }
 
-    fn show_warnings_action(&self) {
-        self.imp().drawing_area.switch_warnings();
-    }
+    fn announce_remaining_action(&self) {
+        let Some(game) = shared_game::try_read(
+            self.imp()
+                .game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        ) else {
+            return;
+        };
 
-    fn show_duplicates_action(&self) {
-        self.imp().drawing_area.switch_duplicates();
+        self.announce_remaining(&game, true);
     }
 
     fn pause_resume_action(&self) {
-        let mut game = self
-            .imp()
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let imp: &imp::HexkudoGameView = self.imp();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::pause_resume_action,
+        ) else {
+            return;
+        };
 
         if !game.solved {
             if game.paused {
@@ -647,39 +2823,189 @@ impl HexkudoGameView {
         self.imp().drawing_area.hide_popover();
     }
 
+    /// Adapt the game view to the width of the window. When the window is narrow, the quick
+    /// action buttons and the number pad panel are hidden, and the number pad replaces the
+    /// number picker popover.
+    pub fn set_compact(&self, compact: bool) {
+        let imp: &imp::HexkudoGameView = self.imp();
+
+        imp.compact.set(compact);
+        imp.menu_button.set_compact(compact);
+        imp.drawing_area.set_compact(compact);
+        self.update_number_pad_visibility();
+    }
+
+    fn update_number_pad_visibility(&self) {
+        let imp: &imp::HexkudoGameView = self.imp();
+
+        imp.number_pad
+            .set_visible(self.show_number_pad() && !imp.compact.get());
+    }
+
     fn sensitive(&self, sensitive: bool, game: &Game) {
         self.imp().drawing_area.set_sensitive(sensitive);
+        self.imp().number_pad.set_sensitive(sensitive);
         self.action_set_enabled("game-view.set-checkpoint", sensitive);
-        self.action_set_enabled("game-view.solve-current-cell", sensitive);
+        self.update_solve_cell_budget(sensitive, game);
+        self.action_set_enabled("game-view.hint", sensitive);
         self.action_set_enabled("game-view.solve-puzzle", sensitive);
+        self.action_set_enabled("game-view.reveal-hint", sensitive && self.practice_mode());
         self.action_set_enabled("game-view.reset-puzzle", sensitive);
         self.action_set_enabled("game-view.print-current", sensitive);
-        self.action_set_enabled("game-view.show_warnings", sensitive);
-        self.action_set_enabled("game-view.show_duplicates", sensitive);
+        self.action_set_enabled("game-view.export-replay", sensitive);
+        self.action_set_enabled("game-view.heat-map", sensitive && game.solved);
+        self.action_set_enabled("game-view.export-puzzle", sensitive);
+        self.action_set_enabled("game-view.save-image", sensitive);
+        self.action_set_enabled("game-view.show-warnings", sensitive && !self.mystery_mode());
+        self.action_set_enabled(
+            "game-view.show-duplicates",
+            sensitive && !self.mystery_mode(),
+        );
+        self.action_set_enabled("game-view.toggle-notes", sensitive);
+        self.action_set_enabled("game-view.announce-remaining", sensitive);
+        self.action_set_enabled("game-view.complete-chains", sensitive);
+        self.action_set_enabled("game-view.revert-cell", sensitive);
+        self.action_set_enabled(
+            "game-view.copy-seed",
+            sensitive && game.seed_code().is_some(),
+        );
+        self.action_set_enabled("game-view.new-game-seed", sensitive);
+        self.action_set_enabled("game-view.new-game-qr-code", sensitive);
+        self.action_set_enabled("game-view.save-slot", sensitive);
+        self.action_set_enabled("game-view.export-save", sensitive);
+        self.action_set_enabled("game-view.history", sensitive);
         if sensitive {
             self.action_set_enabled("game-view.undo", game.player_input.undo_len() > 0);
             self.action_set_enabled("game-view.redo", game.player_input.redo_len() > 0);
             self.action_set_enabled("game-view.undo-checkpoint", game.checkpoints_len() > 0);
+            self.action_set_enabled("game-view.jump-checkpoint", game.checkpoints_len() > 0);
         } else {
             self.action_set_enabled("game-view.undo", false);
             self.action_set_enabled("game-view.redo", false);
             self.action_set_enabled("game-view.undo-checkpoint", false);
+            self.action_set_enabled("game-view.jump-checkpoint", false);
         }
+        self.action_set_enabled("game-view.set-named-checkpoint", sensitive);
+        self.imp()
+            .menu_button
+            .set_checkpoints(&game.get_checkpoint_labels());
     }
 
-    fn set_title(&self, name: &str, difficulty: puzzles::Difficulty) {
-        self.imp().window_title.set_subtitle(
-            &formatx!(
+    fn set_title(&self, name: &str, difficulty: puzzles::Difficulty, seed: Option<SeedCode>) {
+        let subtitle: String = match seed {
+            Some(seed) => formatx!(
+                gettext("{puzzle_name} {difficulty} Difficulty, Seed {seed}"),
+                puzzle_name = name,
+                difficulty = difficulty,
+                seed = seed
+            )
+            .unwrap(),
+            None => formatx!(
                 gettext("{puzzle_name} {difficulty} Difficulty"),
                 puzzle_name = name,
                 difficulty = difficulty
             )
-            .unwrap()
-            .to_string(),
+            .unwrap(),
+        };
+        self.imp().window_title.set_subtitle(&subtitle);
+    }
+
+    /// Return the GSettings key that stores the zoom level for boards of the given number of
+    /// cells.
+    ///
+    /// The zoom level is stored per board size class, so that players do not have to zoom again
+    /// each time they switch between small and large puzzles.
+    fn zoom_key(num_cells: usize) -> &'static str {
+        if num_cells < 30 {
+            "zoom-level-small-boards"
+        } else if num_cells < 50 {
+            "zoom-level-medium-boards"
+        } else {
+            "zoom-level-large-boards"
+        }
+    }
+
+    /// Restore the zoom level that the player selected for boards of the given size.
+    fn restore_zoom_level(&self, num_cells: usize) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let settings: &gio::Settings = imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object");
+        let key: &'static str = Self::zoom_key(num_cells);
+        let zoom_level: draw::ZoomLevel = draw::ZoomLevel::from_repr(settings.enum_(key))
+            .expect("Cannot get the current zoom level");
+
+        imp.zoom_key.set(key);
+        self.set_zoom_level(zoom_level);
+        imp.drawing_area.set_zoom_level(zoom_level);
+        self.enable_zoom_actions();
+    }
+
+    /// Apply the settings that the player overrode for the given difficulty level. The other
+    /// settings come back to their global values, which a previous game might have overridden.
+    fn apply_difficulty_defaults(&self, difficulty: Difficulty) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let settings: &gio::Settings = imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object");
+        let defaults: DifficultyDefaults = DifficultyDefaults::load(settings, difficulty);
+
+        imp.drawing_area.set_highlights(
+            defaults
+                .show_warnings
+                .unwrap_or_else(|| settings.boolean("show-warnings")),
+            defaults
+                .show_duplicates
+                .unwrap_or_else(|| settings.boolean("show-duplicates")),
         );
+        if let Some(zoom_level) = defaults.zoom_level {
+            // Zooming during the game does not change the zoom level for the board size
+            imp.zoom_key.set("");
+            self.set_zoom_level(zoom_level);
+            imp.drawing_area.set_zoom_level(zoom_level);
+            self.enable_zoom_actions();
+        }
+    }
+
+    /// Hide the mistake counter, the points, and the timer, and stop highlighting the mistakes,
+    /// for a zen game. Otherwise, display the widgets that the settings enable.
+    fn apply_zen_mode(&self, zen: bool) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let settings: &gio::Settings = imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object");
+
+        imp.error_box
+            .set_visible(!zen && settings.boolean("show-errors"));
+        imp.points_box
+            .set_visible(!zen && settings.boolean("points-scoring"));
+        imp.clock_box
+            .set_visible(!zen && settings.boolean("show-timer"));
+        if zen {
+            imp.drawing_area.set_highlights(false, false);
+        }
+    }
+
+    /// Save the zoom level for the size of the current board.
+    fn save_zoom_level(&self, zoom_level: draw::ZoomLevel) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let settings: &gio::Settings = imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object");
+        let key: &'static str = imp.zoom_key.get();
+
+        if !key.is_empty() {
+            let _ = settings.set_enum(key, zoom_level as i32);
+        }
     }
 
-    fn enable_zoom_actions(&self) {
+    /// Enable or disable the zoom actions depending on the zoom level of the numbers and on the
+    /// magnification of the board.
+    pub fn enable_zoom_actions(&self) {
         let zoom_level: draw::ZoomLevel = self.zoom_level();
 
         if zoom_level.is_fully_zoomed_out() {
@@ -692,6 +3018,10 @@ impl HexkudoGameView {
         } else {
             self.action_set_enabled("game-view.zoom-in", true);
         }
+        self.action_set_enabled(
+            "game-view.zoom-fit",
+            zoom_level != draw::ZoomLevel::Auto || self.imp().drawing_area.is_magnified(),
+        );
     }
 
     fn set_background_css(&self, css_str: &str) {
@@ -718,154 +3048,317 @@ impl HexkudoGameView {
         }
     }
 
-    pub fn display_scores(&self, highlight_position: Option<usize>) {
+    /// Display the scoreboards of the current puzzle.
+    ///
+    /// The `assisted` parameter selects the scoreboard of the games where the player got help.
+    pub fn display_scores(&self, highlight_position: Option<usize>, assisted: bool) {
         let imp: &imp::HexkudoGameView = self.imp();
         let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
         let puzzle_list = imp
             .puzzle_list
             .get()
             .expect("Cannot retrieve the puzzle list from the object");
-        let game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow();
-        let (puzzle_name, difficulty) = if game.puzzle.name.is_empty() {
-            (String::from("Classic"), puzzles::Difficulty::Easy)
+        let Some(game) = shared_game::try_read(
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        ) else {
+            return;
+        };
+        let (puzzle_name, difficulty, endpoints) = if game.puzzle.name.is_empty() {
+            (
+                String::from("Classic"),
+                puzzles::Difficulty::Easy,
+                diamond_and_map::Endpoints::Both,
+            )
         } else {
-            (game.puzzle.name.clone(), game.puzzle.difficulty)
+            (
+                game.puzzle.name.clone(),
+                game.puzzle.difficulty,
+                game.get_endpoints(),
+            )
         };
-        let scores: HexkudoScoresDialog =
-            HexkudoScoresDialog::new(puzzle_list, &self.get_highscores());
+        let scores: HexkudoScoresDialog = HexkudoScoresDialog::new(
+            imp.settings
+                .get()
+                .expect("Cannot retrieve the settings from the object"),
+            puzzle_list,
+            &self.get_highscores(),
+        );
 
-        scores.select_puzzle(&puzzle_name, difficulty, highlight_position);
+        scores.select_puzzle(
+            &puzzle_name,
+            difficulty,
+            endpoints,
+            assisted,
+            highlight_position,
+        );
         scores.present(Some(&window));
     }
 
+    pub fn display_statistics(&self) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let stats: HexkudoStatsDialog = HexkudoStatsDialog::new(
+            self.imp()
+                .puzzle_list
+                .get()
+                .expect("Cannot retrieve the puzzle list from the object"),
+            &self.get_statistics(),
+        );
+
+        stats.present(Some(&window));
+    }
+
     pub fn continue_game(&self) {
         let imp: &imp::HexkudoGameView = self.imp();
-        let mut game = imp
-            .game
-            .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            Self::continue_game,
+        ) else {
+            return;
+        };
 
         game.puzzle
             .matrix
             .build_edges()
             .expect("The puzzle definition has an error");
+        game.player_input.set_max_undo(self.undo_depth() as usize);
 
-        self.set_title(&game.puzzle.name_i18n[..], game.puzzle.difficulty);
+        self.set_title(
+            &game.puzzle.name_i18n[..],
+            game.puzzle.difficulty,
+            game.seed_code(),
+        );
         imp.drawing_area.init_puzzle(&mut game.puzzle);
-        imp.drawing_area
-            .set_path_from_diamonds_and_map(&game.path, &game.diamonds, &game.map);
+        imp.number_pad.set_puzzle(&game.puzzle);
+        imp.drawing_area.set_path_from_diamonds_and_map(
+            &game.path,
+            &game.diamonds,
+            &game.map,
+            &game.anti_diamonds,
+            &game.regions,
+        );
 
-        self.enable_zoom_actions();
+        self.restore_zoom_level(game.puzzle.matrix.vertexes.num_vertexes);
+        self.apply_difficulty_defaults(game.puzzle.difficulty);
+        self.apply_zen_mode(game.zen);
+        self.load_ghost(&game);
         self.set_background_css(game.puzzle.colors.get_bg_css());
         self.sensitive(true, &game);
         imp.spinner.set_visible(false);
         if game.paused {
             self.pause(&mut game);
+        } else {
+            game.start_clock();
         }
         self.update_error_widget(game.get_errors());
+        self.update_points_widget(game.get_points());
+        self.update_streak_widget(&game);
+        self.update_missing_value_widgets(&game);
+        self.check_solvability(&game);
+        self.prepare_next_game(&game.puzzle);
     }
 
-    pub fn set_puzzle(&self, mut puzzle: puzzles::Puzzle) {
-        let imp: &imp::HexkudoGameView = self.imp();
-        let (sender, receiver) =
-            async_channel::bounded::<(path::Path, diamond_and_map::DiamondAndMap)>(1);
+    /// Return the parameters that the generation of a game for the given puzzle depends on.
+    fn generation_key(&self, puzzle: &puzzles::Puzzle) -> CacheKey {
+        let settings: &gio::Settings = self
+            .imp()
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object");
+
+        // Endpoints of the path to reveal. The predefined games, which are used when the
+        // generation takes too long, always reveal both endpoints.
+        CacheKey {
+            name: puzzle.name.clone(),
+            difficulty: puzzle.difficulty,
+            endpoints: diamond_and_map::Endpoints::from_repr(settings.enum_("revealed-endpoints"))
+                .unwrap_or_default(),
+            negative_clues: settings.boolean("negative-clues"),
+            strict_diamonds: settings.boolean("strict-diamonds"),
+        }
+    }
 
-        imp.spinner.set_visible(true);
-        self.sensitive(
-            false,
-            &imp.game
+    /// Generate the next game for the given puzzle in the background, so that the New Game
+    /// (Same Puzzle) action does not have to wait for the generation.
+    fn prepare_next_game(&self, puzzle: &puzzles::Puzzle) {
+        if self.imp().energy_saver.get() {
+            debug!("Energy saver: the next game is not generated in the background");
+            return;
+        }
+        let key: CacheKey = self.generation_key(puzzle);
+        let pool: Vec<puzzles::PuzzleSampleGame> = self.pool_samples(puzzle);
+
+        self.imp()
+            .generation_cache
+            .borrow_mut()
+            .prepare(key, puzzle, pool);
+    }
+
+    // Retrieve the sample pool from the sample pool file
+    fn get_sample_pool(&self) -> SamplePool {
+        let saver: SaverSamplePool = SaverSamplePool::new(glib::user_data_dir());
+        if let Ok(o) = saver.get_sample_pool() {
+            if let Some(p) = o {
+                return p;
+            }
+        } else {
+            // Delete the file in error for trying to resolve the issue for the next start
+            saver.delete_save();
+        }
+        SamplePool::new()
+    }
+
+    /// Return the games from the sample pool that can be used as fallback for the given puzzle.
+    /// The list is empty when the player does not collect the generated games.
+    fn pool_samples(&self, puzzle: &puzzles::Puzzle) -> Vec<puzzles::PuzzleSampleGame> {
+        if !self
+            .imp()
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object")
+            .boolean("grow-sample-pool")
+        {
+            return Vec::new();
+        }
+        self.get_sample_pool()
+            .get(&puzzle.name, puzzle.difficulty, &puzzle.matrix.vertexes)
+    }
+
+    // Add a generated game to the sample pool, if the player collects the generated games
+    fn add_pool_sample(&self, puzzle: &puzzles::Puzzle, sample: puzzles::PuzzleSampleGame) {
+        if !self
+            .imp()
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object")
+            .boolean("grow-sample-pool")
+        {
+            return;
+        }
+
+        let mut sample_pool: SamplePool = self.get_sample_pool();
+        if !sample_pool.add(&puzzle.name, puzzle.difficulty, sample) {
+            return;
+        }
+        let saver: SaverSamplePool = SaverSamplePool::new(glib::user_data_dir());
+        if let Err(error) = saver.save_sample_pool(&sample_pool) {
+            debug!("Error saving the sample pool: {error}");
+        }
+    }
+
+    /// Start a game with the given puzzle. The seed code drives the random generation of the
+    /// path and clues, so that the same code produces the same puzzle instance, whatever the
+    /// clue preferences.
+    ///
+    /// When None is provided, the game that the generation cache prepared for the puzzle is
+    /// used if it is ready. Otherwise, a game is generated with a random seed and the clue
+    /// preferences.
+    pub fn set_puzzle(&self, mut puzzle: puzzles::Puzzle, seed_code: Option<SeedCode>) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let retry_puzzle: puzzles::Puzzle = puzzle.clone();
+        let Some(mut game) = shared_game::try_write_or_retry(
+            self,
+            imp.game
                 .get()
-                .expect("Cannot retrieve the game data from the object")
-                .borrow_mut(),
-        );
+                .expect("Cannot retrieve the game data from the object"),
+            move |mself: &Self| mself.set_puzzle(retry_puzzle, seed_code),
+        ) else {
+            return;
+        };
+        let mut key: CacheKey = self.generation_key(&puzzle);
+        let options: SeedOptions = match seed_code {
+            Some(code) => {
+                key.endpoints = code.options.endpoints;
+                key.negative_clues = code.options.negative_clues;
+                key.strict_diamonds = code.options.strict_diamonds;
+                code.options
+            }
+            None => SeedOptions {
+                endpoints: key.endpoints,
+                negative_clues: key.negative_clues,
+                strict_diamonds: key.strict_diamonds,
+                sum_regions: self.sum_regions(),
+            },
+        };
+        let cached: Option<generation_cache::GeneratedGame> = match seed_code {
+            Some(_) => None,
+            None => imp.generation_cache.borrow_mut().take(&key),
+        };
+        let seed: u64 = match (&cached, seed_code) {
+            (Some(c), _) => c.seed,
+            (None, Some(code)) => code.seed,
+            (None, None) => random_path::random_seed(),
+        };
+        let (sender, receiver) = async_channel::bounded::<generation_cache::GeneratedGame>(1);
+
+        imp.spinner.set_visible(cached.is_none());
+        self.sensitive(false, &game);
 
         puzzle
             .matrix
             .build_edges()
             .expect("The puzzle definition has an error");
-        self.set_title(&puzzle.name_i18n[..], puzzle.difficulty);
+        self.set_title(&puzzle.name_i18n[..], puzzle.difficulty, None);
         self.update_error_widget(0);
+        self.update_points_widget(0);
+        imp.streak_label.set_visible(false);
+        imp.next_number_button.set_visible(false);
+        imp.last_number_button.set_visible(false);
+        imp.unsolvable_banner.set_revealed(false);
+        imp.solvability_serial.set(imp.solvability_serial.get() + 1);
+        imp.displayed_streak.set(0);
+        imp.announced_remaining.set(0);
+        imp.drawing_area.hide_heat_map();
+
+        // The player leaves the game in progress for a new game
+        if game.started && !game.solved && game.get_progress().0 > 0 {
+            self.record_statistics(&game, false);
+        }
 
         imp.drawing_area.init_puzzle(&mut puzzle);
-        imp.game
+        game.set_puzzle(&puzzle);
+        game.zen = imp
+            .settings
             .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut()
-            .set_puzzle(&puzzle);
-
+            .expect("Cannot retrieve the settings from the object")
+            .boolean("zen-mode");
+        game.player_input.set_max_undo(self.undo_depth() as usize);
+        drop(game);
+
+        // The pool differs between computers, so the pool samples are not used for the games
+        // that must reproduce a seed code
+        let pool: Vec<puzzles::PuzzleSampleGame> = if cached.is_some() || seed_code.is_some() {
+            Vec::new()
+        } else {
+            self.pool_samples(&puzzle)
+        };
         glib::spawn_future_local(clone!(
             #[strong]
             sender,
             #[strong]
             puzzle,
             async move {
-                let (path, m_and_d) = gio::spawn_blocking(move || {
-                    let mut random_path: random_path::RandomPath =
-                        random_path::RandomPath::new(&puzzle.matrix.edges, &puzzle.matrix.vertexes);
-
-                    // Retrieve a path, map, and diamond from the puzzle's list in case the process
-                    // that generates the puzzle or the diamonds takes too long
-                    let random: puzzles::PuzzleSampleGame = (puzzle.get_sample_path_fn)();
-                    let path: path::Path = path::Path::from_vec(&random.path);
-                    let path_len: usize = path.len();
-                    let path_first: usize = path
-                        .get_first()
-                        .expect("Cannot retrieve the first cell in the path");
-                    let path_last: usize = path
-                        .get_last()
-                        .expect("Cannot retrieve the last cell in the path");
-
-                    // Generate a random path
-                    match random_path.generate(None) {
-                        Err(_) =>
-                        // Too long, the generating process gave up
-                        {
-                            debug!("Too long (path)");
-                            (
-                                path,
-                                diamond_and_map::DiamondAndMap::from_vec(
-                                    &random.diamonds,
-                                    &random.map,
-                                    path_len,
-                                    path_first,
-                                    path_last,
-                                ),
-                            )
-                        }
-                        Ok(p) => {
-                            // Generate diamonds and map
-                            let mut diamonds: diamonds::Diamond =
-                                diamonds::Diamond::new(&random_path.edges, &p);
-                            match diamonds.generate_diamonds(&puzzle.matrix.vertexes) {
-                                Err(_) =>
-                                // Too long, the generating process gave up
-                                {
-                                    debug!("Too long (diamonds and map)");
-                                    (
-                                        path,
-                                        diamond_and_map::DiamondAndMap::from_vec(
-                                            &random.diamonds,
-                                            &random.map,
-                                            path_len,
-                                            path_first,
-                                            path_last,
-                                        ),
-                                    )
-                                }
-                                Ok(m_and_d) => (p, m_and_d),
-                            }
-                        }
-                    }
-                })
-                .await
-                .expect("Task needs to finish successfully");
+                let generated: generation_cache::GeneratedGame = match cached {
+                    Some(c) => c,
+                    None => gio::spawn_blocking(move || {
+                        generation_cache::generate(
+                            &puzzle,
+                            seed,
+                            key.endpoints,
+                            key.negative_clues,
+                            key.strict_diamonds,
+                            &pool,
+                        )
+                    })
+                    .await
+                    .expect("Task needs to finish successfully"),
+                };
                 sender
-                    .send((path, m_and_d))
+                    .send(generated)
                     .await
                     .expect("The channel needs to be open");
             }
@@ -877,61 +3370,190 @@ impl HexkudoGameView {
             #[weak(rename_to = mself)]
             self,
             async move {
-                while let Ok(path_and_diamonds) = receiver.recv().await {
-                    let mut game = imp
-                        .game
-                        .get()
-                        .expect("Cannot retrieve the game data from the object")
-                        .borrow_mut();
-                    let (path, diamond_and_map) = path_and_diamonds;
-
-                    game.set_path(&path, &diamond_and_map);
-                    imp.drawing_area.set_path(&path, &diamond_and_map);
-                    imp.spinner.set_visible(false);
-                    mself.sensitive(true, &game);
-                    mself.action_set_enabled("game-view.pause-resume", true);
+                while let Ok(generated) = receiver.recv().await {
+                    let generation_cache::GeneratedGame {
+                        path,
+                        diamond_and_map,
+                        sample,
+                        reproducible,
+                        ..
+                    } = generated;
+
+                    shared_game::with_game_mut(
+                        imp.game
+                            .get()
+                            .expect("Cannot retrieve the game data from the object"),
+                        clone!(
+                            #[weak]
+                            imp,
+                            #[weak]
+                            mself,
+                            move |game: &mut Game| {
+                                game.set_path(&path, &diamond_and_map);
+                                // The seed is not shown when it cannot reproduce the game
+                                game.seed = reproducible.then_some(seed);
+                                game.seed_options = Some(options);
+                                mself.set_title(
+                                    &game.puzzle.name_i18n[..],
+                                    game.puzzle.difficulty,
+                                    game.seed_code(),
+                                );
+                                mself.load_ghost(game);
+                                if options.sum_regions {
+                                    game.regions = regions::generate(
+                                        &path,
+                                        &game.puzzle.matrix.vertexes,
+                                        seed,
+                                    );
+                                }
+                                imp.drawing_area
+                                    .set_path(&path, &diamond_and_map, &game.regions);
+                                imp.spinner.set_visible(false);
+                                mself.sensitive(true, game);
+                                mself.action_set_enabled("game-view.pause-resume", true);
+                                mself.prepare_next_game(&game.puzzle);
+                                if let Some(s) = sample {
+                                    mself.add_pool_sample(&game.puzzle, s);
+                                }
+                            }
+                        ),
+                    );
                 }
             }
         ));
 
-        self.enable_zoom_actions();
+        self.restore_zoom_level(puzzle.matrix.vertexes.num_vertexes);
+        self.apply_difficulty_defaults(puzzle.difficulty);
+        self.apply_zen_mode(
+            imp.settings
+                .get()
+                .expect("Cannot retrieve the settings from the object")
+                .boolean("zen-mode"),
+        );
         self.action_set_enabled("game-view.undo", false);
         self.action_set_enabled("game-view.redo", false);
         self.set_background_css(puzzle.colors.get_bg_css());
     }
 
     pub fn remove_cell_value(&self, game: &mut Game, cell_id: usize) {
+        if game.is_given(cell_id) {
+            return;
+        }
         game.remove_value_from_cell(cell_id);
         self.action_set_enabled("game-view.undo", true);
         self.action_set_enabled("game-view.redo", false);
         self.update_error_widget(game.get_errors());
+        self.update_points_widget(game.get_points());
+        self.update_streak_widget(game);
+        self.update_missing_value_widgets(game);
+        self.check_solvability(game);
     }
 
     pub fn set_cell_value(&self, game: &mut Game, cell_id: usize, cell_value: usize) {
+        if game.is_given(cell_id) {
+            return;
+        }
         game.add_value_to_cell(cell_id, cell_value);
+        if game.is_cell_error(cell_id, cell_value) {
+            self.imp().drawing_area.flash_wrong_entry(cell_id);
+        }
+        if self.auto_complete_chains() {
+            game.complete_chains();
+        }
         self.action_set_enabled("game-view.undo", true);
         self.action_set_enabled("game-view.redo", false);
         self.check_completed(game);
         self.update_error_widget(game.get_errors());
+        self.update_points_widget(game.get_points());
+        self.update_streak_widget(game);
+        self.update_missing_value_widgets(game);
+        self.check_solvability(game);
+        self.announce_remaining(game, false);
+    }
+
+    /// Announce the number of cells that remain to complete to the screen readers.
+    ///
+    /// Unless `forced` is set, the announcement is only made when the number of remaining cells
+    /// reaches a multiple of the interval that the `announce-remaining` GSettings key defines.
+    fn announce_remaining(&self, game: &Game, forced: bool) {
+        let imp: &imp::HexkudoGameView = self.imp();
+        let (completed, to_complete) = game.get_progress();
+        let remaining: usize = to_complete.saturating_sub(completed);
+
+        if !forced {
+            let interval: usize = imp
+                .settings
+                .get()
+                .expect("Cannot retrieve the settings from the object")
+                .uint("announce-remaining") as usize;
+
+            // Do not repeat the announcement when the player clears and sets a cell again
+            if interval == 0
+                || remaining == 0
+                || !remaining.is_multiple_of(interval)
+                || remaining == imp.announced_remaining.get()
+            {
+                return;
+            }
+        }
+        imp.announced_remaining.set(remaining);
+        imp.drawing_area.announce(
+            &formatx!(
+                ngettext(
+                    "{count} cell remaining",
+                    "{count} cells remaining",
+                    remaining as u32
+                ),
+                count = remaining
+            )
+            .unwrap(),
+            gtk::AccessibleAnnouncementPriority::Medium,
+        );
     }
 
     pub fn play_again(&self) {
-        let puzzle = self
+        let Some(game) = shared_game::try_write_or_retry(
+            self,
+            self.imp()
+                .game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+            |mself: &Self| mself.play_again(),
+        ) else {
+            return;
+        };
+        let puzzle: puzzles::Puzzle = game.puzzle.clone();
+        drop(game);
+
+        self.set_puzzle(puzzle, None);
+    }
+
+    /// Start a game with the given puzzle, through the same actions as the puzzle selection
+    /// view.
+    fn start_challenge(&self, puzzle_name: &str, difficulty: Difficulty) {
+        let settings: &gio::Settings = self
             .imp()
-            .game
+            .settings
             .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow()
-            .puzzle
-            .clone();
+            .expect("Cannot retrieve the settings from the object");
 
-        self.set_puzzle(puzzle);
+        // The puzzle selection view selects the puzzle from the `puzzle` GSettings
+        if let Err(error) = settings.set_string("puzzle", puzzle_name) {
+            debug!("Cannot select the {puzzle_name} puzzle: {error}");
+            return;
+        }
+        self.activate_action("app.select-puzzle", Some(&(difficulty as i32).to_variant()))
+            .expect("Cannot activate the action to move to the puzzle selection view");
+        self.activate_action("app.start-game", None)
+            .expect("Cannot activate the action to move to the game view");
     }
 
     fn check_completed(&self, game: &mut Game) {
         if !game.is_solved() {
             return;
         }
+        // Completion time, before loading the scoreboards from the disk
+        let completed: SystemTime = SystemTime::now();
         let imp: &imp::HexkudoGameView = self.imp();
 
         game.started = false;
@@ -940,39 +3562,204 @@ impl HexkudoGameView {
         // Allow rerunning and printing the puzzle
         self.action_set_enabled("game-view.reset-puzzle", true);
         self.action_set_enabled("game-view.print-current", true);
+        self.action_set_enabled("game-view.export-replay", true);
+        self.action_set_enabled("game-view.heat-map", true);
+        self.action_set_enabled("game-view.export-puzzle", true);
+        self.action_set_enabled("game-view.save-image", true);
 
-        let clock_visible: bool = imp.clock_box.is_visible();
+        // The zen games do not go to the scoreboards
+        let clock_visible: bool = imp.clock_box.is_visible() && !game.zen;
         let mut highscore_position: Option<usize> = None;
         let mut highscores: HighScores = self.get_highscores();
 
-        if clock_visible && !game.user_has_cheated {
+        // The games where the player asked for hints or cell values go to the assisted
+        // scoreboards
+        let assisted: bool = game.user_has_cheated;
+        // The games with hidden endpoints have their own scoreboards
+        let endpoints: diamond_and_map::Endpoints = game.get_endpoints();
+
+        if !game.auto_solved {
+            self.record_statistics(game, true);
+        }
+
+        // Do not offer to continue the completed game after a crash
+        SaverGame::new(glib::user_data_dir()).delete_save();
+
+        if clock_visible && !game.auto_solved {
+            let settings: &gio::Settings = imp
+                .settings
+                .get()
+                .expect("Cannot retrieve the settings from the object");
+            let record_history: bool = settings.boolean("record-history");
+            let score: Score = Score::new(
+                game.get_duration(),
+                game.get_errors(),
+                settings
+                    .boolean("points-scoring")
+                    .then(|| game.get_points()),
+                completed,
+            );
+            if record_history {
+                highscores.add_history(
+                    &game.puzzle.name,
+                    game.puzzle.difficulty,
+                    endpoints,
+                    assisted,
+                    game.puzzle.matrix.vertexes.num_vertexes,
+                    &score,
+                );
+            }
             highscore_position = highscores.add_score(
                 &game.puzzle.name,
                 game.puzzle.difficulty,
-                game.get_duration(),
-                game.get_errors(),
+                endpoints,
+                assisted,
+                score,
             );
+            if !assisted {
+                highscores.update_best_splits(
+                    &game.puzzle.name,
+                    game.puzzle.difficulty,
+                    endpoints,
+                    game.get_splits(),
+                );
+                if highscore_position == Some(1) {
+                    highscores.set_best_timeline(
+                        &game.puzzle.name,
+                        game.puzzle.difficulty,
+                        endpoints,
+                        game.get_timeline(),
+                    );
+                }
+                if record_history && let Some(milestone) = game.get_first_milestone() {
+                    highscores.add_milestone(
+                        &game.puzzle.name,
+                        game.puzzle.difficulty,
+                        endpoints,
+                        milestone,
+                    );
+                }
+            }
             // Update the clock one more time to ensure that it displays the same value as the
             // high score board
-            let (h, m, s) = game.get_duration_hms();
-            Self::update_clock_widget(imp, h, m, s);
-            if highscore_position.is_some() {
-                let saver: SaverHighScores = SaverHighScores::new(glib::user_data_dir());
-                match saver.save_highscores(&highscores) {
-                    Ok(()) => (),
-                    Err(error) => {
-                        debug!("Error saving high scores: {error}");
-                        // Delete the file in error for trying to resolve the issue for the next start
-                        saver.delete_save();
-                    }
+            Self::update_clock_widget(imp, game.get_duration());
+
+            // Always save the scoreboards, which also count the games played
+            let saver: SaverHighScores =
+                SaverHighScores::new(glib::user_data_dir(), &player_profiles::current(settings));
+            match saver.save_highscores(&highscores) {
+                Ok(()) => (),
+                Err(error) => {
+                    debug!("Error saving high scores: {error}");
+                    // Delete the file in error for trying to resolve the issue for the next start
+                    saver.delete_save();
                 }
             }
+
+            // Post the result to the online leaderboard, only when the player opted in. The
+            // online leaderboard only ranks the games with both endpoints revealed.
+            let leaderboard_url: glib::GString = settings.string("leaderboard-url");
+            if !assisted
+                && endpoints == diamond_and_map::Endpoints::Both
+                && settings.boolean("leaderboard-enabled")
+                && !leaderboard_url.is_empty()
+            {
+                let result: GameResult = GameResult::new(
+                    &game.puzzle.name,
+                    game.puzzle.difficulty,
+                    game.get_duration(),
+                    game.get_errors(),
+                );
+                glib::spawn_future_local(async move {
+                    if let Err(error) = network::post_result(&leaderboard_url, &result).await {
+                        debug!("Error posting the result to the leaderboard: {error}");
+                    }
+                });
+            }
         }
 
-        let done_dialog: HexkudoDoneDialog =
-            HexkudoDoneDialog::new(game.user_has_cheated, clock_visible, highscore_position);
+        let done_dialog: HexkudoDoneDialog = HexkudoDoneDialog::new(
+            game.user_has_cheated,
+            clock_visible,
+            highscore_position,
+            game.get_pauses(),
+            self.mystery_mode()
+                .then(|| (game.get_errors(), game.get_error_numbers())),
+            imp.drawing_area.board_texture(game, DONE_THUMBNAIL_SIZE),
+        );
         let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
 
+        if imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object")
+            .boolean("points-scoring")
+        {
+            done_dialog.set_points(game.get_points());
+        }
+
+        // Suggest a puzzle that the player has not beaten yet
+        let next_challenge: Option<(String, Difficulty)> = highscores
+            .next_challenge(
+                imp.puzzle_list
+                    .get()
+                    .expect("Cannot retrieve the puzzle list from the object"),
+                &game.puzzle.name,
+                game.puzzle.difficulty,
+            )
+            .map(|p| {
+                done_dialog.set_next_challenge(p);
+                (p.name.clone(), p.difficulty)
+            });
+
+        // Suggest a harder or an easier game depending on the recent games at that difficulty
+        let suggestion: Option<(String, Difficulty)> = recommendation::Thresholds::default()
+            .suggest(highscores.get_history(), game.puzzle.difficulty)
+            .and_then(|s| {
+                recommendation::suggested_puzzle(
+                    s,
+                    imp.puzzle_list
+                        .get()
+                        .expect("Cannot retrieve the puzzle list from the object"),
+                    &game.puzzle,
+                )
+                .map(|p| (s, p))
+            })
+            .filter(|(_, p)| {
+                next_challenge
+                    .as_ref()
+                    .is_none_or(|(name, difficulty)| *name != p.name || *difficulty != p.difficulty)
+            })
+            .map(|(s, p)| {
+                done_dialog.set_suggestion(s, p);
+                (p.name.clone(), p.difficulty)
+            });
+
+        // Players can share a proof string for their time. Other installations regenerate the
+        // puzzle from its share code, so the games without a share code have no proof string.
+        // The games that older versions started have an incomplete move log, which does not
+        // replay.
+        let proof: Option<String> =
+            if clock_visible && !game.user_has_cheated && game.share_code().is_some() {
+                let p: Proof = Proof::new(game);
+                p.verify_moves().is_ok().then(|| {
+                    done_dialog.add_response("copy-proof", &gettext("Copy _Proof"));
+                    p.to_string()
+                })
+            } else {
+                None
+            };
+
+        // Players can share a short summary of their game
+        done_dialog.add_response("copy-summary", &gettext("Copy _Summary"));
+        let summary_text: String = summary::summary(
+            game,
+            imp.settings
+                .get()
+                .expect("Cannot retrieve the settings from the object")
+                .boolean("summary-emoji-grid"),
+        );
+
         done_dialog.connect_response(
             None,
             glib::clone!(
@@ -981,6 +3768,22 @@ impl HexkudoGameView {
                 move |_w, response_id| {
                     if response_id == "play-again" {
                         obj.play_again();
+                    } else if response_id == "next-challenge"
+                        && let Some((name, difficulty)) = &next_challenge
+                    {
+                        obj.start_challenge(name, *difficulty);
+                    } else if response_id == "suggestion"
+                        && let Some((name, difficulty)) = &suggestion
+                    {
+                        obj.start_challenge(name, *difficulty);
+                    } else if response_id == "copy-proof"
+                        && let Some(p) = &proof
+                    {
+                        obj.clipboard().set_text(p);
+                        obj.show_toast(&gettext("Proof copied to the clipboard"));
+                    } else if response_id == "copy-summary" {
+                        obj.clipboard().set_text(&summary_text);
+                        obj.show_toast(&gettext("Summary copied to the clipboard"));
                     }
                 }
             ),
@@ -992,9 +3795,12 @@ impl HexkudoGameView {
                 #[weak(rename_to = obj)]
                 self,
                 move |_w| {
-                    obj.display_scores(highscore_position);
+                    obj.display_scores(highscore_position, assisted);
                 }
             ));
-        done_dialog.present(Some(&window));
+
+        // Trace the solution path before congratulating the player
+        imp.drawing_area
+            .trace_solution(move || done_dialog.present(Some(&window)));
     }
 }
@@ -0,0 +1,172 @@
+/*
+history_dialog.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Dialog for browsing the history of the moves.
+//!
+//! The dialog lists the steps of the undo and redo lists, and lets the player bring the board
+//! back to the state after any of these steps.
+
+use formatx::formatx;
+use gettextrs::{gettext, ngettext};
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib;
+
+use super::game_view::HexkudoGameView;
+use crate::player_input::HistoryStep;
+
+mod imp {
+    use super::*;
+    use std::cell::OnceCell;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/herve4m/Hexkudo/ui/history_dialog.ui")]
+    pub struct HexkudoHistoryDialog {
+        pub game_view: OnceCell<HexkudoGameView>,
+
+        // Template widgets
+        #[template_child]
+        pub history_list: TemplateChild<gtk::ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HexkudoHistoryDialog {
+        const NAME: &'static str = "HexkudoHistoryDialog";
+        type Type = super::HexkudoHistoryDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HexkudoHistoryDialog {}
+    impl WidgetImpl for HexkudoHistoryDialog {}
+    impl AdwDialogImpl for HexkudoHistoryDialog {}
+}
+
+glib::wrapper! {
+    pub struct HexkudoHistoryDialog(ObjectSubclass<imp::HexkudoHistoryDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+#[gtk::template_callbacks]
+impl HexkudoHistoryDialog {
+    /// Create the dialog.
+    ///
+    /// The `steps` parameter provides the history of the moves, and `current` the number of
+    /// steps that are not undone.
+    pub fn new(game_view: &HexkudoGameView, steps: &[HistoryStep], current: usize) -> Self {
+        let obj: HexkudoHistoryDialog = glib::Object::builder().build();
+        let imp: &imp::HexkudoHistoryDialog = obj.imp();
+
+        imp.game_view
+            .set(game_view.clone())
+            .expect("Cannot store the game view in the object");
+
+        // The first row brings the board back to the state before the oldest step
+        imp.history_list.append(&Self::build_row(
+            &gettext("Oldest State"),
+            None,
+            current == 0,
+            false,
+        ));
+        for (i, step) in steps.iter().enumerate() {
+            imp.history_list.append(&Self::build_row(
+                &Self::step_label(step),
+                Self::step_time(step),
+                current == i + 1,
+                step.undone,
+            ));
+        }
+
+        obj
+    }
+
+    /// Return the description of a step.
+    fn step_label(step: &HistoryStep) -> String {
+        if step.cells > 1 {
+            formatx!(
+                ngettext(
+                    "Filled {count} cell",
+                    "Filled {count} cells",
+                    step.cells as u32
+                ),
+                count = step.cells
+            )
+            .unwrap()
+        } else if step.removed {
+            formatx!(gettext("Removed {value}"), value = step.cell_value).unwrap()
+        } else {
+            formatx!(gettext("Placed {value}"), value = step.cell_value).unwrap()
+        }
+    }
+
+    /// Return the local time of a step, or None if the time is unknown.
+    fn step_time(step: &HistoryStep) -> Option<String> {
+        if step.time == 0 {
+            return None;
+        }
+        glib::DateTime::from_unix_local(step.time as i64)
+            .and_then(|t| t.format("%X"))
+            .map(|t| t.to_string())
+            .ok()
+    }
+
+    /// Build a row of the list. The row of the current state has a check mark, and the rows of
+    /// the undone steps are dimmed.
+    fn build_row(title: &str, time: Option<String>, current: bool, undone: bool) -> adw::ActionRow {
+        let row: adw::ActionRow = adw::ActionRow::builder()
+            .title(title)
+            .activatable(!current)
+            .build();
+
+        if let Some(t) = time {
+            row.set_subtitle(&t);
+        }
+        if current {
+            row.add_suffix(&gtk::Image::from_icon_name("object-select-symbolic"));
+        }
+        if undone {
+            row.add_css_class("dim-label");
+        }
+        row
+    }
+
+    /// Callback for the rows. Bring the board back to the state after the selected step.
+    #[template_callback]
+    fn row_activated_cb(&self, row: &gtk::ListBoxRow) {
+        let Ok(step) = usize::try_from(row.index()) else {
+            return;
+        };
+        if let Some(game_view) = self.imp().game_view.get() {
+            game_view.jump_to_history(step);
+        }
+        self.close();
+    }
+}
@@ -0,0 +1,187 @@
+/*
+image_dialog.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Dialog for saving the puzzle as a PNG image or as a PDF document, or for saving the QR code
+//! that starts the same game.
+
+use gettextrs::gettext;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::{gio, glib};
+
+use super::game_view::HexkudoGameView;
+
+/// File format of the saved puzzle, in the order of the format selector.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// PNG image of the given size.
+    Png,
+
+    /// PDF document, rendered through the print operation.
+    Pdf,
+
+    /// PNG image of the QR code of the share code, for the generated games only.
+    QrCode,
+}
+
+impl ImageFormat {
+    /// Return the extension of the files in that format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png | ImageFormat::QrCode => "png",
+            ImageFormat::Pdf => "pdf",
+        }
+    }
+}
+
+mod imp {
+    use super::*;
+    use std::cell::OnceCell;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/herve4m/Hexkudo/ui/image_dialog.ui")]
+    pub struct HexkudoImageDialog {
+        pub game_view: OnceCell<HexkudoGameView>,
+
+        // Template widgets
+        #[template_child]
+        pub image_format: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub image_size: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub image_solution: TemplateChild<adw::SwitchRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HexkudoImageDialog {
+        const NAME: &'static str = "HexkudoImageDialog";
+        type Type = super::HexkudoImageDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HexkudoImageDialog {}
+    impl WidgetImpl for HexkudoImageDialog {}
+    impl AdwDialogImpl for HexkudoImageDialog {}
+}
+
+glib::wrapper! {
+    pub struct HexkudoImageDialog(ObjectSubclass<imp::HexkudoImageDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+#[gtk::template_callbacks]
+impl HexkudoImageDialog {
+    /// Create the dialog.
+    pub fn new(settings: &gio::Settings, game_view: &HexkudoGameView) -> Self {
+        let obj: HexkudoImageDialog = glib::Object::builder().build();
+        let imp: &imp::HexkudoImageDialog = obj.imp();
+
+        // GSettings bindings
+        let image_size_adj: gtk::Adjustment = imp.image_size.adjustment();
+        let image_solution: adw::SwitchRow = imp.image_solution.get();
+        settings
+            .bind("image-size", &image_size_adj, "value")
+            .build();
+        settings
+            .bind("image-solution", &image_solution, "active")
+            .build();
+
+        // Only the generated games have a share code to encode as a QR code
+        if !game_view.has_share_code()
+            && let Some(formats) = imp.image_format.model().and_downcast::<gtk::StringList>()
+        {
+            formats.remove(2);
+        }
+
+        imp.game_view
+            .set(game_view.clone())
+            .expect("Cannot store the game view in the object");
+
+        obj
+    }
+
+    /// Callback for the format selector. The size only applies to the PNG images, and the QR
+    /// code does not show the solution.
+    #[template_callback]
+    fn format_cb(&self) {
+        let imp: &imp::HexkudoImageDialog = self.imp();
+        let format: ImageFormat = self.format();
+
+        imp.image_size.set_sensitive(format != ImageFormat::Pdf);
+        imp.image_solution
+            .set_sensitive(format != ImageFormat::QrCode);
+    }
+
+    /// Return the selected file format.
+    fn format(&self) -> ImageFormat {
+        match self.imp().image_format.selected() {
+            1 => ImageFormat::Pdf,
+            2 => ImageFormat::QrCode,
+            _ => ImageFormat::Png,
+        }
+    }
+
+    /// Callback for the Save button
+    #[template_callback]
+    fn save_cb(&self, _button: &gtk::Button) {
+        let imp: &imp::HexkudoImageDialog = self.imp();
+        let format: ImageFormat = self.format();
+        let size: i32 = imp.image_size.adjustment().value() as i32;
+        let solution: bool = imp.image_solution.is_active();
+        let game_view: HexkudoGameView = imp
+            .game_view
+            .get()
+            .expect("Cannot retrieve the game view from the object")
+            .clone();
+        let window: Option<gtk::Window> = game_view.root().and_downcast::<gtk::Window>();
+
+        // Close the dialog
+        self.close();
+
+        let file_dialog: gtk::FileDialog = gtk::FileDialog::builder()
+            .title(gettext("Save as Image"))
+            .initial_name(format!(
+                "{}.{}",
+                game_view.export_file_stem(),
+                format.extension()
+            ))
+            .modal(true)
+            .build();
+        file_dialog.save(window.as_ref(), gio::Cancellable::NONE, move |result| {
+            if let Ok(file) = result
+                && let Some(path) = file.path()
+            {
+                game_view.save_image(&path, format, size, solution);
+            }
+        });
+    }
+}
@@ -25,7 +25,53 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! Manage the menu buttons.
 
 use adw::subclass::prelude::*;
-use gtk::glib;
+use formatx::formatx;
+use gettextrs::{gettext, ngettext};
+use glib::clone;
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use std::cell::Cell;
+
+/// Action of the Solve Current Cell menu item.
+const SOLVE_CELL_ACTION: &str = "game-view.solve-current-cell";
+
+/// Return the action, the icon name, and the tooltip of the quick action button with the given
+/// name, as listed in the `quick-actions` GSettings key, or None if the name is unknown.
+fn quick_action(name: &str) -> Option<(&'static str, &'static str, String)> {
+    match name {
+        "hint" => Some((
+            "game-view.hint",
+            "edit-find-symbolic",
+            gettext("Find a Forced Cell"),
+        )),
+        "set-checkpoint" => Some((
+            "game-view.set-checkpoint",
+            "bookmark-new-symbolic",
+            gettext("Set Checkpoint"),
+        )),
+        "pause-resume" => Some((
+            "game-view.pause-resume",
+            "media-playback-pause-symbolic",
+            gettext("Pause or Resume"),
+        )),
+        "solve-current-cell" => Some((
+            SOLVE_CELL_ACTION,
+            "emblem-ok-symbolic",
+            gettext("Solve Current Cell"),
+        )),
+        "complete-chains" => Some((
+            "game-view.complete-chains",
+            "view-continuous-symbolic",
+            gettext("Complete Chains"),
+        )),
+        "history" => Some((
+            "game-view.history",
+            "document-open-recent-symbolic",
+            gettext("Jump to a Previous Move"),
+        )),
+        _ => None,
+    }
+}
 
 mod imp {
     use super::*;
@@ -40,6 +86,15 @@ mod imp {
         pub menu_fullscreen_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub menu_unfullscreen_button: TemplateChild<gtk::Button>,
+        #[template_child(id = "checkpoints-section")]
+        pub checkpoints_section: TemplateChild<gio::Menu>,
+        #[template_child(id = "assist-section")]
+        pub assist_section: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub quick_actions_box: TemplateChild<gtk::Box>,
+
+        /// Whether the window is too narrow to display the quick action buttons.
+        pub compact: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -69,6 +124,65 @@ glib::wrapper! {
 }
 
 impl HexkudoMenuButton {
+    /// Display the quick action buttons that the `quick-actions` GSettings key lists, and
+    /// rebuild them when the key changes. Only the game view displays these buttons.
+    pub fn init_quick_actions(&self, settings: &gio::Settings) {
+        self.set_quick_actions(&settings.strv("quick-actions"));
+        settings.connect_changed(
+            Some("quick-actions"),
+            clone!(
+                #[weak(rename_to = mself)]
+                self,
+                move |settings, key| {
+                    mself.set_quick_actions(&settings.strv(key));
+                }
+            ),
+        );
+    }
+
+    /// Rebuild the quick action buttons, in the given order. The unknown and the duplicated
+    /// names are ignored.
+    fn set_quick_actions(&self, names: &glib::StrV) {
+        let quick_actions_box: gtk::Box = self.imp().quick_actions_box.get();
+        let mut added: Vec<&str> = Vec::new();
+
+        while let Some(child) = quick_actions_box.first_child() {
+            quick_actions_box.remove(&child);
+        }
+        for name in names.iter() {
+            let name: &str = name.as_str();
+            if added.contains(&name) {
+                continue;
+            }
+            let Some((action, icon_name, tooltip)) = quick_action(name) else {
+                continue;
+            };
+            let button: gtk::Button = gtk::Button::builder()
+                .icon_name(icon_name)
+                .action_name(action)
+                .tooltip_text(tooltip)
+                .focus_on_click(false)
+                .build();
+            quick_actions_box.append(&button);
+            added.push(name);
+        }
+        self.update_quick_actions_visibility();
+    }
+
+    /// Hide the quick action buttons when the window is narrow. The actions stay available
+    /// from the menu and through their keyboard shortcuts.
+    pub fn set_compact(&self, compact: bool) {
+        self.imp().compact.set(compact);
+        self.update_quick_actions_visibility();
+    }
+
+    fn update_quick_actions_visibility(&self) {
+        let imp: &imp::HexkudoMenuButton = self.imp();
+
+        imp.quick_actions_box
+            .set_visible(!imp.compact.get() && imp.quick_actions_box.first_child().is_some());
+    }
+
     pub fn set_fullscreen_button(&self, fullscreen: bool) {
         let imp: &imp::HexkudoMenuButton = self.imp();
         let unfullscreen_button = imp.menu_unfullscreen_button.get();
@@ -82,4 +196,57 @@ impl HexkudoMenuButton {
                 .set_visible_child(&fullscreen_button);
         }
     }
+
+    /// Rebuild the submenu that lists the checkpoints, from the most recent to the oldest.
+    /// The submenu is hidden when there is no checkpoint.
+    pub fn set_checkpoints(&self, labels: &[String]) {
+        let section: gio::Menu = self.imp().checkpoints_section.get();
+
+        section.remove_all();
+        if labels.is_empty() {
+            return;
+        }
+        let submenu: gio::Menu = gio::Menu::new();
+        for (index, label) in labels.iter().enumerate().rev() {
+            let item: gio::MenuItem = gio::MenuItem::new(Some(label), None);
+            item.set_action_and_target_value(
+                Some("game-view.jump-checkpoint"),
+                Some(&(index as i32).to_variant()),
+            );
+            submenu.append_item(&item);
+        }
+        section.append_submenu(Some(&gettext("_Jump to Checkpoint")), &submenu);
+    }
+
+    /// Display the number of cells that the player can still solve in the label of the Solve
+    /// Current Cell menu item. None means that the number of cells is not limited.
+    pub fn set_solve_cell_budget(&self, budget: Option<usize>) {
+        let section: gio::Menu = self.imp().assist_section.get();
+        let label: String = match budget {
+            Some(count) => formatx!(
+                ngettext(
+                    "Solve Current Ce_ll ({count} Left)",
+                    "Solve Current Ce_ll ({count} Left)",
+                    count as u32
+                ),
+                count = count
+            )
+            .unwrap(),
+            None => gettext("Solve Current Ce_ll"),
+        };
+
+        for i in 0..section.n_items() {
+            let action: Option<String> = section
+                .item_attribute_value(i, "action", Some(glib::VariantTy::STRING))
+                .and_then(|v| v.get::<String>());
+            if action.as_deref() == Some(SOLVE_CELL_ACTION) {
+                section.remove(i);
+                section.insert_item(
+                    i,
+                    &gio::MenuItem::new(Some(&label), Some(SOLVE_CELL_ACTION)),
+                );
+                break;
+            }
+        }
+    }
 }
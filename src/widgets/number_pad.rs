@@ -0,0 +1,201 @@
+/*
+number_pad.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Manage the number pad panel that the game view docks next to the board.
+//!
+//! The panel is an alternative to the number picker popover for the players who only use the
+//! mouse: the buttons insert their value into the selected cell, and the values already in the
+//! board are greyed out.
+
+use log::debug;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::{Button, glib};
+
+use crate::game::Game;
+use crate::generator::puzzles;
+use crate::shared_game;
+use crate::widgets::game_view::HexkudoGameView;
+
+mod imp {
+    use super::*;
+    use std::cell::OnceCell;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/herve4m/Hexkudo/ui/number_pad.ui")]
+    pub struct HexkudoNumberPad {
+        pub buttons: RefCell<Vec<Button>>,
+        pub game: OnceCell<Rc<RefCell<Game>>>,
+
+        // Template widgets
+        #[template_child]
+        pub values_box: TemplateChild<gtk::FlowBox>,
+        #[template_child]
+        pub clear_button: TemplateChild<gtk::Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HexkudoNumberPad {
+        const NAME: &'static str = "HexkudoNumberPad";
+        type Type = super::HexkudoNumberPad;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            debug!("In class_init()");
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            debug!("In instance_init()");
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HexkudoNumberPad {
+        fn dispose(&self) {
+            self.dispose_template();
+        }
+    }
+    impl WidgetImpl for HexkudoNumberPad {}
+    impl BinImpl for HexkudoNumberPad {}
+}
+
+glib::wrapper! {
+    pub struct HexkudoNumberPad(ObjectSubclass<imp::HexkudoNumberPad>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[gtk::template_callbacks]
+impl HexkudoNumberPad {
+    /// Initialize the object.
+    pub fn init(&self, game: &Rc<RefCell<Game>>) {
+        self.imp()
+            .game
+            .set(Rc::clone(game))
+            .expect("Cannot store the game data into the object");
+    }
+
+    /// Create the buttons for the values of the puzzle. The first and the last values are always
+    /// given, and so the pad does not offer them.
+    pub fn set_puzzle(&self, puzzle: &puzzles::Puzzle) {
+        let imp: &imp::HexkudoNumberPad = self.imp();
+        let mut buttons = imp.buttons.borrow_mut();
+
+        imp.values_box.remove_all();
+        buttons.clear();
+
+        for v in 2..puzzle.matrix.vertexes.num_vertexes {
+            let button: Button = Button::builder().label(format!("{v}")).build();
+            button.add_css_class("numeric");
+            button.connect_clicked(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    obj.clicked(v);
+                }
+            ));
+            imp.values_box.append(&button);
+            buttons.push(button);
+        }
+    }
+
+    /// Grey out the buttons of the values that are already in the board.
+    pub fn update(&self, game: &Game) {
+        for (i, b) in self.imp().buttons.borrow().iter().enumerate() {
+            b.set_sensitive(!game.player_input.contains_value(i + 2));
+        }
+    }
+
+    fn get_game_view(&self) -> HexkudoGameView {
+        let mut view_widget: gtk::Widget = self.parent().unwrap();
+        loop {
+            if view_widget.widget_name() == "game_view" {
+                return view_widget.downcast::<HexkudoGameView>().unwrap();
+            }
+            view_widget = view_widget.parent().unwrap();
+        }
+    }
+
+    // Callback for the number buttons
+    fn clicked(&self, value: usize) {
+        let game: &Rc<RefCell<Game>> = self
+            .imp()
+            .game
+            .get()
+            .expect("Cannot retrieve the game data from the object");
+
+        shared_game::with_game_mut(
+            game,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |game: &mut Game| {
+                    let selected_cell_id: usize = match game.get_selected_cell() {
+                        Some(cid) => cid,
+                        None => return,
+                    };
+                    let view: HexkudoGameView = obj.get_game_view();
+
+                    if view.imp().drawing_area.notes_mode() {
+                        game.toggle_note(selected_cell_id, value);
+                    } else {
+                        view.set_cell_value(game, selected_cell_id, value);
+                    }
+                    view.imp().drawing_area.queue_draw();
+                }
+            ),
+        );
+    }
+
+    // Callback for the Clear button
+    #[template_callback]
+    fn clear_cb(&self) {
+        let game: &Rc<RefCell<Game>> = self
+            .imp()
+            .game
+            .get()
+            .expect("Cannot retrieve the game data from the object");
+
+        shared_game::with_game_mut(
+            game,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |game: &mut Game| {
+                    let selected_cell_id: usize = match game.get_selected_cell() {
+                        Some(cid) => cid,
+                        None => return,
+                    };
+                    let view: HexkudoGameView = obj.get_game_view();
+
+                    view.remove_cell_value(game, selected_cell_id);
+                    view.imp().drawing_area.queue_draw();
+                }
+            ),
+        );
+    }
+}
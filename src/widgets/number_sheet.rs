@@ -0,0 +1,296 @@
+/*
+number_sheet.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Manage the number pad that replaces the number picker popover on narrow screens.
+//!
+//! On narrow screens, the popover would hide a large part of the board. The number pad slides
+//! from the bottom of the game view instead, in an [`adw::BottomSheet`].
+
+use formatx::formatx;
+use gettextrs::gettext;
+use log::debug;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::{Button, gio, glib};
+
+use super::popover_number::{self, PickerValues};
+use crate::game::Game;
+use crate::generator::path;
+use crate::generator::puzzles;
+use crate::shared_game;
+use crate::widgets::game_view::HexkudoGameView;
+
+mod imp {
+    use super::*;
+    use std::cell::OnceCell;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/herve4m/Hexkudo/ui/number_sheet.ui")]
+    pub struct HexkudoNumberSheet {
+        pub buttons: RefCell<Vec<Button>>,
+        pub game: OnceCell<Rc<RefCell<Game>>>,
+        pub settings: OnceCell<gio::Settings>,
+
+        // Template widgets
+        #[template_child]
+        pub values_window: TemplateChild<gtk::ScrolledWindow>,
+        #[template_child]
+        pub values_box: TemplateChild<gtk::FlowBox>,
+        #[template_child]
+        pub given_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub clear_button: TemplateChild<gtk::Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HexkudoNumberSheet {
+        const NAME: &'static str = "HexkudoNumberSheet";
+        type Type = super::HexkudoNumberSheet;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HexkudoNumberSheet {
+        fn dispose(&self) {
+            self.dispose_template();
+        }
+    }
+    impl WidgetImpl for HexkudoNumberSheet {}
+    impl BinImpl for HexkudoNumberSheet {}
+}
+
+glib::wrapper! {
+    pub struct HexkudoNumberSheet(ObjectSubclass<imp::HexkudoNumberSheet>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[gtk::template_callbacks]
+impl HexkudoNumberSheet {
+    /// Initialize the object.
+    pub fn init(&self, settings: &gio::Settings, game: &Rc<RefCell<Game>>) {
+        let imp: &imp::HexkudoNumberSheet = self.imp();
+        imp.game
+            .set(Rc::clone(game))
+            .expect("Cannot store the game data into the object");
+        imp.settings
+            .set(settings.clone())
+            .expect("Cannot store the settings into the object");
+    }
+
+    /// Create the buttons for the values of the puzzle.
+    pub fn set_puzzle(&self, puzzle: &puzzles::Puzzle) {
+        let imp: &imp::HexkudoNumberSheet = self.imp();
+        let mut buttons = imp.buttons.borrow_mut();
+
+        imp.values_box.remove_all();
+        buttons.clear();
+
+        // The first and the last values are always given
+        for v in 2..puzzle.matrix.vertexes.num_vertexes {
+            let button: Button = Button::builder().label(format!("{v}")).build();
+            button.add_css_class("numeric");
+            button.connect_clicked(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| obj.clicked(v)
+            ));
+            imp.values_box.append(&button);
+            buttons.push(button);
+        }
+    }
+
+    /// Make the buttons for the mapped (hint) cells insensitive.
+    pub fn set_path(&self, path: &path::Path, map: &Vec<usize>) {
+        let buttons = self.imp().buttons.borrow();
+
+        for m in map {
+            if let Some(v) = path.vertex_index(*m)
+                && v != 0
+                && v != path.len() - 1
+            {
+                buttons[v - 1].set_sensitive(false);
+            }
+        }
+    }
+
+    /// Return the bottom sheet that contains the number pad.
+    fn bottom_sheet(&self) -> Option<adw::BottomSheet> {
+        self.ancestor(adw::BottomSheet::static_type())
+            .and_downcast::<adw::BottomSheet>()
+    }
+
+    fn get_game_view(&self) -> HexkudoGameView {
+        let mut view_widget: gtk::Widget = self.parent().unwrap();
+        loop {
+            if view_widget.widget_name() == "game_view" {
+                return view_widget.downcast::<HexkudoGameView>().unwrap();
+            }
+            view_widget = view_widget.parent().unwrap();
+        }
+    }
+
+    /// Whether the number pad is displayed.
+    pub fn is_open(&self) -> bool {
+        self.bottom_sheet().is_some_and(|sheet| sheet.is_open())
+    }
+
+    /// Display the number pad for the given cell.
+    pub fn show(&self, cell_id: usize) {
+        let imp: &imp::HexkudoNumberSheet = self.imp();
+        let Some(mut game) = imp
+            .game
+            .get()
+            .expect("Cannot retrieve the game data from the object")
+            .try_borrow_mut()
+            .ok()
+        else {
+            debug!("Game data in use: not showing the number pad");
+            return;
+        };
+        let settings: &gio::Settings = imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object");
+
+        // Prevent showing the number pad if the puzzle is not ready yet
+        if game.path.len() == 0 {
+            return;
+        }
+
+        // Only display the number pad if the cell is already selected (second click feature)
+        if settings.string("popover-trigger") == "second-click"
+            && game.get_selected_cell().is_some_and(|cid| cid != cell_id)
+        {
+            game.set_selected_cell(Some(cell_id));
+            return;
+        }
+
+        // The mapped (hint) cells are read-only: only display their value
+        let given: Option<usize> = if game.is_given(cell_id) {
+            game.player_input.get_value_from_id(cell_id)
+        } else {
+            None
+        };
+        imp.values_window.set_visible(given.is_none());
+        imp.clear_button.set_visible(given.is_none());
+        imp.given_label.set_visible(given.is_some());
+        if let Some(value) = given {
+            imp.given_label
+                .set_label(&formatx!(gettext("Given: {}"), value).unwrap());
+        }
+        imp.clear_button
+            .set_sensitive(game.player_input.get_value_from_id(cell_id).is_some());
+
+        let picker_values: PickerValues =
+            PickerValues::from_repr(settings.enum_("number-picker-values")).unwrap_or_default();
+        popover_number::update_value_buttons(&imp.buttons.borrow(), &game, cell_id, picker_values);
+
+        game.set_selected_cell(Some(cell_id));
+        if let Some(sheet) = self.bottom_sheet() {
+            sheet.set_open(true);
+        }
+    }
+
+    /// Hide the number pad.
+    pub fn hide(&self) {
+        if let Some(sheet) = self.bottom_sheet() {
+            sheet.set_open(false);
+        }
+    }
+
+    // Callback for the number buttons
+    fn clicked(&self, value: usize) {
+        let game: &Rc<RefCell<Game>> = self
+            .imp()
+            .game
+            .get()
+            .expect("Cannot retrieve the game data from the object");
+
+        shared_game::with_game_mut(
+            game,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |game: &mut Game| {
+                    let selected_cell_id: usize = match game.get_selected_cell() {
+                        Some(cid) => cid,
+                        None => return,
+                    };
+                    let view: HexkudoGameView = obj.get_game_view();
+
+                    // In notes mode, the number pad stays open so that the player can toggle
+                    // several notes
+                    if view.imp().drawing_area.notes_mode() {
+                        game.toggle_note(selected_cell_id, value);
+                        view.imp().drawing_area.queue_draw();
+                        return;
+                    }
+                    view.set_cell_value(game, selected_cell_id, value);
+                    obj.hide();
+                    game.set_selected_cell_value_updated(false);
+                }
+            ),
+        );
+    }
+
+    // Callback for the Clear button
+    #[template_callback]
+    fn clear_cb(&self) {
+        let game: &Rc<RefCell<Game>> = self
+            .imp()
+            .game
+            .get()
+            .expect("Cannot retrieve the game data from the object");
+
+        shared_game::with_game_mut(
+            game,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |game: &mut Game| {
+                    let selected_cell_id: usize = match game.get_selected_cell() {
+                        Some(cid) => cid,
+                        None => return,
+                    };
+                    let view: HexkudoGameView = obj.get_game_view();
+
+                    // Remove the value of the selected cell
+                    view.remove_cell_value(game, selected_cell_id);
+                    game.set_selected_cell_value_updated(false);
+                    obj.hide();
+                }
+            ),
+        );
+    }
+}
@@ -22,11 +22,13 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Manage the popover window that display the cell values selection.
 
+use formatx::formatx;
 use gettextrs::gettext;
 use log::debug;
 use std::cell::RefCell;
-use std::ops::DerefMut;
+use std::collections::HashMap;
 use std::rc::Rc;
+use strum_macros::FromRepr;
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
@@ -36,8 +38,60 @@ use gtk::{Button, gdk, gio, glib};
 use crate::game::Game;
 use crate::generator::path;
 use crate::generator::puzzles;
+use crate::shared_game;
 use crate::widgets::game_view::HexkudoGameView;
 
+/// Maximum number of values per page of the number picker. The values of larger boards are
+/// split into several pages.
+const PAGE_SIZE: usize = 36;
+
+/// Values that the number picker displays, from the `number-picker-values` GSettings key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr, Default)]
+#[repr(i32)]
+pub enum PickerValues {
+    /// All the values.
+    #[default]
+    All,
+
+    /// All the values, but the values that cannot go in the selected cell are dimmed.
+    Dim,
+
+    /// Only the values that could go in the selected cell.
+    Hide,
+}
+
+/// Update the buttons of the values for the given cell.
+///
+/// The buttons of the values already in the board are marked as duplicates, and the values that
+/// cannot go in the cell are dimmed or hidden, depending on `picker_values`. The first button is
+/// for the value 2.
+pub fn update_value_buttons(
+    buttons: &[Button],
+    game: &Game,
+    cell_id: usize,
+    picker_values: PickerValues,
+) {
+    for (i, b) in buttons.iter().enumerate() {
+        if game.player_input.contains_value(i + 2) {
+            if b.is_sensitive() {
+                b.add_css_class("duplicate");
+            }
+        } else {
+            b.remove_css_class("duplicate");
+        }
+
+        // Dim or hide the values that cannot go in the cell. The buttons of the hint values
+        // are already insensitive.
+        let plausible: bool = !b.is_sensitive() || game.is_value_plausible(cell_id, i + 2);
+        b.set_visible(plausible || picker_values != PickerValues::Hide);
+        if plausible || picker_values != PickerValues::Dim {
+            b.remove_css_class("dim-label");
+        } else {
+            b.add_css_class("dim-label");
+        }
+    }
+}
+
 mod imp {
     use super::*;
     use std::cell::{Cell, OnceCell};
@@ -49,13 +103,32 @@ mod imp {
         pub buttons: RefCell<Vec<Button>>,
         pub clear_button: OnceCell<Button>,
         pub game: OnceCell<Rc<RefCell<Game>>>,
+        pub settings: OnceCell<gio::Settings>,
+
+        /// Number of cells of the board, which selects the page to restore.
+        pub num_vertexes: Cell<usize>,
+        pub num_pages: Cell<usize>,
+        pub page: Cell<usize>,
+
+        /// Number of columns of buttons, for moving the focus up and down.
+        pub columns: Cell<usize>,
 
         #[property(get, set)]
         pub number_picker_second_click: Cell<bool>,
 
         // Template widgets
         #[template_child]
-        pub grid: TemplateChild<gtk::Grid>,
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub page_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub previous_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub page_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub next_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub given_label: TemplateChild<gtk::Label>,
     }
 
     #[glib::object_subclass]
@@ -67,6 +140,7 @@ mod imp {
         fn class_init(klass: &mut Self::Class) {
             debug!("In class_init()");
             klass.bind_template();
+            klass.bind_template_instance_callbacks();
         }
 
         fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
@@ -93,6 +167,17 @@ mod imp {
             self.clear_button
                 .set(b)
                 .expect("Cannot attach the clear button to the object");
+
+            // Keyboard navigation among the number buttons
+            let key_controller: gtk::EventControllerKey = gtk::EventControllerKey::new();
+            key_controller.connect_key_pressed(glib::clone!(
+                #[weak(rename_to = obj)]
+                self.obj(),
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |_, key, _, _| obj.key_pressed(key)
+            ));
+            self.obj().add_controller(key_controller);
         }
 
         fn dispose(&self) {
@@ -109,6 +194,7 @@ glib::wrapper! {
         @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native, gtk::ShortcutManager;
 }
 
+#[gtk::template_callbacks]
 impl HexkudoPopoverNumber {
     /// Initialize the object.
     pub fn init(&self, settings: &gio::Settings, game: &Rc<RefCell<Game>>) {
@@ -116,40 +202,60 @@ impl HexkudoPopoverNumber {
         imp.game
             .set(Rc::clone(game))
             .expect("Cannot store the game data into the object");
+        imp.settings
+            .set(settings.clone())
+            .expect("Cannot store the settings into the object");
 
         settings
-            .bind(
-                "number-picker-second-click",
-                self,
-                "number-picker-second-click",
-            )
+            .bind("popover-trigger", self, "number-picker-second-click")
+            .mapping(|variant, _| Some((variant.str() == Some("second-click")).to_value()))
+            .get()
             .build();
     }
 
     pub fn set_puzzle(&self, puzzle: &puzzles::Puzzle) {
         let imp: &imp::HexkudoPopoverNumber = self.imp();
-        let grid = &imp.grid;
+        let stack = &imp.stack;
         let mut buttons = imp.buttons.borrow_mut();
         let clear_button: &Button = imp
             .clear_button
             .get()
             .expect("Cannot retrieve the clear button from the object");
 
-        // Remove all the button widgets from the grid
-        while let Some(w) = grid.first_child() {
-            grid.remove(&w);
+        // Detach the Clear button from the previous layout
+        if let Some(parent) = clear_button.parent() {
+            if let Some(grid) = parent.downcast_ref::<gtk::Grid>() {
+                grid.remove(clear_button);
+            } else if let Some(page_box) = parent.downcast_ref::<gtk::Box>() {
+                page_box.remove(clear_button);
+            }
+        }
+
+        // Remove all the pages, with their button widgets
+        while let Some(w) = stack.first_child() {
+            stack.remove(&w);
         }
 
         // Delete all the buttons
         buttons.clear();
 
         let num_vertexes: usize = puzzle.matrix.vertexes.num_vertexes;
-        let columns: i32 = (num_vertexes as f32 - 2.0).sqrt().ceil() as i32;
+        let num_values: usize = num_vertexes - 2;
+        let num_pages: usize = num_values.div_ceil(PAGE_SIZE).max(1);
+        let columns: i32 = (num_values.min(PAGE_SIZE) as f32).sqrt().ceil() as i32;
+        let mut grid: gtk::Grid = Self::new_page();
         let mut c: i32 = 0;
         let mut r: i32 = 0;
 
-        // Create the button widgets and attach them to the grid
+        // Create the button widgets and attach them to the grid of their page
         for v in 1..num_vertexes - 1 {
+            if v > 1 && (v - 1).is_multiple_of(PAGE_SIZE) {
+                stack.add_named(&grid, Some(&format!("{}", (v - 2) / PAGE_SIZE)));
+                grid = Self::new_page();
+                c = 0;
+                r = 0;
+            }
+
             let label: String = format!("{}", v + 1);
             let button: Button = Button::builder().label(label).build();
             button.add_css_class("numeric");
@@ -169,12 +275,88 @@ impl HexkudoPopoverNumber {
                 r += 1;
             }
         }
-        // Attach the Clear button in the last row if space permits. Otherwise, add it to a new row
-        if columns - c >= 2 {
+        // With several pages, the Clear button goes next to the page buttons. Otherwise, attach
+        // the Clear button in the last row if space permits, or add it to a new row.
+        if num_pages > 1 {
+            imp.page_box.append(clear_button);
+        } else if columns - c >= 2 {
             grid.attach(clear_button, c, r, columns - c, 1);
         } else {
             grid.attach(clear_button, 0, r + 1, columns, 1);
         }
+        stack.add_named(&grid, Some(&format!("{}", num_pages - 1)));
+        drop(buttons);
+
+        imp.page_box.set_visible(num_pages > 1);
+        imp.num_pages.set(num_pages);
+        imp.num_vertexes.set(num_vertexes);
+        imp.columns.set(columns as usize);
+        let page: u32 = self
+            .saved_pages()
+            .get(&(num_vertexes as u32))
+            .copied()
+            .unwrap_or(0);
+        self.set_page(page as usize);
+    }
+
+    /// Create the grid of a page of buttons.
+    fn new_page() -> gtk::Grid {
+        gtk::Grid::builder()
+            .row_spacing(3)
+            .column_spacing(3)
+            .build()
+    }
+
+    /// Return the page that was last displayed for each board size.
+    fn saved_pages(&self) -> HashMap<u32, u32> {
+        self.imp()
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object")
+            .get::<HashMap<u32, u32>>("number-picker-pages")
+    }
+
+    /// Display the given page of buttons.
+    fn set_page(&self, page: usize) {
+        let imp: &imp::HexkudoPopoverNumber = self.imp();
+        let num_pages: usize = imp.num_pages.get();
+        let page: usize = page.min(num_pages.saturating_sub(1));
+
+        imp.page.set(page);
+        imp.stack.set_visible_child_name(&format!("{page}"));
+        imp.previous_button.set_sensitive(page > 0);
+        imp.next_button.set_sensitive(page + 1 < num_pages);
+        imp.page_label
+            .set_label(&format!("{}/{num_pages}", page + 1));
+    }
+
+    /// Display another page, and remember it for the next games with the same board size.
+    fn change_page(&self, page: usize) {
+        let imp: &imp::HexkudoPopoverNumber = self.imp();
+        self.set_page(page);
+
+        let mut pages: HashMap<u32, u32> = self.saved_pages();
+        pages.insert(imp.num_vertexes.get() as u32, imp.page.get() as u32);
+        if let Err(error) = imp
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object")
+            .set("number-picker-pages", pages)
+        {
+            debug!("Cannot save the number picker page: {error}");
+        }
+    }
+
+    // Callback for the previous page button
+    #[template_callback]
+    fn previous_page_cb(&self) {
+        self.change_page(self.imp().page.get().saturating_sub(1));
+    }
+
+    // Callback for the next page button
+    #[template_callback]
+    fn next_page_cb(&self) {
+        self.change_page(self.imp().page.get() + 1);
     }
 
     fn get_game_view(&self) -> HexkudoGameView {
@@ -189,54 +371,83 @@ impl HexkudoPopoverNumber {
 
     // Callback for the Clear button
     fn clear_cell(&self, clear_button: &Button) {
-        let imp: &imp::HexkudoPopoverNumber = self.imp();
-        let mut game = imp
+        let game: &Rc<RefCell<Game>> = self
+            .imp()
             .game
             .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
-        let selected_cell_id: usize = match game.get_selected_cell() {
-            Some(cid) => cid,
-            None => return,
-        };
-        let view: HexkudoGameView = self.get_game_view();
+            .expect("Cannot retrieve the game data from the object");
 
-        // Remove the value of the selected cell
-        view.remove_cell_value(game.deref_mut(), selected_cell_id);
-        game.set_selected_cell_value_updated(false);
-
-        clear_button.set_sensitive(false);
-
-        // Update the status of the buttons
-        let buttons = imp.buttons.borrow();
-        for (i, b) in buttons.iter().enumerate() {
-            if game.player_input.contains_value(i + 2) {
-                if b.is_sensitive() {
-                    b.add_css_class("duplicate");
+        shared_game::with_game_mut(
+            game,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                clear_button,
+                move |game: &mut Game| {
+                    let imp: &imp::HexkudoPopoverNumber = obj.imp();
+                    let selected_cell_id: usize = match game.get_selected_cell() {
+                        Some(cid) => cid,
+                        None => return,
+                    };
+                    let view: HexkudoGameView = obj.get_game_view();
+
+                    // Remove the value of the selected cell
+                    view.remove_cell_value(game, selected_cell_id);
+                    game.set_selected_cell_value_updated(false);
+
+                    clear_button.set_sensitive(false);
+
+                    // Update the status of the buttons
+                    let buttons = imp.buttons.borrow();
+                    for (i, b) in buttons.iter().enumerate() {
+                        if game.player_input.contains_value(i + 2) {
+                            if b.is_sensitive() {
+                                b.add_css_class("duplicate");
+                            }
+                        } else {
+                            b.remove_css_class("duplicate");
+                        }
+                    }
                 }
-            } else {
-                b.remove_css_class("duplicate");
-            }
-        }
+            ),
+        );
     }
 
     // Callback for the buttons
     fn clicked(&self, value: usize) {
-        let mut game = self
+        let game: &Rc<RefCell<Game>> = self
             .imp()
             .game
             .get()
-            .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
-        let selected_cell_id: usize = match game.get_selected_cell() {
-            Some(cid) => cid,
-            None => return,
-        };
+            .expect("Cannot retrieve the game data from the object");
 
-        self.get_game_view()
-            .set_cell_value(game.deref_mut(), selected_cell_id, value);
-        self.popdown();
-        game.set_selected_cell_value_updated(false);
+        shared_game::with_game_mut(
+            game,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |game: &mut Game| {
+                    let selected_cell_id: usize = match game.get_selected_cell() {
+                        Some(cid) => cid,
+                        None => return,
+                    };
+
+                    let view: HexkudoGameView = obj.get_game_view();
+
+                    // In notes mode, the popover stays open so that the player can toggle
+                    // several notes
+                    if view.imp().drawing_area.notes_mode() {
+                        game.toggle_note(selected_cell_id, value);
+                        view.imp().drawing_area.queue_draw();
+                        return;
+                    }
+                    view.set_cell_value(game, selected_cell_id, value);
+                    obj.popdown();
+                    game.set_selected_cell_value_updated(false);
+                }
+            ),
+        );
     }
 
     /// Make the buttons for the mapped (hint) cells insensitive
@@ -255,11 +466,16 @@ impl HexkudoPopoverNumber {
 
     pub fn show(&self, r: gdk::Rectangle, cell_id: usize) {
         let imp: &imp::HexkudoPopoverNumber = self.imp();
-        let mut game = imp
+        let Some(mut game) = imp
             .game
             .get()
             .expect("Cannot retrieve the game data from the object")
-            .borrow_mut();
+            .try_borrow_mut()
+            .ok()
+        else {
+            debug!("Game data in use: not showing the number picker");
+            return;
+        };
 
         // Prevent showing the popover if the puzzle is not ready yet
         if game.path.len() == 0 {
@@ -274,6 +490,21 @@ impl HexkudoPopoverNumber {
             return;
         }
 
+        // The mapped (hint) cells are read-only: only display their value
+        let given: Option<usize> = if game.is_given(cell_id) {
+            game.player_input.get_value_from_id(cell_id)
+        } else {
+            None
+        };
+        imp.stack.set_visible(given.is_none());
+        imp.page_box
+            .set_visible(given.is_none() && imp.num_pages.get() > 1);
+        imp.given_label.set_visible(given.is_some());
+        if let Some(value) = given {
+            imp.given_label
+                .set_label(&formatx!(gettext("Given: {}"), value).unwrap());
+        }
+
         let clear_button: &Button = imp
             .clear_button
             .get()
@@ -281,21 +512,129 @@ impl HexkudoPopoverNumber {
 
         clear_button.set_sensitive(game.player_input.get_value_from_id(cell_id).is_some());
 
-        let buttons = imp.buttons.borrow();
-        for (i, b) in buttons.iter().enumerate() {
-            if game.player_input.contains_value(i + 2) {
-                if b.is_sensitive() {
-                    b.add_css_class("duplicate");
-                }
-            } else {
-                b.remove_css_class("duplicate");
-            }
-        }
+        let picker_values: PickerValues = PickerValues::from_repr(
+            imp.settings
+                .get()
+                .expect("Cannot retrieve the settings from the object")
+                .enum_("number-picker-values"),
+        )
+        .unwrap_or_default();
+
+        update_value_buttons(&imp.buttons.borrow(), &game, cell_id, picker_values);
 
         game.set_selected_cell(Some(cell_id));
         self.set_pointing_to(Some(&r));
         self.popup();
-        self.grab_focus();
+
+        // Give the focus to the current value of the cell, or to the first available value
+        let current: Option<usize> = game
+            .player_input
+            .get_value_from_id(cell_id)
+            .and_then(|v| v.checked_sub(2));
+        if given.is_some()
+            || (!current.is_some_and(|i| self.focus_button(i)) && !self.focus_first())
+        {
+            self.grab_focus();
+        }
+    }
+
+    /// Give the keyboard focus to the button at the given index, and display its page.
+    ///
+    /// Return whether the button got the focus. Hidden and insensitive buttons cannot get it.
+    fn focus_button(&self, index: usize) -> bool {
+        let imp: &imp::HexkudoPopoverNumber = self.imp();
+        let buttons = imp.buttons.borrow();
+        let Some(button) = buttons.get(index) else {
+            return false;
+        };
+        if !button.is_visible() || !button.is_sensitive() {
+            return false;
+        }
+
+        if index / PAGE_SIZE != imp.page.get() {
+            self.set_page(index / PAGE_SIZE);
+        }
+        button.grab_focus()
+    }
+
+    /// Give the keyboard focus to the first button of the displayed page that can get it.
+    fn focus_first(&self) -> bool {
+        let first: usize = self.imp().page.get() * PAGE_SIZE;
+
+        self.focus_button(first) || self.move_focus(first, 1)
+    }
+
+    /// Return the index of the button that has the keyboard focus.
+    fn focused_button(&self) -> Option<usize> {
+        self.imp()
+            .buttons
+            .borrow()
+            .iter()
+            .position(|b| b.has_focus())
+    }
+
+    /// Move the focus from the button at the given index by the given offset, skipping the
+    /// buttons that cannot get the focus.
+    ///
+    /// Return whether a button got the focus.
+    fn move_focus(&self, from: usize, offset: isize) -> bool {
+        let num_buttons: usize = self.imp().buttons.borrow().len();
+        let mut index: usize = from;
+
+        loop {
+            let Some(next) = index
+                .checked_add_signed(offset)
+                .filter(|n| *n < num_buttons)
+            else {
+                return false;
+            };
+            if self.focus_button(next) {
+                return true;
+            }
+            index = next;
+        }
+    }
+
+    /// Manage the keyboard in the popover: the arrow keys move the focus among the number
+    /// buttons, Enter sets the value of the focused button, and Delete clears the cell.
+    fn key_pressed(&self, key: gdk::Key) -> glib::Propagation {
+        let imp: &imp::HexkudoPopoverNumber = self.imp();
+        let columns: isize = imp.columns.get() as isize;
+        let focused: Option<usize> = self.focused_button();
+
+        let offset: isize = match key {
+            gdk::Key::Left | gdk::Key::KP_Left => -1,
+            gdk::Key::Right | gdk::Key::KP_Right => 1,
+            gdk::Key::Up | gdk::Key::KP_Up => -columns,
+            gdk::Key::Down | gdk::Key::KP_Down => columns,
+            gdk::Key::Return | gdk::Key::KP_Enter | gdk::Key::space => {
+                // Let the other buttons (Clear, page buttons) process the key
+                let Some(index) = focused else {
+                    return glib::Propagation::Proceed;
+                };
+                self.clicked(index + 2);
+                return glib::Propagation::Stop;
+            }
+            gdk::Key::Delete | gdk::Key::KP_Delete | gdk::Key::BackSpace => {
+                self.clear_cell(
+                    imp.clear_button
+                        .get()
+                        .expect("Cannot retrieve the clear button from the object"),
+                );
+                return glib::Propagation::Stop;
+            }
+            gdk::Key::Escape => {
+                self.popdown();
+                return glib::Propagation::Stop;
+            }
+            _ => return glib::Propagation::Proceed,
+        };
+
+        match focused {
+            Some(index) => self.move_focus(index, offset),
+            None => self.focus_first(),
+        };
+        glib::Propagation::Stop
     }
 
     pub fn hide(&self) {
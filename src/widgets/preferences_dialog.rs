@@ -24,12 +24,33 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Manage the preferences dialog.
 
+use formatx::formatx;
 use gettextrs::gettext;
+use log::debug;
 
 use adw::{prelude::*, subclass::prelude::*};
 use gtk::{gdk, gio, glib};
 
+use crate::difficulty_defaults::DifficultyDefaults;
+use crate::draw;
+use crate::energy_saver;
+use crate::generator::puzzles::{ColorPreset, Difficulty, PuzzleColorTheme, PuzzleCustomColor};
+use crate::player_profiles::{self, PlayerProfile};
+use crate::saver::game::SaverGame;
 use crate::saver::highscores::SaverHighScores;
+use crate::saver::sample_pool::SaverSamplePool;
+use crate::saver::statistics::SaverStatistics;
+use crate::saver::themes::{SaverThemes, THEME_EXTENSION};
+use crate::theme::{Theme, Themes};
+
+/// GSettings nicks of the revealed endpoints, in the order of the combo row items.
+const REVEALED_ENDPOINTS: [&str; 4] = ["both", "start", "end", "none"];
+
+/// GSettings nicks of the number picker values, in the order of the combo row items.
+const NUMBER_PICKER_VALUES: [&str; 3] = ["all", "dim", "hide"];
+
+/// GSettings nicks of the number picker triggers, in the order of the combo row items.
+const POPOVER_TRIGGERS: [&str; 4] = ["press", "release", "double-click", "second-click"];
 
 /// Create a [`gdk::RGBA`] object from a GSettings color parameter.
 pub fn get_rgba(settings: &gio::Settings, key: &str) -> gdk::RGBA {
@@ -43,29 +64,139 @@ pub fn get_rgba(settings: &gio::Settings, key: &str) -> gdk::RGBA {
     )
 }
 
+/// Button of a theme row in the theme manager: icon name, tooltip, and callback, which receives
+/// the index of the theme.
+type ThemeButton = (&'static str, String, fn(&HexkudoPreferencesDialog, usize));
+
+/// GSettings keys that are not reset when restoring the default settings.
+const KEEP_KEYS: [&str; 10] = [
+    "default-width",
+    "default-height",
+    "window-is-maximized",
+    "window-is-fullscreen",
+    "difficulty",
+    "puzzle",
+    "last-report-month",
+    "player-profile",
+    "player-profiles",
+    "custom-accels",
+];
+
 mod imp {
     use super::*;
-    use std::cell::OnceCell;
+    use std::cell::{Cell, OnceCell, RefCell};
 
     #[derive(Debug, Default, gtk::CompositeTemplate)]
     #[template(resource = "/io/github/herve4m/Hexkudo/ui/preferences_dialog.ui")]
     pub struct HexkudoPreferencesDialog {
         pub settings: OnceCell<gio::Settings>,
+        /// Whether the difficulty override rows are being updated from GSettings, in which
+        /// case their changes must not be saved back.
+        pub loading_defaults: Cell<bool>,
+        /// Whether the player profile row is being rebuilt, in which case its selection changes
+        /// must not be saved back.
+        pub loading_profiles: Cell<bool>,
+        /// IDs of the player profiles, in the order of the player profile row.
+        pub profile_ids: RefCell<Vec<String>>,
+        /// Rows of the theme manager, one for each saved theme.
+        pub theme_rows: RefCell<Vec<adw::ActionRow>>,
 
         // Template widgets
         #[template_child]
         pub show_timer: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub player_profile: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub rename_player_row: TemplateChild<adw::ButtonRow>,
+        #[template_child]
+        pub delete_player_row: TemplateChild<adw::ButtonRow>,
+        #[template_child]
+        pub color_preset: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub themes_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub show_timer_tenths: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub freeze_timer: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_splits: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_ghost: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub show_errors: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub show_streak: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_next_number: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_last_number: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub show_remaining_numbers: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub points_scoring: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub draw_path: TemplateChild<adw::SwitchRow>,
         #[template_child]
-        pub number_picker_second_click: TemplateChild<adw::SwitchRow>,
+        pub show_number_pad: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub warn_unsolvable: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub auto_complete_chains: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub hint_fills_cell: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub unlimited_assist: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub popover_trigger: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub number_picker_values: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub select_hint_cells: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub practice_mode: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub mystery_mode: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub monthly_report: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub summary_emoji_grid: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub inhibit_idle: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub toast_timeout: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub announce_remaining: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub guided_reveal_delay: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub undo_depth: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub revealed_endpoints: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub energy_saver: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub sum_regions: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub negative_clues: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub strict_diamonds: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub grow_sample_pool: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub show_warnings: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub show_duplicates: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub defaults_difficulty: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub defaults_warnings: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub defaults_duplicates: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub defaults_zoom: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub board_spin: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub default_color_cell_values: TemplateChild<gtk::Switch>,
         #[template_child]
         pub color_cell_values: TemplateChild<gtk::ColorDialogButton>,
@@ -96,7 +227,31 @@ mod imp {
         #[template_child]
         pub show_puzzle_bg: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub seasonal_themes: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub sel_thick_border: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub number_words: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub pause_thumbnail: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub fullscreen_monitor: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub record_history: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub leaderboard_enabled: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub leaderboard_url: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub saved_game_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub saved_slots_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub highscores_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub statistics_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub sample_pool_row: TemplateChild<adw::ActionRow>,
     }
 
     #[glib::object_subclass]
@@ -135,11 +290,43 @@ impl HexkudoPreferencesDialog {
         let imp: &imp::HexkudoPreferencesDialog = obj.imp();
 
         let show_timer: adw::SwitchRow = imp.show_timer.get();
+        let show_timer_tenths: adw::SwitchRow = imp.show_timer_tenths.get();
+        let freeze_timer: adw::SwitchRow = imp.freeze_timer.get();
+        let show_splits: adw::SwitchRow = imp.show_splits.get();
+        let show_ghost: adw::SwitchRow = imp.show_ghost.get();
         let show_errors: adw::SwitchRow = imp.show_errors.get();
+        let show_streak: adw::SwitchRow = imp.show_streak.get();
+        let show_next_number: adw::SwitchRow = imp.show_next_number.get();
+        let show_last_number: adw::SwitchRow = imp.show_last_number.get();
+        let show_remaining_numbers: adw::SwitchRow = imp.show_remaining_numbers.get();
+        let points_scoring: adw::SwitchRow = imp.points_scoring.get();
         let draw_path: adw::SwitchRow = imp.draw_path.get();
-        let number_picker_second_click: adw::SwitchRow = imp.number_picker_second_click.get();
+        let show_number_pad: adw::SwitchRow = imp.show_number_pad.get();
+        let warn_unsolvable: adw::SwitchRow = imp.warn_unsolvable.get();
+        let auto_complete_chains: adw::SwitchRow = imp.auto_complete_chains.get();
+        let hint_fills_cell: adw::SwitchRow = imp.hint_fills_cell.get();
+        let unlimited_assist: adw::SwitchRow = imp.unlimited_assist.get();
+        let popover_trigger: adw::ComboRow = imp.popover_trigger.get();
+        let number_picker_values: adw::ComboRow = imp.number_picker_values.get();
+        let select_hint_cells: adw::SwitchRow = imp.select_hint_cells.get();
+        let practice_mode: adw::SwitchRow = imp.practice_mode.get();
+        let mystery_mode: adw::SwitchRow = imp.mystery_mode.get();
+        let monthly_report: adw::SwitchRow = imp.monthly_report.get();
+        let summary_emoji_grid: adw::SwitchRow = imp.summary_emoji_grid.get();
+        let inhibit_idle: adw::SwitchRow = imp.inhibit_idle.get();
+        let toast_timeout_adj: gtk::Adjustment = imp.toast_timeout.adjustment();
+        let announce_remaining_adj: gtk::Adjustment = imp.announce_remaining.adjustment();
+        let guided_reveal_delay_adj: gtk::Adjustment = imp.guided_reveal_delay.adjustment();
+        let undo_depth_adj: gtk::Adjustment = imp.undo_depth.adjustment();
+        let revealed_endpoints: adw::ComboRow = imp.revealed_endpoints.get();
+        let energy_saver: adw::ComboRow = imp.energy_saver.get();
+        let sum_regions: adw::SwitchRow = imp.sum_regions.get();
+        let negative_clues: adw::SwitchRow = imp.negative_clues.get();
+        let strict_diamonds: adw::SwitchRow = imp.strict_diamonds.get();
+        let grow_sample_pool: adw::SwitchRow = imp.grow_sample_pool.get();
         let show_warnings: adw::SwitchRow = imp.show_warnings.get();
         let show_duplicates: adw::SwitchRow = imp.show_duplicates.get();
+        let board_spin: adw::SwitchRow = imp.board_spin.get();
         let default_color_cell_values: gtk::Switch = imp.default_color_cell_values.get();
         let default_color_cell_wrong: gtk::Switch = imp.default_color_cell_wrong.get();
         let default_color_cell_bg: gtk::Switch = imp.default_color_cell_bg.get();
@@ -147,26 +334,158 @@ impl HexkudoPreferencesDialog {
         let default_color_sel_cell_bg: gtk::Switch = imp.default_color_sel_cell_bg.get();
         let default_color_cell_borders: gtk::Switch = imp.default_color_cell_borders.get();
         let default_color_path: gtk::Switch = imp.default_color_path.get();
-        let color_cell_values: gtk::ColorDialogButton = imp.color_cell_values.get();
-        let color_cell_wrong: gtk::ColorDialogButton = imp.color_cell_wrong.get();
-        let color_cell_bg: gtk::ColorDialogButton = imp.color_cell_bg.get();
-        let color_sel_cell_bg: gtk::ColorDialogButton = imp.color_sel_cell_bg.get();
-        let color_cell_hint_bg: gtk::ColorDialogButton = imp.color_cell_hint_bg.get();
-        let color_cell_borders: gtk::ColorDialogButton = imp.color_cell_borders.get();
-        let color_path: gtk::ColorDialogButton = imp.color_path.get();
         let show_puzzle_bg: adw::SwitchRow = imp.show_puzzle_bg.get();
+        let seasonal_themes: adw::SwitchRow = imp.seasonal_themes.get();
         let sel_thick_border: adw::SwitchRow = imp.sel_thick_border.get();
+        let number_words: adw::SwitchRow = imp.number_words.get();
+        let pause_thumbnail: adw::SwitchRow = imp.pause_thumbnail.get();
+        let record_history: adw::SwitchRow = imp.record_history.get();
+        let leaderboard_enabled: adw::SwitchRow = imp.leaderboard_enabled.get();
+        let leaderboard_url: adw::EntryRow = imp.leaderboard_url.get();
 
         // GSettings bindings
         settings.bind("show-timer", &show_timer, "active").build();
+        settings
+            .bind("show-timer-tenths", &show_timer_tenths, "active")
+            .build();
+        settings
+            .bind("freeze-timer", &freeze_timer, "active")
+            .build();
+        settings.bind("show-splits", &show_splits, "active").build();
+        settings.bind("show-ghost", &show_ghost, "active").build();
         settings.bind("show-errors", &show_errors, "active").build();
+        settings.bind("show-streak", &show_streak, "active").build();
+        settings
+            .bind("show-next-number", &show_next_number, "active")
+            .build();
+        settings
+            .bind("show-last-number", &show_last_number, "active")
+            .build();
+        settings
+            .bind("show-remaining-numbers", &show_remaining_numbers, "active")
+            .build();
+        settings
+            .bind("points-scoring", &points_scoring, "active")
+            .build();
         settings.bind("draw-path", &draw_path, "active").build();
         settings
-            .bind(
-                "number-picker-second-click",
-                &number_picker_second_click,
-                "active",
-            )
+            .bind("show-number-pad", &show_number_pad, "active")
+            .build();
+        settings
+            .bind("warn-unsolvable", &warn_unsolvable, "active")
+            .build();
+        settings
+            .bind("auto-complete-chains", &auto_complete_chains, "active")
+            .build();
+        settings
+            .bind("hint-fills-cell", &hint_fills_cell, "active")
+            .build();
+        settings
+            .bind("unlimited-assist", &unlimited_assist, "active")
+            .build();
+        settings
+            .bind("popover-trigger", &popover_trigger, "selected")
+            .mapping(|variant, _| {
+                variant
+                    .str()
+                    .and_then(|nick| POPOVER_TRIGGERS.iter().position(|n| *n == nick))
+                    .map(|i| (i as u32).to_value())
+            })
+            .set_mapping(|value, _| {
+                value
+                    .get::<u32>()
+                    .ok()
+                    .and_then(|i| POPOVER_TRIGGERS.get(i as usize))
+                    .map(|nick| nick.to_variant())
+            })
+            .build();
+        settings
+            .bind("number-picker-values", &number_picker_values, "selected")
+            .mapping(|variant, _| {
+                variant
+                    .str()
+                    .and_then(|nick| NUMBER_PICKER_VALUES.iter().position(|n| *n == nick))
+                    .map(|i| (i as u32).to_value())
+            })
+            .set_mapping(|value, _| {
+                value
+                    .get::<u32>()
+                    .ok()
+                    .and_then(|i| NUMBER_PICKER_VALUES.get(i as usize))
+                    .map(|nick| nick.to_variant())
+            })
+            .build();
+        settings
+            .bind("select-hint-cells", &select_hint_cells, "active")
+            .build();
+        settings
+            .bind("practice-mode", &practice_mode, "active")
+            .build();
+        settings
+            .bind("mystery-mode", &mystery_mode, "active")
+            .build();
+        settings
+            .bind("monthly-report", &monthly_report, "active")
+            .build();
+        settings
+            .bind("summary-emoji-grid", &summary_emoji_grid, "active")
+            .build();
+        settings
+            .bind("inhibit-idle", &inhibit_idle, "active")
+            .build();
+        settings
+            .bind("toast-timeout", &toast_timeout_adj, "value")
+            .build();
+        settings
+            .bind("announce-remaining", &announce_remaining_adj, "value")
+            .build();
+        settings
+            .bind("guided-reveal-delay", &guided_reveal_delay_adj, "value")
+            .build();
+        settings
+            .bind("undo-depth", &undo_depth_adj, "value")
+            .build();
+        settings
+            .bind("revealed-endpoints", &revealed_endpoints, "selected")
+            .mapping(|variant, _| {
+                variant
+                    .str()
+                    .and_then(|nick| REVEALED_ENDPOINTS.iter().position(|n| *n == nick))
+                    .map(|i| (i as u32).to_value())
+            })
+            .set_mapping(|value, _| {
+                value
+                    .get::<u32>()
+                    .ok()
+                    .and_then(|i| REVEALED_ENDPOINTS.get(i as usize))
+                    .map(|nick| nick.to_variant())
+            })
+            .build();
+        settings
+            .bind("energy-saver", &energy_saver, "selected")
+            .mapping(|variant, _| {
+                variant
+                    .str()
+                    .and_then(|nick| energy_saver::MODES.iter().position(|n| *n == nick))
+                    .map(|i| (i as u32).to_value())
+            })
+            .set_mapping(|value, _| {
+                value
+                    .get::<u32>()
+                    .ok()
+                    .and_then(|i| energy_saver::MODES.get(i as usize))
+                    .map(|nick| nick.to_variant())
+            })
+            .build();
+        settings.bind("sum-regions", &sum_regions, "active").build();
+        settings
+            .bind("negative-clues", &negative_clues, "active")
+            .build();
+        settings
+            .bind("strict-diamonds", &strict_diamonds, "active")
+            .build();
+        settings
+            .bind("grow-sample-pool", &grow_sample_pool, "active")
             .build();
         settings
             .bind("show-warnings", &show_warnings, "active")
@@ -174,6 +493,7 @@ impl HexkudoPreferencesDialog {
         settings
             .bind("show-duplicates", &show_duplicates, "active")
             .build();
+        settings.bind("board-spin", &board_spin, "active").build();
         settings
             .bind(
                 "use-default-color-cell-values",
@@ -218,31 +538,468 @@ impl HexkudoPreferencesDialog {
         settings
             .bind("show-puzzle-bg", &show_puzzle_bg, "active")
             .build();
+        settings
+            .bind("seasonal-themes", &seasonal_themes, "active")
+            .build();
         settings
             .bind("sel-thick-border", &sel_thick_border, "active")
             .build();
+        settings
+            .bind("number-words", &number_words, "active")
+            .build();
+        settings
+            .bind("pause-thumbnail", &pause_thumbnail, "active")
+            .build();
+        settings
+            .bind("record-history", &record_history, "active")
+            .build();
+        settings
+            .bind("leaderboard-enabled", &leaderboard_enabled, "active")
+            .build();
+        settings
+            .bind("leaderboard-url", &leaderboard_url, "text")
+            .build();
+        obj.setup_fullscreen_monitor(settings);
+
+        // Show where the data is stored
+        imp.saved_game_row.set_subtitle(
+            &SaverGame::new(glib::user_data_dir())
+                .path()
+                .display()
+                .to_string(),
+        );
+        imp.saved_slots_row.set_subtitle(
+            &SaverGame::slots_dir(glib::user_data_dir())
+                .display()
+                .to_string(),
+        );
+        imp.sample_pool_row.set_subtitle(
+            &SaverSamplePool::new(glib::user_data_dir())
+                .path()
+                .display()
+                .to_string(),
+        );
+        obj.update_profile_rows(settings);
 
         // Initialize the colors in the Preferences dialog from the GSettings values
+        obj.refresh_colors(settings);
+
+        imp.settings
+            .set(settings.clone())
+            .expect("Cannot store the settings in the object");
+        obj.load_difficulty_defaults();
+        obj.load_player_profiles();
+        obj.refresh_themes();
+
+        obj
+    }
+
+    /// Load the list of the saved color themes from the disk.
+    fn get_themes(&self) -> Themes {
+        SaverThemes::new(glib::user_data_dir())
+            .get_themes()
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    /// Save the list of the color themes and update the theme manager.
+    fn save_themes(&self, themes: &Themes) {
+        if let Err(error) = SaverThemes::new(glib::user_data_dir()).save_themes(themes) {
+            debug!("Error saving the themes: {error}");
+        }
+        self.refresh_themes();
+    }
+
+    /// Rebuild the rows of the theme manager.
+    fn refresh_themes(&self) {
+        let imp: &imp::HexkudoPreferencesDialog = self.imp();
+
+        for row in imp.theme_rows.take() {
+            imp.themes_group.remove(&row);
+        }
+        for (i, theme) in self.get_themes().themes.iter().enumerate() {
+            let row: adw::ActionRow = adw::ActionRow::builder()
+                .title(theme.name.as_str())
+                .use_markup(false)
+                .build();
+            let buttons: [ThemeButton; 3] = [
+                (
+                    "object-select-symbolic",
+                    gettext("Apply the Theme"),
+                    Self::apply_theme,
+                ),
+                (
+                    "document-save-symbolic",
+                    gettext("Export the Theme"),
+                    Self::export_theme,
+                ),
+                (
+                    "user-trash-symbolic",
+                    gettext("Delete the Theme"),
+                    Self::delete_theme,
+                ),
+            ];
+            for (icon_name, tooltip, callback) in buttons {
+                let button: gtk::Button = gtk::Button::builder()
+                    .icon_name(icon_name)
+                    .tooltip_text(tooltip)
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+                button.connect_clicked(glib::clone!(
+                    #[weak(rename_to = obj)]
+                    self,
+                    move |_| callback(&obj, i)
+                ));
+                row.add_suffix(&button);
+            }
+            imp.themes_group.add(&row);
+            imp.theme_rows.borrow_mut().push(row);
+        }
+    }
+
+    /// Replace the custom colors with the colors of the theme at the given index.
+    fn apply_theme(&self, index: usize) {
+        let imp: &imp::HexkudoPreferencesDialog = self.imp();
+        let Some(settings) = imp.settings.get() else {
+            return;
+        };
+        if let Some(theme) = self.get_themes().themes.get(index) {
+            theme.apply(settings);
+            // The color buttons are not bound to GSettings
+            self.refresh_colors(settings);
+        }
+    }
+
+    /// Ask for a file and write the theme at the given index to it.
+    fn export_theme(&self, index: usize) {
+        let Some(theme) = self.get_themes().themes.get(index).cloned() else {
+            return;
+        };
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let file_dialog: gtk::FileDialog = gtk::FileDialog::builder()
+            .title(gettext("Export Theme"))
+            .initial_name(format!(
+                "{}.{THEME_EXTENSION}",
+                theme.name.replace('/', "-")
+            ))
+            .modal(true)
+            .build();
+        file_dialog.save(
+            Some(&window),
+            gio::Cancellable::NONE,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |result| {
+                    let Some(path) = result.ok().and_then(|file| file.path()) else {
+                        return;
+                    };
+                    if let Err(error) = SaverThemes::export_theme(&path, &theme) {
+                        debug!("Error exporting the theme: {error}");
+                        obj.show_theme_error(
+                            &gettext("Cannot Export the Theme"),
+                            &error.to_string(),
+                        );
+                    }
+                }
+            ),
+        );
+    }
+
+    /// Remove the theme at the given index from the theme manager.
+    fn delete_theme(&self, index: usize) {
+        let mut themes: Themes = self.get_themes();
+        if index < themes.themes.len() {
+            themes.themes.remove(index);
+            self.save_themes(&themes);
+        }
+    }
+
+    /// Display an error about a theme operation.
+    fn show_theme_error(&self, heading: &str, body: &str) {
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(Some(heading), Some(body));
+        dialog.add_response("close", &gettext("_Close"));
+        dialog.present(Some(self));
+    }
+
+    /// Show where the high scores and the statistics of the players are stored, one file per
+    /// line.
+    fn update_profile_rows(&self, settings: &gio::Settings) {
+        let imp: &imp::HexkudoPreferencesDialog = self.imp();
+        let ids: Vec<String> = player_profiles::all_ids(settings);
+        let highscores: Vec<String> = ids
+            .iter()
+            .map(|id| {
+                SaverHighScores::new(glib::user_data_dir(), id)
+                    .path()
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        let statistics: Vec<String> = ids
+            .iter()
+            .map(|id| {
+                SaverStatistics::new(glib::user_data_dir(), id)
+                    .path()
+                    .display()
+                    .to_string()
+            })
+            .collect();
+
+        imp.highscores_row.set_subtitle(&highscores.join("\n"));
+        imp.statistics_row.set_subtitle(&statistics.join("\n"));
+    }
+
+    /// Rebuild the player profile row from GSettings and select the current player.
+    fn load_player_profiles(&self) {
+        let imp: &imp::HexkudoPreferencesDialog = self.imp();
+        let Some(settings) = imp.settings.get() else {
+            return;
+        };
+        let profiles: Vec<PlayerProfile> = player_profiles::list(settings);
+        let current: String = player_profiles::current(settings);
+
+        let model: gtk::StringList = gtk::StringList::new(&[""; 0]);
+        model.append(&gettext("Default Player"));
+        for profile in &profiles {
+            model.append(&profile.name);
+        }
+        let ids: Vec<String> = player_profiles::all_ids(settings);
+        let selected: u32 = ids.iter().position(|id| *id == current).unwrap_or(0) as u32;
+
+        imp.profile_ids.replace(ids);
+        imp.loading_profiles.set(true);
+        imp.player_profile.set_model(Some(&model));
+        imp.player_profile.set_selected(selected);
+        imp.loading_profiles.set(false);
+        self.update_player_buttons(&current);
+    }
+
+    /// Only the player profiles, and not the default player, can be renamed or deleted.
+    fn update_player_buttons(&self, current: &str) {
+        let imp: &imp::HexkudoPreferencesDialog = self.imp();
+
+        imp.rename_player_row.set_sensitive(!current.is_empty());
+        imp.delete_player_row.set_sensitive(!current.is_empty());
+    }
+
+    /// Ask for the name of a player, and then run the provided function with the name.
+    ///
+    /// The name must not be empty, and no other profile can use it.
+    fn ask_player_name<F: Fn(&Self, &gio::Settings, &str) + 'static>(
+        &self,
+        heading: &str,
+        body: &str,
+        response: &str,
+        initial_name: &str,
+        apply: F,
+    ) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(Some(heading), Some(body));
+        let entry: gtk::Entry = gtk::Entry::builder()
+            .placeholder_text(gettext("Player Name"))
+            .text(initial_name)
+            .activates_default(true)
+            .build();
+        let initial_name: String = String::from(initial_name);
+
+        dialog.set_extra_child(Some(&entry));
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("apply", response);
+        dialog.set_response_appearance("apply", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("apply"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                entry,
+                move |_w, response_id| {
+                    let name: glib::GString = entry.text();
+                    let name: &str = name.trim();
+                    if response_id != "apply" || name.is_empty() || name == initial_name {
+                        return;
+                    }
+                    let Some(settings) = obj.imp().settings.get() else {
+                        return;
+                    };
+                    if player_profiles::name_exists(settings, name) {
+                        obj.add_toast(adw::Toast::new(&gettext(
+                            "A player with this name already exists",
+                        )));
+                        return;
+                    }
+                    apply(&obj, settings, name);
+                }
+            ),
+        );
+        dialog.present(Some(&window));
+    }
+
+    /// Return the difficulty level that the difficulty override rows display.
+    fn defaults_difficulty(&self) -> Difficulty {
+        Difficulty::from_repr(self.imp().defaults_difficulty.selected() as i32).unwrap_or_default()
+    }
+
+    /// Update the difficulty override rows from GSettings.
+    fn load_difficulty_defaults(&self) {
+        let imp: &imp::HexkudoPreferencesDialog = self.imp();
+        let Some(settings) = imp.settings.get() else {
+            return;
+        };
+        let defaults: DifficultyDefaults =
+            DifficultyDefaults::load(settings, self.defaults_difficulty());
+        let override_index = |value: Option<bool>| match value {
+            None => 0,
+            Some(true) => 1,
+            Some(false) => 2,
+        };
+
+        imp.loading_defaults.set(true);
+        imp.defaults_warnings
+            .set_selected(override_index(defaults.show_warnings));
+        imp.defaults_duplicates
+            .set_selected(override_index(defaults.show_duplicates));
+        imp.defaults_zoom
+            .set_selected(defaults.zoom_level.map_or(0, |z| z as u32 + 1));
+        imp.loading_defaults.set(false);
+    }
+
+    /// List the connected monitors in the fullscreen display combo row.
+    ///
+    /// The row is only visible when several monitors are connected. The first item selects the
+    /// monitor where the window currently is.
+    fn setup_fullscreen_monitor(&self, settings: &gio::Settings) {
+        let fullscreen_monitor: adw::ComboRow = self.imp().fullscreen_monitor.get();
+        let monitors: Vec<gdk::Monitor> = match gdk::Display::default() {
+            Some(display) => display
+                .monitors()
+                .iter::<gdk::Monitor>()
+                .filter_map(|m| m.ok())
+                .collect(),
+            None => return,
+        };
+        if monitors.len() < 2 {
+            return;
+        }
+
+        let model: gtk::StringList = gtk::StringList::new(&[""; 0]);
+        let mut connectors: Vec<String> = vec![String::new()];
+        model.append(&gettext("Current Display"));
+        for monitor in &monitors {
+            let connector: String = monitor
+                .connector()
+                .map(|c| c.to_string())
+                .unwrap_or_default();
+            let label: String = match monitor.description() {
+                Some(description) => format!("{description} ({connector})"),
+                None => connector.clone(),
+            };
+            model.append(&label);
+            connectors.push(connector);
+        }
+        fullscreen_monitor.set_model(Some(&model));
+        fullscreen_monitor.set_visible(true);
+
+        let set_connectors: Vec<String> = connectors.clone();
+        settings
+            .bind("fullscreen-monitor", &fullscreen_monitor, "selected")
+            .mapping(move |variant, _| {
+                // Select the current display when the saved monitor is not connected anymore
+                let i: usize = variant
+                    .str()
+                    .and_then(|name| connectors.iter().position(|c| c == name))
+                    .unwrap_or(0);
+                Some((i as u32).to_value())
+            })
+            .set_mapping(move |value, _| {
+                value
+                    .get::<u32>()
+                    .ok()
+                    .and_then(|i| set_connectors.get(i as usize))
+                    .map(|name| name.to_variant())
+            })
+            .build();
+    }
+
+    /// Update the color buttons from the GSettings values.
+    ///
+    /// The color buttons are not bound to GSettings, and therefore they must be refreshed
+    /// manually when the values change.
+    fn refresh_colors(&self, settings: &gio::Settings) {
+        let imp: &imp::HexkudoPreferencesDialog = self.imp();
+
         let mut rgba: gdk::RGBA = get_rgba(settings, "color-cell-values");
-        color_cell_values.set_rgba(&rgba);
+        imp.color_cell_values.set_rgba(&rgba);
         rgba = get_rgba(settings, "color-cell-wrong");
-        color_cell_wrong.set_rgba(&rgba);
+        imp.color_cell_wrong.set_rgba(&rgba);
         rgba = get_rgba(settings, "color-cell-bg");
-        color_cell_bg.set_rgba(&rgba);
+        imp.color_cell_bg.set_rgba(&rgba);
         rgba = get_rgba(settings, "color-cell-hint-bg");
-        color_cell_hint_bg.set_rgba(&rgba);
+        imp.color_cell_hint_bg.set_rgba(&rgba);
         rgba = get_rgba(settings, "color-sel-cell-bg");
-        color_sel_cell_bg.set_rgba(&rgba);
+        imp.color_sel_cell_bg.set_rgba(&rgba);
         rgba = get_rgba(settings, "color-cell-borders");
-        color_cell_borders.set_rgba(&rgba);
+        imp.color_cell_borders.set_rgba(&rgba);
         rgba = get_rgba(settings, "color-path");
-        color_path.set_rgba(&rgba);
+        imp.color_path.set_rgba(&rgba);
+    }
 
-        imp.settings
-            .set(settings.clone())
-            .expect("Cannot store the settings in the object");
+    /// Reset all the GSettings keys to their default values.
+    ///
+    /// The keys that store the window state are not reset.
+    fn restore_defaults(&self) {
+        let imp: &imp::HexkudoPreferencesDialog = self.imp();
+        let Some(settings) = imp.settings.get() else {
+            return;
+        };
+        let Some(schema) = settings.settings_schema() else {
+            return;
+        };
 
-        obj
+        for key in schema.list_keys() {
+            if !KEEP_KEYS.contains(&key.as_str()) {
+                settings.reset(&key);
+            }
+        }
+
+        // The widgets bound to GSettings are automatically updated, but not the color buttons
+        // and the difficulty overrides
+        self.refresh_colors(settings);
+        self.load_difficulty_defaults();
+    }
+
+    #[template_callback]
+    fn restore_defaults_cb(&self) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(
+            Some(&gettext("Restore the Default Settings?")),
+            Some(&gettext(
+                "All the preferences, including the custom colors, are going to be reset to their default values.",
+            )),
+        );
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("restore", &gettext("Restore"));
+        dialog.set_response_appearance("restore", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = mself)]
+                self,
+                move |_w, response_id| {
+                    if response_id == "restore" {
+                        mself.restore_defaults();
+                    }
+                }
+            ),
+        );
+        dialog.present(Some(&window));
     }
 
     #[template_callback]
@@ -251,9 +1008,15 @@ impl HexkudoPreferencesDialog {
         let dialog: adw::AlertDialog = adw::AlertDialog::new(
             Some(&gettext("Reset the High Score Boards?")),
             Some(&gettext(
-                "Are you sure that you want to delete all the high scores?",
+                "Are you sure that you want to delete all the high scores of the current player?",
             )),
         );
+        let profile_id: String = self
+            .imp()
+            .settings
+            .get()
+            .map(player_profiles::current)
+            .unwrap_or_default();
         dialog.add_response("cancel", &gettext("Cancel"));
         dialog.add_response("reset", &gettext("Reset"));
         dialog.set_response_appearance("reset", adw::ResponseAppearance::Destructive);
@@ -263,13 +1026,155 @@ impl HexkudoPreferencesDialog {
             None,
             glib::clone!(move |_w, response_id| {
                 if response_id == "reset" {
-                    SaverHighScores::new(glib::user_data_dir()).delete_save();
+                    SaverHighScores::new(glib::user_data_dir(), &profile_id).delete_save();
                 }
             }),
         );
         dialog.present(Some(&window));
     }
 
+    /// Ask for confirmation, and then run the provided function that deletes the data.
+    fn confirm_delete<F: Fn() + 'static>(&self, heading: &str, body: &str, delete: F) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(Some(heading), Some(body));
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("delete", &gettext("Delete"));
+        dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(None, move |_w, response_id| {
+            if response_id == "delete" {
+                delete();
+            }
+        });
+        dialog.present(Some(&window));
+    }
+
+    #[template_callback]
+    fn delete_saved_game_cb(&self) {
+        self.confirm_delete(
+            &gettext("Delete the Saved Game?"),
+            &gettext(
+                "The game in progress is saved again when you quit Hexkudo before completing it.",
+            ),
+            || SaverGame::new(glib::user_data_dir()).delete_save(),
+        );
+    }
+
+    #[template_callback]
+    fn delete_saved_slots_cb(&self) {
+        self.confirm_delete(
+            &gettext("Delete the Saved Games?"),
+            &gettext("All the games that you saved with a name are deleted."),
+            || SaverGame::delete_slots(glib::user_data_dir()),
+        );
+    }
+
+    #[template_callback]
+    fn delete_highscores_cb(&self) {
+        let Some(settings) = self.imp().settings.get() else {
+            return;
+        };
+        let ids: Vec<String> = player_profiles::all_ids(settings);
+        self.confirm_delete(
+            &gettext("Delete the High Scores of All Players?"),
+            &gettext(
+                "The high score boards and the game history of all the player profiles are deleted.",
+            ),
+            move || {
+                for id in &ids {
+                    SaverHighScores::new(glib::user_data_dir(), id).delete_save();
+                }
+            },
+        );
+    }
+
+    #[template_callback]
+    fn delete_statistics_cb(&self) {
+        let Some(settings) = self.imp().settings.get() else {
+            return;
+        };
+        let ids: Vec<String> = player_profiles::all_ids(settings);
+        self.confirm_delete(
+            &gettext("Delete the Statistics of All Players?"),
+            &gettext(
+                "The statistics and the monthly reports of all the player profiles start over.",
+            ),
+            move || {
+                for id in &ids {
+                    SaverStatistics::new(glib::user_data_dir(), id).delete_save();
+                }
+            },
+        );
+    }
+
+    #[template_callback]
+    fn delete_sample_pool_cb(&self) {
+        self.confirm_delete(
+            &gettext("Delete the Collected Games?"),
+            &gettext(
+                "Hexkudo uses the games that it provides when generating a new game takes too long.",
+            ),
+            || SaverSamplePool::new(glib::user_data_dir()).delete_save(),
+        );
+    }
+
+    #[template_callback]
+    fn delete_history_cb(&self) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(
+            Some(&gettext("Delete the Game History?")),
+            Some(&gettext(
+                "The statistics and the monthly reports start over. The high scores are kept.",
+            )),
+        );
+        let profile_id: String = self
+            .imp()
+            .settings
+            .get()
+            .map(player_profiles::current)
+            .unwrap_or_default();
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("delete", &gettext("Delete"));
+        dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(move |_w, response_id| {
+                if response_id != "delete" {
+                    return;
+                }
+                let saver: SaverHighScores =
+                    SaverHighScores::new(glib::user_data_dir(), &profile_id);
+                if let Ok(Some(mut highscores)) = saver.get_highscores() {
+                    highscores.clear_history();
+                    if saver.save_highscores(&highscores).is_err() {
+                        saver.delete_save();
+                    }
+                }
+                SaverStatistics::new(glib::user_data_dir(), &profile_id).delete_save();
+            }),
+        );
+        dialog.present(Some(&window));
+    }
+
+    #[template_callback]
+    fn open_data_folder_cb(&self) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let folder: gio::File = gio::File::for_path(glib::user_data_dir());
+
+        gtk::FileLauncher::new(Some(&folder)).launch(
+            Some(&window),
+            gio::Cancellable::NONE,
+            |result| {
+                if let Err(error) = result {
+                    debug!("Cannot open the data folder: {error}");
+                }
+            },
+        );
+    }
+
     // Update a GSettings with the provided color.
     fn set_gsettings(&self, color_widget: gtk::ColorDialogButton, key: &str) {
         let imp: &imp::HexkudoPreferencesDialog = self.imp();
@@ -288,6 +1193,282 @@ impl HexkudoPreferencesDialog {
         }
     }
 
+    #[template_callback]
+    fn player_profile_cb(&self) {
+        let imp: &imp::HexkudoPreferencesDialog = self.imp();
+        if imp.loading_profiles.get() {
+            return;
+        }
+        let Some(settings) = imp.settings.get() else {
+            return;
+        };
+        // The first entry is the default player, which has an empty ID
+        let id: String = imp
+            .profile_ids
+            .borrow()
+            .get(imp.player_profile.selected() as usize)
+            .cloned()
+            .unwrap_or_default();
+
+        player_profiles::set_current(settings, &id);
+        self.update_player_buttons(&id);
+    }
+
+    #[template_callback]
+    fn add_player_profile_cb(&self) {
+        self.ask_player_name(
+            &gettext("Add a Player"),
+            &gettext("The new player starts with empty high score boards and statistics."),
+            &gettext("_Add"),
+            "",
+            |obj, settings, name| {
+                let id: String = player_profiles::add(settings, name);
+                player_profiles::set_current(settings, &id);
+                obj.load_player_profiles();
+                obj.update_profile_rows(settings);
+            },
+        );
+    }
+
+    #[template_callback]
+    fn rename_player_profile_cb(&self) {
+        let Some(settings) = self.imp().settings.get() else {
+            return;
+        };
+        let id: String = player_profiles::current(settings);
+        let Some(profile) = player_profiles::list(settings)
+            .into_iter()
+            .find(|p| p.id == id)
+        else {
+            return;
+        };
+        self.ask_player_name(
+            &gettext("Rename the Player"),
+            &gettext("The high score boards and the statistics of the player are kept."),
+            &gettext("_Rename"),
+            &profile.name,
+            move |obj, settings, name| {
+                player_profiles::rename(settings, &id, name);
+                obj.load_player_profiles();
+            },
+        );
+    }
+
+    #[template_callback]
+    fn delete_player_profile_cb(&self) {
+        let Some(settings) = self.imp().settings.get() else {
+            return;
+        };
+        let id: String = player_profiles::current(settings);
+        let Some(profile) = player_profiles::list(settings)
+            .into_iter()
+            .find(|p| p.id == id)
+        else {
+            return;
+        };
+        self.confirm_delete(
+            &gettext("Delete the Player?"),
+            &formatx!(
+                gettext(
+                    "The high score boards, the game history, and the statistics of {name} are \
+                     deleted."
+                ),
+                name = profile.name
+            )
+            .unwrap_or_default(),
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move || {
+                    let Some(settings) = obj.imp().settings.get() else {
+                        return;
+                    };
+                    player_profiles::remove(settings, &id);
+                    SaverHighScores::new(glib::user_data_dir(), &id).delete_save();
+                    SaverStatistics::new(glib::user_data_dir(), &id).delete_save();
+                    obj.load_player_profiles();
+                    obj.update_profile_rows(settings);
+                }
+            ),
+        );
+    }
+
+    #[template_callback]
+    fn defaults_difficulty_cb(&self) {
+        self.load_difficulty_defaults();
+    }
+
+    #[template_callback]
+    fn defaults_changed_cb(&self) {
+        let imp: &imp::HexkudoPreferencesDialog = self.imp();
+        if imp.loading_defaults.get() {
+            return;
+        }
+        let Some(settings) = imp.settings.get() else {
+            return;
+        };
+        let override_value = |index: u32| match index {
+            1 => Some(true),
+            2 => Some(false),
+            _ => None,
+        };
+
+        DifficultyDefaults {
+            show_warnings: override_value(imp.defaults_warnings.selected()),
+            show_duplicates: override_value(imp.defaults_duplicates.selected()),
+            zoom_level: imp
+                .defaults_zoom
+                .selected()
+                .checked_sub(1)
+                .and_then(|z| draw::ZoomLevel::from_repr(z as i32)),
+        }
+        .save(settings, self.defaults_difficulty());
+    }
+
+    #[template_callback]
+    fn save_theme_cb(&self) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(
+            Some(&gettext("Save the Colors as a Theme")),
+            Some(&gettext("A theme with the same name is replaced.")),
+        );
+        let entry: gtk::Entry = gtk::Entry::builder()
+            .placeholder_text(gettext("Theme Name"))
+            .activates_default(true)
+            .build();
+
+        dialog.set_extra_child(Some(&entry));
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("save", &gettext("_Save"));
+        dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("save"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                #[weak]
+                entry,
+                move |_w, response_id| {
+                    let name: glib::GString = entry.text();
+                    let name: &str = name.trim();
+                    if response_id != "save" || name.is_empty() {
+                        return;
+                    }
+                    let Some(settings) = obj.imp().settings.get() else {
+                        return;
+                    };
+                    let theme: Theme = Theme::from_settings(name, settings);
+                    let mut themes: Themes = obj.get_themes();
+                    match theme.validate().and_then(|()| themes.add(theme)) {
+                        Ok(()) => obj.save_themes(&themes),
+                        Err(error) => {
+                            debug!("Error adding the theme: {error}");
+                            obj.show_theme_error(&gettext("Cannot Save the Theme"), &error);
+                        }
+                    }
+                }
+            ),
+        );
+        dialog.present(Some(&window));
+    }
+
+    #[template_callback]
+    fn import_theme_cb(&self) {
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let filter: gtk::FileFilter = gtk::FileFilter::new();
+        filter.set_name(Some(&gettext("Hexkudo Themes")));
+        filter.add_suffix(THEME_EXTENSION);
+        let filters: gio::ListStore = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let file_dialog: gtk::FileDialog = gtk::FileDialog::builder()
+            .title(gettext("Import Theme"))
+            .filters(&filters)
+            .modal(true)
+            .build();
+        file_dialog.open(
+            Some(&window),
+            gio::Cancellable::NONE,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |result| {
+                    let Some(path) = result.ok().and_then(|file| file.path()) else {
+                        return;
+                    };
+                    let mut themes: Themes = obj.get_themes();
+                    let imported: Result<(), Box<dyn std::error::Error>> =
+                        SaverThemes::import_theme(&path)
+                            .and_then(|theme| themes.add(theme).map_err(|e| e.into()));
+                    match imported {
+                        Ok(()) => obj.save_themes(&themes),
+                        Err(error) => {
+                            debug!("Error importing the {path:?} theme: {error}");
+                            obj.show_theme_error(
+                                &gettext("Cannot Import the Theme"),
+                                &gettext("The file is not a valid Hexkudo theme."),
+                            );
+                        }
+                    }
+                }
+            ),
+        );
+    }
+
+    #[template_callback]
+    fn apply_color_preset_cb(&self) {
+        let imp: &imp::HexkudoPreferencesDialog = self.imp();
+        let Some(settings) = imp.settings.get() else {
+            return;
+        };
+        let Some(preset) = ColorPreset::from_repr(imp.color_preset.selected() as i32) else {
+            return;
+        };
+        let colors: PuzzleCustomColor = PuzzleColorTheme::preset(preset);
+
+        for (key, default_key, color) in [
+            (
+                "color-cell-values",
+                "use-default-color-cell-values",
+                colors.get_text(),
+            ),
+            (
+                "color-cell-wrong",
+                "use-default-color-cell-wrong",
+                colors.get_text_wrong(),
+            ),
+            ("color-cell-bg", "use-default-color-bg", colors.get_bg()),
+            (
+                "color-cell-hint-bg",
+                "use-default-color-hint-bg",
+                colors.get_bg_map(),
+            ),
+            (
+                "color-sel-cell-bg",
+                "use-default-sel-color-bg",
+                colors.get_selection(),
+            ),
+            (
+                "color-cell-borders",
+                "use-default-color-borders",
+                colors.get_border(),
+            ),
+            ("color-path", "use-default-color-path", colors.get_path()),
+        ] {
+            if let Some(color) = color {
+                settings
+                    .set_value(key, &color.to_variant())
+                    .expect("Cannot save the color in GSettings");
+                let _ = settings.set_boolean(default_key, false);
+            }
+        }
+
+        // The color buttons are not bound to GSettings
+        self.refresh_colors(settings);
+    }
+
     #[template_callback]
     fn on_color_cell_values(&self) {
         let imp: &imp::HexkudoPreferencesDialog = self.imp();
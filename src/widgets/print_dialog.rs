@@ -31,10 +31,9 @@ use adw::{prelude::*, subclass::prelude::*};
 use glib::{Properties, clone};
 use gtk::{gio, glib};
 
-use super::print_job::{HexkudoPrintJob, PrintJobParameters};
+use super::print_job::{HexkudoPrintJob, PrintJobParameters, generate_puzzle};
 use super::print_progress::HexkudoPrintProgress;
 use crate::generator::diamond_and_map;
-use crate::generator::diamonds;
 use crate::generator::path;
 use crate::generator::puzzles;
 use crate::generator::random_path;
@@ -235,62 +234,12 @@ impl HexkudoPrintDialog {
                         p.matrix.edges,
                         #[strong(rename_to = vertexes)]
                         p.matrix.vertexes,
-                        move || {
-                            let mut random_path: random_path::RandomPath =
-                                random_path::RandomPath::new(&edges, &vertexes);
-
-                            // Retrieve a path, diamond, and map from the puzzle's list in case the
-                            // process that generates the puzzle or the diamonds takes too long.
-                            let random: puzzles::PuzzleSampleGame = (p.get_sample_path_fn)();
-                            let path: path::Path = path::Path::from_vec(&random.path);
-                            let path_len: usize = path.len();
-                            let path_first: usize = path
-                                .get_first()
-                                .expect("Cannot retrieve the first cell in the path");
-                            let path_last: usize = path
-                                .get_last()
-                                .expect("Cannot retrieve the last cell in the path");
-
-                            // Generate a random path
-                            match random_path.generate(None) {
-                                Err(_) =>
-                                // Too long, the generating process gave up
-                                {
-                                    (
-                                        path,
-                                        diamond_and_map::DiamondAndMap::from_vec(
-                                            &random.diamonds,
-                                            &random.map,
-                                            path_len,
-                                            path_first,
-                                            path_last,
-                                        ),
-                                    )
-                                }
-                                Ok(p) => {
-                                    // Generate diamonds and map
-                                    let mut diamonds: diamonds::Diamond =
-                                        diamonds::Diamond::new(&random_path.edges, &p);
-                                    match diamonds.generate_diamonds(&vertexes) {
-                                        Err(_) =>
-                                        // Too long, the generating process gave up
-                                        {
-                                            (
-                                                path,
-                                                diamond_and_map::DiamondAndMap::from_vec(
-                                                    &random.diamonds,
-                                                    &random.map,
-                                                    path_len,
-                                                    path_first,
-                                                    path_last,
-                                                ),
-                                            )
-                                        }
-                                        Ok(d_and_m) => (p, d_and_m),
-                                    }
-                                }
-                            }
-                        }
+                        move || generate_puzzle(
+                            &edges,
+                            &vertexes,
+                            p.get_sample_path_fn,
+                            random_path::random_seed(),
+                        )
                     ))
                     .await
                     .expect("Task needs to finish successfully");
@@ -338,7 +287,7 @@ impl HexkudoPrintDialog {
 
                 // Create a print job with the generated puzzles
                 let print_job = HexkudoPrintJob::new(PrintJobParameters {
-                    window,
+                    window: Some(window),
                     puzzle: puzzle.2,
                     paths,
                     diamonds,
@@ -33,14 +33,20 @@ use gtk::glib;
 use gtk::subclass::prelude::*;
 
 use crate::draw;
+use crate::generator::diamond_and_map;
+use crate::generator::diamonds;
+use crate::generator::edges::Edges;
 use crate::generator::path;
 use crate::generator::puzzles;
+use crate::generator::random_path;
+use crate::generator::vertexes::Vertexes;
 
 /// Print parameters
 #[derive(Debug)]
 pub struct PrintJobParameters {
-    /// `GtkWindow` required to run the print operation.
-    pub window: gtk::Window,
+    /// `GtkWindow` that displays the print dialog. When no window is provided, the print
+    /// operation runs without user interaction, from the command line.
+    pub window: Option<gtk::Window>,
 
     /// [`puzzles::Puzzle`] object to print.
     pub puzzle: puzzles::Puzzle,
@@ -65,6 +71,60 @@ pub struct PrintJobParameters {
     pub solutions: bool,
 }
 
+/// Generate a random path, diamonds, and map for the puzzle.
+///
+/// When the generation takes too long, return a path, diamonds, and map from the puzzle's
+/// sample list. The seed drives the random choices.
+pub fn generate_puzzle(
+    edges: &Edges,
+    vertexes: &Vertexes,
+    get_sample_path_fn: fn(u64) -> puzzles::PuzzleSampleGame,
+    seed: u64,
+) -> (path::Path, diamond_and_map::DiamondAndMap) {
+    let mut random_path: random_path::RandomPath =
+        random_path::RandomPath::new_with_seed(edges, vertexes, seed);
+
+    // Retrieve a path, diamond, and map from the puzzle's list in case the process that
+    // generates the puzzle or the diamonds takes too long.
+    let random: puzzles::PuzzleSampleGame = get_sample_path_fn(seed);
+    let path: path::Path = path::Path::from_vec(&random.path);
+    let path_len: usize = path.len();
+    let path_first: usize = path
+        .get_first()
+        .expect("Cannot retrieve the first cell in the path");
+    let path_last: usize = path
+        .get_last()
+        .expect("Cannot retrieve the last cell in the path");
+    let sample = |path: path::Path| {
+        (
+            path,
+            diamond_and_map::DiamondAndMap::from_vec(
+                &random.diamonds,
+                &random.map,
+                path_len,
+                path_first,
+                path_last,
+            ),
+        )
+    };
+
+    // Generate a random path
+    match random_path.generate(None) {
+        // Too long, the generating process gave up
+        Err(_) => sample(path),
+        Ok(p) => {
+            // Generate diamonds and map
+            let mut diamonds: diamonds::Diamond = diamonds::Diamond::new(&random_path.edges, &p);
+            diamonds.set_seed(seed);
+            match diamonds.generate_diamonds(vertexes) {
+                // Too long, the generating process gave up
+                Err(_) => sample(path),
+                Ok(d_and_m) => (p, d_and_m),
+            }
+        }
+    }
+}
+
 mod imp {
     use super::*;
     use std::cell::OnceCell;
@@ -136,7 +196,9 @@ impl HexkudoPrintJob {
             .parameters
             .get()
             .expect("Cannot retrieve the printing parameters");
-        let window: &gtk::Window = &p.window;
+        let Some(window) = &p.window else {
+            return;
+        };
 
         match self.run(gtk::PrintOperationAction::PrintDialog, Some(window)) {
             Ok(_) => (),
@@ -151,6 +213,25 @@ impl HexkudoPrintJob {
         }
     }
 
+    /// Print without user interaction, to the given printer or to the default printer.
+    pub fn print_silently(&self, printer: Option<&str>) -> Result<(), glib::Error> {
+        let settings: gtk::PrintSettings = gtk::PrintSettings::new();
+
+        if let Some(name) = printer {
+            settings.set_printer(name);
+        }
+        self.set_print_settings(Some(&settings));
+        self.run(gtk::PrintOperationAction::Print, None::<&gtk::Window>)
+            .map(|_| ())
+    }
+
+    /// Render the puzzles into a PDF document, without user interaction.
+    pub fn export_pdf(&self, path: &std::path::Path) -> Result<(), glib::Error> {
+        self.set_export_filename(path);
+        self.run(gtk::PrintOperationAction::Export, None::<&gtk::Window>)
+            .map(|_| ())
+    }
+
     /// Callback for when the printing process is initiated.
     pub fn begin_print(&self) {
         let imp: &imp::HexkudoPrintJob = self.imp();
@@ -276,8 +357,14 @@ impl HexkudoPrintJob {
             draw.puzzle_frame().expect("Cannot draw the puzzle frame");
 
             // Draw the map and diamonds
-            draw.puzzle_maps_and_diamonds(path, map, &p.diamonds[puzzle_number])
-                .expect("Cannot draw the hints and the diamonds");
+            draw.puzzle_maps_and_diamonds(
+                path,
+                map,
+                &p.diamonds[puzzle_number],
+                &Vec::new(),
+                &Vec::new(),
+            )
+            .expect("Cannot draw the hints and the diamonds");
 
             // Draw the cell numbers. If printing the solution, then display all the cell numbers.
             let m: &Vec<usize> = if solution { path.get() } else { map };
@@ -286,7 +373,7 @@ impl HexkudoPrintJob {
                 .expect("Cannot draw the cell numbers");
             let path: Option<Surface> = if solution {
                 // Draw the solution path (line) over the puzzle
-                Some(draw.path(path).expect("Cannot draw the solution path"))
+                Some(draw.path(path, 1.0).expect("Cannot draw the solution path"))
             } else {
                 None
             };
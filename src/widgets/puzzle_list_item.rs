@@ -135,4 +135,9 @@ impl HexkudoPuzzleListItem {
     pub fn is_active(&self) -> bool {
         self.imp().check_button.is_active()
     }
+
+    /// Give the keyboard focus to the item, which also scrolls the list to it.
+    pub fn focus(&self) {
+        self.imp().check_button.grab_focus();
+    }
 }
@@ -0,0 +1,185 @@
+/*
+remaining_numbers.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Manage the header bar button that lists the values that the player has not placed yet.
+//!
+//! Clicking a value in the list flashes the cells where that value could go.
+
+use formatx::formatx;
+use gettextrs::{gettext, ngettext};
+use log::debug;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::{Button, glib};
+
+use crate::game::Game;
+use crate::shared_game;
+use crate::widgets::game_view::HexkudoGameView;
+
+mod imp {
+    use super::*;
+    use std::cell::OnceCell;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/herve4m/Hexkudo/ui/remaining_numbers.ui")]
+    pub struct HexkudoRemainingNumbers {
+        pub game: OnceCell<Rc<RefCell<Game>>>,
+
+        // Template widgets
+        #[template_child]
+        pub menu_button: TemplateChild<gtk::MenuButton>,
+        #[template_child]
+        pub popover: TemplateChild<gtk::Popover>,
+        #[template_child]
+        pub values_box: TemplateChild<gtk::FlowBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HexkudoRemainingNumbers {
+        const NAME: &'static str = "HexkudoRemainingNumbers";
+        type Type = super::HexkudoRemainingNumbers;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            debug!("In class_init()");
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            debug!("In instance_init()");
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HexkudoRemainingNumbers {
+        fn dispose(&self) {
+            self.dispose_template();
+        }
+    }
+    impl WidgetImpl for HexkudoRemainingNumbers {}
+    impl BinImpl for HexkudoRemainingNumbers {}
+}
+
+glib::wrapper! {
+    pub struct HexkudoRemainingNumbers(ObjectSubclass<imp::HexkudoRemainingNumbers>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[gtk::template_callbacks]
+impl HexkudoRemainingNumbers {
+    /// Initialize the object.
+    pub fn init(&self, game: &Rc<RefCell<Game>>) {
+        self.imp()
+            .game
+            .set(Rc::clone(game))
+            .expect("Cannot store the game data into the object");
+    }
+
+    /// Display the number of values that the player has not placed yet.
+    pub fn update(&self, game: &Game) {
+        let imp: &imp::HexkudoRemainingNumbers = self.imp();
+        let count: usize = game.get_missing_values().len();
+
+        imp.menu_button.set_label(
+            &formatx!(
+                ngettext("{count} value left", "{count} values left", count as u32),
+                count = count
+            )
+            .unwrap(),
+        );
+        if imp.popover.is_visible() {
+            self.fill_values(game);
+        }
+    }
+
+    /// Create a button for each missing value in the popover.
+    fn fill_values(&self, game: &Game) {
+        let values_box: &gtk::FlowBox = &self.imp().values_box;
+
+        values_box.remove_all();
+        for v in game.get_missing_values() {
+            let button: Button = Button::builder().label(format!("{v}")).build();
+            button.add_css_class("flat");
+            button.add_css_class("numeric");
+            button.connect_clicked(glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_| {
+                    obj.clicked(v);
+                }
+            ));
+            values_box.append(&button);
+        }
+    }
+
+    fn get_game_view(&self) -> HexkudoGameView {
+        let mut view_widget: gtk::Widget = self.parent().unwrap();
+        loop {
+            if view_widget.widget_name() == "game_view" {
+                return view_widget.downcast::<HexkudoGameView>().unwrap();
+            }
+            view_widget = view_widget.parent().unwrap();
+        }
+    }
+
+    // Callback for the popover, to list the missing values when it opens
+    #[template_callback]
+    fn popover_show_cb(&self) {
+        if let Some(game) = shared_game::try_read(
+            self.imp()
+                .game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        ) {
+            self.fill_values(&game);
+        }
+    }
+
+    // Callback for the value buttons
+    fn clicked(&self, value: usize) {
+        let imp: &imp::HexkudoRemainingNumbers = self.imp();
+        let Some(game) = shared_game::try_read(
+            imp.game
+                .get()
+                .expect("Cannot retrieve the game data from the object"),
+        ) else {
+            return;
+        };
+        let cells: Vec<usize> = game.get_candidate_cells(value);
+        drop(game);
+
+        imp.popover.popdown();
+        let view: HexkudoGameView = self.get_game_view();
+        if cells.is_empty() {
+            view.show_toast(
+                &formatx!(gettext("No empty cell can hold {value}"), value = value).unwrap(),
+            );
+        } else {
+            view.imp().drawing_area.flash_candidate_cells(cells);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+/*
+replay_dialog.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Dialog for exporting the replay of the game as a sequence of images.
+
+use gettextrs::gettext;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::{gio, glib};
+
+use super::game_view::HexkudoGameView;
+
+mod imp {
+    use super::*;
+    use std::cell::OnceCell;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/herve4m/Hexkudo/ui/replay_dialog.ui")]
+    pub struct HexkudoReplayDialog {
+        pub game_view: OnceCell<HexkudoGameView>,
+
+        // Template widgets
+        #[template_child]
+        pub frame_size: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub frame_duration: TemplateChild<adw::SpinRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HexkudoReplayDialog {
+        const NAME: &'static str = "HexkudoReplayDialog";
+        type Type = super::HexkudoReplayDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HexkudoReplayDialog {}
+    impl WidgetImpl for HexkudoReplayDialog {}
+    impl AdwDialogImpl for HexkudoReplayDialog {}
+}
+
+glib::wrapper! {
+    pub struct HexkudoReplayDialog(ObjectSubclass<imp::HexkudoReplayDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+#[gtk::template_callbacks]
+impl HexkudoReplayDialog {
+    /// Create the dialog.
+    pub fn new(settings: &gio::Settings, game_view: &HexkudoGameView) -> Self {
+        let obj: HexkudoReplayDialog = glib::Object::builder().build();
+        let imp: &imp::HexkudoReplayDialog = obj.imp();
+
+        // GSettings bindings
+        let frame_size_adj: gtk::Adjustment = imp.frame_size.adjustment();
+        let frame_duration_adj: gtk::Adjustment = imp.frame_duration.adjustment();
+        settings
+            .bind("replay-size", &frame_size_adj, "value")
+            .build();
+        settings
+            .bind("replay-frame-duration", &frame_duration_adj, "value")
+            .build();
+
+        imp.game_view
+            .set(game_view.clone())
+            .expect("Cannot store the game view in the object");
+
+        obj
+    }
+
+    /// Callback for the Export button
+    #[template_callback]
+    fn export_cb(&self, _button: &gtk::Button) {
+        let imp: &imp::HexkudoReplayDialog = self.imp();
+        let size: i32 = imp.frame_size.adjustment().value() as i32;
+        let frame_duration: f64 = imp.frame_duration.adjustment().value();
+        let game_view: HexkudoGameView = imp
+            .game_view
+            .get()
+            .expect("Cannot retrieve the game view from the object")
+            .clone();
+        let window: Option<gtk::Window> = game_view.root().and_downcast::<gtk::Window>();
+
+        // Close the dialog
+        self.close();
+
+        let file_dialog: gtk::FileDialog = gtk::FileDialog::builder()
+            .title(gettext("Select a Folder for the Replay Images"))
+            .modal(true)
+            .build();
+        file_dialog.select_folder(window.as_ref(), gio::Cancellable::NONE, move |result| {
+            if let Ok(folder) = result
+                && let Some(path) = folder.path()
+            {
+                game_view.export_replay(&path, size, frame_duration);
+            }
+        });
+    }
+}
@@ -0,0 +1,175 @@
+/*
+saved_games_dialog.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Dialog for browsing the save slots.
+//!
+//! The dialog lists the games that the player saved in named slots, with their progress. The
+//! player can continue a game or delete its slot.
+
+use formatx::formatx;
+use gettextrs::gettext;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib;
+
+use crate::game::Game;
+use crate::saver::game::SaverGame;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/herve4m/Hexkudo/ui/saved_games_dialog.ui")]
+    pub struct HexkudoSavedGamesDialog {
+        // Template widgets
+        #[template_child]
+        pub view_stack: TemplateChild<adw::ViewStack>,
+        #[template_child]
+        pub no_slot_page: TemplateChild<adw::StatusPage>,
+        #[template_child]
+        pub slot_page: TemplateChild<gtk::ScrolledWindow>,
+        #[template_child]
+        pub slot_list: TemplateChild<gtk::ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HexkudoSavedGamesDialog {
+        const NAME: &'static str = "HexkudoSavedGamesDialog";
+        type Type = super::HexkudoSavedGamesDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HexkudoSavedGamesDialog {}
+    impl WidgetImpl for HexkudoSavedGamesDialog {}
+    impl AdwDialogImpl for HexkudoSavedGamesDialog {}
+}
+
+glib::wrapper! {
+    pub struct HexkudoSavedGamesDialog(ObjectSubclass<imp::HexkudoSavedGamesDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl Default for HexkudoSavedGamesDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HexkudoSavedGamesDialog {
+    /// Create the dialog.
+    ///
+    /// Selecting a slot activates the `app.load-slot` action with the slot name.
+    pub fn new() -> Self {
+        let obj: HexkudoSavedGamesDialog = glib::Object::builder().build();
+        let imp: &imp::HexkudoSavedGamesDialog = obj.imp();
+
+        for (name, game) in SaverGame::list_slots(glib::user_data_dir()) {
+            imp.slot_list.append(&obj.build_row(&name, &game));
+        }
+        obj.update_page();
+        obj
+    }
+
+    /// Display the list of slots, or a status page when there is no slot.
+    fn update_page(&self) {
+        let imp: &imp::HexkudoSavedGamesDialog = self.imp();
+
+        if imp.slot_list.first_child().is_some() {
+            imp.view_stack.set_visible_child(&*imp.slot_page);
+        } else {
+            imp.view_stack.set_visible_child(&*imp.no_slot_page);
+        }
+    }
+
+    /// Return the description of a saved game: puzzle, difficulty, elapsed time, and progress.
+    fn slot_subtitle(game: &Game) -> String {
+        let (h, m, s) = game.get_duration_hms();
+        let time_str: String = if h > 0 {
+            format!("{h:02}:{m:02}:{s:02}")
+        } else {
+            format!("{m:02}:{s:02}")
+        };
+        let (completed, to_complete) = game.get_progress();
+        let percent: usize = (completed * 100).checked_div(to_complete).unwrap_or(100);
+
+        formatx!(
+            gettext("{puzzle} · {difficulty} · {time} · {percent}% complete"),
+            puzzle = game.puzzle.name_i18n.clone(),
+            difficulty = game.puzzle.difficulty,
+            time = time_str,
+            percent = percent
+        )
+        .unwrap()
+    }
+
+    /// Build the row of a slot, with a button to delete the slot.
+    fn build_row(&self, name: &str, game: &Game) -> adw::ActionRow {
+        let row: adw::ActionRow = adw::ActionRow::builder()
+            .title(name)
+            .subtitle(Self::slot_subtitle(game))
+            .activatable(true)
+            .build();
+        let delete_button: gtk::Button = gtk::Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text(gettext("Delete"))
+            .valign(gtk::Align::Center)
+            .css_classes(["flat"])
+            .build();
+
+        row.add_suffix(&delete_button);
+        row.connect_activated(glib::clone!(
+            #[weak(rename_to = mself)]
+            self,
+            #[to_owned]
+            name,
+            move |_| {
+                let _ = mself.activate_action("app.load-slot", Some(&name.to_variant()));
+                mself.close();
+            }
+        ));
+        delete_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = mself)]
+            self,
+            #[weak]
+            row,
+            #[to_owned]
+            name,
+            move |_| {
+                if let Some(saver) = SaverGame::new_slot(glib::user_data_dir(), &name) {
+                    saver.delete_save();
+                }
+                mself.imp().slot_list.remove(&row);
+                mself.update_page();
+            }
+        ));
+        row
+    }
+}
@@ -25,19 +25,29 @@ SPDX-License-Identifier: GPL-3.0-or-later
 //! Dialog for the high score boards.
 
 use chrono::{DateTime, Local};
+use formatx::formatx;
+use gettextrs::gettext;
+use log::debug;
 use std::cell::Ref;
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::error::Error;
 use std::ops::Deref;
+use std::time::{Duration, SystemTime};
 
 use adw::{prelude::*, subclass::prelude::*};
 use gtk::{
     gio,
-    glib::{self, BoxedAnyObject},
+    glib::{self, BoxedAnyObject, clone},
 };
 
+use crate::game::FIRST_MILESTONE_VALUES;
+use crate::generator::diamond_and_map::Endpoints;
 use crate::generator::puzzles;
-use crate::highscores::{HighScores, Score};
+use crate::highscores::{ExportFormat, HighScores, Score};
+use crate::network::{self, Standing};
+use crate::player_profiles;
+use crate::saver::highscores::SaverHighScores;
 use crate::widgets::scores_dialog_item::{Entry, HexkudoScoreItem};
 
 /// Object that represents a puzzle in the puzzle selection combo box.
@@ -46,6 +56,8 @@ pub struct APuzzle {
     name: String,
     difficulty: puzzles::Difficulty,
     puzzle: puzzles::Puzzle,
+    endpoints: Endpoints,
+    assisted: bool,
 }
 
 mod imp {
@@ -56,12 +68,21 @@ mod imp {
     #[template(resource = "/io/github/herve4m/Hexkudo/ui/scores_dialog.ui")]
     pub struct HexkudoScoresDialog {
         pub puzzle_list: OnceCell<Vec<APuzzle>>,
-        pub highscores: OnceCell<HighScores>,
+        pub highscores: RefCell<HighScores>,
+        pub profile_id: OnceCell<String>,
         pub puzzle_name: OnceCell<String>,
         pub difficulty: OnceCell<puzzles::Difficulty>,
         pub position: OnceCell<Option<usize>>,
         pub current_puzzle_name: RefCell<String>,
         pub current_difficulty: Cell<puzzles::Difficulty>,
+        pub current_endpoints: Cell<Endpoints>,
+        pub current_assisted: Cell<bool>,
+        pub endpoints: OnceCell<Endpoints>,
+        pub assisted: OnceCell<bool>,
+        /// Address of the online leaderboard. Empty when the player did not enable it.
+        pub leaderboard_url: OnceCell<String>,
+        /// Identifier of the last request to the online leaderboard.
+        pub online_request: Cell<u32>,
 
         // Template widgets
         #[template_child]
@@ -73,11 +94,35 @@ mod imp {
         #[template_child]
         pub column_view: TemplateChild<gtk::ColumnView>,
         #[template_child]
+        pub position_column: TemplateChild<gtk::ColumnViewColumn>,
+        #[template_child]
+        pub score_column: TemplateChild<gtk::ColumnViewColumn>,
+        #[template_child]
+        pub error_column: TemplateChild<gtk::ColumnViewColumn>,
+        #[template_child]
+        pub points_column: TemplateChild<gtk::ColumnViewColumn>,
+        #[template_child]
+        pub datetime_column: TemplateChild<gtk::ColumnViewColumn>,
+        #[template_child]
         pub view_stack: TemplateChild<adw::ViewStack>,
         #[template_child]
         pub no_score_page: TemplateChild<adw::StatusPage>,
         #[template_child]
         pub view_score_page: TemplateChild<adw::Clamp>,
+        #[template_child]
+        pub milestone_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub board_stack: TemplateChild<adw::ViewStack>,
+        #[template_child]
+        pub board_switcher: TemplateChild<adw::ViewSwitcherBar>,
+        #[template_child]
+        pub online_stack: TemplateChild<adw::ViewStack>,
+        #[template_child]
+        pub online_status_page: TemplateChild<adw::StatusPage>,
+        #[template_child]
+        pub online_page: TemplateChild<gtk::ScrolledWindow>,
+        #[template_child]
+        pub online_list: TemplateChild<gtk::ListBox>,
     }
 
     #[glib::object_subclass]
@@ -111,6 +156,7 @@ glib::wrapper! {
 impl HexkudoScoresDialog {
     /// Create the dialog.
     pub fn new(
+        settings: &gio::Settings,
         puzzle_list: &HashMap<(String, puzzles::Difficulty), puzzles::Puzzle>,
         highscores: &HighScores,
     ) -> Self {
@@ -118,19 +164,43 @@ impl HexkudoScoresDialog {
         let imp: &imp::HexkudoScoresDialog = obj.imp();
 
         // Convert the puzzle list to a list of APuzzle objects that get sorted by difficulty and
-        // name for using as a model from the puzzle selection combobox list
+        // name for using as a model from the puzzle selection combobox list.
+        // The puzzles with assisted games, or with games with hidden endpoints, also get an
+        // entry for each of these scoreboards.
         let mut puzzles: Vec<APuzzle> = puzzle_list
             .iter()
             .map(|k| APuzzle {
                 name: k.0.0.clone(),
                 difficulty: k.0.1,
                 puzzle: k.1.clone(),
+                endpoints: Endpoints::Both,
+                assisted: false,
+            })
+            .collect();
+        let other_puzzles: Vec<APuzzle> = puzzles
+            .iter()
+            .flat_map(|p| {
+                Endpoints::ALL
+                    .into_iter()
+                    .flat_map(|endpoints| [(endpoints, false), (endpoints, true)])
+                    .filter(|(endpoints, assisted)| *endpoints != Endpoints::Both || *assisted)
+                    .filter(|(endpoints, assisted)| {
+                        highscores
+                            .get_score(&p.name, p.difficulty, *endpoints, *assisted)
+                            .is_some_and(|s| !s.is_empty())
+                    })
+                    .map(|(endpoints, assisted)| APuzzle {
+                        endpoints,
+                        assisted,
+                        ..p.clone()
+                    })
             })
             .collect();
+        puzzles.extend(other_puzzles);
         puzzles.sort_by(|a, b| {
             if a.difficulty == b.difficulty {
                 if a.puzzle.name_i18n == b.puzzle.name_i18n {
-                    return Ordering::Equal;
+                    return (a.endpoints as i32, a.assisted).cmp(&(b.endpoints as i32, b.assisted));
                 }
                 if a.puzzle.name_i18n < b.puzzle.name_i18n {
                     return Ordering::Less;
@@ -146,19 +216,36 @@ impl HexkudoScoresDialog {
         // Construct the model that stores the sorted list of puzzles
         let puzzle_string_list: gtk::StringList = gtk::StringList::new(&[""; 0]);
         for a_puzzle in &puzzles {
-            puzzle_string_list.append(&format!(
-                "{} - {}",
-                a_puzzle.difficulty, a_puzzle.puzzle.name_i18n
-            ));
+            let mut label: String =
+                format!("{} - {}", a_puzzle.difficulty, a_puzzle.puzzle.name_i18n);
+            if let Some(endpoints) = a_puzzle.endpoints.description() {
+                label.push_str(&format!(" ({endpoints})"));
+            }
+            if a_puzzle.assisted {
+                label = formatx!(gettext("{board} (Assisted)"), board = label).unwrap();
+            }
+            puzzle_string_list.append(&label);
         }
 
+        // The online leaderboard tab is only available when the player opted in
+        let leaderboard_url: String = if settings.boolean("leaderboard-enabled") {
+            settings.string("leaderboard-url").to_string()
+        } else {
+            String::new()
+        };
+        imp.board_switcher.set_reveal(!leaderboard_url.is_empty());
+        imp.leaderboard_url
+            .set(leaderboard_url)
+            .expect("Cannot store the leaderboard URL in the object");
+
         // Save the given puzzle list and the highscores to the object
         imp.puzzle_list
             .set(puzzles)
             .expect("Cannot store the puzzle list in the object");
-        imp.highscores
-            .set(highscores.clone())
-            .expect("Cannot store the highscore table in the object");
+        imp.highscores.replace(highscores.clone());
+        imp.profile_id
+            .set(player_profiles::current(settings))
+            .expect("Cannot store the player profile in the object");
 
         if highscores.is_empty() {
             imp.headerbar.set_show_title(false);
@@ -168,14 +255,286 @@ impl HexkudoScoresDialog {
             imp.dropdown.set_model(Some(&puzzle_string_list));
         }
 
+        // Columns that players can hide
+        let actions: gio::SimpleActionGroup = gio::SimpleActionGroup::new();
+        actions.add_action(&settings.create_action("scores-show-errors"));
+        actions.add_action(&settings.create_action("scores-show-date"));
+        // Export the scoreboards for reading them in other tools
+        for (name, all) in [("export-board", false), ("export-all", true)] {
+            let action: gio::SimpleAction = gio::SimpleAction::new(name, None);
+            action.set_enabled(!highscores.is_empty());
+            action.connect_activate(clone!(
+                #[weak]
+                obj,
+                move |_, _| obj.export(all)
+            ));
+            actions.add_action(&action);
+        }
+        obj.insert_action_group("scores", Some(&actions));
+        settings
+            .bind("scores-show-errors", &*imp.error_column, "visible")
+            .build();
+        settings
+            .bind("scores-show-date", &*imp.datetime_column, "visible")
+            .build();
+        // The points column is only useful when the player enabled the points-based scoring
+        settings
+            .bind("points-scoring", &*imp.points_column, "visible")
+            .get()
+            .build();
+
+        // Sort the scores by clicking the column headers
+        imp.position_column
+            .set_sorter(Some(&Self::score_sorter(|a, b| a.0.cmp(&b.0))));
+        imp.score_column
+            .set_sorter(Some(&Self::score_sorter(|a, b| a.1.time.cmp(&b.1.time))));
+        imp.error_column
+            .set_sorter(Some(&Self::score_sorter(|a, b| {
+                a.1.errors.cmp(&b.1.errors).then(a.0.cmp(&b.0))
+            })));
+        imp.points_column
+            .set_sorter(Some(&Self::score_sorter(|a, b| {
+                a.1.points.cmp(&b.1.points).then(a.0.cmp(&b.0))
+            })));
+        imp.datetime_column
+            .set_sorter(Some(&Self::score_sorter(|a, b| a.1.when.cmp(&b.1.when))));
+
+        // Restore the sort order that the player selected previously
+        let sort_column: Option<&gtk::ColumnViewColumn> =
+            match settings.string("scores-sort-column").as_str() {
+                "position" => Some(&*imp.position_column),
+                "time" => Some(&*imp.score_column),
+                "errors" => Some(&*imp.error_column),
+                "points" => Some(&*imp.points_column),
+                "date" => Some(&*imp.datetime_column),
+                _ => None,
+            };
+        let sort_order: gtk::SortType = if settings.boolean("scores-sort-descending") {
+            gtk::SortType::Descending
+        } else {
+            gtk::SortType::Ascending
+        };
+        imp.column_view.sort_by_column(sort_column, sort_order);
+
+        if let Some(sorter) = imp
+            .column_view
+            .sorter()
+            .and_downcast::<gtk::ColumnViewSorter>()
+        {
+            sorter.connect_changed(clone!(
+                #[strong]
+                settings,
+                move |sorter, _| {
+                    let id: String = sorter
+                        .primary_sort_column()
+                        .and_then(|c| c.id())
+                        .map(|id| id.to_string())
+                        .unwrap_or_default();
+                    let _ = settings.set_string("scores-sort-column", &id);
+                    let _ = settings.set_boolean(
+                        "scores-sort-descending",
+                        sorter.primary_sort_order() == gtk::SortType::Descending,
+                    );
+                }
+            ));
+        }
+
         obj
     }
 
+    /// Ask for a file and export the selected scoreboard, or all the scoreboards, to it.
+    ///
+    /// The file extension selects the format: JSON for the `.json` files, CSV otherwise.
+    fn export(&self, all: bool) {
+        let csv_filter: gtk::FileFilter = gtk::FileFilter::new();
+        csv_filter.set_name(Some(&gettext("CSV Files")));
+        csv_filter.add_suffix("csv");
+        let json_filter: gtk::FileFilter = gtk::FileFilter::new();
+        json_filter.set_name(Some(&gettext("JSON Files")));
+        json_filter.add_suffix("json");
+        let filters: gio::ListStore = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&csv_filter);
+        filters.append(&json_filter);
+
+        let window: gtk::Window = self.root().unwrap().downcast::<gtk::Window>().unwrap();
+        let file_dialog: gtk::FileDialog = gtk::FileDialog::builder()
+            .title(gettext("Export Scores"))
+            .initial_name("hexkudo-scores.csv")
+            .filters(&filters)
+            .modal(true)
+            .build();
+        file_dialog.save(
+            Some(&window),
+            gio::Cancellable::NONE,
+            clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |result| {
+                    let Some(path) = result.ok().and_then(|file| file.path()) else {
+                        return;
+                    };
+                    let format: ExportFormat = if path
+                        .extension()
+                        .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+                    {
+                        ExportFormat::Json
+                    } else {
+                        ExportFormat::Csv
+                    };
+                    let imp: &imp::HexkudoScoresDialog = obj.imp();
+                    let puzzle_name: String = imp.current_puzzle_name.borrow().clone();
+                    let puzzle: Option<(&String, puzzles::Difficulty, Endpoints, bool)> = (!all)
+                        .then_some((
+                            &puzzle_name,
+                            imp.current_difficulty.get(),
+                            imp.current_endpoints.get(),
+                            imp.current_assisted.get(),
+                        ));
+                    let exported: Result<(), Box<dyn Error>> = imp
+                        .highscores
+                        .borrow()
+                        .export(puzzle, format)
+                        .map_err(|e| e.into())
+                        .and_then(|data| std::fs::write(&path, data).map_err(|e| e.into()));
+
+                    if let Err(error) = exported {
+                        debug!("Error exporting the scores: {error}");
+                        let dialog: adw::AlertDialog = adw::AlertDialog::new(
+                            Some(&gettext("Cannot Export the Scores")),
+                            Some(&error.to_string()),
+                        );
+                        dialog.add_response("close", &gettext("Close"));
+                        dialog.present(Some(&obj));
+                    }
+                }
+            ),
+        );
+    }
+
+    /// Format the duration of a game for the scoreboards.
+    fn format_time(duration: Duration) -> String {
+        let secs: u64 = duration.as_secs();
+        let h: u64 = secs / 3600;
+        let m: u64 = (secs % 3600) / 60;
+        let s: u64 = (secs % 3600) % 60;
+        let ms: u32 = duration.subsec_millis() / 10;
+        if h > 0 {
+            format!("{h:02}h {m:02}m {s:02}.{ms:02}s")
+        } else if m > 0 {
+            format!("{m:02}m {s:02}.{ms:02}s")
+        } else {
+            format!("{s:02}.{ms:02}s")
+        }
+    }
+
+    /// Retrieve and display the online leaderboard standings for the selected puzzle, when the
+    /// online leaderboard tab is visible.
+    fn refresh_online(&self) {
+        let imp: &imp::HexkudoScoresDialog = self.imp();
+        let url: String = imp.leaderboard_url.get().cloned().unwrap_or_default();
+        if url.is_empty() || imp.board_stack.visible_child_name().as_deref() != Some("online") {
+            return;
+        }
+
+        // Ignore the responses to the previous requests
+        let request: u32 = imp.online_request.get().wrapping_add(1);
+        imp.online_request.set(request);
+        imp.online_stack.set_visible_child(&*imp.online_status_page);
+        imp.online_status_page.set_description(None);
+
+        let puzzle_name: String = imp.current_puzzle_name.borrow().clone();
+        if puzzle_name.is_empty() {
+            imp.online_status_page
+                .set_title(&gettext("No Puzzle Selected"));
+            return;
+        }
+        if imp.current_assisted.get() {
+            imp.online_status_page
+                .set_title(&gettext("Assisted Games Are Not Posted"));
+            return;
+        }
+        if imp.current_endpoints.get() != Endpoints::Both {
+            imp.online_status_page
+                .set_title(&gettext("Games With Hidden Endpoints Are Not Posted"));
+            return;
+        }
+        imp.online_status_page
+            .set_title(&gettext("Retrieving the Standings…"));
+
+        let difficulty: puzzles::Difficulty = imp.current_difficulty.get();
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = obj)]
+            self,
+            async move {
+                let result: Result<Vec<Standing>, Box<dyn Error>> =
+                    network::fetch_standings(&url, &puzzle_name, difficulty).await;
+                let imp: &imp::HexkudoScoresDialog = obj.imp();
+                if imp.online_request.get() != request {
+                    return;
+                }
+                match result {
+                    Ok(standings) if standings.is_empty() => {
+                        imp.online_status_page
+                            .set_title(&gettext("No Online Scores Yet"));
+                    }
+                    Ok(standings) => obj.show_standings(&standings),
+                    Err(error) => {
+                        debug!("Error retrieving the leaderboard standings: {error}");
+                        imp.online_status_page
+                            .set_title(&gettext("Cannot Retrieve the Standings"));
+                        imp.online_status_page
+                            .set_description(Some(&error.to_string()));
+                    }
+                }
+            }
+        ));
+    }
+
+    /// Display the given online leaderboard standings.
+    fn show_standings(&self, standings: &[Standing]) {
+        let imp: &imp::HexkudoScoresDialog = self.imp();
+
+        imp.online_list.remove_all();
+        for (i, standing) in standings.iter().enumerate() {
+            let title: String = if standing.player.is_empty() {
+                gettext("Anonymous")
+            } else {
+                glib::markup_escape_text(&standing.player).to_string()
+            };
+            let time: Duration = Duration::try_from_secs_f64(standing.time).unwrap_or_default();
+            let row: adw::ActionRow = adw::ActionRow::builder()
+                .title(title)
+                .subtitle(formatx!(gettext("Errors: {count}"), count = standing.errors).unwrap())
+                .build();
+            row.add_prefix(&gtk::Label::new(Some(&(i + 1).to_string())));
+            row.add_suffix(&gtk::Label::new(Some(&Self::format_time(time))));
+            imp.online_list.append(&row);
+        }
+        imp.online_stack.set_visible_child(&*imp.online_page);
+    }
+
+    /// Return a sorter that compares the scores with the given function.
+    fn score_sorter(cmp: fn(&(usize, Score), &(usize, Score)) -> Ordering) -> gtk::CustomSorter {
+        gtk::CustomSorter::new(move |a, b| {
+            let a: Ref<(usize, Score)> = a
+                .downcast_ref::<BoxedAnyObject>()
+                .expect("The item must be a score")
+                .borrow();
+            let b: Ref<(usize, Score)> = b
+                .downcast_ref::<BoxedAnyObject>()
+                .expect("The item must be a score")
+                .borrow();
+            cmp(&a, &b).into()
+        })
+    }
+
     /// Specify the puzzle for which the scoreboard should be displayed.
     pub fn select_puzzle(
         &self,
         puzzle_name: &String,
         puzzle_difficulty: puzzles::Difficulty,
+        endpoints: Endpoints,
+        assisted: bool,
         highlight_position: Option<usize>,
     ) {
         let imp: &imp::HexkudoScoresDialog = self.imp();
@@ -193,9 +552,19 @@ impl HexkudoScoresDialog {
         imp.position
             .set(highlight_position)
             .expect("Cannot store the player's position in the object");
+        imp.endpoints
+            .set(endpoints)
+            .expect("Cannot store the revealed endpoints in the object");
+        imp.assisted
+            .set(assisted)
+            .expect("Cannot store the assisted flag in the object");
 
         for (i, a_puzzle) in puzzles.iter().enumerate() {
-            if &a_puzzle.name == puzzle_name && a_puzzle.difficulty == puzzle_difficulty {
+            if &a_puzzle.name == puzzle_name
+                && a_puzzle.difficulty == puzzle_difficulty
+                && a_puzzle.endpoints == endpoints
+                && a_puzzle.assisted == assisted
+            {
                 imp.dropdown.set_selected(i as u32);
                 return;
             }
@@ -203,6 +572,11 @@ impl HexkudoScoresDialog {
         imp.view_stack.set_visible_child(&*imp.no_score_page);
     }
 
+    #[template_callback]
+    fn board_stack_cb(&self) {
+        self.refresh_online();
+    }
+
     #[template_callback]
     fn select_puzzle_cb(&self) {
         let imp: &imp::HexkudoScoresDialog = self.imp();
@@ -211,15 +585,27 @@ impl HexkudoScoresDialog {
             .puzzle_list
             .get()
             .expect("Cannot retrieve the puzzle list")[puzzle_id as usize];
-        let highscores: &HighScores = imp
-            .highscores
-            .get()
-            .expect("Cannot retrieve the highscore table from the object");
-        let puzzle_scores: Option<&Vec<Score>> =
-            highscores.get_score(&puzzle.name, puzzle.difficulty);
+        let highscores = imp.highscores.borrow();
+        let puzzle_scores: Option<&Vec<Score>> = highscores.get_score(
+            &puzzle.name,
+            puzzle.difficulty,
+            puzzle.endpoints,
+            puzzle.assisted,
+        );
 
         imp.current_puzzle_name.replace(puzzle.name.clone());
         imp.current_difficulty.set(puzzle.difficulty);
+        imp.current_endpoints.set(puzzle.endpoints);
+        imp.current_assisted.set(puzzle.assisted);
+        self.refresh_online();
+        self.update_milestone(highscores.get_milestones(
+            &puzzle.name,
+            puzzle.difficulty,
+            puzzle.endpoints,
+        ));
+        if puzzle.assisted {
+            imp.milestone_label.set_visible(false);
+        }
         if puzzle_scores.is_none_or(|score| score.is_empty()) {
             imp.view_stack.set_visible_child(&*imp.no_score_page);
             return;
@@ -227,15 +613,44 @@ impl HexkudoScoresDialog {
 
         let store: gio::ListStore = gio::ListStore::new::<BoxedAnyObject>();
         for (i, score) in puzzle_scores.unwrap().iter().enumerate() {
-            store.append(&BoxedAnyObject::new((i, *score)));
+            store.append(&BoxedAnyObject::new((i, score.clone())));
         }
 
-        let sel: gtk::SingleSelection = gtk::SingleSelection::new(Some(store));
+        let sorted: gtk::SortListModel =
+            gtk::SortListModel::new(Some(store), imp.column_view.sorter());
+        let sel: gtk::SingleSelection = gtk::SingleSelection::new(Some(sorted));
         let nosel: gtk::NoSelection = gtk::NoSelection::new(Some(sel));
         imp.column_view.set_model(Some(&nosel));
         imp.view_stack.set_visible_child(&*imp.view_score_page);
     }
 
+    /// Display the fastest and the latest times to reach the first milestone of the game.
+    fn update_milestone(&self, milestones: Option<&Vec<(SystemTime, Duration)>>) {
+        let imp: &imp::HexkudoScoresDialog = self.imp();
+        let format_time = |duration: Duration| -> String {
+            let secs: u64 = duration.as_secs();
+            format!("{:02}:{:02}", secs / 60, secs % 60)
+        };
+
+        match milestones.and_then(|m| m.iter().map(|(_, t)| *t).min().zip(m.last())) {
+            Some((best, (_, latest))) => {
+                imp.milestone_label.set_label(
+                    &formatx!(
+                        gettext(
+                            "Fastest time to place the first {count} numbers: {best} (latest: {latest})"
+                        ),
+                        count = FIRST_MILESTONE_VALUES,
+                        best = format_time(best),
+                        latest = format_time(*latest)
+                    )
+                    .unwrap(),
+                );
+                imp.milestone_label.set_visible(true);
+            }
+            None => imp.milestone_label.set_visible(false),
+        }
+    }
+
     fn use_tags(&self, position: usize) -> bool {
         let imp: &imp::HexkudoScoresDialog = self.imp();
 
@@ -254,10 +669,20 @@ impl HexkudoScoresDialog {
                 .expect("Cannot retrieve the puzzle difficulty from the object");
             let current_puzzle_name = imp.current_puzzle_name.borrow();
             let current_difficulty: puzzles::Difficulty = imp.current_difficulty.get();
+            let endpoints: Endpoints = *imp
+                .endpoints
+                .get()
+                .expect("Cannot retrieve the revealed endpoints from the object");
+            let assisted: bool = *imp
+                .assisted
+                .get()
+                .expect("Cannot retrieve the assisted flag from the object");
 
             if position == *pos
                 && *puzzle_name == *current_puzzle_name.deref()
                 && *difficulty == current_difficulty
+                && endpoints == imp.current_endpoints.get()
+                && assisted == imp.current_assisted.get()
             {
                 return true;
             }
@@ -279,6 +704,72 @@ impl HexkudoScoresDialog {
         listitem.set_child(Some(&row));
     }
 
+    #[template_callback]
+    fn item_setup_delete_cb(&self, listitem: &gtk::ListItem) {
+        let button: gtk::Button = gtk::Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text(gettext("Delete the Score"))
+            .css_classes(["flat", "circular"])
+            .build();
+        button.connect_clicked(clone!(
+            #[weak(rename_to = obj)]
+            self,
+            #[weak]
+            listitem,
+            move |_| {
+                if let Some(entry) = listitem.item().and_downcast::<BoxedAnyObject>() {
+                    let index: usize = entry.borrow::<(usize, Score)>().0;
+                    obj.delete_score(index);
+                }
+            }
+        ));
+        listitem.set_child(Some(&button));
+    }
+
+    /// Ask for confirmation, and then delete the score at the given index from the scoreboard
+    /// of the displayed puzzle.
+    fn delete_score(&self, index: usize) {
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(
+            Some(&gettext("Delete the Score?")),
+            Some(&gettext(
+                "Are you sure that you want to delete this score from the high score board?",
+            )),
+        );
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("delete", &gettext("Delete"));
+        dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |_w, response_id| {
+                    if response_id != "delete" {
+                        return;
+                    }
+                    let imp: &imp::HexkudoScoresDialog = obj.imp();
+                    let puzzle_name: String = imp.current_puzzle_name.borrow().clone();
+                    let difficulty: puzzles::Difficulty = imp.current_difficulty.get();
+                    let endpoints: Endpoints = imp.current_endpoints.get();
+                    let assisted: bool = imp.current_assisted.get();
+                    let mut highscores = imp.highscores.borrow_mut();
+
+                    if highscores.remove_score(&puzzle_name, difficulty, endpoints, assisted, index)
+                    {
+                        let profile_id: &str = imp.profile_id.get().map_or("", |p| p.as_str());
+                        let _ = SaverHighScores::new(glib::user_data_dir(), profile_id)
+                            .save_highscores(&highscores);
+                        drop(highscores);
+                        obj.select_puzzle_cb();
+                    }
+                }
+            ),
+        );
+        dialog.present(Some(self));
+    }
+
     #[template_callback]
     fn item_bind_pos_cb(&self, listitem: &gtk::ListItem) {
         let child: HexkudoScoreItem = listitem.child().and_downcast::<HexkudoScoreItem>().unwrap();
@@ -301,19 +792,7 @@ impl HexkudoScoresDialog {
         let child: HexkudoScoreItem = listitem.child().and_downcast::<HexkudoScoreItem>().unwrap();
         let entry: BoxedAnyObject = listitem.item().and_downcast::<BoxedAnyObject>().unwrap();
         let r: Ref<(usize, Score)> = entry.borrow();
-        let duration: std::time::Duration = r.1.time;
-        let secs: u64 = duration.as_secs();
-        let h: u64 = secs / 3600;
-        let m: u64 = (secs % 3600) / 60;
-        let s: u64 = (secs % 3600) % 60;
-        let ms: u32 = duration.subsec_millis() / 10;
-        let time_str: String = if h > 0 {
-            format!("{h:02}h {m:02}m {s:02}.{ms:02}s")
-        } else if m > 0 {
-            format!("{m:02}m {s:02}.{ms:02}s")
-        } else {
-            format!("{s:02}.{ms:02}s")
-        };
+        let time_str: String = Self::format_time(r.1.time);
 
         let time_str: String = if self.use_tags(r.0 + 1) {
             format!("<b><big>{time_str}</big></b>")
@@ -342,6 +821,23 @@ impl HexkudoScoresDialog {
         child.set_entry(&ent);
     }
 
+    #[template_callback]
+    fn item_bind_points_cb(&self, listitem: &gtk::ListItem) {
+        let child: HexkudoScoreItem = listitem.child().and_downcast::<HexkudoScoreItem>().unwrap();
+        let entry: BoxedAnyObject = listitem.item().and_downcast::<BoxedAnyObject>().unwrap();
+        let r: Ref<(usize, Score)> = entry.borrow();
+
+        // Scores recorded without the points-based scoring
+        let points_str: String = match r.1.points {
+            Some(points) if self.use_tags(r.0 + 1) => format!("<b><big>{points}</big></b>"),
+            Some(points) => format!("{points}"),
+            None => String::from("-"),
+        };
+
+        let ent: Entry = Entry { name: points_str };
+        child.set_entry(&ent);
+    }
+
     #[template_callback]
     fn item_bind_datetime_cb(&self, listitem: &gtk::ListItem) {
         let child: HexkudoScoreItem = listitem.child().and_downcast::<HexkudoScoreItem>().unwrap();
@@ -352,5 +848,19 @@ impl HexkudoScoresDialog {
             name: format!("{}", dt.format("%c")),
         };
         child.set_entry(&ent);
+
+        // Show the version that recorded the score, and flag the versions with scoring bugs
+        let tooltip: String = if r.1.is_flagged() {
+            child.add_css_class("warning");
+            formatx!(
+                gettext("Recorded with Hexkudo {}, which might have miscomputed this score"),
+                &r.1.version
+            )
+            .unwrap()
+        } else {
+            child.remove_css_class("warning");
+            formatx!(gettext("Recorded with Hexkudo {}"), &r.1.version).unwrap()
+        };
+        child.set_tooltip_text(Some(&tooltip));
     }
 }
@@ -190,6 +190,25 @@ impl HexkudoSelectPuzzleView {
         None
     }
 
+    /// Remember the puzzle that the player highlighted, so that it is selected again when the
+    /// player comes back to the view.
+    pub fn save_selection(&self) {
+        let widget_items = self.imp().widget_items.borrow();
+
+        if let Some(w) = widget_items.iter().find(|w| w.is_active()) {
+            self.set_puzzle(w.get_puzzle_name());
+        }
+    }
+
+    /// Give the keyboard focus to the selected puzzle.
+    pub fn focus_selection(&self) {
+        let widget_items = self.imp().widget_items.borrow();
+
+        if let Some(w) = widget_items.iter().find(|w| w.is_active()) {
+            w.focus();
+        }
+    }
+
     // Callback for the "Start Game" button
     #[template_callback]
     fn start_game_cb(&self, button: &gtk::Button) {
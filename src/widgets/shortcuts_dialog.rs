@@ -0,0 +1,245 @@
+/*
+shortcuts_dialog.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Dialog for customizing the keyboard shortcuts.
+//!
+//! The dialog lists the bindings of the [`keybindings`] module. Selecting a binding opens a
+//! dialog that captures the new shortcut.
+
+use formatx::formatx;
+use gettextrs::gettext;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::{gdk, gio, glib, glib::clone};
+
+use crate::keybindings::{self, BINDINGS, Binding, Section};
+
+mod imp {
+    use super::*;
+    use std::cell::{OnceCell, RefCell};
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/herve4m/Hexkudo/ui/shortcuts_dialog.ui")]
+    pub struct HexkudoShortcutsDialog {
+        pub settings: OnceCell<gio::Settings>,
+
+        /// Bindings that the dialog displays, with the label of their accelerators and the
+        /// button that restores their default accelerators.
+        pub rows: RefCell<Vec<(&'static Binding, adw::ShortcutLabel, gtk::Button)>>,
+
+        // Template widgets
+        #[template_child]
+        pub general_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub game_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub navigation_group: TemplateChild<adw::PreferencesGroup>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HexkudoShortcutsDialog {
+        const NAME: &'static str = "HexkudoShortcutsDialog";
+        type Type = super::HexkudoShortcutsDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HexkudoShortcutsDialog {}
+    impl WidgetImpl for HexkudoShortcutsDialog {}
+    impl AdwDialogImpl for HexkudoShortcutsDialog {}
+}
+
+glib::wrapper! {
+    pub struct HexkudoShortcutsDialog(ObjectSubclass<imp::HexkudoShortcutsDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+#[gtk::template_callbacks]
+impl HexkudoShortcutsDialog {
+    /// Create the dialog.
+    pub fn new(settings: &gio::Settings) -> Self {
+        let obj: HexkudoShortcutsDialog = glib::Object::builder().build();
+        let imp: &imp::HexkudoShortcutsDialog = obj.imp();
+
+        imp.settings
+            .set(settings.clone())
+            .expect("Cannot store the settings into the object");
+
+        for binding in BINDINGS.iter() {
+            let group: &adw::PreferencesGroup = match binding.section {
+                Section::General => &imp.general_group,
+                Section::Game => &imp.game_group,
+                Section::Navigation => &imp.navigation_group,
+            };
+            group.add(&obj.build_row(binding));
+        }
+        obj.refresh();
+
+        obj
+    }
+
+    /// Return the GSettings object.
+    fn settings(&self) -> &gio::Settings {
+        self.imp()
+            .settings
+            .get()
+            .expect("Cannot retrieve the settings from the object")
+    }
+
+    /// Build the row of a binding.
+    fn build_row(&self, binding: &'static Binding) -> adw::ActionRow {
+        let label: adw::ShortcutLabel = adw::ShortcutLabel::new("");
+        label.set_disabled_text(&gettext("Disabled"));
+        label.set_valign(gtk::Align::Center);
+        let reset_button: gtk::Button = gtk::Button::builder()
+            .icon_name("edit-undo-symbolic")
+            .tooltip_text(gettext("Restore the Default Shortcut"))
+            .valign(gtk::Align::Center)
+            .css_classes(["flat"])
+            .build();
+        reset_button.connect_clicked(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_| {
+                binding.reset(mself.settings());
+                mself.refresh();
+            }
+        ));
+
+        let row: adw::ActionRow = adw::ActionRow::builder()
+            .title(binding.title())
+            .activatable(true)
+            .build();
+        row.add_suffix(&label);
+        row.add_suffix(&reset_button);
+        row.connect_activated(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            move |_| mself.capture(binding)
+        ));
+
+        self.imp()
+            .rows
+            .borrow_mut()
+            .push((binding, label, reset_button));
+        row
+    }
+
+    /// Update the accelerators that the rows display.
+    fn refresh(&self) {
+        let settings: &gio::Settings = self.settings();
+        for (binding, label, reset_button) in self.imp().rows.borrow().iter() {
+            label.set_accelerator(&binding.accels(settings).join(" "));
+            reset_button.set_visible(binding.is_custom(settings));
+        }
+    }
+
+    /// Open the dialog that captures the new shortcut of a binding.
+    fn capture(&self, binding: &'static Binding) {
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(
+            Some(&gettext("Set Shortcut")),
+            Some(
+                &formatx!(
+                    gettext(
+                        "Press the new shortcut for “{action}”, or press Backspace to disable \
+                         the shortcut."
+                    ),
+                    action = binding.title()
+                )
+                .unwrap(),
+            ),
+        );
+        dialog.add_response("cancel", &gettext("_Cancel"));
+        dialog.set_close_response("cancel");
+
+        // Catch the key presses before the dialog uses them for its buttons
+        let controller: gtk::EventControllerKey = gtk::EventControllerKey::new();
+        controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+        controller.connect_key_pressed(clone!(
+            #[weak(rename_to = mself)]
+            self,
+            #[weak]
+            dialog,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |controller, keyval, _, modifier| {
+                mself.key_captured(&dialog, controller, binding, keyval, modifier)
+            }
+        ));
+        dialog.add_controller(controller);
+        dialog.present(Some(self));
+    }
+
+    /// Assign the captured shortcut to the binding.
+    ///
+    /// Escape cancels the capture, and Backspace disables the shortcut of the binding.
+    fn key_captured(
+        &self,
+        dialog: &adw::AlertDialog,
+        controller: &gtk::EventControllerKey,
+        binding: &Binding,
+        keyval: gdk::Key,
+        modifier: gdk::ModifierType,
+    ) -> glib::Propagation {
+        // Wait for the key that goes with the modifiers
+        let is_modifier: bool = controller
+            .current_event()
+            .and_then(|e| e.downcast::<gdk::KeyEvent>().ok())
+            .is_some_and(|e| e.is_modifier());
+        if is_modifier {
+            return glib::Propagation::Proceed;
+        }
+
+        let settings: &gio::Settings = self.settings();
+        let modifier: gdk::ModifierType = modifier & gtk::accelerator_get_default_mod_mask();
+        match keyval {
+            gdk::Key::Escape if modifier.is_empty() => return glib::Propagation::Proceed,
+            gdk::Key::BackSpace if modifier.is_empty() => keybindings::disable(settings, binding),
+            _ => {
+                let keyval: gdk::Key = keyval.to_lower();
+                if !gtk::accelerator_valid(keyval, modifier) {
+                    return glib::Propagation::Stop;
+                }
+                keybindings::assign(settings, binding, &gtk::accelerator_name(keyval, modifier));
+            }
+        }
+        dialog.close();
+        self.refresh();
+        glib::Propagation::Stop
+    }
+
+    /// Callback for the Reset All button. Restore all the default shortcuts.
+    #[template_callback]
+    fn reset_all_cb(&self) {
+        keybindings::reset_all(self.settings());
+        self.refresh();
+    }
+}
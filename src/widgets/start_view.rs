@@ -26,17 +26,23 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Manage the initial view, which displays the difficulty levels.
 
+use formatx::formatx;
+use gettextrs::ngettext;
+
+use adw::prelude::*;
 use adw::subclass::prelude::*;
 use glib::Properties;
-use gtk::prelude::*;
 use gtk::{gio, glib};
 
 use super::menu_button::HexkudoMenuButton;
 use crate::generator::puzzles;
+use crate::highscores::HighScores;
+use crate::player_profiles;
+use crate::saver::highscores::SaverHighScores;
 
 mod imp {
     use super::*;
-    use std::cell::Cell;
+    use std::cell::{Cell, OnceCell};
 
     #[derive(Debug, Default, Properties, gtk::CompositeTemplate)]
     #[properties(wrapper_type = super::HexkudoStartView)]
@@ -46,6 +52,8 @@ mod imp {
         #[property(get, set, builder(puzzles::Difficulty::Easy))]
         pub difficulty: Cell<puzzles::Difficulty>,
 
+        pub settings: OnceCell<gio::Settings>,
+
         // Template widgets
         #[template_child]
         pub menu_button: TemplateChild<HexkudoMenuButton>,
@@ -55,6 +63,14 @@ mod imp {
         pub medium_check: TemplateChild<gtk::CheckButton>,
         #[template_child]
         pub hard_check: TemplateChild<gtk::CheckButton>,
+        #[template_child]
+        pub easy_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub medium_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub hard_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub zen_row: TemplateChild<adw::SwitchRow>,
     }
 
     #[glib::object_subclass]
@@ -91,7 +107,11 @@ impl HexkudoStartView {
     pub fn init(&self, settings: &gio::Settings) {
         let imp: &imp::HexkudoStartView = self.imp();
 
+        imp.settings
+            .set(settings.clone())
+            .expect("Cannot store the settings in the object");
         settings.bind("difficulty", self, "difficulty").build();
+        settings.bind("zen-mode", &*imp.zen_row, "active").build();
         match puzzles::Difficulty::from_repr(settings.enum_("difficulty"))
             .expect("Cannot retrieve the default difficulty level")
         {
@@ -99,6 +119,63 @@ impl HexkudoStartView {
             puzzles::Difficulty::Medium => imp.medium_check.activate(),
             puzzles::Difficulty::Hard => imp.hard_check.activate(),
         };
+        self.refresh_stats();
+    }
+
+    /// Display the best time and the number of games played under each difficulty level.
+    pub fn refresh_stats(&self) {
+        let imp: &imp::HexkudoStartView = self.imp();
+        let profile_id: String = imp
+            .settings
+            .get()
+            .map(player_profiles::current)
+            .unwrap_or_default();
+        let highscores: HighScores = SaverHighScores::new(glib::user_data_dir(), &profile_id)
+            .get_highscores()
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+
+        for (difficulty, row) in [
+            (puzzles::Difficulty::Easy, &imp.easy_row),
+            (puzzles::Difficulty::Medium, &imp.medium_row),
+            (puzzles::Difficulty::Hard, &imp.hard_row),
+        ] {
+            let subtitle: String = match highscores.get_difficulty_stats(difficulty) {
+                (Some(best), played) => {
+                    let secs: u64 = best.as_secs();
+                    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, (secs % 3600) % 60);
+                    let time_str: String = if h > 0 {
+                        format!("{h:02}:{m:02}:{s:02}")
+                    } else {
+                        format!("{m:02}:{s:02}")
+                    };
+                    formatx!(
+                        ngettext(
+                            "Best time {time} · {count} game played",
+                            "Best time {time} · {count} games played",
+                            played as u32
+                        ),
+                        time = time_str,
+                        count = played
+                    )
+                    .unwrap()
+                }
+                (None, _) => String::new(),
+            };
+            row.set_subtitle(&subtitle);
+        }
+    }
+
+    /// Give the keyboard focus to the selected difficulty level.
+    pub fn focus_difficulty(&self) {
+        let imp: &imp::HexkudoStartView = self.imp();
+
+        for check in [&imp.easy_check, &imp.medium_check, &imp.hard_check] {
+            if check.is_active() {
+                check.grab_focus();
+            }
+        }
     }
 
     #[template_callback]
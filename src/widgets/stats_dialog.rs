@@ -0,0 +1,196 @@
+/*
+stats_dialog.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Dialog for browsing the lifetime statistics.
+//!
+//! The dialog displays a group for each difficulty level, with the totals for the level and
+//! a row for each puzzle that the player played.
+
+use formatx::formatx;
+use gettextrs::{gettext, ngettext};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib;
+
+use crate::generator::diamond_and_map::Endpoints;
+use crate::generator::puzzles::{self, Difficulty};
+use crate::statistics::{PuzzleStats, Statistics};
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/herve4m/Hexkudo/ui/stats_dialog.ui")]
+    pub struct HexkudoStatsDialog {
+        // Template widgets
+        #[template_child]
+        pub view_stack: TemplateChild<adw::ViewStack>,
+        #[template_child]
+        pub no_stats_page: TemplateChild<adw::StatusPage>,
+        #[template_child]
+        pub stats_page: TemplateChild<gtk::ScrolledWindow>,
+        #[template_child]
+        pub stats_box: TemplateChild<gtk::Box>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HexkudoStatsDialog {
+        const NAME: &'static str = "HexkudoStatsDialog";
+        type Type = super::HexkudoStatsDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HexkudoStatsDialog {}
+    impl WidgetImpl for HexkudoStatsDialog {}
+    impl AdwDialogImpl for HexkudoStatsDialog {}
+}
+
+glib::wrapper! {
+    pub struct HexkudoStatsDialog(ObjectSubclass<imp::HexkudoStatsDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+impl HexkudoStatsDialog {
+    /// Create the dialog.
+    pub fn new(
+        puzzle_list: &HashMap<(String, puzzles::Difficulty), puzzles::Puzzle>,
+        statistics: &Statistics,
+    ) -> Self {
+        let obj: HexkudoStatsDialog = glib::Object::builder().build();
+        let imp: &imp::HexkudoStatsDialog = obj.imp();
+
+        if statistics.is_empty() {
+            imp.view_stack.set_visible_child(&*imp.no_stats_page);
+            return obj;
+        }
+        imp.view_stack.set_visible_child(&*imp.stats_page);
+
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let totals: PuzzleStats = statistics.get_difficulty(difficulty);
+            if totals.played() == 0 {
+                continue;
+            }
+
+            let group: adw::PreferencesGroup = adw::PreferencesGroup::builder()
+                .title(difficulty.to_string())
+                .description(Self::stats_summary(&totals))
+                .build();
+
+            // Sort the puzzles by their translated names. The games with hidden endpoints get
+            // their own rows.
+            let mut rows: Vec<(String, &PuzzleStats)> = puzzle_list
+                .iter()
+                .filter(|((_, d), _)| *d == difficulty)
+                .flat_map(|((name, d), puzzle)| {
+                    Endpoints::ALL.into_iter().filter_map(move |endpoints| {
+                        let stats: &PuzzleStats = statistics
+                            .get(name, *d, endpoints)
+                            .filter(|s| s.played() > 0)?;
+                        let title: String = match endpoints.description() {
+                            Some(e) => format!("{} ({e})", puzzle.name_i18n),
+                            None => puzzle.name_i18n.clone(),
+                        };
+                        Some((title, stats))
+                    })
+                })
+                .collect();
+            rows.sort_by(|a, b| a.0.cmp(&b.0));
+
+            for (name, stats) in rows {
+                let row: adw::ActionRow = adw::ActionRow::builder()
+                    .title(name)
+                    .subtitle(Self::stats_summary(stats))
+                    .subtitle_selectable(true)
+                    .build();
+                row.add_css_class("property");
+                group.add(&row);
+            }
+            imp.stats_box.append(&group);
+        }
+
+        obj
+    }
+
+    /// Return the description of the given aggregates.
+    fn stats_summary(stats: &PuzzleStats) -> String {
+        let mut lines: Vec<String> = vec![
+            formatx!(
+                ngettext(
+                    "{played} game played, {won} won",
+                    "{played} games played, {won} won",
+                    stats.played() as u32
+                ),
+                played = stats.played(),
+                won = stats.won
+            )
+            .unwrap(),
+        ];
+
+        if let Some(average) = stats.average_time() {
+            lines.push(
+                formatx!(
+                    gettext("Average time: {time}"),
+                    time = Self::format_duration(average)
+                )
+                .unwrap(),
+            );
+        }
+        lines.push(
+            formatx!(
+                gettext("Best streak: {streak}, hints used: {hints}"),
+                streak = stats.best_streak,
+                hints = stats.hints
+            )
+            .unwrap(),
+        );
+        lines.push(
+            formatx!(
+                gettext("Abandoned: {rate}%"),
+                rate = (stats.abandonment_rate() * 100.0).round() as u32
+            )
+            .unwrap(),
+        );
+        lines.join("\n")
+    }
+
+    /// Return the given duration as a string.
+    fn format_duration(duration: Duration) -> String {
+        let secs: u64 = duration.as_secs();
+        let (h, m, s): (u64, u64, u64) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+        if h > 0 {
+            format!("{h:02}:{m:02}:{s:02}")
+        } else {
+            format!("{m:02}:{s:02}")
+        }
+    }
+}
@@ -0,0 +1,187 @@
+/*
+verify_dialog.rs
+
+Copyright 2025 Hervé Quatremain
+
+This file is part of Hexkudo.
+
+Hexkudo is free software: you can redistribute it and/or modify it under the
+terms of the GNU General Public License as published by the Free Software
+Foundation, either version 3 of the License, or (at your option) any later
+version.
+
+Hexkudo is distributed in the hope that it will be useful, but WITHOUT ANY
+WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR
+A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License along with
+Hexkudo. If not, see <https://www.gnu.org/licenses/>.
+
+SPDX-License-Identifier: GPL-3.0-or-later
+*/
+
+//! Dialog to verify the proof string of a solved puzzle.
+//!
+//! See [`crate::proof`] for the details of the proof strings.
+
+use gettextrs::gettext;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::{gio, glib};
+
+use crate::proof::{Proof, ProofError};
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/herve4m/Hexkudo/ui/verify_dialog.ui")]
+    pub struct HexkudoVerifyDialog {
+        // Template widgets
+        #[template_child]
+        pub proof_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub result_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub status_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub puzzle_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub time_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub errors_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub seed_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub verify_button: TemplateChild<gtk::Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HexkudoVerifyDialog {
+        const NAME: &'static str = "HexkudoVerifyDialog";
+        type Type = super::HexkudoVerifyDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HexkudoVerifyDialog {}
+    impl WidgetImpl for HexkudoVerifyDialog {}
+    impl AdwDialogImpl for HexkudoVerifyDialog {}
+}
+
+glib::wrapper! {
+    pub struct HexkudoVerifyDialog(ObjectSubclass<imp::HexkudoVerifyDialog>)
+        @extends gtk::Widget, adw::Dialog,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::ShortcutManager;
+}
+
+#[gtk::template_callbacks]
+impl HexkudoVerifyDialog {
+    /// Create the dialog.
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    /// Callback for when the proof string changes.
+    #[template_callback]
+    fn proof_changed_cb(&self) {
+        let imp: &imp::HexkudoVerifyDialog = self.imp();
+
+        imp.verify_button
+            .set_sensitive(!imp.proof_entry.text().trim().is_empty());
+        imp.result_group.set_visible(false);
+    }
+
+    /// Callback for the Verify button.
+    #[template_callback]
+    fn verify_cb(&self) {
+        let imp: &imp::HexkudoVerifyDialog = self.imp();
+
+        imp.status_row.remove_css_class("success");
+        imp.status_row.remove_css_class("error");
+        imp.result_group.set_visible(true);
+
+        let text: glib::GString = imp.proof_entry.text();
+        let proof: Proof = match Proof::parse(&text) {
+            Ok(p) => p,
+            Err(error) => {
+                imp.status_row.set_subtitle(&error.to_string());
+                imp.status_row.add_css_class("error");
+                imp.puzzle_row.set_visible(false);
+                imp.time_row.set_visible(false);
+                imp.errors_row.set_visible(false);
+                imp.seed_row.set_visible(false);
+                return;
+            }
+        };
+
+        // Display the details of the proof
+        let puzzle = &proof.export.puzzle;
+        imp.puzzle_row
+            .set_subtitle(&format!("{} - {}", puzzle.difficulty, puzzle.name_i18n));
+
+        let secs: u64 = proof.time.as_secs();
+        let h: u64 = secs / 3600;
+        let m: u64 = (secs % 3600) / 60;
+        let s: u64 = (secs % 3600) % 60;
+        let cs: u32 = proof.time.subsec_millis() / 10;
+        let time_str: String = if h > 0 {
+            format!("{h:02}h {m:02}m {s:02}.{cs:02}s")
+        } else if m > 0 {
+            format!("{m:02}m {s:02}.{cs:02}s")
+        } else {
+            format!("{s:02}.{cs:02}s")
+        };
+        imp.time_row.set_subtitle(&time_str);
+        imp.errors_row.set_subtitle(&format!("{}", proof.errors));
+        if let Some(seed) = proof.seed {
+            imp.seed_row.set_subtitle(&format!("{seed}"));
+        }
+
+        imp.puzzle_row.set_visible(true);
+        imp.time_row.set_visible(true);
+        imp.errors_row.set_visible(true);
+        imp.seed_row.set_visible(proof.seed.is_some());
+
+        // Regenerating the puzzle from its seed code takes some time
+        imp.status_row.set_subtitle(&gettext("Verifying…"));
+        imp.verify_button.set_sensitive(false);
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = mself)]
+            self,
+            async move {
+                let result: Result<(), ProofError> = gio::spawn_blocking(move || proof.verify())
+                    .await
+                    .expect("Task needs to finish successfully");
+                let imp: &imp::HexkudoVerifyDialog = mself.imp();
+                imp.verify_button
+                    .set_sensitive(!imp.proof_entry.text().trim().is_empty());
+
+                // The player changed the proof string during the verification
+                if imp.proof_entry.text() != text {
+                    return;
+                }
+                match result {
+                    Ok(()) => {
+                        imp.status_row.set_subtitle(&gettext(
+                            "The seed generates the puzzle, and the moves solve it in the claimed time, with the claimed number of mistakes.",
+                        ));
+                        imp.status_row.add_css_class("success");
+                    }
+                    Err(error) => {
+                        imp.status_row.set_subtitle(&error.to_string());
+                        imp.status_row.add_css_class("error");
+                    }
+                }
+            }
+        ));
+    }
+}
@@ -24,13 +24,15 @@ SPDX-License-Identifier: GPL-3.0-or-later
 
 //! Hexkudo main window.
 
+use formatx::formatx;
+use gettextrs::gettext;
 use log::debug;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+use adw::prelude::*;
 use adw::subclass::prelude::*;
-use gtk::prelude::*;
 use gtk::{gio, glib};
 
 use super::game_view::HexkudoGameView;
@@ -38,13 +40,24 @@ use super::select_puzzle_view::HexkudoSelectPuzzleView;
 use super::start_view::HexkudoStartView;
 use crate::game::Game;
 use crate::generator::puzzles;
+use crate::navigation::{Navigation, View};
+use crate::shared_game;
 
 mod imp {
     use super::*;
+    use std::cell::{Cell, OnceCell, RefCell};
 
     #[derive(Debug, Default, gtk::CompositeTemplate)]
     #[template(resource = "/io/github/herve4m/Hexkudo/ui/window.ui")]
     pub struct HexkudoWindow {
+        pub game: OnceCell<Rc<RefCell<Game>>>,
+
+        /// Whether the player confirmed closing the window while a game is in progress.
+        pub close_confirmed: Cell<bool>,
+
+        /// Navigation between the views and navigation history.
+        pub navigation: RefCell<Navigation>,
+
         // Template widgets
         #[template_child]
         pub view_stack: TemplateChild<adw::ViewStack>,
@@ -114,10 +127,16 @@ impl HexkudoWindow {
             .bind("window-is-fullscreen", &obj, "fullscreened")
             .build();
 
+        obj.imp()
+            .game
+            .set(game.clone())
+            .expect("Cannot store the game data in the object");
+        obj.connect_close_request(|window| window.close_request_cb());
+
         obj.imp().start_view.init(settings);
         obj.imp().select_puzzle_view.init(settings);
         obj.imp().game_view.init(settings, game, puzzle_list);
-        if game.borrow().started {
+        if shared_game::try_read(game).is_some_and(|g| g.started) {
             // obj.action_set_enabled("app.new-game", true);
             obj.continue_game();
         }
@@ -126,30 +145,15 @@ impl HexkudoWindow {
     }
 
     pub fn go_to_select_puzzle(&self, puzzles: Vec<&puzzles::Puzzle>) {
-        let imp: &imp::HexkudoWindow = self.imp();
-
-        self.action_set_enabled("app.back-start", true);
-        self.action_set_enabled("app.new-game", false);
-        self.action_set_enabled("app.new-game-same-puzzle", false);
-        self.action_set_enabled("game-view.print-current", false);
-        self.action_set_enabled("game-view.zoom-out", false);
-        self.action_set_enabled("game-view.zoom-in", false);
-
-        imp.select_puzzle_view.get().init_puzzle_list(puzzles);
-        imp.view_stack.set_visible_child(&*imp.select_puzzle_view);
+        self.imp()
+            .select_puzzle_view
+            .get()
+            .init_puzzle_list(puzzles);
+        self.navigate(View::SelectPuzzle);
     }
 
     pub fn go_to_start(&self) {
-        let imp: &imp::HexkudoWindow = self.imp();
-
-        self.action_set_enabled("app.back-start", true);
-        self.action_set_enabled("app.new-game", false);
-        self.action_set_enabled("app.new-game-same-puzzle", false);
-        self.action_set_enabled("game-view.print-current", false);
-        self.action_set_enabled("game-view.zoom-out", false);
-        self.action_set_enabled("game-view.zoom-in", false);
-
-        imp.view_stack.set_visible_child(&*imp.start_view);
+        self.navigate(View::Start);
     }
 
     pub fn go_to_game(&self) {
@@ -159,41 +163,181 @@ impl HexkudoWindow {
             .get_selected_puzzle()
             .expect("Cannot retrieve the selected puzzle");
 
-        self.action_set_enabled("app.back-start", false);
-        self.action_set_enabled("app.new-game", true);
-        self.action_set_enabled("app.new-game-same-puzzle", true);
-        self.action_set_enabled("game-view.print-current", true);
-
-        imp.view_stack.set_visible_child(&*imp.game_view);
-        imp.game_view.set_puzzle(puzzle);
+        if self.navigate(View::Game) {
+            imp.game_view.set_puzzle(puzzle, None);
+        }
     }
 
     pub fn play_same_puzzle(&self) {
-        let imp: &imp::HexkudoWindow = self.imp();
+        if self.navigate(View::Game) {
+            self.imp().game_view.play_again();
+        }
+    }
 
-        self.action_set_enabled("app.back-start", false);
-        self.action_set_enabled("app.new-game", true);
-        self.action_set_enabled("app.new-game-same-puzzle", true);
-        self.action_set_enabled("game-view.print-current", true);
+    pub fn continue_game(&self) {
+        if self.navigate(View::Game) {
+            self.imp().game_view.continue_game();
+        }
+    }
 
-        imp.view_stack.set_visible_child(&*imp.game_view);
-        imp.game_view.play_again();
+    /// Go back to the previous view. When a dialog is displayed, close the dialog instead.
+    pub fn go_back(&self) {
+        if let Some(dialog) = self.visible_dialog() {
+            dialog.close();
+            return;
+        }
+        let previous: View = self.imp().navigation.borrow().current();
+        let view: Option<View> = self.imp().navigation.borrow_mut().back();
+        if let Some(v) = view {
+            self.show_view(previous, v);
+        }
     }
 
-    pub fn continue_game(&self) {
+    /// Go forward to the view that the player left with the Back action.
+    pub fn go_forward(&self) {
+        if self.visible_dialog().is_some() {
+            return;
+        }
+        let previous: View = self.imp().navigation.borrow().current();
+        let view: Option<View> = self.imp().navigation.borrow_mut().forward();
+        if let Some(v) = view {
+            self.show_view(previous, v);
+        }
+    }
+
+    /// Go to the given view. Return false if the navigation state machine does not allow that
+    /// transition from the current view.
+    fn navigate(&self, view: View) -> bool {
+        let previous: View = self.imp().navigation.borrow().current();
+
+        if !self.imp().navigation.borrow_mut().go_to(view) {
+            debug!("Navigation from {previous:?} to {view:?} is not allowed");
+            return false;
+        }
+        self.show_view(previous, view);
+        true
+    }
+
+    /// Display the given view, coming from the previous view, and enable the actions that apply
+    /// to that view.
+    fn show_view(&self, previous: View, view: View) {
         let imp: &imp::HexkudoWindow = self.imp();
+        let in_game: bool = view == View::Game;
+
+        // Leaving the puzzle selection view: keep the puzzle that the player highlighted
+        if previous == View::SelectPuzzle && view != View::SelectPuzzle {
+            imp.select_puzzle_view.save_selection();
+        }
 
-        self.action_set_enabled("app.back-start", false);
-        self.action_set_enabled("app.new-game", true);
-        self.action_set_enabled("app.new-game-same-puzzle", true);
-        self.action_set_enabled("game-view.print-current", true);
+        self.action_set_enabled("app.new-game", in_game);
+        self.action_set_enabled("app.new-game-same-puzzle", in_game);
+        self.action_set_enabled("game-view.print-current", in_game);
+        if !in_game {
+            self.action_set_enabled("game-view.export-replay", false);
+            self.action_set_enabled("game-view.heat-map", false);
+            self.action_set_enabled("game-view.export-puzzle", false);
+            self.action_set_enabled("game-view.zoom-out", false);
+            self.action_set_enabled("game-view.zoom-in", false);
+            self.action_set_enabled("game-view.zoom-fit", false);
+        }
+        let (can_go_back, can_go_forward): (bool, bool) = {
+            let navigation = imp.navigation.borrow();
+            (navigation.can_go_back(), navigation.can_go_forward())
+        };
+        self.action_set_enabled("app.back-start", can_go_back);
+        self.action_set_enabled("app.forward", can_go_forward);
+
+        match view {
+            View::Start => {
+                imp.start_view.refresh_stats();
+                imp.view_stack.set_visible_child(&*imp.start_view);
+                imp.start_view.focus_difficulty();
+            }
+            View::SelectPuzzle => {
+                imp.view_stack.set_visible_child(&*imp.select_puzzle_view);
+                imp.select_puzzle_view.focus_selection();
+            }
+            View::Game => imp.view_stack.set_visible_child(&*imp.game_view),
+        }
+    }
+
+    /// Ask for confirmation before closing the window when a game is in progress.
+    fn close_request_cb(&self) -> glib::Propagation {
+        let imp: &imp::HexkudoWindow = self.imp();
+        if imp.close_confirmed.get() {
+            return glib::Propagation::Proceed;
+        }
 
-        imp.view_stack.set_visible_child(&*imp.game_view);
-        imp.game_view.continue_game();
+        let (completed, to_complete, h, m, s) = {
+            let game = imp
+                .game
+                .get()
+                .expect("Cannot retrieve the game data from the object")
+                .borrow();
+            if !game.started || game.solved {
+                return glib::Propagation::Proceed;
+            }
+            let (completed, to_complete) = game.get_progress();
+            let (h, m, s) = game.get_duration_hms();
+            (completed, to_complete, h, m, s)
+        };
+
+        let time_str: String = if h > 0 {
+            format!("{h:02}:{m:02}:{s:02}")
+        } else {
+            format!("{m:02}:{s:02}")
+        };
+        let dialog: adw::AlertDialog = adw::AlertDialog::new(
+            Some(&gettext("Quit Hexkudo?")),
+            Some(
+                &formatx!(
+                    gettext(
+                        "You have been playing for {time} and completed {completed} of {total} cells. The game is saved and you can continue it the next time you start Hexkudo."
+                    ),
+                    time = time_str,
+                    completed = completed,
+                    total = to_complete
+                )
+                .unwrap()
+                .to_string(),
+            ),
+        );
+        dialog.add_response("cancel", &gettext("Cancel"));
+        dialog.add_response("quit", &gettext("Quit"));
+        dialog.set_response_appearance("quit", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("quit"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = mself)]
+                self,
+                move |_w, response_id| {
+                    if response_id == "quit" {
+                        mself.imp().close_confirmed.set(true);
+                        mself.close();
+                    }
+                }
+            ),
+        );
+        dialog.present(Some(self));
+        glib::Propagation::Stop
     }
 
     pub fn display_scores(&self) {
-        self.imp().game_view.display_scores(None);
+        self.imp().game_view.display_scores(None, false);
+    }
+
+    pub fn display_statistics(&self) {
+        self.imp().game_view.display_statistics();
+    }
+
+    pub fn select_cell(&self, parameter: Option<&glib::Variant>) {
+        self.imp().game_view.select_cell_action(parameter);
+    }
+
+    pub fn set_cell(&self, parameter: Option<&glib::Variant>) {
+        self.imp().game_view.set_cell_action(parameter);
     }
 
     #[template_callback]
@@ -214,4 +358,15 @@ impl HexkudoWindow {
             .menu_button
             .set_fullscreen_button(is_fullscreen);
     }
+
+    /// Adapt the game view when the window becomes narrow.
+    #[template_callback]
+    fn narrow_cb(&self) {
+        self.imp().game_view.set_compact(true);
+    }
+
+    #[template_callback]
+    fn wide_cb(&self) {
+        self.imp().game_view.set_compact(false);
+    }
 }